@@ -0,0 +1,356 @@
+//! Stellar history archive layout
+//!
+//! A history archive published by stellar-core looks like:
+//!
+//! ```text
+//! .well-known/stellar-history.json
+//! history/xx/yy/zz/history-XXXXXXXX.json
+//! ledger/xx/yy/zz/ledger-XXXXXXXX.xdr.gz
+//! transactions/xx/yy/zz/transactions-XXXXXXXX.xdr.gz
+//! results/xx/yy/zz/results-XXXXXXXX.xdr.gz
+//! bucket/aa/bb/cc/bucket-<sha256>.xdr.gz
+//! ```
+//!
+//! where `XXXXXXXX` is the checkpoint ledger in hex and `xx/yy/zz` are its first
+//! three bytes. Buckets are content-addressed and referenced from the
+//! per-checkpoint history JSON files.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use tracing::{debug, warn};
+
+use super::scheduler::ArchiveSegment;
+
+/// Path of the root History Archive State file
+pub const WELL_KNOWN_HAS_PATH: &str = ".well-known/stellar-history.json";
+
+/// Segment type of History Archive State files (root and per-checkpoint)
+pub const SEGMENT_TYPE_HAS: &str = "history";
+/// Segment type of ledger header files
+pub const SEGMENT_TYPE_LEDGER: &str = "ledger";
+/// Segment type of transaction set files
+pub const SEGMENT_TYPE_TRANSACTIONS: &str = "transactions";
+/// Segment type of transaction result files
+pub const SEGMENT_TYPE_RESULTS: &str = "results";
+/// Segment type of bucket files
+pub const SEGMENT_TYPE_BUCKET: &str = "bucket";
+
+/// Offset of `header.scpValue.closeTime` in an XDR `LedgerHeaderHistoryEntry`:
+/// entry hash, ledger version, previous ledger hash and tx set hash
+const CLOSE_TIME_OFFSET: usize = 32 + 4 + 32 + 32;
+
+/// Bucket hash used by stellar-core for empty bucket slots
+const EMPTY_BUCKET_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// History Archive State, as stored in `stellar-history.json` files
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryArchiveState {
+    pub current_ledger: u64,
+    #[serde(default)]
+    pub current_buckets: Vec<HistoryBucketLevel>,
+    #[serde(default)]
+    pub hot_archive_buckets: Vec<HistoryBucketLevel>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HistoryBucketLevel {
+    pub curr: String,
+    pub snap: String,
+    #[serde(default)]
+    pub next: HistoryBucketNext,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HistoryBucketNext {
+    #[serde(default)]
+    pub output: Option<String>,
+}
+
+impl HistoryArchiveState {
+    /// Non-empty bucket hashes referenced by this state, without duplicates
+    pub fn bucket_hashes(&self) -> BTreeSet<String> {
+        self.current_buckets
+            .iter()
+            .chain(self.hot_archive_buckets.iter())
+            .flat_map(|level| {
+                [
+                    Some(&level.curr),
+                    Some(&level.snap),
+                    level.next.output.as_ref(),
+                ]
+                .into_iter()
+                .flatten()
+            })
+            .filter(|h| h.as_str() != EMPTY_BUCKET_HASH && !h.is_empty())
+            .cloned()
+            .collect()
+    }
+}
+
+/// Relative path of a per-checkpoint file (`history`, `ledger`, ...)
+pub fn checkpoint_file_path(category: &str, checkpoint: u64, extension: &str) -> String {
+    let hex = format!("{checkpoint:08x}");
+    format!(
+        "{category}/{}/{}/{}/{category}-{hex}.{extension}",
+        &hex[0..2],
+        &hex[2..4],
+        &hex[4..6]
+    )
+}
+
+/// Relative path of a bucket file
+///
+/// Fails unless `hash` is a hex SHA-256, so a malformed history file cannot
+/// produce paths outside `bucket/`.
+pub fn bucket_path(hash: &str) -> Result<String> {
+    if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        bail!("Invalid bucket hash {hash:?}");
+    }
+    Ok(format!(
+        "bucket/{}/{}/{}/bucket-{hash}.xdr.gz",
+        &hash[0..2],
+        &hash[2..4],
+        &hash[4..6]
+    ))
+}
+
+/// What the manifest recorded about an archive file when it was uploaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KnownFile {
+    pub(crate) size: u64,
+    pub(crate) modified: DateTime<Utc>,
+    pub(crate) sha256: String,
+}
+
+/// Known files by archive-relative path
+pub(crate) type KnownFiles = HashMap<String, KnownFile>;
+
+/// SHA-256 of a file, hex encoded
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Close time of the last ledger in a `ledger-XXXXXXXX.xdr.gz` file.
+///
+/// The file holds XDR `LedgerHeaderHistoryEntry` records, each preceded by
+/// an RFC 5531 record mark.
+pub fn ledger_close_time(path: &Path) -> Result<DateTime<Utc>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut data = Vec::new();
+    GzDecoder::new(file)
+        .read_to_end(&mut data)
+        .with_context(|| format!("Failed to decompress {}", path.display()))?;
+
+    let mut offset = 0;
+    let mut close_time = None;
+    while offset < data.len() {
+        let mark = data
+            .get(offset..offset + 4)
+            .context("Truncated ledger header record mark")?;
+        let len = (u32::from_be_bytes(mark.try_into()?) & 0x7fff_ffff) as usize;
+        let record = data
+            .get(offset + 4..offset + 4 + len)
+            .context("Truncated ledger header record")?;
+        let field = record
+            .get(CLOSE_TIME_OFFSET..CLOSE_TIME_OFFSET + 8)
+            .context("Ledger header record too short")?;
+        close_time = Some(u64::from_be_bytes(field.try_into()?));
+        offset += 4 + len;
+    }
+
+    let seconds = close_time.with_context(|| format!("{} has no ledgers", path.display()))?;
+    DateTime::from_timestamp(i64::try_from(seconds)?, 0).context("Invalid ledger close time")
+}
+
+fn read_has(path: &Path) -> Result<HistoryArchiveState> {
+    let data = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_slice(&data).with_context(|| format!("Invalid HAS file {}", path.display()))
+}
+
+/// Checkpoints that have a `history-XXXXXXXX.json` file in the archive
+pub fn published_checkpoints(root: &Path) -> Result<BTreeSet<u64>> {
+    let mut checkpoints = BTreeSet::new();
+    let history = root.join("history");
+    if !history.is_dir() {
+        return Ok(checkpoints);
+    }
+
+    for l1 in std::fs::read_dir(&history)? {
+        let l1 = l1?.path();
+        if !l1.is_dir() {
+            continue;
+        }
+        for l2 in std::fs::read_dir(&l1)? {
+            let l2 = l2?.path();
+            if !l2.is_dir() {
+                continue;
+            }
+            for l3 in std::fs::read_dir(&l2)? {
+                let l3 = l3?.path();
+                if !l3.is_dir() {
+                    continue;
+                }
+                for entry in std::fs::read_dir(&l3)? {
+                    let name = entry?.file_name();
+                    let Some(hex) = name
+                        .to_str()
+                        .and_then(|n| n.strip_prefix("history-"))
+                        .and_then(|n| n.strip_suffix(".json"))
+                    else {
+                        continue;
+                    };
+                    match u64::from_str_radix(hex, 16) {
+                        Ok(checkpoint) => {
+                            checkpoints.insert(checkpoint);
+                        }
+                        Err(_) => warn!("Ignoring unexpected history file {:?}", name),
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(checkpoints)
+}
+
+/// Describe one archive file. Its hash is taken from `known` when the size
+/// and modification time still match, and computed otherwise.
+fn segment(
+    root: &Path,
+    relative: &str,
+    ledger: u64,
+    segment_type: &str,
+    known: &KnownFiles,
+) -> Result<ArchiveSegment> {
+    let path = root.join(relative);
+    let metadata =
+        std::fs::metadata(&path).with_context(|| format!("Failed to stat {}", path.display()))?;
+    let size = metadata.len();
+    let modified = metadata.modified().ok().map(DateTime::<Utc>::from);
+    let hash = match known.get(relative) {
+        Some(k) if k.size == size && Some(k.modified) == modified => k.sha256.clone(),
+        _ => sha256_file(&path)?,
+    };
+    Ok(ArchiveSegment {
+        filename: relative.to_string(),
+        path: path.to_string_lossy().into_owned(),
+        hash,
+        ledger,
+        segment_type: segment_type.to_string(),
+        bucket_refs: Vec::new(),
+        closed_at: None,
+        size,
+        modified,
+    })
+}
+
+/// Walk a history archive on disk and describe every file as a segment
+///
+/// Checkpoints are visited in ascending order. Each one yields its history
+/// JSON, ledger, transactions and results files, followed by the buckets it
+/// references that were not already emitted for an earlier checkpoint.
+/// Checkpoints newer than the root HAS are skipped because stellar-core may
+/// still be publishing them. The root HAS is emitted last so a restore never
+/// sees it ahead of the files it describes.
+///
+/// Files listed in `known` with an unchanged size and modification time are
+/// not hashed again.
+pub(crate) fn scan_archive(root: &Path, known: &KnownFiles) -> Result<Vec<ArchiveSegment>> {
+    let root_has_path = root.join(WELL_KNOWN_HAS_PATH);
+    let root_has = read_has(&root_has_path)
+        .with_context(|| format!("{} is not a history archive", root.display()))?;
+
+    let mut segments = Vec::new();
+    let mut seen_buckets = HashSet::new();
+
+    for checkpoint in published_checkpoints(root)? {
+        if checkpoint > root_has.current_ledger {
+            debug!(
+                "Skipping checkpoint {} beyond published ledger {}",
+                checkpoint, root_has.current_ledger
+            );
+            continue;
+        }
+
+        let history = checkpoint_file_path("history", checkpoint, "json");
+        let has = read_has(&root.join(&history))?;
+        let mut history_segment = segment(root, &history, checkpoint, SEGMENT_TYPE_HAS, known)?;
+        history_segment.bucket_refs = has.bucket_hashes().into_iter().collect();
+        segments.push(history_segment);
+
+        for category in [
+            SEGMENT_TYPE_LEDGER,
+            SEGMENT_TYPE_TRANSACTIONS,
+            SEGMENT_TYPE_RESULTS,
+        ] {
+            let relative = checkpoint_file_path(category, checkpoint, "xdr.gz");
+            if root.join(&relative).is_file() {
+                let mut file_segment = segment(root, &relative, checkpoint, category, known)?;
+                if category == SEGMENT_TYPE_LEDGER {
+                    match ledger_close_time(&root.join(&relative)) {
+                        Ok(closed_at) => file_segment.closed_at = Some(closed_at),
+                        Err(e) => warn!("Cannot read close time from {}: {:#}", relative, e),
+                    }
+                }
+                segments.push(file_segment);
+            } else {
+                warn!("Checkpoint {} is missing {}", checkpoint, relative);
+            }
+        }
+
+        for hash in has.bucket_hashes() {
+            if !seen_buckets.insert(hash.clone()) {
+                continue;
+            }
+            let relative = match bucket_path(&hash) {
+                Ok(relative) => relative,
+                Err(e) => {
+                    warn!("Checkpoint {} references {:#}", checkpoint, e);
+                    continue;
+                }
+            };
+            if root.join(&relative).is_file() {
+                segments.push(segment(
+                    root,
+                    &relative,
+                    checkpoint,
+                    SEGMENT_TYPE_BUCKET,
+                    known,
+                )?);
+            } else {
+                warn!("Checkpoint {} references missing {}", checkpoint, relative);
+            }
+        }
+    }
+
+    segments.push(segment(
+        root,
+        WELL_KNOWN_HAS_PATH,
+        root_has.current_ledger,
+        SEGMENT_TYPE_HAS,
+        known,
+    )?);
+
+    Ok(segments)
+}
@@ -0,0 +1,338 @@
+//! Tests for history archive segment discovery against a fixture archive on disk.
+
+#[cfg(test)]
+mod tests {
+    use crate::backup::archive::*;
+    use crate::backup::providers::{
+        RemovalOutcome, StorageProviderTrait, StoredObject, UploadMetadata,
+    };
+    use crate::backup::scheduler::BackupScheduler;
+    use crate::backup::*;
+
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use sha2::{Digest, Sha256};
+    use std::path::Path;
+    use std::sync::Arc;
+
+    struct NoopProvider;
+
+    #[async_trait]
+    impl StorageProviderTrait for NoopProvider {
+        async fn upload(&self, _data: Vec<u8>, _metadata: UploadMetadata) -> Result<String> {
+            Ok("cid".to_string())
+        }
+
+        async fn exists(&self, _content_hash: &str) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn verify(&self, _cid: &str, _expected_hash: &str) -> Result<bool> {
+            Ok(true)
+        }
+
+        async fn download(&self, _cid: &str) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn list(&self) -> Result<Vec<StoredObject>> {
+            Ok(Vec::new())
+        }
+
+        async fn delete(&self, _cid: &str) -> Result<RemovalOutcome> {
+            Ok(RemovalOutcome::Removed)
+        }
+    }
+
+    const BUCKET_A: &str = "aa11223344556677889900aabbccddeeff00112233445566778899aabbccddee";
+    const BUCKET_B: &str = "bb11223344556677889900aabbccddeeff00112233445566778899aabbccddee";
+    const ZERO: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+    fn write(root: &Path, relative: &str, contents: &[u8]) {
+        let path = root.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    fn has_json(ledger: u64, buckets: &[(&str, &str)]) -> Vec<u8> {
+        let levels: Vec<_> = buckets
+            .iter()
+            .map(|(curr, snap)| {
+                serde_json::json!({ "curr": curr, "snap": snap, "next": { "state": 0 } })
+            })
+            .collect();
+        serde_json::to_vec(&serde_json::json!({
+            "version": 1,
+            "server": "stellar-core 21.0.0",
+            "currentLedger": ledger,
+            "currentBuckets": levels
+        }))
+        .unwrap()
+    }
+
+    /// Two published checkpoints (63, 127) plus a third (191) still being written
+    fn fixture_archive() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        write(
+            root,
+            WELL_KNOWN_HAS_PATH,
+            &has_json(127, &[(BUCKET_B, BUCKET_A)]),
+        );
+
+        for (checkpoint, buckets) in [
+            (63u64, vec![(BUCKET_A, ZERO)]),
+            (127, vec![(BUCKET_B, BUCKET_A)]),
+            (191, vec![(BUCKET_B, BUCKET_A)]),
+        ] {
+            write(
+                root,
+                &checkpoint_file_path("history", checkpoint, "json"),
+                &has_json(checkpoint, &buckets),
+            );
+            for category in ["ledger", "transactions", "results"] {
+                write(
+                    root,
+                    &checkpoint_file_path(category, checkpoint, "xdr.gz"),
+                    format!("{category}-{checkpoint}").as_bytes(),
+                );
+            }
+        }
+        write(root, &bucket_path(BUCKET_A).unwrap(), b"bucket a");
+        write(root, &bucket_path(BUCKET_B).unwrap(), b"bucket b");
+
+        dir
+    }
+
+    #[test]
+    fn test_checkpoint_paths() {
+        assert_eq!(
+            checkpoint_file_path("ledger", 63, "xdr.gz"),
+            "ledger/00/00/00/ledger-0000003f.xdr.gz"
+        );
+        assert_eq!(
+            checkpoint_file_path("history", 0x01ab_cdff, "json"),
+            "history/01/ab/cd/history-01abcdff.json"
+        );
+        assert_eq!(
+            bucket_path(BUCKET_A).unwrap(),
+            format!("bucket/aa/11/22/bucket-{BUCKET_A}.xdr.gz")
+        );
+        // Short, non-hex or path-like hashes are rejected instead of panicking
+        assert!(bucket_path("aa").is_err());
+        assert!(bucket_path(&BUCKET_A.replace('a', "g")).is_err());
+        assert!(bucket_path(&format!("../../{}", &BUCKET_A[6..])).is_err());
+    }
+
+    #[test]
+    fn test_bucket_hashes_skip_empty() {
+        let has: HistoryArchiveState =
+            serde_json::from_slice(&has_json(63, &[(BUCKET_A, ZERO), (BUCKET_A, BUCKET_B)]))
+                .unwrap();
+        let hashes: Vec<_> = has.bucket_hashes().into_iter().collect();
+        assert_eq!(hashes, vec![BUCKET_A.to_string(), BUCKET_B.to_string()]);
+    }
+
+    #[test]
+    fn test_published_checkpoints() {
+        let dir = fixture_archive();
+        let checkpoints: Vec<_> = published_checkpoints(dir.path())
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(checkpoints, vec![63, 127, 191]);
+    }
+
+    #[test]
+    fn test_scan_archive_checkpoint_by_checkpoint() {
+        let dir = fixture_archive();
+        let segments = scan_archive(dir.path(), &KnownFiles::new()).unwrap();
+
+        let summary: Vec<_> = segments
+            .iter()
+            .map(|s| (s.ledger, s.segment_type.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (63, SEGMENT_TYPE_HAS),
+                (63, SEGMENT_TYPE_LEDGER),
+                (63, SEGMENT_TYPE_TRANSACTIONS),
+                (63, SEGMENT_TYPE_RESULTS),
+                (63, SEGMENT_TYPE_BUCKET),
+                (127, SEGMENT_TYPE_HAS),
+                (127, SEGMENT_TYPE_LEDGER),
+                (127, SEGMENT_TYPE_TRANSACTIONS),
+                (127, SEGMENT_TYPE_RESULTS),
+                (127, SEGMENT_TYPE_BUCKET),
+                (127, SEGMENT_TYPE_HAS),
+            ]
+        );
+        assert_eq!(segments.last().unwrap().filename, WELL_KNOWN_HAS_PATH);
+        assert_eq!(segments[4].filename, bucket_path(BUCKET_A).unwrap());
+        assert_eq!(segments[9].filename, bucket_path(BUCKET_B).unwrap());
+    }
+
+    #[test]
+    fn test_scan_archive_hashes_file_contents() {
+        let dir = fixture_archive();
+        let segments = scan_archive(dir.path(), &KnownFiles::new()).unwrap();
+
+        let ledger = segments
+            .iter()
+            .find(|s| s.filename == "ledger/00/00/00/ledger-0000003f.xdr.gz")
+            .unwrap();
+        assert_eq!(ledger.hash, format!("{:x}", Sha256::digest(b"ledger-63")));
+        assert!(Path::new(&ledger.path).is_file());
+    }
+
+    /// Gzipped ledger headers file: one record per close time, each padded
+    /// to the size of a real `LedgerHeaderHistoryEntry`
+    fn ledger_headers(close_times: &[u64]) -> Vec<u8> {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        for close_time in close_times {
+            let mut record = vec![0u8; 100];
+            record.extend_from_slice(&close_time.to_be_bytes());
+            record.resize(340, 0);
+            encoder
+                .write_all(&(0x8000_0000u32 | record.len() as u32).to_be_bytes())
+                .unwrap();
+            encoder.write_all(&record).unwrap();
+        }
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_scan_archive_reads_ledger_close_times() {
+        let dir = fixture_archive();
+        let relative = checkpoint_file_path("ledger", 63, "xdr.gz");
+        write(
+            dir.path(),
+            &relative,
+            &ledger_headers(&[1_700_000_000, 1_700_000_005]),
+        );
+        assert_eq!(
+            ledger_close_time(&dir.path().join(&relative))
+                .unwrap()
+                .timestamp(),
+            1_700_000_005
+        );
+
+        let segments = scan_archive(dir.path(), &KnownFiles::new()).unwrap();
+        let ledger = segments.iter().find(|s| s.filename == relative).unwrap();
+        assert_eq!(ledger.closed_at.unwrap().timestamp(), 1_700_000_005);
+        // Unreadable ledger files are backed up without a close time
+        let other = checkpoint_file_path("ledger", 127, "xdr.gz");
+        let ledger = segments.iter().find(|s| s.filename == other).unwrap();
+        assert!(ledger.closed_at.is_none());
+
+        write(dir.path(), &relative, &ledger_headers(&[]));
+        assert!(ledger_close_time(&dir.path().join(&relative)).is_err());
+    }
+
+    #[test]
+    fn test_scan_archive_reuses_hashes_of_unchanged_files() {
+        let dir = fixture_archive();
+        let relative = checkpoint_file_path("ledger", 63, "xdr.gz");
+        let metadata = std::fs::metadata(dir.path().join(&relative)).unwrap();
+        let known_file = |size: u64| KnownFile {
+            size,
+            modified: metadata.modified().unwrap().into(),
+            sha256: "recorded".to_string(),
+        };
+
+        // Same size and modification time: the recorded hash is trusted
+        let known = KnownFiles::from([(relative.clone(), known_file(metadata.len()))]);
+        let segments = scan_archive(dir.path(), &known).unwrap();
+        let ledger = segments.iter().find(|s| s.filename == relative).unwrap();
+        assert_eq!(ledger.hash, "recorded");
+        assert_eq!(ledger.size, metadata.len());
+
+        // A changed file is hashed again
+        let known = KnownFiles::from([(relative.clone(), known_file(metadata.len() + 1))]);
+        let segments = scan_archive(dir.path(), &known).unwrap();
+        let ledger = segments.iter().find(|s| s.filename == relative).unwrap();
+        assert_eq!(ledger.hash, format!("{:x}", Sha256::digest(b"ledger-63")));
+    }
+
+    #[test]
+    fn test_scan_archive_tolerates_missing_files() {
+        let dir = fixture_archive();
+        std::fs::remove_file(
+            dir.path()
+                .join(checkpoint_file_path("results", 63, "xdr.gz")),
+        )
+        .unwrap();
+        std::fs::remove_file(dir.path().join(bucket_path(BUCKET_B).unwrap())).unwrap();
+
+        let segments = scan_archive(dir.path(), &KnownFiles::new()).unwrap();
+        assert_eq!(segments.len(), 9);
+    }
+
+    #[test]
+    fn test_scan_rejects_non_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(scan_archive(dir.path(), &KnownFiles::new()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_discover_new_segments_skips_uploaded() {
+        let dir = fixture_archive();
+        let config = DecentralizedBackupConfig {
+            enabled: true,
+            provider: StorageProvider::IPFS {
+                api_url: "http://localhost:5001".to_string(),
+                pinning_service: None,
+            },
+            schedule: "0 0 */6 * * *".to_string(),
+            max_concurrent_uploads: 1,
+            compression_enabled: false,
+            retention: None,
+            archive_path: "history".to_string(),
+        };
+        let scheduler = BackupScheduler::new(config, Arc::new(NoopProvider));
+        let archive_path = dir.path().to_string_lossy().into_owned();
+
+        let first = scheduler
+            .discover_new_segments(&archive_path)
+            .await
+            .unwrap();
+        assert_eq!(first.len(), 11);
+
+        scheduler.run_backup(&archive_path).await.unwrap();
+        assert!(scheduler
+            .discover_new_segments(&archive_path)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Publishing checkpoint 191 only adds its own files and the new root HAS
+        write(
+            dir.path(),
+            WELL_KNOWN_HAS_PATH,
+            &has_json(191, &[(BUCKET_B, BUCKET_A)]),
+        );
+        let next = scheduler
+            .discover_new_segments(&archive_path)
+            .await
+            .unwrap();
+        let summary: Vec<_> = next
+            .iter()
+            .map(|s| (s.ledger, s.segment_type.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (191, SEGMENT_TYPE_HAS),
+                (191, SEGMENT_TYPE_LEDGER),
+                (191, SEGMENT_TYPE_TRANSACTIONS),
+                (191, SEGMENT_TYPE_RESULTS),
+                (191, SEGMENT_TYPE_HAS),
+            ]
+        );
+    }
+}
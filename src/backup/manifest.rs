@@ -0,0 +1,433 @@
+//! Durable record of uploaded history archive files
+//!
+//! The manifest maps checkpoint → archive file → content identifier (IPFS CID,
+//! Arweave transaction id, Filecoin root CID). It survives operator restarts so
+//! files are not uploaded twice, and it is the input for restoring an archive.
+//!
+//! A manifest for a long-lived archive outgrows a single ConfigMap (etcd caps
+//! objects at about 1MiB), so [`ConfigMapManifestStore`] splits the gzipped
+//! manifest across shard ConfigMaps. Shards are written to alternating slots
+//! and the index ConfigMap is switched to the new slot last, so a run that
+//! dies mid-save leaves the previous manifest intact.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::api::{Api, Patch, PatchParams};
+use kube::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::Read;
+use tracing::debug;
+
+use super::archive::{KnownFile, KnownFiles};
+use super::scheduler::{compress_data, ArchiveSegment};
+
+/// Current manifest format version
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// ConfigMap `binaryData` key holding the gzipped JSON manifest, or a shard
+/// of it
+pub const MANIFEST_DATA_KEY: &str = "manifest.json.gz";
+
+/// Largest shard, leaving headroom below the etcd object limit
+pub const MANIFEST_SHARD_BYTES: usize = 900 * 1024;
+
+/// Most shards a manifest may use; the backup Role names each shard
+pub const MAX_MANIFEST_SHARDS: usize = 16;
+
+/// The two alternating shard slots
+pub const MANIFEST_SLOTS: [&str; 2] = ["a", "b"];
+
+/// Name of shard `index` of the manifest `name` in `slot`
+pub fn manifest_shard_name(name: &str, slot: &str, index: usize) -> String {
+    format!("{name}-{slot}{index}")
+}
+
+/// Every shard ConfigMap name the manifest `name` may use
+pub fn manifest_shard_names(name: &str) -> Vec<String> {
+    MANIFEST_SLOTS
+        .iter()
+        .flat_map(|slot| (0..MAX_MANIFEST_SHARDS).map(move |i| manifest_shard_name(name, slot, i)))
+        .collect()
+}
+
+/// Split a gzipped manifest into shards that each fit in a ConfigMap
+pub fn split_manifest(data: &[u8]) -> Result<Vec<&[u8]>> {
+    let shards: Vec<&[u8]> = data.chunks(MANIFEST_SHARD_BYTES).collect();
+    if shards.len() > MAX_MANIFEST_SHARDS {
+        anyhow::bail!(
+            "Backup manifest is {} bytes compressed, more than {} shards of {} bytes",
+            data.len(),
+            MAX_MANIFEST_SHARDS,
+            MANIFEST_SHARD_BYTES
+        );
+    }
+    Ok(shards)
+}
+
+/// Reassemble shards produced by [`split_manifest`], checking them against
+/// the SHA-256 recorded when they were written
+pub fn join_manifest(shards: Vec<Vec<u8>>, sha256: &str) -> Result<BackupManifest> {
+    let data = shards.concat();
+    let actual = format!("{:x}", Sha256::digest(&data));
+    if actual != sha256 {
+        anyhow::bail!("Backup manifest shards do not match their checksum {sha256}");
+    }
+    BackupManifest::from_gzip_json(&data)
+}
+
+/// One uploaded archive file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupFileEntry {
+    /// Content identifier returned by the storage provider
+    pub cid: String,
+    /// SHA-256 of the original (uncompressed) archive file
+    pub sha256: String,
+    /// Segment type (`history`, `ledger`, `transactions`, `results`, `bucket`)
+    pub segment_type: String,
+    /// Whether the operator gzipped the file before upload
+    #[serde(default)]
+    pub compressed: bool,
+    pub uploaded_at: DateTime<Utc>,
+    /// Size of the archive file when it was hashed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// Modification time of the archive file when it was hashed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<DateTime<Utc>>,
+    /// Set when retention released this file but the provider still holds it
+    /// (Arweave data is permanent, Filecoin deals run until they expire)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub superseded_at: Option<DateTime<Utc>>,
+}
+
+impl BackupFileEntry {
+    /// Whether the file still counts as backed up
+    pub fn is_active(&self) -> bool {
+        self.superseded_at.is_none()
+    }
+}
+
+/// Files backed up for a single checkpoint, keyed by archive-relative path
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointBackup {
+    pub files: BTreeMap<String, BackupFileEntry>,
+    /// Bucket hashes referenced by this checkpoint's history file
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub bucket_refs: BTreeSet<String>,
+    /// Close time of the checkpoint's last ledger
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl CheckpointBackup {
+    /// Time of the most recent upload for this checkpoint
+    pub fn last_uploaded(&self) -> Option<DateTime<Utc>> {
+        self.files.values().map(|f| f.uploaded_at).max()
+    }
+
+    /// Time the checkpoint's age is measured from: its ledger close time, or
+    /// the last upload for checkpoints recorded without one
+    pub fn age_reference(&self) -> Option<DateTime<Utc>> {
+        self.closed_at.or_else(|| self.last_uploaded())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub version: u32,
+    /// Checkpoint ledger → uploaded files
+    #[serde(default)]
+    pub checkpoints: BTreeMap<u64, CheckpointBackup>,
+    /// Highest checkpoint released by retention; older archive files are not
+    /// uploaded again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pruned_through: Option<u64>,
+    /// Index of content hash → (cid, compressed), rebuilt on load
+    #[serde(skip)]
+    by_hash: HashMap<String, (String, bool)>,
+}
+
+impl Default for BackupManifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            checkpoints: BTreeMap::new(),
+            pruned_through: None,
+            by_hash: HashMap::new(),
+        }
+    }
+}
+
+impl BackupManifest {
+    /// Whether this exact file (path and content) is already recorded, or
+    /// belongs to a checkpoint already released by retention
+    pub(crate) fn contains_segment(&self, segment: &ArchiveSegment) -> bool {
+        if self.pruned_through.is_some_and(|p| segment.ledger <= p) {
+            return true;
+        }
+        self.checkpoints
+            .get(&segment.ledger)
+            .and_then(|c| c.files.get(&segment.filename))
+            .is_some_and(|f| f.sha256 == segment.hash)
+    }
+
+    /// Existing upload with the same content, if any
+    pub fn find_by_hash(&self, sha256: &str) -> Option<(&str, bool)> {
+        self.by_hash
+            .get(sha256)
+            .map(|(cid, compressed)| (cid.as_str(), *compressed))
+    }
+
+    /// Record an uploaded segment
+    pub(crate) fn record(&mut self, segment: &ArchiveSegment, cid: String, compressed: bool) {
+        self.by_hash
+            .insert(segment.hash.clone(), (cid.clone(), compressed));
+        let checkpoint = self.checkpoints.entry(segment.ledger).or_default();
+        checkpoint
+            .bucket_refs
+            .extend(segment.bucket_refs.iter().cloned());
+        if segment.closed_at.is_some() {
+            checkpoint.closed_at = segment.closed_at;
+        }
+        checkpoint.files.insert(
+            segment.filename.clone(),
+            BackupFileEntry {
+                cid,
+                sha256: segment.hash.clone(),
+                segment_type: segment.segment_type.clone(),
+                compressed,
+                uploaded_at: Utc::now(),
+                size: Some(segment.size),
+                modified: segment.modified,
+                superseded_at: None,
+            },
+        );
+    }
+
+    /// Size, modification time and hash of recorded files, so an archive
+    /// scan can skip hashing files that have not changed
+    pub(crate) fn known_files(&self) -> KnownFiles {
+        self.checkpoints
+            .values()
+            .flat_map(|c| c.files.iter())
+            .filter_map(|(name, f)| {
+                let known = KnownFile {
+                    size: f.size?,
+                    modified: f.modified?,
+                    sha256: f.sha256.clone(),
+                };
+                Some((name.clone(), known))
+            })
+            .collect()
+    }
+
+    /// Total number of recorded files
+    pub fn file_count(&self) -> usize {
+        self.checkpoints.values().map(|c| c.files.len()).sum()
+    }
+
+    /// Highest checkpoint with at least one recorded file
+    pub fn latest_checkpoint(&self) -> Option<u64> {
+        self.checkpoints.keys().next_back().copied()
+    }
+
+    pub(crate) fn rebuild_index(&mut self) {
+        self.by_hash = self
+            .checkpoints
+            .values()
+            .flat_map(|c| c.files.values())
+            .filter(|f| f.is_active())
+            .map(|f| (f.sha256.clone(), (f.cid.clone(), f.compressed)))
+            .collect();
+    }
+
+    /// Serialize as gzipped JSON
+    pub fn to_gzip_json(&self) -> Result<Vec<u8>> {
+        let json = serde_json::to_vec(self).context("Failed to serialize backup manifest")?;
+        compress_data(&json)
+    }
+
+    /// Parse a manifest produced by [`BackupManifest::to_gzip_json`]
+    pub fn from_gzip_json(data: &[u8]) -> Result<Self> {
+        let mut json = Vec::new();
+        GzDecoder::new(data)
+            .read_to_end(&mut json)
+            .context("Backup manifest is not valid gzip")?;
+        let mut manifest: Self =
+            serde_json::from_slice(&json).context("Backup manifest is not valid JSON")?;
+        if manifest.version > MANIFEST_VERSION {
+            anyhow::bail!(
+                "Backup manifest version {} is newer than supported version {}",
+                manifest.version,
+                MANIFEST_VERSION
+            );
+        }
+        manifest.rebuild_index();
+        Ok(manifest)
+    }
+}
+
+/// Where the manifest is persisted between runs
+#[async_trait]
+pub trait ManifestStore: Send + Sync {
+    /// Load the manifest, returning an empty one if none was saved yet
+    async fn load(&self) -> Result<BackupManifest>;
+
+    /// Persist the manifest
+    async fn save(&self, manifest: &BackupManifest) -> Result<()>;
+}
+
+/// Stores the manifest gzipped in shard ConfigMaps, indexed by the ConfigMap
+/// `name`.
+///
+/// The index records the active slot, its shard count and the SHA-256 of the
+/// gzipped manifest. Index ConfigMaps that still hold the whole manifest in
+/// `binaryData` are read as before and converted on the next save.
+pub struct ConfigMapManifestStore {
+    client: Client,
+    namespace: String,
+    name: String,
+}
+
+impl ConfigMapManifestStore {
+    pub fn new(client: Client, namespace: String, name: String) -> Self {
+        Self {
+            client,
+            namespace,
+            name,
+        }
+    }
+
+    async fn apply(&self, api: &Api<ConfigMap>, cm: ConfigMap) -> Result<()> {
+        let name = cm.metadata.name.clone().unwrap_or_default();
+        api.patch(
+            &name,
+            &PatchParams::apply("stellar-operator").force(),
+            &Patch::Apply(&cm),
+        )
+        .await
+        .with_context(|| format!("Failed to write backup manifest ConfigMap {name}"))?;
+        Ok(())
+    }
+
+    fn metadata(&self, name: String, component: &str) -> kube::api::ObjectMeta {
+        kube::api::ObjectMeta {
+            name: Some(name),
+            namespace: Some(self.namespace.clone()),
+            labels: Some(BTreeMap::from([
+                ("app".to_string(), "stellar-operator".to_string()),
+                ("component".to_string(), component.to_string()),
+            ])),
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl ManifestStore for ConfigMapManifestStore {
+    async fn load(&self) -> Result<BackupManifest> {
+        let api: Api<ConfigMap> = Api::namespaced(self.client.clone(), &self.namespace);
+        let Some(cm) = api
+            .get_opt(&self.name)
+            .await
+            .context("Failed to read backup manifest ConfigMap")?
+        else {
+            debug!("No backup manifest ConfigMap {} yet", self.name);
+            return Ok(BackupManifest::default());
+        };
+
+        if let Some(data) = cm
+            .binary_data
+            .as_ref()
+            .and_then(|d| d.get(MANIFEST_DATA_KEY))
+        {
+            return BackupManifest::from_gzip_json(&data.0);
+        }
+        let index = cm.data.unwrap_or_default();
+        let (Some(slot), Some(count), Some(sha256)) = (
+            index.get("slot"),
+            index.get("shards").and_then(|n| n.parse::<usize>().ok()),
+            index.get("sha256"),
+        ) else {
+            return Ok(BackupManifest::default());
+        };
+
+        let mut shards = Vec::with_capacity(count);
+        for i in 0..count {
+            let name = manifest_shard_name(&self.name, slot, i);
+            let shard = api
+                .get(&name)
+                .await
+                .with_context(|| format!("Failed to read backup manifest shard {name}"))?;
+            let data = shard
+                .binary_data
+                .and_then(|mut d| d.remove(MANIFEST_DATA_KEY))
+                .with_context(|| format!("Backup manifest shard {name} has no data"))?;
+            shards.push(data.0);
+        }
+        join_manifest(shards, sha256)
+    }
+
+    async fn save(&self, manifest: &BackupManifest) -> Result<()> {
+        let api: Api<ConfigMap> = Api::namespaced(self.client.clone(), &self.namespace);
+        let data = manifest.to_gzip_json()?;
+        let shards = split_manifest(&data)?;
+
+        // Write the slot the index does not point at, then switch to it
+        let current = api
+            .get_opt(&self.name)
+            .await
+            .context("Failed to read backup manifest ConfigMap")?
+            .and_then(|cm| cm.data)
+            .and_then(|mut d| d.remove("slot"));
+        let slot = if current.as_deref() == Some(MANIFEST_SLOTS[0]) {
+            MANIFEST_SLOTS[1]
+        } else {
+            MANIFEST_SLOTS[0]
+        };
+
+        for (i, shard) in shards.iter().enumerate() {
+            let cm = ConfigMap {
+                metadata: self.metadata(
+                    manifest_shard_name(&self.name, slot, i),
+                    "backup-manifest-shard",
+                ),
+                binary_data: Some(BTreeMap::from([(
+                    MANIFEST_DATA_KEY.to_string(),
+                    k8s_openapi::ByteString(shard.to_vec()),
+                )])),
+                ..Default::default()
+            };
+            self.apply(&api, cm).await?;
+        }
+
+        let index = ConfigMap {
+            metadata: self.metadata(self.name.clone(), "backup-manifest"),
+            data: Some(BTreeMap::from([
+                ("files".to_string(), manifest.file_count().to_string()),
+                (
+                    "latestCheckpoint".to_string(),
+                    manifest
+                        .latest_checkpoint()
+                        .map(|c| c.to_string())
+                        .unwrap_or_default(),
+                ),
+                ("slot".to_string(), slot.to_string()),
+                ("shards".to_string(), shards.len().to_string()),
+                ("sha256".to_string(), format!("{:x}", Sha256::digest(&data))),
+            ])),
+            // Server-side apply drops the inline manifest of older operators
+            binary_data: None,
+            ..Default::default()
+        };
+        self.apply(&api, index).await
+    }
+}
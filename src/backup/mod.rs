@@ -1,15 +1,30 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+pub mod archive;
+pub mod manifest;
 pub mod providers;
+pub mod restore;
+pub mod retention;
 pub mod scheduler;
+pub mod worker;
 
+#[cfg(test)]
+mod archive_test;
+#[cfg(test)]
+mod providers_test;
+#[cfg(test)]
+mod restore_test;
+#[cfg(test)]
+mod retention_test;
 #[cfg(test)]
 mod scheduler_test;
+#[cfg(test)]
+mod worker_test;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DecentralizedBackupConfig {
     /// Enable decentralized backups
@@ -27,6 +42,9 @@ pub struct DecentralizedBackupConfig {
     pub compression_enabled: bool,
     /// Retention policy (optional)
     pub retention: Option<RetentionPolicy>,
+    /// History archive directory, relative to the node's data volume
+    #[serde(default = "default_archive_path")]
+    pub archive_path: String,
 }
 
 fn default_schedule() -> String {
@@ -41,7 +59,11 @@ fn default_compression() -> bool {
     true
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+fn default_archive_path() -> String {
+    "history".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum StorageProvider {
     Arweave {
@@ -52,6 +74,7 @@ pub enum StorageProvider {
         gateway: String,
         /// Tags to add to transactions
         #[serde(default)]
+        #[schemars(with = "Vec<Vec<String>>")]
         tags: Vec<(String, String)>,
     },
     IPFS {
@@ -68,20 +91,85 @@ pub enum StorageProvider {
         /// Storage deal parameters
         deal_params: FilecoinDealParams,
     },
+    S3 {
+        /// Bucket name
+        bucket: String,
+        /// Region used for request signing (default: us-east-1)
+        #[serde(default = "default_s3_region")]
+        region: String,
+        /// Custom endpoint for MinIO or GCS interop (default: AWS S3)
+        #[serde(default)]
+        endpoint: Option<String>,
+        /// Key prefix for backup objects
+        #[serde(default)]
+        prefix: String,
+        /// Secret holding `accessKeyId` and `secretAccessKey`
+        credentials_secret: String,
+        /// Use path-style bucket addressing (required by MinIO)
+        #[serde(default)]
+        force_path_style: bool,
+        /// Multipart upload part size in bytes (at least 5 MiB)
+        #[serde(default = "default_s3_part_size")]
+        part_size: u64,
+    },
+    Filesystem {
+        /// Directory to store backups in, typically a mounted PVC
+        path: String,
+        /// PVC the backup Job mounts read-write at `path` (required for
+        /// managed backups)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        claim_name: Option<String>,
+    },
+}
+
+impl StorageProvider {
+    /// Provider name as used in the `type` field
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Arweave { .. } => "arweave",
+            Self::IPFS { .. } => "ipfs",
+            Self::Filecoin { .. } => "filecoin",
+            Self::S3 { .. } => "s3",
+            Self::Filesystem { .. } => "filesystem",
+        }
+    }
 }
 
 fn default_arweave_gateway() -> String {
     "https://arweave.net".to_string()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_s3_part_size() -> u64 {
+    providers::s3::DEFAULT_PART_SIZE as u64
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PinningService {
     pub service_type: PinningServiceType,
+    /// Secret holding the pinning service token under `apiKey`
     pub api_key_secret: String,
+    /// Pinning Service API endpoint (defaults for Pinata and Web3Storage)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+}
+
+impl PinningService {
+    /// Endpoint pins are posted to, if one is configured or known
+    pub fn service_url(&self) -> Option<String> {
+        self.endpoint.clone().or_else(|| match self.service_type {
+            PinningServiceType::Pinata => Some("https://api.pinata.cloud/psa/pins".to_string()),
+            PinningServiceType::Web3Storage => Some("https://api.web3.storage/pins".to_string()),
+            PinningServiceType::Infura => None,
+        })
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum PinningServiceType {
     Pinata,
@@ -89,7 +177,7 @@ pub enum PinningServiceType {
     Infura,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FilecoinDealParams {
     /// Price per epoch in attoFIL
@@ -101,7 +189,7 @@ pub struct FilecoinDealParams {
     pub verified: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RetentionPolicy {
     /// Keep backups for this many days (0 = forever)
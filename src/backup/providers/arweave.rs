@@ -1,4 +1,4 @@
-use super::{StorageProviderTrait, UploadMetadata};
+use super::{RemovalOutcome, StorageProviderTrait, StoredObject, UploadMetadata};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use base64::Engine;
@@ -99,4 +99,86 @@ impl StorageProviderTrait for ArweaveProvider {
 
         Ok(hash == expected_hash)
     }
+
+    async fn download(&self, cid: &str) -> Result<Vec<u8>> {
+        let data = self
+            .client
+            .get(format!("{}/{}", self.gateway, cid))
+            .send()
+            .await
+            .context("Failed to fetch Arweave transaction data")?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        Ok(data.to_vec())
+    }
+
+    async fn list(&self) -> Result<Vec<StoredObject>> {
+        let mut objects = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let after = cursor
+                .as_deref()
+                .map(|c| format!(", after: \"{c}\""))
+                .unwrap_or_default();
+            let query = json!({
+                "query": format!(
+                    "{{ transactions(first: 100{after}, tags: [{{name: \"App-Name\", values: [\"Stellar-Archive-Backup\"]}}]) {{ pageInfo {{ hasNextPage }} edges {{ cursor node {{ id tags {{ name value }} }} }} }} }}"
+                )
+            });
+
+            let response: Value = self
+                .client
+                .post(format!("{}/graphql", self.gateway))
+                .json(&query)
+                .send()
+                .await
+                .context("Failed to query Arweave transactions")?
+                .json()
+                .await?;
+
+            let transactions = &response["data"]["transactions"];
+            let edges = transactions["edges"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default();
+            for edge in &edges {
+                let node = &edge["node"];
+                let Some(id) = node["id"].as_str() else {
+                    continue;
+                };
+                objects.push(StoredObject {
+                    cid: id.to_string(),
+                    name: node["tags"].as_array().and_then(|tags| {
+                        tags.iter()
+                            .find(|t| t["name"] == "File-Name")
+                            .and_then(|t| t["value"].as_str())
+                            .map(str::to_string)
+                    }),
+                });
+            }
+
+            cursor = edges
+                .last()
+                .and_then(|e| e["cursor"].as_str())
+                .map(str::to_string);
+            if !transactions["pageInfo"]["hasNextPage"]
+                .as_bool()
+                .unwrap_or(false)
+                || cursor.is_none()
+            {
+                break;
+            }
+        }
+
+        Ok(objects)
+    }
+
+    async fn delete(&self, _cid: &str) -> Result<RemovalOutcome> {
+        // Arweave storage is permanent; retention only marks entries as
+        // superseded in the backup manifest
+        Ok(RemovalOutcome::Permanent)
+    }
 }
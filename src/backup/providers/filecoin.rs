@@ -1,4 +1,4 @@
-use super::{StorageProviderTrait, UploadMetadata};
+use super::{RemovalOutcome, StorageProviderTrait, StoredObject, UploadMetadata};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use reqwest::Client;
@@ -78,4 +78,51 @@ impl StorageProviderTrait for FilecoinProvider {
 
         Ok(hash == expected_hash)
     }
+
+    async fn download(&self, cid: &str) -> Result<Vec<u8>> {
+        let data = self
+            .client
+            .post(format!("{}/api/v0/client/retrieve", self.lotus_api))
+            .json(&serde_json::json!({ "cid": cid }))
+            .send()
+            .await
+            .context("Failed to retrieve data from Filecoin")?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        Ok(data.to_vec())
+    }
+
+    async fn list(&self) -> Result<Vec<StoredObject>> {
+        let response: Value = self
+            .client
+            .post(format!("{}/api/v0/client/list-imports", self.lotus_api))
+            .send()
+            .await
+            .context("Failed to list Filecoin imports")?
+            .json()
+            .await?;
+
+        Ok(response
+            .as_array()
+            .map(|imports| {
+                imports
+                    .iter()
+                    .filter_map(|i| {
+                        Some(StoredObject {
+                            cid: i["Root"]["/"].as_str()?.to_string(),
+                            name: i["FilePath"].as_str().map(str::to_string),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn delete(&self, _cid: &str) -> Result<RemovalOutcome> {
+        // Storage deals cannot be cancelled; they are simply not renewed and
+        // the data is dropped by the miner when the deal expires
+        Ok(RemovalOutcome::Expiring)
+    }
 }
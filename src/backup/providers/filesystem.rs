@@ -0,0 +1,111 @@
+//! Local directory / PVC storage
+//!
+//! Objects are stored content-addressed as `<root>/objects/<aa>/<sha256>`,
+//! where `<sha256>` is the hash of the stored bytes and doubles as the CID.
+
+use super::{RemovalOutcome, StorageProviderTrait, StoredObject, UploadMetadata};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+pub struct FilesystemProvider {
+    root: PathBuf,
+}
+
+impl FilesystemProvider {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn objects_dir(&self) -> PathBuf {
+        self.root.join("objects")
+    }
+
+    fn object_path(&self, cid: &str) -> Result<PathBuf> {
+        if cid.len() < 3 || !cid.bytes().all(|b| b.is_ascii_hexdigit()) {
+            bail!("Invalid filesystem backup object id {cid:?}");
+        }
+        Ok(self.objects_dir().join(&cid[0..2]).join(cid))
+    }
+}
+
+async fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let tmp = path.with_extension("partial");
+    tokio::fs::write(&tmp, data)
+        .await
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    tokio::fs::rename(&tmp, path)
+        .await
+        .with_context(|| format!("Failed to move {} into place", path.display()))?;
+    Ok(())
+}
+
+#[async_trait]
+impl StorageProviderTrait for FilesystemProvider {
+    async fn upload(&self, data: Vec<u8>, _metadata: UploadMetadata) -> Result<String> {
+        let cid = format!("{:x}", Sha256::digest(&data));
+        let path = self.object_path(&cid)?;
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            write_atomic(&path, &data).await?;
+        }
+        Ok(cid)
+    }
+
+    async fn exists(&self, content_hash: &str) -> Result<bool> {
+        Ok(tokio::fs::try_exists(self.object_path(content_hash)?).await?)
+    }
+
+    async fn verify(&self, cid: &str, expected_hash: &str) -> Result<bool> {
+        let data = self.download(cid).await?;
+        Ok(format!("{:x}", Sha256::digest(&data)) == expected_hash)
+    }
+
+    async fn download(&self, cid: &str) -> Result<Vec<u8>> {
+        let path = self.object_path(cid)?;
+        tokio::fs::read(&path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))
+    }
+
+    async fn list(&self) -> Result<Vec<StoredObject>> {
+        let mut objects = Vec::new();
+        let mut shards = match tokio::fs::read_dir(self.objects_dir()).await {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(objects),
+            Err(e) => return Err(e.into()),
+        };
+
+        while let Some(shard) = shards.next_entry().await? {
+            if !shard.file_type().await?.is_dir() {
+                continue;
+            }
+            let mut entries = tokio::fs::read_dir(shard.path()).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if name.ends_with(".partial") {
+                    continue;
+                }
+                objects.push(StoredObject {
+                    cid: name,
+                    name: None,
+                });
+            }
+        }
+
+        Ok(objects)
+    }
+
+    async fn delete(&self, cid: &str) -> Result<RemovalOutcome> {
+        match tokio::fs::remove_file(self.object_path(cid)?).await {
+            Ok(()) => Ok(RemovalOutcome::Removed),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(RemovalOutcome::Removed),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::{StorageProviderTrait, UploadMetadata};
+use super::{RemovalOutcome, StorageProviderTrait, StoredObject, UploadMetadata};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use reqwest::Client;
@@ -90,6 +90,67 @@ impl StorageProviderTrait for IPFSProvider {
 
         Ok(hash == expected_hash)
     }
+
+    async fn download(&self, cid: &str) -> Result<Vec<u8>> {
+        let data = self
+            .client
+            .post(format!("{}/api/v0/cat?arg={}", self.api_url, cid))
+            .send()
+            .await
+            .context("Failed to fetch from IPFS")?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        Ok(data.to_vec())
+    }
+
+    async fn list(&self) -> Result<Vec<StoredObject>> {
+        let pins: Value = self
+            .client
+            .post(format!("{}/api/v0/pin/ls?type=recursive", self.api_url))
+            .send()
+            .await
+            .context("Failed to list IPFS pins")?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(pins["Keys"]
+            .as_object()
+            .map(|keys| {
+                keys.keys()
+                    .map(|cid| StoredObject {
+                        cid: cid.clone(),
+                        name: None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn delete(&self, cid: &str) -> Result<RemovalOutcome> {
+        let response = self
+            .client
+            .post(format!("{}/api/v0/pin/rm?arg={}", self.api_url, cid))
+            .send()
+            .await
+            .context("Failed to unpin from IPFS")?;
+
+        if !response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            // Content that is no longer pinned is already released
+            if !body.contains("not pinned") {
+                anyhow::bail!("IPFS pin/rm for {} failed: {}", cid, body);
+            }
+        }
+
+        if let Some(ref pinning) = self.pinning_service {
+            self.unpin_from_service(cid, pinning).await?;
+        }
+
+        Ok(RemovalOutcome::Removed)
+    }
 }
 
 impl IPFSProvider {
@@ -108,7 +169,8 @@ impl IPFSProvider {
             }
         });
 
-        self.client
+        let response = self
+            .client
             .post(&config.service_url)
             .bearer_auth(&config.api_key)
             .json(&pin_data)
@@ -116,6 +178,81 @@ impl IPFSProvider {
             .await
             .context("Failed to pin to service")?;
 
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!(
+                "Pinning service refused to pin {}: HTTP {}: {}",
+                cid,
+                status,
+                body
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Remove every pin of `cid` from the remote pinning service.
+    ///
+    /// The Pinning Service API deletes by request id, so the pins are looked
+    /// up first (`GET <service_url>?cid=<cid>`), then each is deleted
+    /// (`DELETE <service_url>/<requestid>`).
+    async fn unpin_from_service(&self, cid: &str, config: &PinningConfig) -> Result<()> {
+        let service_url = config.service_url.trim_end_matches('/');
+        let response = self
+            .client
+            .get(service_url)
+            .query(&[("cid", cid)])
+            .bearer_auth(&config.api_key)
+            .send()
+            .await
+            .context("Failed to look up pins on service")?;
+
+        // No pins for the CID: nothing left to release
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Pinning service refused to list pins of {}: HTTP {}",
+                cid,
+                response.status()
+            );
+        }
+        let pins: Value = response
+            .json()
+            .await
+            .context("Invalid pin list from pinning service")?;
+        let request_ids = pins["results"]
+            .as_array()
+            .context("Missing results in pin list")?
+            .iter()
+            .map(|pin| {
+                pin["requestid"]
+                    .as_str()
+                    .context("Missing requestid in pin list")
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for request_id in request_ids {
+            let response = self
+                .client
+                .delete(format!("{service_url}/{request_id}"))
+                .bearer_auth(&config.api_key)
+                .send()
+                .await
+                .context("Failed to unpin from service")?;
+
+            if !response.status().is_success() {
+                anyhow::bail!(
+                    "Pinning service refused to unpin {} (request {}): HTTP {}",
+                    cid,
+                    request_id,
+                    response.status()
+                );
+            }
+        }
+
         Ok(())
     }
 }
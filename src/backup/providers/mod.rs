@@ -1,6 +1,8 @@
 pub mod arweave;
 pub mod filecoin;
+pub mod filesystem;
 pub mod ipfs;
+pub mod s3;
 
 use anyhow::Result;
 use async_trait::async_trait;
@@ -15,6 +17,34 @@ pub trait StorageProviderTrait: Send + Sync {
 
     /// Verify uploaded content
     async fn verify(&self, cid: &str, expected_hash: &str) -> Result<bool>;
+
+    /// Download previously uploaded content by its identifier
+    async fn download(&self, cid: &str) -> Result<Vec<u8>>;
+
+    /// List content currently held for the operator
+    async fn list(&self) -> Result<Vec<StoredObject>>;
+
+    /// Release content that is no longer needed (delete or unpin)
+    async fn delete(&self, cid: &str) -> Result<RemovalOutcome>;
+}
+
+/// Content held by a storage provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredObject {
+    pub cid: String,
+    /// File name recorded at upload, when the provider keeps it
+    pub name: Option<String>,
+}
+
+/// What releasing content actually did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemovalOutcome {
+    /// The content was deleted or unpinned
+    Removed,
+    /// The content stays available until its storage deal expires and will not be renewed
+    Expiring,
+    /// The content is permanent and cannot be removed
+    Permanent,
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,564 @@
+//! S3-compatible object storage (AWS S3, MinIO, GCS XML interop)
+//!
+//! Requests are signed with AWS Signature Version 4. Objects are stored under
+//! `<prefix><sha256>` so identical content is written once, and uploads larger
+//! than the part size use the multipart upload API.
+
+use super::{RemovalOutcome, StorageProviderTrait, StoredObject, UploadMetadata};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Method, Url};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use tracing::{debug, warn};
+
+/// Default multipart part size (S3 requires at least 5 MiB for all but the last part)
+pub const DEFAULT_PART_SIZE: usize = 8 * 1024 * 1024;
+/// Smallest part size S3 accepts for all but the last part
+pub const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+
+/// Static credentials for SigV4 signing
+#[derive(Clone)]
+pub struct S3Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+/// Connection settings for an S3-compatible bucket
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    pub bucket: String,
+    pub region: String,
+    /// Custom endpoint, e.g. `http://minio:9000` or `https://storage.googleapis.com`
+    pub endpoint: Option<String>,
+    /// Key prefix for backup objects
+    pub prefix: String,
+    /// Address the bucket as `<endpoint>/<bucket>` instead of `<bucket>.<endpoint>`
+    pub force_path_style: bool,
+    /// Multipart part size in bytes
+    pub part_size: usize,
+}
+
+pub struct S3Provider {
+    client: Client,
+    config: S3Config,
+    signer: SigV4Signer,
+    base_url: Url,
+}
+
+impl S3Provider {
+    pub fn new(config: S3Config, credentials: S3Credentials) -> Result<Self> {
+        let base_url = bucket_url(&config)?;
+        Ok(Self {
+            client: Client::new(),
+            signer: SigV4Signer::new(credentials, config.region.clone(), "s3".to_string()),
+            config,
+            base_url,
+        })
+    }
+
+    fn object_key(&self, content_hash: &str) -> String {
+        format!("{}{}", self.config.prefix, content_hash)
+    }
+
+    fn object_url(&self, key: &str, query: &[(&str, &str)]) -> Url {
+        let mut url = self.base_url.clone();
+        let path = format!(
+            "{}/{}",
+            url.path().trim_end_matches('/'),
+            uri_encode(key, false)
+        );
+        url.set_path(&path);
+        set_query(&mut url, query);
+        url
+    }
+
+    /// Send a signed request
+    async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        extra_headers: BTreeMap<String, String>,
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response> {
+        let payload_hash = hex_sha256(&body);
+        let now = Utc::now();
+
+        let mut headers = extra_headers;
+        headers.insert("host".to_string(), host_header(&url)?);
+        headers.insert("x-amz-content-sha256".to_string(), payload_hash.clone());
+        headers.insert("x-amz-date".to_string(), amz_date(now));
+
+        let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        let authorization = self.signer.authorization(
+            method.as_str(),
+            url.path(),
+            &query,
+            &headers,
+            &payload_hash,
+            now,
+        );
+
+        let mut request = self.client.request(method, url);
+        for (name, value) in headers.iter().filter(|(n, _)| n.as_str() != "host") {
+            request = request.header(name, value);
+        }
+        let response = request
+            .header("authorization", authorization)
+            .body(body)
+            .send()
+            .await
+            .context("S3 request failed")?;
+
+        Ok(response)
+    }
+
+    async fn expect_success(
+        response: reqwest::Response,
+        action: &str,
+    ) -> Result<reqwest::Response> {
+        if response.status().is_success() {
+            return Ok(response);
+        }
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        bail!("S3 {action} failed with HTTP {status}: {body}")
+    }
+
+    async fn put_object(&self, key: &str, data: Vec<u8>, metadata: &UploadMetadata) -> Result<()> {
+        let response = self
+            .send(
+                Method::PUT,
+                self.object_url(key, &[]),
+                object_headers(metadata),
+                data,
+            )
+            .await?;
+        Self::expect_success(response, "PutObject").await?;
+        Ok(())
+    }
+
+    async fn multipart_upload(
+        &self,
+        key: &str,
+        data: Vec<u8>,
+        metadata: &UploadMetadata,
+    ) -> Result<()> {
+        let response = self
+            .send(
+                Method::POST,
+                self.object_url(key, &[("uploads", "")]),
+                object_headers(metadata),
+                Vec::new(),
+            )
+            .await?;
+        let body = Self::expect_success(response, "CreateMultipartUpload")
+            .await?
+            .text()
+            .await?;
+        let upload_id = xml_values(&body, "UploadId")
+            .into_iter()
+            .next()
+            .context("Missing UploadId in CreateMultipartUpload response")?;
+
+        match self.upload_parts(key, &upload_id, &data).await {
+            Ok(etags) => self.complete_multipart(key, &upload_id, &etags).await,
+            Err(e) => {
+                let abort = self
+                    .send(
+                        Method::DELETE,
+                        self.object_url(key, &[("uploadId", &upload_id)]),
+                        BTreeMap::new(),
+                        Vec::new(),
+                    )
+                    .await;
+                if let Err(abort_err) = abort {
+                    warn!(
+                        "Failed to abort multipart upload {}: {}",
+                        upload_id, abort_err
+                    );
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn upload_parts(&self, key: &str, upload_id: &str, data: &[u8]) -> Result<Vec<String>> {
+        let mut etags = Vec::new();
+        for (index, chunk) in data.chunks(self.config.part_size.max(1)).enumerate() {
+            let part_number = (index + 1).to_string();
+            debug!("Uploading part {} of {}", part_number, key);
+
+            let response = self
+                .send(
+                    Method::PUT,
+                    self.object_url(
+                        key,
+                        &[("partNumber", &part_number), ("uploadId", upload_id)],
+                    ),
+                    BTreeMap::new(),
+                    chunk.to_vec(),
+                )
+                .await?;
+            let response = Self::expect_success(response, "UploadPart").await?;
+            let etag = response
+                .headers()
+                .get("etag")
+                .and_then(|v| v.to_str().ok())
+                .context("Missing ETag in UploadPart response")?
+                .to_string();
+            etags.push(etag);
+        }
+        Ok(etags)
+    }
+
+    async fn complete_multipart(&self, key: &str, upload_id: &str, etags: &[String]) -> Result<()> {
+        let parts: String = etags
+            .iter()
+            .enumerate()
+            .map(|(i, etag)| {
+                format!(
+                    "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+                    i + 1,
+                    xml_escape(etag)
+                )
+            })
+            .collect();
+        let body = format!("<CompleteMultipartUpload>{parts}</CompleteMultipartUpload>");
+
+        let response = self
+            .send(
+                Method::POST,
+                self.object_url(key, &[("uploadId", upload_id)]),
+                BTreeMap::from([("content-type".to_string(), "application/xml".to_string())]),
+                body.into_bytes(),
+            )
+            .await?;
+        let body = Self::expect_success(response, "CompleteMultipartUpload")
+            .await?
+            .text()
+            .await?;
+        // S3 may report a failure with HTTP 200 and an <Error> body
+        if body.contains("<Error>") {
+            bail!("S3 CompleteMultipartUpload failed: {body}");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StorageProviderTrait for S3Provider {
+    async fn upload(&self, data: Vec<u8>, metadata: UploadMetadata) -> Result<String> {
+        let key = self.object_key(&hex_sha256(&data));
+
+        if self.exists(&hex_sha256(&data)).await.unwrap_or(false) {
+            debug!("S3 object {} already present", key);
+            return Ok(key);
+        }
+
+        if data.len() > self.config.part_size {
+            self.multipart_upload(&key, data, &metadata).await?;
+        } else {
+            self.put_object(&key, data, &metadata).await?;
+        }
+
+        Ok(key)
+    }
+
+    async fn exists(&self, content_hash: &str) -> Result<bool> {
+        let response = self
+            .send(
+                Method::HEAD,
+                self.object_url(&self.object_key(content_hash), &[]),
+                BTreeMap::new(),
+                Vec::new(),
+            )
+            .await?;
+        match response.status() {
+            s if s.is_success() => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            s => bail!("S3 HeadObject failed with HTTP {s}"),
+        }
+    }
+
+    async fn verify(&self, cid: &str, expected_hash: &str) -> Result<bool> {
+        let data = self.download(cid).await?;
+        Ok(hex_sha256(&data) == expected_hash)
+    }
+
+    async fn download(&self, cid: &str) -> Result<Vec<u8>> {
+        let response = self
+            .send(
+                Method::GET,
+                self.object_url(cid, &[]),
+                BTreeMap::new(),
+                Vec::new(),
+            )
+            .await?;
+        let data = Self::expect_success(response, "GetObject")
+            .await?
+            .bytes()
+            .await?;
+        Ok(data.to_vec())
+    }
+
+    async fn list(&self) -> Result<Vec<StoredObject>> {
+        let mut objects = Vec::new();
+        let mut token: Option<String> = None;
+
+        loop {
+            let mut url = self.base_url.clone();
+            let mut query = vec![("list-type", "2"), ("prefix", self.config.prefix.as_str())];
+            if let Some(t) = &token {
+                query.push(("continuation-token", t));
+            }
+            set_query(&mut url, &query);
+
+            let response = self
+                .send(Method::GET, url, BTreeMap::new(), Vec::new())
+                .await?;
+            let body = Self::expect_success(response, "ListObjectsV2")
+                .await?
+                .text()
+                .await?;
+
+            objects.extend(
+                xml_values(&body, "Key")
+                    .into_iter()
+                    .map(|key| StoredObject {
+                        cid: key,
+                        name: None,
+                    }),
+            );
+
+            token = xml_values(&body, "NextContinuationToken")
+                .into_iter()
+                .next();
+            let truncated = xml_values(&body, "IsTruncated")
+                .first()
+                .is_some_and(|v| v == "true");
+            if !truncated || token.is_none() {
+                break;
+            }
+        }
+
+        Ok(objects)
+    }
+
+    async fn delete(&self, cid: &str) -> Result<RemovalOutcome> {
+        let response = self
+            .send(
+                Method::DELETE,
+                self.object_url(cid, &[]),
+                BTreeMap::new(),
+                Vec::new(),
+            )
+            .await?;
+        // DeleteObject returns 204 whether or not the key existed
+        Self::expect_success(response, "DeleteObject").await?;
+        Ok(RemovalOutcome::Removed)
+    }
+}
+
+/// Base URL addressing the bucket
+fn bucket_url(config: &S3Config) -> Result<Url> {
+    let url = match &config.endpoint {
+        None => format!(
+            "https://{}.s3.{}.amazonaws.com/",
+            config.bucket, config.region
+        ),
+        Some(endpoint) if config.force_path_style => {
+            format!("{}/{}/", endpoint.trim_end_matches('/'), config.bucket)
+        }
+        Some(endpoint) => {
+            let mut url = Url::parse(endpoint).context("Invalid S3 endpoint")?;
+            let host = url.host_str().context("S3 endpoint has no host")?;
+            let host = format!("{}.{}", config.bucket, host);
+            url.set_host(Some(&host))
+                .context("Invalid S3 bucket host")?;
+            url.to_string()
+        }
+    };
+    Url::parse(&url).context("Invalid S3 bucket URL")
+}
+
+/// Set the query string, encoded the way SigV4 canonicalizes it.
+///
+/// `Url::query_pairs_mut` uses form encoding (`+` for spaces, `~` escaped),
+/// which S3 would decode differently from the signed canonical query.
+fn set_query(url: &mut Url, query: &[(&str, &str)]) {
+    if query.is_empty() {
+        url.set_query(None);
+        return;
+    }
+    let encoded = query
+        .iter()
+        .map(|(k, v)| format!("{}={}", uri_encode(k, true), uri_encode(v, true)))
+        .collect::<Vec<_>>()
+        .join("&");
+    url.set_query(Some(&encoded));
+}
+
+fn host_header(url: &Url) -> Result<String> {
+    let host = url.host_str().context("S3 URL has no host")?;
+    Ok(match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    })
+}
+
+fn object_headers(metadata: &UploadMetadata) -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("content-type".to_string(), metadata.content_type.clone()),
+        ("x-amz-meta-sha256".to_string(), metadata.sha256.clone()),
+        (
+            "x-amz-meta-filename".to_string(),
+            uri_encode(&metadata.filename, true),
+        ),
+    ])
+}
+
+fn hex_sha256(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn amz_date(now: DateTime<Utc>) -> String {
+    now.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// URI-encode per SigV4 rules; `/` is kept unless `encode_slash` is set
+pub(crate) fn uri_encode(input: &str, encode_slash: bool) -> String {
+    let mut out = String::with_capacity(input.len());
+    for b in input.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            }
+            b'/' if !encode_slash => out.push('/'),
+            _ => out.push_str(&format!("%{b:02X}")),
+        }
+    }
+    out
+}
+
+/// Text content of every `<tag>...</tag>` element in an XML document
+fn xml_values(body: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let mut values = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        let Some(end) = after.find(&close) else {
+            break;
+        };
+        values.push(xml_unescape(&after[..end]));
+        rest = &after[end + close.len()..];
+    }
+    values
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// AWS Signature Version 4 signer
+#[derive(Clone)]
+pub struct SigV4Signer {
+    credentials: S3Credentials,
+    region: String,
+    service: String,
+}
+
+impl SigV4Signer {
+    pub fn new(credentials: S3Credentials, region: String, service: String) -> Self {
+        Self {
+            credentials,
+            region,
+            service,
+        }
+    }
+
+    /// Build the `Authorization` header value for a request
+    ///
+    /// `path` must already be URI-encoded (as it appears in the URL), `query`
+    /// holds decoded pairs and `headers` the lowercase headers to sign.
+    pub fn authorization(
+        &self,
+        method: &str,
+        path: &str,
+        query: &[(String, String)],
+        headers: &BTreeMap<String, String>,
+        payload_hash: &str,
+        now: DateTime<Utc>,
+    ) -> String {
+        let date = now.format("%Y%m%d").to_string();
+        let scope = format!("{date}/{}/{}/aws4_request", self.region, self.service);
+
+        let mut query: Vec<(String, String)> = query
+            .iter()
+            .map(|(k, v)| (uri_encode(k, true), uri_encode(v, true)))
+            .collect();
+        query.sort();
+        let canonical_query = query
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(k, v)| format!("{}:{}\n", k.to_lowercase(), v.trim()))
+            .collect();
+        let signed_headers = headers
+            .keys()
+            .map(|k| k.to_lowercase())
+            .collect::<Vec<_>>()
+            .join(";");
+
+        let canonical_request = format!(
+            "{method}\n{path}\n{canonical_query}\n{canonical_headers}\n{signed_headers}\n{payload_hash}"
+        );
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{scope}\n{}",
+            amz_date(now),
+            hex_sha256(canonical_request.as_bytes())
+        );
+
+        let k_date = hmac_sha256(
+            format!("AWS4{}", self.credentials.secret_access_key).as_bytes(),
+            date.as_bytes(),
+        );
+        let k_region = hmac_sha256(&k_date, self.region.as_bytes());
+        let k_service = hmac_sha256(&k_region, self.service.as_bytes());
+        let k_signing = hmac_sha256(&k_service, b"aws4_request");
+        let signature: String = hmac_sha256(&k_signing, string_to_sign.as_bytes())
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            self.credentials.access_key_id
+        )
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
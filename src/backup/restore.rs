@@ -0,0 +1,165 @@
+//! Rebuild a history archive directory from decentralized storage
+//!
+//! Every file listed in the [`BackupManifest`] is downloaded through the
+//! configured [`StorageProviderTrait`], decompressed if the operator gzipped it
+//! on upload, checked against its recorded SHA-256 and written to disk. The
+//! resulting directory can be served as a regular Stellar history archive.
+
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use tracing::{debug, info};
+
+use super::archive::{self, WELL_KNOWN_HAS_PATH};
+use super::manifest::{BackupFileEntry, BackupManifest};
+use super::providers::StorageProviderTrait;
+
+/// Outcome of a restore
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestoreReport {
+    /// Files downloaded and written
+    pub restored: usize,
+    /// Files already present on disk with the expected hash
+    pub skipped: usize,
+    /// Highest checkpoint restored
+    pub latest_checkpoint: Option<u64>,
+}
+
+/// Resolve an archive-relative path below `dest`, rejecting path traversal
+fn target_path(dest: &Path, relative: &str) -> Result<PathBuf> {
+    let relative = Path::new(relative);
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        bail!(
+            "Refusing to restore unsafe path {} from manifest",
+            relative.display()
+        );
+    }
+    Ok(dest.join(relative))
+}
+
+/// Download one file, undo operator compression and verify its hash
+async fn fetch_verified(
+    provider: &dyn StorageProviderTrait,
+    filename: &str,
+    entry: &BackupFileEntry,
+) -> Result<Vec<u8>> {
+    let mut data = provider
+        .download(&entry.cid)
+        .await
+        .with_context(|| format!("Failed to download {filename} ({})", entry.cid))?;
+
+    if entry.compressed {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(data.as_slice())
+            .read_to_end(&mut decompressed)
+            .with_context(|| format!("Failed to decompress {filename}"))?;
+        data = decompressed;
+    }
+
+    let actual = format!("{:x}", Sha256::digest(&data));
+    if actual != entry.sha256 {
+        bail!(
+            "Hash mismatch for {filename} ({}): expected {}, got {actual}",
+            entry.cid,
+            entry.sha256
+        );
+    }
+
+    Ok(data)
+}
+
+async fn write_file(path: &Path, data: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    // Write to a temporary file first so an interrupted restore never leaves
+    // a truncated file behind that would later pass the existence check
+    let tmp = path.with_extension("partial");
+    tokio::fs::write(&tmp, data)
+        .await
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    tokio::fs::rename(&tmp, path)
+        .await
+        .with_context(|| format!("Failed to move {} into place", path.display()))?;
+    Ok(())
+}
+
+async fn already_restored(path: &Path, sha256: &str) -> bool {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || archive::sha256_file(&path).ok())
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|h| h == sha256)
+}
+
+/// Restore every checkpoint up to `up_to` (all checkpoints when `None`)
+///
+/// Files already present in `dest` with the expected hash are kept, so an
+/// interrupted restore can simply be re-run. The root
+/// `.well-known/stellar-history.json` is taken from the newest restored
+/// checkpoint and written last, once everything it references is in place.
+pub async fn restore_archive(
+    provider: &dyn StorageProviderTrait,
+    manifest: &BackupManifest,
+    dest: &Path,
+    up_to: Option<u64>,
+) -> Result<RestoreReport> {
+    let mut report = RestoreReport::default();
+    let mut root_has: Option<(&str, &BackupFileEntry)> = None;
+
+    for (checkpoint, backup) in manifest.checkpoints.range(..=up_to.unwrap_or(u64::MAX)) {
+        debug!("Restoring checkpoint {}", checkpoint);
+
+        for (filename, entry) in &backup.files {
+            if !entry.is_active() {
+                continue;
+            }
+            if filename == WELL_KNOWN_HAS_PATH {
+                root_has = Some((filename, entry));
+                continue;
+            }
+
+            let path = target_path(dest, filename)?;
+            if already_restored(&path, &entry.sha256).await {
+                report.skipped += 1;
+                continue;
+            }
+
+            let data = fetch_verified(provider, filename, entry).await?;
+            write_file(&path, &data).await?;
+            report.restored += 1;
+        }
+
+        report.latest_checkpoint = Some(*checkpoint);
+    }
+
+    match root_has {
+        Some((filename, entry)) => {
+            let data = fetch_verified(provider, filename, entry).await?;
+            write_file(&target_path(dest, filename)?, &data).await?;
+            report.restored += 1;
+        }
+        None if report.latest_checkpoint.is_some() => {
+            bail!("Backup manifest has no {WELL_KNOWN_HAS_PATH} for the restored checkpoints")
+        }
+        None => bail!("Backup manifest has no checkpoints to restore"),
+    }
+
+    info!(
+        "Restored history archive to {} ({} files downloaded, {} already present, latest checkpoint {:?})",
+        dest.display(),
+        report.restored,
+        report.skipped,
+        report.latest_checkpoint
+    );
+
+    Ok(report)
+}
@@ -0,0 +1,452 @@
+//! Tests for the persistent backup manifest and restoring an archive from it.
+
+#[cfg(test)]
+mod tests {
+    use crate::backup::archive::*;
+    use crate::backup::manifest::*;
+    use crate::backup::providers::{
+        RemovalOutcome, StorageProviderTrait, StoredObject, UploadMetadata,
+    };
+    use crate::backup::restore::restore_archive;
+    use crate::backup::scheduler::BackupScheduler;
+    use crate::backup::*;
+
+    use anyhow::{Context, Result};
+    use async_trait::async_trait;
+    use sha2::{Digest, Sha256};
+    use std::collections::HashMap;
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+
+    /// Content-addressed in-memory provider
+    #[derive(Default)]
+    struct MemoryProvider {
+        blobs: RwLock<HashMap<String, Vec<u8>>>,
+        uploads: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl StorageProviderTrait for MemoryProvider {
+        async fn upload(&self, data: Vec<u8>, _metadata: UploadMetadata) -> Result<String> {
+            self.uploads.fetch_add(1, Ordering::SeqCst);
+            let cid = format!("cid-{:x}", Sha256::digest(&data));
+            self.blobs.write().await.insert(cid.clone(), data);
+            Ok(cid)
+        }
+
+        async fn exists(&self, content_hash: &str) -> Result<bool> {
+            Ok(self.blobs.read().await.contains_key(content_hash))
+        }
+
+        async fn verify(&self, cid: &str, expected_hash: &str) -> Result<bool> {
+            let data = self.download(cid).await?;
+            Ok(format!("{:x}", Sha256::digest(&data)) == expected_hash)
+        }
+
+        async fn download(&self, cid: &str) -> Result<Vec<u8>> {
+            self.blobs
+                .read()
+                .await
+                .get(cid)
+                .cloned()
+                .context("unknown cid")
+        }
+
+        async fn list(&self) -> Result<Vec<StoredObject>> {
+            Ok(self
+                .blobs
+                .read()
+                .await
+                .keys()
+                .map(|cid| StoredObject {
+                    cid: cid.clone(),
+                    name: None,
+                })
+                .collect())
+        }
+
+        async fn delete(&self, cid: &str) -> Result<RemovalOutcome> {
+            self.blobs.write().await.remove(cid);
+            Ok(RemovalOutcome::Removed)
+        }
+    }
+
+    #[derive(Default)]
+    struct MemoryManifestStore {
+        saved: RwLock<Option<Vec<u8>>>,
+        saves: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ManifestStore for MemoryManifestStore {
+        async fn load(&self) -> Result<BackupManifest> {
+            match self.saved.read().await.as_ref() {
+                Some(data) => BackupManifest::from_gzip_json(data),
+                None => Ok(BackupManifest::default()),
+            }
+        }
+
+        async fn save(&self, manifest: &BackupManifest) -> Result<()> {
+            *self.saved.write().await = Some(manifest.to_gzip_json()?);
+            self.saves.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    const BUCKET: &str = "ab11223344556677889900aabbccddeeff00112233445566778899aabbccddee";
+
+    fn write(root: &Path, relative: &str, contents: &[u8]) {
+        let path = root.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    fn has_json(ledger: u64) -> Vec<u8> {
+        serde_json::to_vec(&serde_json::json!({
+            "version": 1,
+            "currentLedger": ledger,
+            "currentBuckets": [{ "curr": BUCKET, "snap": BUCKET, "next": { "state": 0 } }]
+        }))
+        .unwrap()
+    }
+
+    fn fixture_archive(checkpoints: &[u64]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for &checkpoint in checkpoints {
+            write(
+                root,
+                &checkpoint_file_path("history", checkpoint, "json"),
+                &has_json(checkpoint),
+            );
+            for category in ["ledger", "transactions", "results"] {
+                write(
+                    root,
+                    &checkpoint_file_path(category, checkpoint, "xdr.gz"),
+                    format!("{category}-{checkpoint}").as_bytes(),
+                );
+            }
+        }
+        write(root, &bucket_path(BUCKET).unwrap(), b"bucket contents");
+        write(
+            root,
+            WELL_KNOWN_HAS_PATH,
+            &has_json(*checkpoints.last().unwrap()),
+        );
+        dir
+    }
+
+    fn config() -> DecentralizedBackupConfig {
+        DecentralizedBackupConfig {
+            enabled: true,
+            provider: StorageProvider::IPFS {
+                api_url: "http://localhost:5001".to_string(),
+                pinning_service: None,
+            },
+            schedule: "0 0 */6 * * *".to_string(),
+            max_concurrent_uploads: 2,
+            compression_enabled: true,
+            retention: None,
+            archive_path: "history".to_string(),
+        }
+    }
+
+    async fn backed_up(
+        checkpoints: &[u64],
+    ) -> (tempfile::TempDir, Arc<MemoryProvider>, BackupManifest) {
+        let archive = fixture_archive(checkpoints);
+        let provider = Arc::new(MemoryProvider::default());
+        let scheduler = BackupScheduler::new(config(), provider.clone());
+        scheduler
+            .run_backup(&archive.path().to_string_lossy())
+            .await
+            .unwrap();
+        let manifest = scheduler.manifest().await;
+        (archive, provider, manifest)
+    }
+
+    fn assert_same_tree(expected: &Path, actual: &Path) {
+        for segment in scan_archive(expected, &KnownFiles::new()).unwrap() {
+            let restored = actual.join(&segment.filename);
+            assert_eq!(
+                std::fs::read(&restored).unwrap(),
+                std::fs::read(&segment.path).unwrap(),
+                "{} differs",
+                segment.filename
+            );
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Manifest
+    // -------------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn test_manifest_records_every_file_by_checkpoint() {
+        let (_archive, _provider, manifest) = backed_up(&[63, 127]).await;
+
+        assert_eq!(manifest.latest_checkpoint(), Some(127));
+        // 4 files per checkpoint, one bucket, plus the root HAS
+        assert_eq!(manifest.file_count(), 10);
+
+        let ledger = &manifest.checkpoints[&63].files["ledger/00/00/00/ledger-0000003f.xdr.gz"];
+        assert_eq!(ledger.segment_type, SEGMENT_TYPE_LEDGER);
+        assert!(!ledger.compressed);
+        assert!(ledger.cid.starts_with("cid-"));
+
+        let has = &manifest.checkpoints[&127].files[WELL_KNOWN_HAS_PATH];
+        assert!(has.compressed);
+    }
+
+    #[tokio::test]
+    async fn test_manifest_reuses_cid_for_identical_content() {
+        let (_archive, provider, manifest) = backed_up(&[63]).await;
+
+        // The root HAS is identical to the checkpoint's history file
+        let files = &manifest.checkpoints[&63].files;
+        assert_eq!(
+            files[WELL_KNOWN_HAS_PATH].cid,
+            files["history/00/00/00/history-0000003f.json"].cid
+        );
+        assert!(provider.uploads.load(Ordering::SeqCst) <= manifest.file_count());
+    }
+
+    #[test]
+    fn test_manifest_gzip_roundtrip() {
+        let mut manifest = BackupManifest::default();
+        manifest.checkpoints.entry(63).or_default().files.insert(
+            "ledger/00/00/00/ledger-0000003f.xdr.gz".to_string(),
+            BackupFileEntry {
+                cid: "bafy".to_string(),
+                sha256: "aa".to_string(),
+                segment_type: SEGMENT_TYPE_LEDGER.to_string(),
+                compressed: false,
+                uploaded_at: chrono::Utc::now(),
+                size: None,
+                modified: None,
+                superseded_at: None,
+            },
+        );
+
+        let parsed = BackupManifest::from_gzip_json(&manifest.to_gzip_json().unwrap()).unwrap();
+        assert_eq!(parsed.checkpoints, manifest.checkpoints);
+        assert_eq!(parsed.find_by_hash("aa"), Some(("bafy", false)));
+    }
+
+    #[test]
+    fn test_manifest_rejects_newer_version() {
+        let mut manifest = BackupManifest::default();
+        manifest.version = MANIFEST_VERSION + 1;
+        assert!(BackupManifest::from_gzip_json(&manifest.to_gzip_json().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_large_manifest_is_sharded() {
+        let mut manifest = BackupManifest::default();
+        for ledger in 0..12_000u64 {
+            let checkpoint = manifest.checkpoints.entry(ledger * 64 + 63).or_default();
+            for kind in ["ledger", "transactions", "results", "bucket"] {
+                let hash = format!("{:x}", Sha256::digest(format!("{kind}-{ledger}")));
+                checkpoint.files.insert(
+                    format!("{kind}/{ledger}.xdr.gz"),
+                    BackupFileEntry {
+                        cid: format!("bafy{}", &hash[..40]),
+                        sha256: hash,
+                        segment_type: kind.to_string(),
+                        compressed: false,
+                        uploaded_at: chrono::Utc::now(),
+                        size: None,
+                        modified: None,
+                        superseded_at: None,
+                    },
+                );
+            }
+        }
+        let data = manifest.to_gzip_json().unwrap();
+        let sha256 = format!("{:x}", Sha256::digest(&data));
+
+        let shards = split_manifest(&data).unwrap();
+        assert!(shards.len() > 1, "{} bytes fit in one shard", data.len());
+        assert!(shards.iter().all(|s| s.len() <= MANIFEST_SHARD_BYTES));
+
+        let shards: Vec<Vec<u8>> = shards.into_iter().map(<[u8]>::to_vec).collect();
+        let joined = join_manifest(shards.clone(), &sha256).unwrap();
+        assert_eq!(joined.checkpoints, manifest.checkpoints);
+
+        // A shard left over from an interrupted save is detected
+        let mut mixed = shards;
+        mixed[0][10] ^= 0xff;
+        assert!(join_manifest(mixed, &sha256).is_err());
+
+        let oversized = vec![0u8; MANIFEST_SHARD_BYTES * MAX_MANIFEST_SHARDS + 1];
+        assert!(split_manifest(&oversized).is_err());
+        assert_eq!(
+            manifest_shard_names("v-backup-manifest").len(),
+            2 * MAX_MANIFEST_SHARDS
+        );
+    }
+
+    #[tokio::test]
+    async fn test_manifest_store_survives_restart() {
+        let archive = fixture_archive(&[63]);
+        let path = archive.path().to_string_lossy().into_owned();
+        let provider = Arc::new(MemoryProvider::default());
+        let store = Arc::new(MemoryManifestStore::default());
+
+        let first =
+            BackupScheduler::new(config(), provider.clone()).with_manifest_store(store.clone());
+        first.run_backup(&path).await.unwrap();
+        let uploads = provider.uploads.load(Ordering::SeqCst);
+
+        // A fresh scheduler loads the manifest and has nothing left to upload
+        let second =
+            BackupScheduler::new(config(), provider.clone()).with_manifest_store(store.clone());
+        second.load_manifest().await.unwrap();
+        assert!(second
+            .discover_new_segments(&path)
+            .await
+            .unwrap()
+            .is_empty());
+        second.run_backup(&path).await.unwrap();
+        assert_eq!(provider.uploads.load(Ordering::SeqCst), uploads);
+    }
+
+    #[tokio::test]
+    async fn test_manifest_is_saved_during_long_runs() {
+        let checkpoints: Vec<u64> = (0..30).map(|i| i * 64 + 63).collect();
+        let archive = fixture_archive(&checkpoints);
+        let provider = Arc::new(MemoryProvider::default());
+        let store = Arc::new(MemoryManifestStore::default());
+
+        let scheduler =
+            BackupScheduler::new(config(), provider.clone()).with_manifest_store(store.clone());
+        let stats = scheduler
+            .run_backup(&archive.path().to_string_lossy())
+            .await
+            .unwrap();
+
+        // Two intermediate saves and the final one
+        assert_eq!(stats.uploaded + stats.deduplicated, 122);
+        assert_eq!(
+            store.saves.load(Ordering::SeqCst),
+            1 + 122 / crate::backup::scheduler::MANIFEST_SAVE_INTERVAL
+        );
+        assert_eq!(store.load().await.unwrap().file_count(), 122);
+    }
+
+    // -------------------------------------------------------------------------
+    // Restore
+    // -------------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn test_restore_rebuilds_archive() {
+        let (archive, provider, manifest) = backed_up(&[63, 127]).await;
+        let dest = tempfile::tempdir().unwrap();
+
+        let report = restore_archive(provider.as_ref(), &manifest, dest.path(), None)
+            .await
+            .unwrap();
+        assert_eq!(report.restored, 10);
+        assert_eq!(report.latest_checkpoint, Some(127));
+        assert_same_tree(archive.path(), dest.path());
+
+        // Re-running skips everything already in place except the root HAS
+        let report = restore_archive(provider.as_ref(), &manifest, dest.path(), None)
+            .await
+            .unwrap();
+        assert_eq!(report.skipped, 9);
+        assert_eq!(report.restored, 1);
+    }
+
+    #[tokio::test]
+    async fn test_restore_up_to_checkpoint() {
+        let archive = fixture_archive(&[63]);
+        let provider = Arc::new(MemoryProvider::default());
+        let scheduler = BackupScheduler::new(config(), provider.clone());
+        let path = archive.path().to_string_lossy().into_owned();
+        scheduler.run_backup(&path).await.unwrap();
+
+        // Publish checkpoint 127 and back it up too
+        for category in ["ledger", "transactions", "results"] {
+            write(
+                archive.path(),
+                &checkpoint_file_path(category, 127, "xdr.gz"),
+                format!("{category}-127").as_bytes(),
+            );
+        }
+        write(
+            archive.path(),
+            &checkpoint_file_path("history", 127, "json"),
+            &has_json(127),
+        );
+        write(archive.path(), WELL_KNOWN_HAS_PATH, &has_json(127));
+        scheduler.run_backup(&path).await.unwrap();
+        let manifest = scheduler.manifest().await;
+
+        let dest = tempfile::tempdir().unwrap();
+        let report = restore_archive(provider.as_ref(), &manifest, dest.path(), Some(63))
+            .await
+            .unwrap();
+        assert_eq!(report.latest_checkpoint, Some(63));
+        assert!(!dest
+            .path()
+            .join(checkpoint_file_path("ledger", 127, "xdr.gz"))
+            .exists());
+        assert_eq!(
+            std::fs::read(dest.path().join(WELL_KNOWN_HAS_PATH)).unwrap(),
+            has_json(63)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_detects_tampered_content() {
+        let (_archive, provider, manifest) = backed_up(&[63]).await;
+
+        let cid = manifest.checkpoints[&63].files["ledger/00/00/00/ledger-0000003f.xdr.gz"]
+            .cid
+            .clone();
+        provider
+            .blobs
+            .write()
+            .await
+            .insert(cid, b"tampered".to_vec());
+
+        let dest = tempfile::tempdir().unwrap();
+        let err = restore_archive(provider.as_ref(), &manifest, dest.path(), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Hash mismatch"));
+    }
+
+    #[tokio::test]
+    async fn test_restore_rejects_path_traversal() {
+        let (_archive, provider, mut manifest) = backed_up(&[63]).await;
+        let entry = manifest.checkpoints[&63].files[WELL_KNOWN_HAS_PATH].clone();
+        manifest
+            .checkpoints
+            .get_mut(&63)
+            .unwrap()
+            .files
+            .insert("../escape.json".to_string(), entry);
+
+        let dest = tempfile::tempdir().unwrap();
+        assert!(
+            restore_archive(provider.as_ref(), &manifest, dest.path(), None)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_empty_manifest_fails() {
+        let provider = MemoryProvider::default();
+        let dest = tempfile::tempdir().unwrap();
+        assert!(
+            restore_archive(&provider, &BackupManifest::default(), dest.path(), None)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,253 @@
+//! Retention enforcement for decentralized backups
+//!
+//! Checkpoints whose last ledger closed more than `RetentionPolicy::days` ago
+//! are released, oldest first, while the newest `min_backups` complete
+//! checkpoints are always kept. Checkpoints recorded without a close time are
+//! aged by their last upload instead. A
+//! checkpoint is complete when its history, ledger, transactions and results
+//! files and every bucket its history file references are backed up.
+//!
+//! Buckets are shared between checkpoints, so content is only released when no
+//! retained checkpoint still needs it. What releasing means depends on the
+//! provider: IPFS content is unpinned and dropped from the manifest, Filecoin
+//! deals are left to expire and Arweave entries are marked superseded.
+//! Superseded entries are dropped from the manifest once they have been
+//! superseded for another `days`, so the manifest does not keep growing.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use tracing::{info, warn};
+
+use super::archive::{
+    bucket_path, SEGMENT_TYPE_HAS, SEGMENT_TYPE_LEDGER, SEGMENT_TYPE_RESULTS,
+    SEGMENT_TYPE_TRANSACTIONS,
+};
+use super::manifest::{BackupManifest, CheckpointBackup};
+use super::providers::{RemovalOutcome, StorageProviderTrait};
+use super::RetentionPolicy;
+
+/// Checkpoints and content selected for release
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrunePlan {
+    /// Checkpoints newly released by this pass
+    pub checkpoints: Vec<u64>,
+    /// Content identifiers no retained checkpoint needs any more
+    pub release: BTreeSet<String>,
+}
+
+/// Outcome of a retention pass
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PruneReport {
+    pub pruned_checkpoints: usize,
+    /// Content deleted or unpinned
+    pub removed: usize,
+    /// Content left with the provider and marked superseded
+    pub superseded: usize,
+    /// Superseded entries dropped from the manifest
+    pub forgotten: usize,
+    /// Content the provider failed to release; retried on the next pass
+    pub failed: usize,
+}
+
+fn has_active(checkpoint: &CheckpointBackup, segment_type: &str) -> bool {
+    checkpoint
+        .files
+        .values()
+        .any(|f| f.is_active() && f.segment_type == segment_type)
+}
+
+/// CIDs of active files in the manifest, by archive path
+fn active_files(manifest: &BackupManifest) -> BTreeMap<&str, &str> {
+    manifest
+        .checkpoints
+        .values()
+        .flat_map(|c| c.files.iter())
+        .filter(|(_, f)| f.is_active())
+        .map(|(name, f)| (name.as_str(), f.cid.as_str()))
+        .collect()
+}
+
+/// Whether a checkpoint can be restored from the manifest on its own
+pub fn is_complete(manifest: &BackupManifest, checkpoint: u64) -> bool {
+    manifest
+        .checkpoints
+        .get(&checkpoint)
+        .is_some_and(|b| complete_with(b, &active_files(manifest)))
+}
+
+fn complete_with(backup: &CheckpointBackup, files: &BTreeMap<&str, &str>) -> bool {
+    [
+        SEGMENT_TYPE_HAS,
+        SEGMENT_TYPE_LEDGER,
+        SEGMENT_TYPE_TRANSACTIONS,
+        SEGMENT_TYPE_RESULTS,
+    ]
+    .iter()
+    .all(|t| has_active(backup, t))
+        && backup
+            .bucket_refs
+            .iter()
+            .all(|h| bucket_path(h).is_ok_and(|p| files.contains_key(p.as_str())))
+}
+
+/// Decide what a retention pass releases
+///
+/// Checkpoints are released strictly oldest-first, so everything up to
+/// `BackupManifest::pruned_through` has been released and never needs to be
+/// uploaded again.
+pub fn plan_prune(
+    manifest: &BackupManifest,
+    policy: &RetentionPolicy,
+    now: DateTime<Utc>,
+) -> PrunePlan {
+    let mut plan = PrunePlan::default();
+    if policy.days == 0 {
+        return plan;
+    }
+
+    let files = active_files(manifest);
+    let complete: Vec<u64> = manifest
+        .checkpoints
+        .iter()
+        .filter(|(c, _)| manifest.pruned_through.is_none_or(|p| **c > p))
+        .filter(|(_, b)| complete_with(b, &files))
+        .map(|(c, _)| *c)
+        .collect();
+    if complete.len() < policy.min_backups as usize {
+        warn!(
+            "Only {} complete backup checkpoints, below min_backups {}; not pruning",
+            complete.len(),
+            policy.min_backups
+        );
+        return plan;
+    }
+    let protected: HashSet<u64> = complete
+        .iter()
+        .rev()
+        .take(policy.min_backups as usize)
+        .copied()
+        .collect();
+
+    let cutoff = now - Duration::days(i64::from(policy.days));
+    for (checkpoint, backup) in &manifest.checkpoints {
+        if manifest.pruned_through.is_some_and(|p| *checkpoint <= p) {
+            continue;
+        }
+        if protected.contains(checkpoint) || backup.age_reference().is_none_or(|t| t > cutoff) {
+            break;
+        }
+        plan.checkpoints.push(*checkpoint);
+    }
+
+    let released_through = plan
+        .checkpoints
+        .last()
+        .copied()
+        .max(manifest.pruned_through);
+    let Some(released_through) = released_through else {
+        return plan;
+    };
+
+    // Content still needed by retained checkpoints, including shared buckets
+    let mut needed = HashSet::new();
+    for backup in manifest
+        .checkpoints
+        .range(released_through + 1..)
+        .map(|(_, b)| b)
+    {
+        needed.extend(
+            backup
+                .files
+                .values()
+                .filter(|f| f.is_active())
+                .map(|f| f.cid.as_str()),
+        );
+        needed.extend(
+            backup
+                .bucket_refs
+                .iter()
+                .filter_map(|h| files.get(bucket_path(h).ok()?.as_str()).copied()),
+        );
+    }
+
+    plan.release = manifest
+        .checkpoints
+        .range(..=released_through)
+        .flat_map(|(_, b)| b.files.values())
+        .filter(|f| f.is_active() && !needed.contains(f.cid.as_str()))
+        .map(|f| f.cid.clone())
+        .collect();
+
+    plan
+}
+
+/// Apply the retention policy: release content through the provider and
+/// update the manifest accordingly
+pub async fn enforce_retention(
+    provider: &dyn StorageProviderTrait,
+    manifest: &mut BackupManifest,
+    policy: &RetentionPolicy,
+    now: DateTime<Utc>,
+) -> Result<PruneReport> {
+    let plan = plan_prune(manifest, policy, now);
+    let mut report = PruneReport {
+        pruned_checkpoints: plan.checkpoints.len(),
+        ..Default::default()
+    };
+
+    let mut outcomes = BTreeMap::new();
+    for cid in &plan.release {
+        match provider.delete(cid).await {
+            Ok(outcome) => {
+                outcomes.insert(cid.as_str(), outcome);
+            }
+            Err(e) => {
+                warn!("Failed to release backup content {}: {}", cid, e);
+                report.failed += 1;
+            }
+        }
+    }
+
+    if let Some(last) = plan.checkpoints.last() {
+        manifest.pruned_through = Some(manifest.pruned_through.map_or(*last, |p| p.max(*last)));
+    }
+
+    let forget_before = (policy.days > 0).then(|| now - Duration::days(i64::from(policy.days)));
+    for backup in manifest.checkpoints.values_mut() {
+        backup.files.retain(|_, f| {
+            if forget_before.is_some_and(|b| f.superseded_at.is_some_and(|t| t <= b)) {
+                report.forgotten += 1;
+                return false;
+            }
+            match outcomes.get(f.cid.as_str()) {
+                Some(RemovalOutcome::Removed) => {
+                    report.removed += 1;
+                    return false;
+                }
+                Some(RemovalOutcome::Expiring | RemovalOutcome::Permanent) if f.is_active() => {
+                    f.superseded_at = Some(now);
+                    report.superseded += 1;
+                }
+                _ => {}
+            }
+            true
+        });
+    }
+    manifest.checkpoints.retain(|_, b| !b.files.is_empty());
+    manifest.rebuild_index();
+
+    if report != PruneReport::default() {
+        info!(
+            "Retention released {} checkpoints ({} files removed, {} superseded, {} failed, \
+             {} superseded entries dropped)",
+            report.pruned_checkpoints,
+            report.removed,
+            report.superseded,
+            report.failed,
+            report.forgotten
+        );
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,354 @@
+//! Tests for retention planning and enforcement against a synthetic manifest.
+
+#[cfg(test)]
+mod tests {
+    use crate::backup::archive::*;
+    use crate::backup::manifest::BackupManifest;
+    use crate::backup::providers::{
+        RemovalOutcome, StorageProviderTrait, StoredObject, UploadMetadata,
+    };
+    use crate::backup::retention::*;
+    use crate::backup::scheduler::ArchiveSegment;
+    use crate::backup::RetentionPolicy;
+
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use chrono::{Duration, Utc};
+    use tokio::sync::Mutex;
+
+    /// Provider that answers every delete with a fixed outcome (or an error)
+    struct FixedOutcomeProvider {
+        outcome: Option<RemovalOutcome>,
+        deleted: Mutex<Vec<String>>,
+    }
+
+    impl FixedOutcomeProvider {
+        fn new(outcome: Option<RemovalOutcome>) -> Self {
+            Self {
+                outcome,
+                deleted: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl StorageProviderTrait for FixedOutcomeProvider {
+        async fn upload(&self, _data: Vec<u8>, _metadata: UploadMetadata) -> Result<String> {
+            Ok("cid".to_string())
+        }
+
+        async fn exists(&self, _content_hash: &str) -> Result<bool> {
+            Ok(true)
+        }
+
+        async fn verify(&self, _cid: &str, _expected_hash: &str) -> Result<bool> {
+            Ok(true)
+        }
+
+        async fn download(&self, _cid: &str) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn list(&self) -> Result<Vec<StoredObject>> {
+            Ok(Vec::new())
+        }
+
+        async fn delete(&self, cid: &str) -> Result<RemovalOutcome> {
+            self.deleted.lock().await.push(cid.to_string());
+            self.outcome
+                .ok_or_else(|| anyhow::anyhow!("pinning service unavailable"))
+        }
+    }
+
+    const BUCKET_A: &str = "aa00000000000000000000000000000000000000000000000000000000000000";
+    const BUCKET_B: &str = "bb00000000000000000000000000000000000000000000000000000000000000";
+    const BUCKET_C: &str = "cc00000000000000000000000000000000000000000000000000000000000000";
+
+    fn cid(filename: &str) -> String {
+        format!("cid-{filename}")
+    }
+
+    fn record(
+        manifest: &mut BackupManifest,
+        ledger: u64,
+        filename: String,
+        segment_type: &str,
+        bucket_refs: &[&str],
+    ) {
+        let segment = ArchiveSegment {
+            hash: format!("sha-{filename}"),
+            path: filename.clone(),
+            filename: filename.clone(),
+            ledger,
+            segment_type: segment_type.to_string(),
+            bucket_refs: bucket_refs.iter().map(|s| s.to_string()).collect(),
+            closed_at: None,
+            size: 0,
+            modified: None,
+        };
+        manifest.record(&segment, cid(&filename), false);
+    }
+
+    /// Four checkpoints, 40, 35, 20 and 1 days old.
+    ///
+    /// Bucket A is uploaded with checkpoint 63 and referenced by all of them,
+    /// bucket B only by 127, and bucket C by 191 and 255.
+    fn fixture_manifest() -> BackupManifest {
+        let mut manifest = BackupManifest::default();
+        let layout: [(u64, i64, &[&str], Option<&str>); 4] = [
+            (63, 40, &[BUCKET_A], Some(BUCKET_A)),
+            (127, 35, &[BUCKET_A, BUCKET_B], Some(BUCKET_B)),
+            (191, 20, &[BUCKET_A, BUCKET_C], Some(BUCKET_C)),
+            (255, 1, &[BUCKET_A, BUCKET_C], None),
+        ];
+
+        for (checkpoint, age_days, refs, new_bucket) in layout {
+            record(
+                &mut manifest,
+                checkpoint,
+                checkpoint_file_path("history", checkpoint, "json"),
+                SEGMENT_TYPE_HAS,
+                refs,
+            );
+            for category in [
+                SEGMENT_TYPE_LEDGER,
+                SEGMENT_TYPE_TRANSACTIONS,
+                SEGMENT_TYPE_RESULTS,
+            ] {
+                record(
+                    &mut manifest,
+                    checkpoint,
+                    checkpoint_file_path(category, checkpoint, "xdr.gz"),
+                    category,
+                    &[],
+                );
+            }
+            if let Some(bucket) = new_bucket {
+                record(
+                    &mut manifest,
+                    checkpoint,
+                    bucket_path(bucket).unwrap(),
+                    SEGMENT_TYPE_BUCKET,
+                    &[],
+                );
+            }
+
+            let uploaded_at = Utc::now() - Duration::days(age_days);
+            for file in manifest
+                .checkpoints
+                .get_mut(&checkpoint)
+                .unwrap()
+                .files
+                .values_mut()
+            {
+                file.uploaded_at = uploaded_at;
+            }
+        }
+
+        manifest
+    }
+
+    fn policy(days: u32, min_backups: u32) -> RetentionPolicy {
+        RetentionPolicy { days, min_backups }
+    }
+
+    #[test]
+    fn test_plan_releases_expired_checkpoints_but_keeps_shared_buckets() {
+        let manifest = fixture_manifest();
+        let plan = plan_prune(&manifest, &policy(30, 1), Utc::now());
+
+        assert_eq!(plan.checkpoints, vec![63, 127]);
+        // 4 files each for 63 and 127, plus bucket B; bucket A is still needed
+        assert_eq!(plan.release.len(), 9);
+        assert!(plan.release.contains(&cid(&bucket_path(BUCKET_B).unwrap())));
+        assert!(!plan.release.contains(&cid(&bucket_path(BUCKET_A).unwrap())));
+    }
+
+    #[test]
+    fn test_plan_keeps_min_backups() {
+        let manifest = fixture_manifest();
+        let plan = plan_prune(&manifest, &policy(30, 3), Utc::now());
+        assert_eq!(plan.checkpoints, vec![63]);
+
+        // Fewer complete checkpoints than min_backups: nothing is released
+        let plan = plan_prune(&manifest, &policy(30, 5), Utc::now());
+        assert_eq!(plan, PrunePlan::default());
+    }
+
+    #[test]
+    fn test_plan_zero_days_keeps_everything() {
+        let manifest = fixture_manifest();
+        assert_eq!(
+            plan_prune(&manifest, &policy(0, 0), Utc::now()),
+            PrunePlan::default()
+        );
+    }
+
+    #[test]
+    fn test_plan_ages_checkpoints_by_close_time() {
+        let mut manifest = fixture_manifest();
+        // An archive backed up for the first time today: every upload is new,
+        // but checkpoints 63 and 127 closed long ago
+        for (checkpoint, backup) in manifest.checkpoints.iter_mut() {
+            for file in backup.files.values_mut() {
+                file.uploaded_at = Utc::now();
+            }
+            if *checkpoint <= 127 {
+                backup.closed_at = Some(Utc::now() - Duration::days(60));
+            }
+        }
+        let plan = plan_prune(&manifest, &policy(30, 1), Utc::now());
+        assert_eq!(plan.checkpoints, vec![63, 127]);
+
+        // A recent close time keeps a checkpoint uploaded long ago
+        let mut manifest = fixture_manifest();
+        manifest.checkpoints.get_mut(&63).unwrap().closed_at = Some(Utc::now());
+        let plan = plan_prune(&manifest, &policy(30, 1), Utc::now());
+        assert!(plan.checkpoints.is_empty());
+    }
+
+    #[test]
+    fn test_incomplete_checkpoints_do_not_count_towards_min_backups() {
+        let mut manifest = fixture_manifest();
+        manifest
+            .checkpoints
+            .get_mut(&191)
+            .unwrap()
+            .files
+            .remove(&bucket_path(BUCKET_C).unwrap());
+
+        assert!(is_complete(&manifest, 127));
+        assert!(!is_complete(&manifest, 191));
+        assert!(!is_complete(&manifest, 255));
+
+        // 127 is now the newest complete checkpoint and must be kept
+        let plan = plan_prune(&manifest, &policy(30, 1), Utc::now());
+        assert_eq!(plan.checkpoints, vec![63]);
+    }
+
+    #[tokio::test]
+    async fn test_enforce_unpins_and_drops_removed_content() {
+        let mut manifest = fixture_manifest();
+        let provider = FixedOutcomeProvider::new(Some(RemovalOutcome::Removed));
+
+        let report = enforce_retention(&provider, &mut manifest, &policy(30, 1), Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(report.pruned_checkpoints, 2);
+        assert_eq!(report.removed, 9);
+        assert_eq!(provider.deleted.lock().await.len(), 9);
+
+        assert_eq!(manifest.pruned_through, Some(127));
+        assert!(!manifest.checkpoints.contains_key(&127));
+        // Only the shared bucket remains for checkpoint 63
+        let remaining: Vec<_> = manifest.checkpoints[&63].files.keys().cloned().collect();
+        assert_eq!(remaining, vec![bucket_path(BUCKET_A).unwrap()]);
+
+        // Released checkpoints are not uploaded again
+        let old_ledger = ArchiveSegment {
+            filename: checkpoint_file_path("ledger", 63, "xdr.gz"),
+            path: String::new(),
+            hash: "sha".to_string(),
+            ledger: 63,
+            segment_type: SEGMENT_TYPE_LEDGER.to_string(),
+            bucket_refs: Vec::new(),
+            closed_at: None,
+            size: 0,
+            modified: None,
+        };
+        assert!(manifest.contains_segment(&old_ledger));
+
+        // A second pass has nothing left to do
+        let report = enforce_retention(&provider, &mut manifest, &policy(30, 1), Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(report, PruneReport::default());
+    }
+
+    #[tokio::test]
+    async fn test_enforce_marks_permanent_content_superseded() {
+        let mut manifest = fixture_manifest();
+        let provider = FixedOutcomeProvider::new(Some(RemovalOutcome::Permanent));
+
+        let report = enforce_retention(&provider, &mut manifest, &policy(30, 1), Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(report.superseded, 9);
+        assert_eq!(report.removed, 0);
+
+        let ledger =
+            &manifest.checkpoints[&127].files[&checkpoint_file_path("ledger", 127, "xdr.gz")];
+        assert!(!ledger.is_active());
+        assert!(manifest.find_by_hash(&ledger.sha256).is_none());
+        assert!(manifest.checkpoints[&63].files[&bucket_path(BUCKET_A).unwrap()].is_active());
+
+        let report = enforce_retention(&provider, &mut manifest, &policy(30, 1), Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(report, PruneReport::default());
+    }
+
+    #[tokio::test]
+    async fn test_superseded_entries_are_dropped_after_a_retention_period() {
+        let mut manifest = fixture_manifest();
+        let provider = FixedOutcomeProvider::new(Some(RemovalOutcome::Permanent));
+        let now = Utc::now();
+
+        enforce_retention(&provider, &mut manifest, &policy(30, 1), now)
+            .await
+            .unwrap();
+        let files = manifest.file_count();
+
+        // Still within a retention period of being superseded. Later passes
+        // keep 191 and 255, so nothing new is superseded.
+        let report = enforce_retention(
+            &provider,
+            &mut manifest,
+            &policy(30, 2),
+            now + Duration::days(29),
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.forgotten, 0);
+
+        let report = enforce_retention(
+            &provider,
+            &mut manifest,
+            &policy(30, 2),
+            now + Duration::days(31),
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.forgotten, 9);
+        assert_eq!(manifest.file_count(), files - 9);
+        assert!(!manifest.checkpoints.contains_key(&127));
+        assert!(manifest
+            .checkpoints
+            .values()
+            .flat_map(|c| c.files.values())
+            .all(|f| f.is_active()));
+    }
+
+    #[tokio::test]
+    async fn test_enforce_retries_failed_releases() {
+        let mut manifest = fixture_manifest();
+        let failing = FixedOutcomeProvider::new(None);
+
+        let report = enforce_retention(&failing, &mut manifest, &policy(30, 1), Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(report.failed, 9);
+        assert_eq!(manifest.pruned_through, Some(127));
+        assert!(manifest.checkpoints[&127]
+            .files
+            .values()
+            .all(|f| f.is_active()));
+
+        let working = FixedOutcomeProvider::new(Some(RemovalOutcome::Expiring));
+        let report = enforce_retention(&working, &mut manifest, &policy(30, 1), Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(report.pruned_checkpoints, 0);
+        assert_eq!(report.superseded, 9);
+    }
+}
@@ -1,16 +1,47 @@
+use super::archive;
+use super::manifest::{BackupManifest, ManifestStore};
 use super::providers::{StorageProviderTrait, UploadMetadata};
+use super::retention;
 use super::*;
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use cron::Schedule;
+use futures::StreamExt;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
 use tokio::time::sleep;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+/// Segments recorded between manifest saves during a run, so a worker that
+/// dies mid-run does not upload them again
+pub const MANIFEST_SAVE_INTERVAL: usize = 50;
+
+/// Outcome of one backup run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackupRunStats {
+    /// Segments uploaded to the provider
+    pub uploaded: usize,
+    /// Segments whose content was already stored
+    pub deduplicated: usize,
+    /// Segments that failed to upload
+    pub failed: usize,
+    /// Bytes sent to the provider, after compression
+    pub bytes_uploaded: u64,
+}
+
+/// Result of uploading a single segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SegmentUpload {
+    Uploaded { bytes: u64 },
+    Deduplicated,
+}
 
 pub struct BackupScheduler {
     config: DecentralizedBackupConfig,
     provider: Arc<dyn StorageProviderTrait>,
-    uploaded_hashes: Arc<RwLock<HashSet<String>>>,
+    manifest: Arc<RwLock<BackupManifest>>,
+    manifest_store: Option<Arc<dyn ManifestStore>>,
 }
 
 impl BackupScheduler {
@@ -18,14 +49,45 @@ impl BackupScheduler {
         Self {
             config,
             provider,
-            uploaded_hashes: Arc::new(RwLock::new(HashSet::new())),
+            manifest: Arc::new(RwLock::new(BackupManifest::default())),
+            manifest_store: None,
         }
     }
 
+    /// Persist the backup manifest in `store` so uploads survive restarts
+    pub fn with_manifest_store(mut self, store: Arc<dyn ManifestStore>) -> Self {
+        self.manifest_store = Some(store);
+        self
+    }
+
+    /// Snapshot of the current backup manifest
+    pub async fn manifest(&self) -> BackupManifest {
+        self.manifest.read().await.clone()
+    }
+
+    /// Load the persisted manifest, if a store is configured
+    pub async fn load_manifest(&self) -> Result<()> {
+        if let Some(store) = &self.manifest_store {
+            let loaded = store
+                .load()
+                .await
+                .context("Failed to load backup manifest")?;
+            info!(
+                "Loaded backup manifest with {} files (latest checkpoint {:?})",
+                loaded.file_count(),
+                loaded.latest_checkpoint()
+            );
+            *self.manifest.write().await = loaded;
+        }
+        Ok(())
+    }
+
     pub async fn start(&self, history_archive_path: String) -> Result<()> {
         let schedule =
             Schedule::from_str(&self.config.schedule).context("Invalid cron schedule")?;
 
+        self.load_manifest().await?;
+
         info!(
             "Starting backup scheduler with schedule: {}",
             self.config.schedule
@@ -49,7 +111,7 @@ impl BackupScheduler {
         }
     }
 
-    async fn run_backup(&self, archive_path: &str) -> Result<()> {
+    pub(crate) async fn run_backup(&self, archive_path: &str) -> Result<BackupRunStats> {
         info!("Starting backup of history archive: {}", archive_path);
 
         // Discover new archive segments
@@ -65,48 +127,121 @@ impl BackupScheduler {
         for segment in segments {
             let sem = semaphore.clone();
             let provider = self.provider.clone();
-            let uploaded = self.uploaded_hashes.clone();
+            let manifest = self.manifest.clone();
             let compression = self.config.compression_enabled;
 
             let task = tokio::spawn(async move {
                 let _permit = sem.acquire().await.unwrap();
-                Self::upload_segment(segment, provider, uploaded, compression).await
+                Self::upload_segment(segment, provider, manifest, compression).await
             });
 
             tasks.push(task);
         }
 
-        let results = futures::future::join_all(tasks).await;
-        let successful = results.iter().filter(|r| r.is_ok()).count();
+        let total = tasks.len();
+        let mut tasks: futures::stream::FuturesUnordered<_> = tasks.into_iter().collect();
+        let mut stats = BackupRunStats::default();
+        let mut unsaved = 0;
+        while let Some(result) = tasks.next().await {
+            match result {
+                Ok(Ok(SegmentUpload::Uploaded { bytes })) => {
+                    stats.uploaded += 1;
+                    stats.bytes_uploaded += bytes;
+                    unsaved += 1;
+                }
+                Ok(Ok(SegmentUpload::Deduplicated)) => {
+                    stats.deduplicated += 1;
+                    unsaved += 1;
+                }
+                Ok(Err(e)) => {
+                    stats.failed += 1;
+                    error!("Segment upload failed: {:#}", e);
+                }
+                Err(e) => {
+                    stats.failed += 1;
+                    error!("Segment upload task failed: {}", e);
+                }
+            }
+
+            if unsaved >= MANIFEST_SAVE_INTERVAL {
+                unsaved = 0;
+                // The final save reports errors; a failed intermediate one
+                // only risks uploading these segments again
+                if let Err(e) = self.save_manifest().await {
+                    warn!("Failed to save backup manifest: {:#}", e);
+                }
+            }
+        }
 
         info!(
-            "Backup completed: {}/{} successful",
-            successful,
-            results.len()
+            "Backup completed: {}/{} successful, {} bytes uploaded",
+            stats.uploaded + stats.deduplicated,
+            total,
+            stats.bytes_uploaded
         );
 
+        if let Some(policy) = &self.config.retention {
+            let mut manifest = self.manifest.write().await;
+            retention::enforce_retention(
+                self.provider.as_ref(),
+                &mut manifest,
+                policy,
+                chrono::Utc::now(),
+            )
+            .await
+            .context("Retention enforcement failed")?;
+        }
+
+        self.save_manifest().await?;
+        Ok(stats)
+    }
+
+    /// Persist the manifest, if a store is configured
+    async fn save_manifest(&self) -> Result<()> {
+        if let Some(store) = &self.manifest_store {
+            let manifest = self.manifest.read().await.clone();
+            store
+                .save(&manifest)
+                .await
+                .context("Failed to save backup manifest")?;
+        }
         Ok(())
     }
 
-    async fn discover_new_segments(&self, _archive_path: &str) -> Result<Vec<ArchiveSegment>> {
-        // In production, scan the history archive directory structure
-        // History archives follow: /bucket/hex/hex/hex/history-hexhexhex.xdr.gz
-        // This is a simplified placeholder
-        Ok(vec![])
+    /// Scan the history archive and return the segments not uploaded yet
+    pub(crate) async fn discover_new_segments(
+        &self,
+        archive_path: &str,
+    ) -> Result<Vec<ArchiveSegment>> {
+        let root = PathBuf::from(archive_path);
+        let known = self.manifest.read().await.known_files();
+        let segments = tokio::task::spawn_blocking(move || archive::scan_archive(&root, &known))
+            .await
+            .context("Archive scan task failed")??;
+
+        let manifest = self.manifest.read().await;
+        Ok(segments
+            .into_iter()
+            .filter(|s| !manifest.contains_segment(s))
+            .collect())
     }
 
     pub(crate) async fn upload_segment(
         segment: ArchiveSegment,
         provider: Arc<dyn StorageProviderTrait>,
-        uploaded_hashes: Arc<RwLock<HashSet<String>>>,
+        manifest: Arc<RwLock<BackupManifest>>,
         compression_enabled: bool,
-    ) -> Result<()> {
-        // Check if already uploaded (deduplication)
+    ) -> Result<SegmentUpload> {
+        // Check if the same content was already uploaded (deduplication)
         {
-            let hashes = uploaded_hashes.read().await;
-            if hashes.contains(&segment.hash) {
+            let mut manifest = manifest.write().await;
+            if let Some((cid, compressed)) = manifest
+                .find_by_hash(&segment.hash)
+                .map(|(cid, compressed)| (cid.to_string(), compressed))
+            {
                 info!("Segment {} already uploaded, skipping", segment.filename);
-                return Ok(());
+                manifest.record(&segment, cid, compressed);
+                return Ok(SegmentUpload::Deduplicated);
             }
         }
 
@@ -116,7 +251,8 @@ impl BackupScheduler {
             .context("Failed to read segment")?;
 
         // Apply additional compression if enabled and not already compressed
-        if compression_enabled && !segment.filename.ends_with(".gz") {
+        let compressed = compression_enabled && !segment.filename.ends_with(".gz");
+        if compressed {
             data = compress_data(&data)?;
         }
 
@@ -132,6 +268,7 @@ impl BackupScheduler {
         };
 
         // Upload
+        let bytes = data.len() as u64;
         let cid = provider
             .upload(data, metadata)
             .await
@@ -139,13 +276,10 @@ impl BackupScheduler {
 
         info!("Uploaded {} -> {}", segment.filename, cid);
 
-        // Mark as uploaded
-        {
-            let mut hashes = uploaded_hashes.write().await;
-            hashes.insert(segment.hash.clone());
-        }
+        // Record in the manifest
+        manifest.write().await.record(&segment, cid, compressed);
 
-        Ok(())
+        Ok(SegmentUpload::Uploaded { bytes })
     }
 }
 
@@ -156,6 +290,14 @@ pub(crate) struct ArchiveSegment {
     pub(crate) hash: String,
     pub(crate) ledger: u64,
     pub(crate) segment_type: String,
+    /// Buckets referenced by a per-checkpoint history file (empty otherwise)
+    pub(crate) bucket_refs: Vec<String>,
+    /// Close time of the checkpoint's last ledger, read from its ledger file
+    pub(crate) closed_at: Option<DateTime<Utc>>,
+    /// File size in bytes
+    pub(crate) size: u64,
+    /// File modification time, where the filesystem reports one
+    pub(crate) modified: Option<DateTime<Utc>>,
 }
 
 pub(crate) fn compress_data(data: &[u8]) -> Result<Vec<u8>> {
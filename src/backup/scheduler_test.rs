@@ -8,7 +8,9 @@
 
 #[cfg(test)]
 mod tests {
-    use crate::backup::providers::{StorageProviderTrait, UploadMetadata};
+    use crate::backup::providers::{
+        RemovalOutcome, StorageProviderTrait, StoredObject, UploadMetadata,
+    };
     use crate::backup::scheduler::{compress_data, BackupScheduler};
     use crate::backup::*;
 
@@ -52,6 +54,18 @@ mod tests {
         async fn verify(&self, _cid: &str, _expected_hash: &str) -> Result<bool> {
             Ok(true)
         }
+
+        async fn download(&self, _cid: &str) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn list(&self) -> Result<Vec<StoredObject>> {
+            Ok(Vec::new())
+        }
+
+        async fn delete(&self, _cid: &str) -> Result<RemovalOutcome> {
+            Ok(RemovalOutcome::Removed)
+        }
     }
 
     // ---------------------------------------------------------------------
@@ -79,6 +93,7 @@ mod tests {
                 days: 30,
                 min_backups: 5,
             }),
+            archive_path: "history".to_string(),
         }
     }
 
@@ -90,12 +105,14 @@ mod tests {
                 pinning_service: Some(PinningService {
                     service_type: PinningServiceType::Pinata,
                     api_key_secret: "pinata-key".to_string(),
+                    endpoint: None,
                 }),
             },
             schedule: DAILY_MIDNIGHT_CRON.to_string(),
             max_concurrent_uploads: 5,
             compression_enabled: false,
             retention: None,
+            archive_path: "history".to_string(),
         }
     }
 
@@ -118,6 +135,7 @@ mod tests {
                 days: 90,
                 min_backups: 10,
             }),
+            archive_path: "history".to_string(),
         }
     }
 
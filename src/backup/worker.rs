@@ -0,0 +1,252 @@
+//! Backup worker run by the decentralized backup CronJob
+//!
+//! Each run reads `spec.decentralizedBackup` from its StellarNode, builds the
+//! storage provider with credentials injected from Secrets as environment
+//! variables, uploads the history archive segments not in the manifest yet and
+//! records the outcome in `status.backup`. The manifest is persisted in the
+//! `<node>-backup-manifest` ConfigMap so runs only upload new segments.
+//!
+//! [`restore_once`] reads the same manifest and provider configuration to
+//! rebuild the archive, for the `restore` subcommand.
+
+use super::manifest::{BackupManifest, ConfigMapManifestStore, ManifestStore};
+use super::providers::arweave::ArweaveProvider;
+use super::providers::filecoin::FilecoinProvider;
+use super::providers::filesystem::FilesystemProvider;
+use super::providers::ipfs::{IPFSProvider, PinningConfig};
+use super::providers::s3::{S3Config, S3Credentials, S3Provider};
+use super::providers::StorageProviderTrait;
+use super::restore::{restore_archive, RestoreReport};
+use super::scheduler::{BackupRunStats, BackupScheduler};
+use super::StorageProvider;
+use crate::crd::{DecentralizedBackupStatus, StellarNode};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use kube::api::{Api, Patch, PatchParams};
+use kube::Client;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// Secret key holding the Arweave wallet JWK
+pub const ARWEAVE_WALLET_KEY: &str = "wallet.json";
+/// Secret key holding the pinning service token
+pub const PINNING_API_KEY_KEY: &str = "apiKey";
+/// Secret keys holding the S3 credentials
+pub const S3_ACCESS_KEY_ID_KEY: &str = "accessKeyId";
+pub const S3_SECRET_ACCESS_KEY_KEY: &str = "secretAccessKey";
+
+/// Environment variables the CronJob fills from the provider's Secrets
+pub const ENV_ARWEAVE_WALLET: &str = "BACKUP_ARWEAVE_WALLET";
+pub const ENV_PINNING_API_KEY: &str = "BACKUP_PINNING_API_KEY";
+pub const ENV_S3_ACCESS_KEY_ID: &str = "BACKUP_S3_ACCESS_KEY_ID";
+pub const ENV_S3_SECRET_ACCESS_KEY: &str = "BACKUP_S3_SECRET_ACCESS_KEY";
+
+/// Name of the ConfigMap persisting a node's backup manifest
+pub fn manifest_configmap_name(node_name: &str) -> String {
+    format!("{node_name}-backup-manifest")
+}
+
+/// Build the storage provider, resolving credentials through `env`
+pub async fn build_provider(
+    provider: &StorageProvider,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<Arc<dyn StorageProviderTrait>> {
+    let require = |name: &str| {
+        env(name)
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| anyhow!("{name} is not set"))
+    };
+
+    Ok(match provider {
+        StorageProvider::Arweave { gateway, .. } => {
+            let wallet = serde_json::from_str(&require(ENV_ARWEAVE_WALLET)?)
+                .context("Arweave wallet is not a valid JWK")?;
+            Arc::new(ArweaveProvider::new(gateway.clone(), wallet).await?)
+        }
+        StorageProvider::IPFS {
+            api_url,
+            pinning_service,
+        } => {
+            let pinning = match pinning_service {
+                Some(service) => Some(PinningConfig {
+                    service_url: service
+                        .service_url()
+                        .context("Pinning service has no endpoint")?,
+                    api_key: require(ENV_PINNING_API_KEY)?,
+                }),
+                None => None,
+            };
+            Arc::new(IPFSProvider::new(api_url.clone(), pinning))
+        }
+        StorageProvider::Filecoin {
+            lotus_api,
+            wallet_address,
+            ..
+        } => Arc::new(FilecoinProvider::new(
+            lotus_api.clone(),
+            wallet_address.clone(),
+        )),
+        StorageProvider::S3 {
+            bucket,
+            region,
+            endpoint,
+            prefix,
+            force_path_style,
+            part_size,
+            ..
+        } => Arc::new(S3Provider::new(
+            S3Config {
+                bucket: bucket.clone(),
+                region: region.clone(),
+                endpoint: endpoint.clone(),
+                prefix: prefix.clone(),
+                force_path_style: *force_path_style,
+                part_size: usize::try_from(*part_size).context("S3 part_size is too large")?,
+            },
+            S3Credentials {
+                access_key_id: require(ENV_S3_ACCESS_KEY_ID)?,
+                secret_access_key: require(ENV_S3_SECRET_ACCESS_KEY)?,
+            },
+        )?),
+        StorageProvider::Filesystem { path, .. } => Arc::new(FilesystemProvider::new(path)),
+    })
+}
+
+/// Fold the outcome of a run into the previous backup status
+pub fn next_status(
+    previous: Option<&DecentralizedBackupStatus>,
+    started: DateTime<Utc>,
+    finished: DateTime<Utc>,
+    outcome: &Result<BackupRunStats>,
+    manifest: Option<&BackupManifest>,
+) -> DecentralizedBackupStatus {
+    let mut status = previous.cloned().unwrap_or_default();
+    status.last_run_time = Some(started.to_rfc3339_opts(SecondsFormat::Secs, true));
+    if let Some(manifest) = manifest {
+        status.files_backed_up = manifest.file_count() as u64;
+        status.latest_checkpoint = manifest.latest_checkpoint();
+    }
+
+    match outcome {
+        Ok(stats) => {
+            status.last_bytes_uploaded = stats.bytes_uploaded;
+            status.total_bytes_uploaded += stats.bytes_uploaded;
+            if stats.failed == 0 {
+                status.last_success_time =
+                    Some(finished.to_rfc3339_opts(SecondsFormat::Secs, true));
+                status.last_error = None;
+            } else {
+                status.last_error = Some(format!(
+                    "{} of {} segments failed to upload",
+                    stats.failed,
+                    stats.uploaded + stats.deduplicated + stats.failed
+                ));
+            }
+        }
+        Err(e) => {
+            status.last_bytes_uploaded = 0;
+            status.last_error = Some(format!("{e:#}"));
+        }
+    }
+    status
+}
+
+/// Back up the history archive of `node_name` once and report the outcome.
+///
+/// `data_dir` is where the node's data volume is mounted; the archive lives
+/// at `spec.decentralizedBackup.archivePath` below it.
+pub async fn run_once(
+    client: Client,
+    namespace: &str,
+    node_name: &str,
+    data_dir: &Path,
+) -> Result<()> {
+    let api: Api<StellarNode> = Api::namespaced(client.clone(), namespace);
+    let node = api
+        .get(node_name)
+        .await
+        .with_context(|| format!("Failed to get StellarNode {namespace}/{node_name}"))?;
+
+    let Some(config) = node.spec.decentralized_backup.clone() else {
+        bail!("StellarNode {namespace}/{node_name} has no spec.decentralizedBackup");
+    };
+    if !config.enabled {
+        info!("Decentralized backup is disabled for {namespace}/{node_name}, skipping");
+        return Ok(());
+    }
+
+    let started = Utc::now();
+    let archive_path = data_dir.join(&config.archive_path);
+    let store = Arc::new(ConfigMapManifestStore::new(
+        client,
+        namespace.to_string(),
+        manifest_configmap_name(node_name),
+    ));
+
+    let mut manifest = None;
+    let outcome = async {
+        let provider = build_provider(&config.provider, |name| std::env::var(name).ok()).await?;
+        let scheduler = BackupScheduler::new(config.clone(), provider).with_manifest_store(store);
+        scheduler.load_manifest().await?;
+        let result = scheduler.run_backup(&archive_path.to_string_lossy()).await;
+        manifest = Some(scheduler.manifest().await);
+        result
+    }
+    .await;
+
+    let status = next_status(
+        node.status.as_ref().and_then(|s| s.backup.as_ref()),
+        started,
+        Utc::now(),
+        &outcome,
+        manifest.as_ref(),
+    );
+    let patch = serde_json::json!({ "status": { "backup": status } });
+    api.patch_status(node_name, &PatchParams::default(), &Patch::Merge(&patch))
+        .await
+        .context("Failed to update backup status")?;
+
+    if let Some(error) = status.last_error {
+        error!("Backup of {namespace}/{node_name} failed: {error}");
+        bail!(error);
+    }
+    if let Ok(stats) = outcome {
+        info!(
+            "Backup of {namespace}/{node_name} uploaded {} segments ({} bytes), {} already stored",
+            stats.uploaded, stats.bytes_uploaded, stats.deduplicated
+        );
+    }
+    Ok(())
+}
+
+/// Restore the history archive of `node_name` into `dest` from its backup
+/// manifest, up to checkpoint `up_to` (everything when `None`)
+pub async fn restore_once(
+    client: Client,
+    namespace: &str,
+    node_name: &str,
+    dest: &Path,
+    up_to: Option<u64>,
+) -> Result<RestoreReport> {
+    let api: Api<StellarNode> = Api::namespaced(client.clone(), namespace);
+    let node = api
+        .get(node_name)
+        .await
+        .with_context(|| format!("Failed to get StellarNode {namespace}/{node_name}"))?;
+    let Some(config) = node.spec.decentralized_backup else {
+        bail!("StellarNode {namespace}/{node_name} has no spec.decentralizedBackup");
+    };
+
+    let store = ConfigMapManifestStore::new(
+        client,
+        namespace.to_string(),
+        manifest_configmap_name(node_name),
+    );
+    let manifest = store
+        .load()
+        .await
+        .context("Failed to load backup manifest")?;
+    let provider = build_provider(&config.provider, |name| std::env::var(name).ok()).await?;
+    restore_archive(provider.as_ref(), &manifest, dest, up_to).await
+}
@@ -0,0 +1,182 @@
+//! Unit tests for the backup worker
+//!
+//! Covers provider construction from Secret-backed environment variables and
+//! how run outcomes are folded into `status.backup`.
+
+#[cfg(test)]
+mod tests {
+    use crate::backup::manifest::BackupManifest;
+    use crate::backup::scheduler::BackupRunStats;
+    use crate::backup::worker::*;
+    use crate::backup::{PinningService, PinningServiceType, StorageProvider};
+    use crate::crd::DecentralizedBackupStatus;
+    use chrono::{TimeZone, Utc};
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    fn s3_provider() -> StorageProvider {
+        StorageProvider::S3 {
+            bucket: "stellar-history".to_string(),
+            region: "us-east-1".to_string(),
+            endpoint: Some("http://minio:9000".to_string()),
+            prefix: "validator-0/".to_string(),
+            credentials_secret: "backup-s3".to_string(),
+            force_path_style: true,
+            part_size: 16 * 1024 * 1024,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_build_s3_provider_requires_credentials() {
+        let err = build_provider(&s3_provider(), env(&[(ENV_S3_ACCESS_KEY_ID, "AKIA")]))
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains(ENV_S3_SECRET_ACCESS_KEY));
+
+        build_provider(
+            &s3_provider(),
+            env(&[
+                (ENV_S3_ACCESS_KEY_ID, "AKIA"),
+                (ENV_S3_SECRET_ACCESS_KEY, "secret"),
+            ]),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_build_provider_resolves_pinning_and_wallet() {
+        let ipfs = |service_type, endpoint: Option<&str>| StorageProvider::IPFS {
+            api_url: "http://ipfs:5001".to_string(),
+            pinning_service: Some(PinningService {
+                service_type,
+                api_key_secret: "pinning".to_string(),
+                endpoint: endpoint.map(String::from),
+            }),
+        };
+        let key = env(&[(ENV_PINNING_API_KEY, "token")]);
+
+        assert!(
+            build_provider(&ipfs(PinningServiceType::Pinata, None), &key)
+                .await
+                .is_ok()
+        );
+        // Infura has no Pinning Service API default
+        assert!(
+            build_provider(&ipfs(PinningServiceType::Infura, None), &key)
+                .await
+                .is_err()
+        );
+        assert!(build_provider(
+            &ipfs(PinningServiceType::Infura, Some("https://pins.example")),
+            &key
+        )
+        .await
+        .is_ok());
+
+        let arweave = StorageProvider::Arweave {
+            wallet_secret: "arweave-wallet".to_string(),
+            gateway: "https://arweave.net".to_string(),
+            tags: vec![],
+        };
+        assert!(
+            build_provider(&arweave, env(&[(ENV_ARWEAVE_WALLET, "not json")]))
+                .await
+                .is_err()
+        );
+        assert!(
+            build_provider(&arweave, env(&[(ENV_ARWEAVE_WALLET, r#"{"kty":"RSA"}"#)]))
+                .await
+                .is_ok()
+        );
+
+        // The filesystem provider needs no credentials
+        let filesystem = StorageProvider::Filesystem {
+            path: "/backups".to_string(),
+            claim_name: Some("backup-pvc".to_string()),
+        };
+        assert!(build_provider(&filesystem, env(&[])).await.is_ok());
+    }
+
+    #[test]
+    fn test_next_status_accumulates_successful_runs() {
+        let started = Utc.with_ymd_and_hms(2026, 3, 1, 6, 0, 0).unwrap();
+        let finished = Utc.with_ymd_and_hms(2026, 3, 1, 6, 2, 30).unwrap();
+        let previous = DecentralizedBackupStatus {
+            total_bytes_uploaded: 1000,
+            last_error: Some("connection refused".to_string()),
+            ..Default::default()
+        };
+        let outcome = Ok(BackupRunStats {
+            uploaded: 3,
+            deduplicated: 1,
+            failed: 0,
+            bytes_uploaded: 500,
+        });
+
+        let status = next_status(
+            Some(&previous),
+            started,
+            finished,
+            &outcome,
+            Some(&BackupManifest::default()),
+        );
+        assert_eq!(
+            status.last_run_time.as_deref(),
+            Some("2026-03-01T06:00:00Z")
+        );
+        assert_eq!(
+            status.last_success_time.as_deref(),
+            Some("2026-03-01T06:02:30Z")
+        );
+        assert_eq!(status.last_error, None);
+        assert_eq!(status.last_bytes_uploaded, 500);
+        assert_eq!(status.total_bytes_uploaded, 1500);
+    }
+
+    #[test]
+    fn test_next_status_keeps_last_success_on_failure() {
+        let previous = DecentralizedBackupStatus {
+            last_success_time: Some("2026-03-01T00:02:00Z".to_string()),
+            total_bytes_uploaded: 1000,
+            files_backed_up: 42,
+            latest_checkpoint: Some(127),
+            ..Default::default()
+        };
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 6, 0, 0).unwrap();
+
+        // Partial failure: bytes still count, the run is not a success
+        let partial = Ok(BackupRunStats {
+            uploaded: 1,
+            deduplicated: 0,
+            failed: 2,
+            bytes_uploaded: 10,
+        });
+        let status = next_status(Some(&previous), now, now, &partial, None);
+        assert_eq!(
+            status.last_error.as_deref(),
+            Some("2 of 3 segments failed to upload")
+        );
+        assert_eq!(status.last_success_time, previous.last_success_time);
+        assert_eq!(status.total_bytes_uploaded, 1010);
+
+        // A run that never loaded the manifest keeps the previous counts
+        let failed = Err(anyhow::anyhow!("BACKUP_S3_ACCESS_KEY_ID is not set"));
+        let status = next_status(Some(&previous), now, now, &failed, None);
+        assert_eq!(
+            status.last_error.as_deref(),
+            Some("BACKUP_S3_ACCESS_KEY_ID is not set")
+        );
+        assert_eq!(status.last_bytes_uploaded, 0);
+        assert_eq!(status.files_backed_up, 42);
+        assert_eq!(status.latest_checkpoint, Some(127));
+    }
+}
@@ -1,17 +1,33 @@
 //! Carbon intensity API integration
 
-use crate::carbon_aware::types::{CarbonIntensityData, CarbonProvider, RegionCarbonData};
+use crate::carbon_aware::timeseries::{self, IntensityPoint};
+use crate::carbon_aware::types::{
+    CarbonForecast, CarbonIntensityData, CarbonProvider, RegionCarbonData,
+};
 use crate::error::Result;
-use chrono::Utc;
-use reqwest::Client;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
 use serde_json::Value;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
 use tracing::{info, warn};
 
+/// WattTime tokens are valid for 30 minutes; renew them a little earlier
+const WATTTIME_TOKEN_TTL: Duration = Duration::from_secs(25 * 60);
+
+/// Converts lbs CO2/MWh, WattTime's unit, to gCO2/kWh
+const LBS_PER_MWH_TO_G_PER_KWH: f64 = 0.453_592;
+
 /// Carbon intensity API client
 #[derive(Clone)]
 pub struct CarbonIntensityAPI {
     client: Client,
     provider: CarbonProvider,
+    /// Cached WattTime login token and when it was issued
+    watttime_token: Arc<Mutex<Option<(String, Instant)>>>,
+    /// How long the last point of a time series counts as current
+    max_data_age: chrono::Duration,
 }
 
 impl CarbonIntensityAPI {
@@ -20,9 +36,18 @@ impl CarbonIntensityAPI {
         Self {
             client: Client::new(),
             provider,
+            watttime_token: Arc::new(Mutex::new(None)),
+            max_data_age: chrono::Duration::minutes(15),
         }
     }
 
+    /// Drop regions of a time series whose last point is older than
+    /// `minutes`, instead of reporting it as current until the file changes
+    pub fn with_max_data_age(mut self, minutes: i64) -> Self {
+        self.max_data_age = chrono::Duration::minutes(minutes);
+        self
+    }
+
     /// Fetch current carbon intensity data for all regions
     pub async fn fetch_all_regions(&self) -> Result<RegionCarbonData> {
         match &self.provider {
@@ -32,6 +57,17 @@ impl CarbonIntensityAPI {
             CarbonProvider::Custom { url, auth_header } => {
                 self.fetch_custom_data(url, auth_header).await
             }
+            CarbonProvider::WattTime {
+                url,
+                username,
+                password,
+                regions,
+                forecast_hours,
+            } => {
+                self.fetch_watttime_data(url, username, password, regions, *forecast_hours)
+                    .await
+            }
+            CarbonProvider::File { path } => self.fetch_file_data(path).await,
             CarbonProvider::Mock => self.fetch_mock_data().await,
         }
     }
@@ -175,6 +211,166 @@ impl CarbonIntensityAPI {
         })
     }
 
+    /// Fetch marginal emissions and their forecast from WattTime.
+    ///
+    /// The forecast starts at the current interval, so one request per region
+    /// yields both the current intensity and the forecast.
+    async fn fetch_watttime_data(
+        &self,
+        base_url: &str,
+        username: &str,
+        password: &str,
+        regions: &[String],
+        forecast_hours: u32,
+    ) -> Result<RegionCarbonData> {
+        let mut points = Vec::new();
+        let mut last_error = None;
+        for region in regions {
+            let query = [
+                ("region", region.clone()),
+                ("signal_type", "co2_moer".to_string()),
+                ("horizon_hours", forecast_hours.to_string()),
+            ];
+            let json = match self
+                .watttime_get(base_url, username, password, "/v3/forecast", &query)
+                .await
+            {
+                Ok(json) => json,
+                Err(e) => {
+                    warn!(
+                        "Failed to fetch WattTime forecast for region {}: {}",
+                        region, e
+                    );
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+            let region_points = parse_watttime_forecast(region, &json);
+            if region_points.is_empty() {
+                warn!("WattTime returned no forecast for region {}", region);
+            }
+            points.extend(region_points);
+        }
+        // Keep the previous data when no region could be fetched
+        if let (true, Some(e)) = (points.is_empty(), last_error) {
+            return Err(e);
+        }
+
+        let region_data =
+            timeseries::to_region_data(points, "WattTime", Utc::now(), self.max_data_age);
+        info!(
+            "Fetched carbon intensity data for {} regions from WattTime",
+            region_data.regions.len()
+        );
+        Ok(region_data)
+    }
+
+    /// GET a WattTime endpoint, logging in again once if the token was rejected
+    async fn watttime_get(
+        &self,
+        base_url: &str,
+        username: &str,
+        password: &str,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<Value> {
+        let url = format!("{}{}", base_url, path);
+        let token = self
+            .watttime_login(base_url, username, password, false)
+            .await?;
+        let mut response = self
+            .client
+            .get(&url)
+            .bearer_auth(&token)
+            .query(query)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::UNAUTHORIZED {
+            let token = self
+                .watttime_login(base_url, username, password, true)
+                .await?;
+            response = self
+                .client
+                .get(&url)
+                .bearer_auth(&token)
+                .query(query)
+                .send()
+                .await?;
+        }
+
+        if !response.status().is_success() {
+            return Err(crate::error::Error::NetworkError(format!(
+                "WattTime API error: {}",
+                response.status()
+            )));
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Return the cached WattTime token, logging in when it is missing,
+    /// expired or `renew` is set
+    async fn watttime_login(
+        &self,
+        base_url: &str,
+        username: &str,
+        password: &str,
+        renew: bool,
+    ) -> Result<String> {
+        let mut cached = self.watttime_token.lock().await;
+        if let Some((token, issued)) = cached.as_ref() {
+            if !renew && issued.elapsed() < WATTTIME_TOKEN_TTL {
+                return Ok(token.clone());
+            }
+        }
+
+        let response = self
+            .client
+            .get(format!("{}/login", base_url))
+            .basic_auth(username, Some(password))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(crate::error::Error::NetworkError(format!(
+                "WattTime login failed: {}",
+                response.status()
+            )));
+        }
+
+        let json: Value = response.json().await?;
+        let token = json
+            .get("token")
+            .and_then(|t| t.as_str())
+            .ok_or_else(|| {
+                crate::error::Error::NetworkError("WattTime login returned no token".to_string())
+            })?
+            .to_string();
+        *cached = Some((token.clone(), Instant::now()));
+        Ok(token)
+    }
+
+    /// Read a per-region time series from a file.
+    ///
+    /// The file is read on every refresh, so edits to a mounted ConfigMap are
+    /// picked up once the kubelet syncs them.
+    async fn fetch_file_data(&self, path: &str) -> Result<RegionCarbonData> {
+        let contents = tokio::fs::read_to_string(path).await.map_err(|e| {
+            crate::error::Error::ConfigError(format!(
+                "Failed to read carbon intensity file {}: {}",
+                path, e
+            ))
+        })?;
+        let points = timeseries::parse_file(path, &contents)?;
+        let region_data = timeseries::to_region_data(points, "File", Utc::now(), self.max_data_age);
+
+        info!(
+            "Loaded carbon intensity data for {} regions from {}",
+            region_data.regions.len(),
+            path
+        );
+        Ok(region_data)
+    }
+
     /// Generate mock data for testing
     async fn fetch_mock_data(&self) -> Result<RegionCarbonData> {
         let mut region_data = RegionCarbonData::new();
@@ -199,7 +395,7 @@ impl CarbonIntensityAPI {
                 timestamp: now,
                 source: "Mock".to_string(),
                 renewable_percentage: renewable,
-                forecast: None,
+                forecast: Some(mock_forecast(intensity, now)),
             };
             region_data.update_region(carbon_data);
         }
@@ -223,7 +419,71 @@ impl CarbonIntensityAPI {
                 let response = self.client.get(url).send().await?;
                 Ok(response.status().is_success())
             }
+            CarbonProvider::WattTime {
+                url,
+                username,
+                password,
+                ..
+            } => Ok(self
+                .watttime_login(url, username, password, false)
+                .await
+                .is_ok()),
+            CarbonProvider::File { path } => Ok(tokio::fs::metadata(path).await.is_ok()),
             CarbonProvider::Mock => Ok(true),
         }
     }
 }
+
+/// 24 hourly points, cleaner during daylight hours and less certain further out
+fn mock_forecast(base_intensity: f64, now: DateTime<Utc>) -> Vec<CarbonForecast> {
+    (1..=24)
+        .map(|hour| {
+            let variation = if (8..=18).contains(&hour) {
+                -20.0
+            } else {
+                30.0
+            };
+            CarbonForecast {
+                timestamp: now + chrono::Duration::hours(hour),
+                carbon_intensity: (base_intensity + variation).max(10.0),
+                confidence: (0.8 - hour as f64 * 0.02).max(0.5),
+            }
+        })
+        .collect()
+}
+
+/// Convert a WattTime `/v3/forecast` response into intensity points in
+/// gCO2/kWh. Points that cannot be parsed are skipped.
+pub fn parse_watttime_forecast(region: &str, json: &Value) -> Vec<IntensityPoint> {
+    let units = json
+        .pointer("/meta/units")
+        .and_then(|u| u.as_str())
+        .unwrap_or("lbs_co2_per_mwh");
+    let factor = if units == "lbs_co2_per_mwh" {
+        LBS_PER_MWH_TO_G_PER_KWH
+    } else {
+        1.0
+    };
+
+    json.get("data")
+        .and_then(|d| d.as_array())
+        .map(|data| {
+            data.iter()
+                .filter_map(|item| {
+                    let timestamp = item
+                        .get("point_time")
+                        .and_then(|t| t.as_str())
+                        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())?
+                        .with_timezone(&Utc);
+                    let value = item.get("value").and_then(|v| v.as_f64())?;
+                    Some(IntensityPoint {
+                        region: region.to_string(),
+                        timestamp,
+                        carbon_intensity: value * factor,
+                        renewable_percentage: None,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
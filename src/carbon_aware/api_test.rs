@@ -0,0 +1,151 @@
+//! Tests for the WattTime and file carbon intensity providers.
+
+#[cfg(test)]
+mod tests {
+    use crate::carbon_aware::api::parse_watttime_forecast;
+    use crate::carbon_aware::timeseries::{parse_csv, parse_file, parse_json, to_region_data};
+    use crate::carbon_aware::{CarbonAwareConfig, CarbonIntensityAPI, CarbonProvider};
+    use chrono::{Duration, TimeZone, Utc};
+
+    const CSV: &str = "\
+# exported from the grid operator
+region,timestamp,carbon_intensity,renewable_percentage
+DE,2026-03-01T10:00:00Z,320,22
+DE,2026-03-01T11:00:00Z,300,
+DE,2026-03-01T12:00:00Z,250,40
+
+FR,2026-03-01T13:00:00Z,55,70
+";
+
+    #[test]
+    fn test_csv_and_json_time_series() {
+        let points = parse_csv(CSV).unwrap();
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[0].region, "DE");
+        assert_eq!(points[0].renewable_percentage, Some(22.0));
+        assert_eq!(points[1].renewable_percentage, None);
+        assert_eq!(
+            points[3].timestamp,
+            Utc.with_ymd_and_hms(2026, 3, 1, 13, 0, 0).unwrap()
+        );
+
+        // Columns may be reordered and the renewable column omitted
+        let reordered =
+            parse_csv("timestamp,carbon_intensity,region\n2026-03-01T10:00:00Z,80,SE\n");
+        assert_eq!(reordered.unwrap()[0].carbon_intensity, 80.0);
+
+        assert!(parse_csv("").is_err());
+        assert!(parse_csv("region,timestamp\n").is_err());
+        assert!(parse_csv("region,timestamp,carbon_intensity\nDE,yesterday,300\n").is_err());
+        assert!(parse_csv("region,timestamp,carbon_intensity\nDE,2026-03-01T10:00:00Z\n").is_err());
+
+        let json = r#"[
+            {"region": "DE", "timestamp": "2026-03-01T10:00:00Z", "carbonIntensity": 320},
+            {"region": "FR", "timestamp": "2026-03-01T10:00:00Z", "carbonIntensity": 55, "renewablePercentage": 70}
+        ]"#;
+        let points = parse_json(json).unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].renewable_percentage, Some(70.0));
+        assert_eq!(parse_file("/config/intensity.JSON", json).unwrap(), points);
+        assert!(parse_file("/config/intensity.yaml", json).is_err());
+    }
+
+    #[test]
+    fn test_series_splits_into_current_and_forecast() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 11, 30, 0).unwrap();
+        let data = to_region_data(parse_csv(CSV).unwrap(), "File", now, Duration::minutes(15));
+
+        let de = data.get_region("DE").unwrap();
+        assert_eq!(de.carbon_intensity, 300.0);
+        assert_eq!(
+            de.timestamp,
+            Utc.with_ymd_and_hms(2026, 3, 1, 11, 0, 0).unwrap()
+        );
+        assert_eq!(de.source, "File");
+        let forecast = de.forecast.as_ref().unwrap();
+        assert_eq!(forecast.len(), 1);
+        assert_eq!(forecast[0].carbon_intensity, 250.0);
+
+        // Only future points: the earliest one is current
+        let fr = data.get_region("FR").unwrap();
+        assert_eq!(fr.carbon_intensity, 55.0);
+        assert!(fr.forecast.as_ref().unwrap().is_empty());
+
+        // Once every point is in the past, the last one is only current for
+        // the maximum data age
+        let later = Utc.with_ymd_and_hms(2026, 3, 1, 13, 10, 0).unwrap();
+        let data = to_region_data(
+            parse_csv(CSV).unwrap(),
+            "File",
+            later,
+            Duration::minutes(15),
+        );
+        assert!(data.get_region("DE").is_none());
+        assert_eq!(data.get_region("FR").unwrap().carbon_intensity, 55.0);
+        let data = to_region_data(parse_csv(CSV).unwrap(), "File", later, Duration::hours(2));
+        assert_eq!(data.get_region("DE").unwrap().carbon_intensity, 250.0);
+    }
+
+    #[test]
+    fn test_watttime_forecast_is_converted_to_grams_per_kwh() {
+        let json = serde_json::json!({
+            "data": [
+                {"point_time": "2026-03-01T10:05:00+00:00", "value": 1000.0},
+                {"point_time": "2026-03-01T10:00:00+00:00", "value": 900.0},
+                {"point_time": "not a time", "value": 1.0},
+            ],
+            "meta": {"region": "CAISO_NORTH", "signal_type": "co2_moer", "units": "lbs_co2_per_mwh"},
+        });
+        let points = parse_watttime_forecast("CAISO_NORTH", &json);
+        assert_eq!(points.len(), 2);
+        assert!((points[0].carbon_intensity - 453.592).abs() < 1e-9);
+
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 10, 2, 0).unwrap();
+        let data = to_region_data(points, "WattTime", now, Duration::minutes(15));
+        let region = data.get_region("CAISO_NORTH").unwrap();
+        assert!((region.carbon_intensity - 408.2328).abs() < 1e-9);
+        assert_eq!(region.forecast.as_ref().unwrap().len(), 1);
+
+        assert!(parse_watttime_forecast("CAISO_NORTH", &serde_json::json!({})).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_file_provider_reads_configured_path() {
+        let path = std::env::temp_dir().join(format!("carbon-{}.csv", std::process::id()));
+        let now = Utc::now();
+        let csv = format!(
+            "region,timestamp,carbon_intensity\nDE,{},300\nFR,{},55\n",
+            now.to_rfc3339(),
+            (now + Duration::hours(1)).to_rfc3339()
+        );
+        std::fs::write(&path, csv).unwrap();
+
+        let config = CarbonAwareConfig::from_yaml(&format!(
+            "provider:\n  type: file\n  path: {}\n",
+            path.display()
+        ))
+        .unwrap();
+        let api = CarbonIntensityAPI::new(config.provider);
+        assert!(api.health_check().await.unwrap());
+        let data = api.fetch_all_regions().await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(data.regions.len(), 2);
+        assert!(!api.health_check().await.unwrap());
+        assert!(api.fetch_all_regions().await.is_err());
+    }
+
+    #[test]
+    fn test_watttime_config_defaults() {
+        let config = CarbonAwareConfig::from_yaml(
+            "provider:\n  type: wattTime\n  username: grid\n  password: secret\n  regions: [CAISO_NORTH]\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            config.provider,
+            CarbonProvider::WattTime { ref url, ref regions, forecast_hours: 24, .. }
+                if url == "https://api.watttime.org" && regions == &["CAISO_NORTH"]
+        ));
+        assert!(CarbonAwareConfig::from_yaml("provider:\n  type: wattTime\n").is_err());
+    }
+}
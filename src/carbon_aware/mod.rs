@@ -4,9 +4,21 @@
 //! to optimize Stellar node placement for minimal CO2 footprint.
 
 pub mod api;
+#[cfg(test)]
+mod api_test;
 pub mod scheduler;
+#[cfg(test)]
+mod scheduler_test;
+pub mod timeseries;
 pub mod types;
+pub mod window;
+#[cfg(test)]
+mod window_test;
 
 pub use api::CarbonIntensityAPI;
-pub use scheduler::CarbonAwareScheduler;
-pub use types::{CarbonIntensityData, RegionCarbonData};
+pub use scheduler::{CarbonAwareScheduler, CarbonDataStore};
+pub use types::{
+    BatchJobKind, CarbonAwareConfig, CarbonIntensityData, CarbonProvider, DeferralConfig,
+    RegionCarbonData,
+};
+pub use window::{CarbonWindowDecision, CarbonWindowService, CarbonWindowVerdict};
@@ -2,36 +2,147 @@
 
 use crate::carbon_aware::api::CarbonIntensityAPI;
 use crate::carbon_aware::types::{CarbonAwareConfig, RegionCarbonData};
-use crate::error::Result;
-use k8s_openapi::api::core::v1::{Node, Pod};
-use kube::{Client, ResourceExt};
+use crate::error::{Error, Result};
+use k8s_openapi::api::core::v1::{ConfigMap, Node, Pod};
+use kube::api::{Api, Patch, PatchParams};
+use kube::ResourceExt;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+/// ConfigMap key holding the leader's carbon data as JSON
+pub const CARBON_DATA_KEY: &str = "data.json";
+
+/// ConfigMap through which the leader shares carbon data with standby replicas
+#[derive(Clone)]
+pub struct CarbonDataStore {
+    api: Api<ConfigMap>,
+    name: String,
+}
+
+impl CarbonDataStore {
+    pub fn new(client: kube::Client, namespace: &str, name: impl Into<String>) -> Self {
+        Self {
+            api: Api::namespaced(client, namespace),
+            name: name.into(),
+        }
+    }
+
+    /// Publish the leader's carbon data
+    pub async fn save(&self, data: &RegionCarbonData) -> Result<()> {
+        let json = serde_json::to_string(data).map_err(|e| {
+            Error::ConfigError(format!("Failed to serialize carbon intensity data: {e}"))
+        })?;
+        let cm = ConfigMap {
+            metadata: kube::api::ObjectMeta {
+                name: Some(self.name.clone()),
+                labels: Some(BTreeMap::from([
+                    ("app".to_string(), "stellar-operator".to_string()),
+                    ("component".to_string(), "carbon-data".to_string()),
+                ])),
+                ..Default::default()
+            },
+            data: Some(BTreeMap::from([(CARBON_DATA_KEY.to_string(), json)])),
+            ..Default::default()
+        };
+        self.api
+            .patch(
+                &self.name,
+                &PatchParams::apply("stellar-operator").force(),
+                &Patch::Apply(&cm),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Carbon data last published by the leader, if any
+    pub async fn load(&self) -> Result<Option<RegionCarbonData>> {
+        let Some(cm) = self.api.get_opt(&self.name).await? else {
+            return Ok(None);
+        };
+        cm.data
+            .and_then(|mut d| d.remove(CARBON_DATA_KEY))
+            .map(|json| {
+                serde_json::from_str(&json).map_err(|e| {
+                    Error::ConfigError(format!(
+                        "Invalid carbon intensity data in ConfigMap {}: {e}",
+                        self.name
+                    ))
+                })
+            })
+            .transpose()
+    }
+}
+
 /// Carbon-aware scheduler that enhances node scoring with carbon intensity
 pub struct CarbonAwareScheduler {
     /// Carbon intensity API client
     api: CarbonIntensityAPI,
     /// Configuration
     config: CarbonAwareConfig,
-    /// Cached carbon intensity data
+    /// Cached carbon intensity data, shared with the sustainability API
     carbon_data: Arc<RwLock<RegionCarbonData>>,
-    /// Kubernetes client
-    _kube_client: Client,
+    /// Only refresh while set
+    is_leader: Option<Arc<AtomicBool>>,
+    /// Where the leader publishes its data for standby replicas
+    store: Option<CarbonDataStore>,
 }
 
 impl CarbonAwareScheduler {
     /// Create new carbon-aware scheduler
-    pub fn new(api: CarbonIntensityAPI, config: CarbonAwareConfig, kube_client: Client) -> Self {
+    pub fn new(api: CarbonIntensityAPI, config: CarbonAwareConfig) -> Self {
         Self {
             api,
             config,
             carbon_data: Arc::new(RwLock::new(RegionCarbonData::new())),
-            _kube_client: kube_client,
+            is_leader: None,
+            store: None,
         }
     }
 
+    /// Only call the carbon intensity API while `is_leader` is set, so
+    /// replicas do not multiply the provider's request quota.
+    pub fn with_leader_election(mut self, is_leader: Arc<AtomicBool>) -> Self {
+        self.is_leader = Some(is_leader);
+        self
+    }
+
+    /// Share carbon data through a ConfigMap: the leader writes every
+    /// refresh to it and standby replicas read it, so they can serve the
+    /// sustainability API and score nodes without calling the provider.
+    pub fn with_shared_store(mut self, store: CarbonDataStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Carbon intensity API client
+    pub fn api(&self) -> &CarbonIntensityAPI {
+        &self.api
+    }
+
+    /// Configuration
+    pub fn config(&self) -> &CarbonAwareConfig {
+        &self.config
+    }
+
+    /// Shared carbon intensity data kept current by the refresh loop
+    pub fn carbon_data(&self) -> Arc<RwLock<RegionCarbonData>> {
+        self.carbon_data.clone()
+    }
+
+    /// Whether there is carbon data recent enough to score with
+    pub async fn has_fresh_data(&self) -> bool {
+        let data = self.carbon_data.read().await;
+        !data.regions.is_empty() && !data.is_stale(self.config.max_data_age_minutes)
+    }
+
+    /// Fetch carbon intensity data once and replace the shared data
+    pub async fn refresh(&self) -> Result<()> {
+        refresh_carbon_data(&self.api, &self.carbon_data).await
+    }
+
     /// Start background carbon data refresh
     pub async fn start_refresh_loop(&self) -> Result<()> {
         if !self.config.enabled {
@@ -40,25 +151,35 @@ impl CarbonAwareScheduler {
         }
 
         let api = self.api.clone();
-        let _config = self.config.clone();
         let carbon_data = self.carbon_data.clone();
+        let is_leader = self.is_leader.clone();
+        let store = self.store.clone();
+        let period = tokio::time::Duration::from_secs(self.config.refresh_interval_seconds.max(1));
 
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(
-                tokio::time::Duration::from_secs(60), // Refresh every minute
-            );
+            let mut interval = tokio::time::interval(period);
 
             loop {
                 interval.tick().await;
 
-                match api.fetch_all_regions().await {
-                    Ok(data) => {
-                        let mut guard = carbon_data.write().await;
-                        *guard = data;
-                        debug!("Refreshed carbon intensity data");
+                if !is_leader.as_ref().is_none_or(|l| l.load(Ordering::Relaxed)) {
+                    if let Some(store) = &store {
+                        match store.load().await {
+                            Ok(Some(data)) => *carbon_data.write().await = data,
+                            Ok(None) => debug!("Leader has not published carbon data yet"),
+                            Err(e) => warn!("Failed to load shared carbon data: {}", e),
+                        }
                     }
-                    Err(e) => {
-                        warn!("Failed to refresh carbon intensity data: {}", e);
+                    continue;
+                }
+                if let Err(e) = refresh_carbon_data(&api, &carbon_data).await {
+                    warn!("Failed to refresh carbon intensity data: {}", e);
+                    continue;
+                }
+                if let Some(store) = &store {
+                    let data = carbon_data.read().await.clone();
+                    if let Err(e) = store.save(&data).await {
+                        warn!("Failed to publish carbon data: {}", e);
                     }
                 }
             }
@@ -106,7 +227,8 @@ impl CarbonAwareScheduler {
         let region = self.extract_node_region(node);
 
         if let Some(region) = region {
-            if let Some(carbon_info) = carbon_data.get_region(&region) {
+            let zone = self.config.zone_for_region(&region);
+            if let Some(carbon_info) = carbon_data.get_region(zone) {
                 // Convert carbon intensity to score (lower intensity = higher score)
                 // Normalize to 0-1 range where 1 is best (lowest carbon)
                 let max_intensity = 1000.0; // gCO2/kWh - reasonable upper bound
@@ -331,6 +453,16 @@ impl CarbonAwareScheduler {
     }
 }
 
+async fn refresh_carbon_data(
+    api: &CarbonIntensityAPI,
+    carbon_data: &RwLock<RegionCarbonData>,
+) -> Result<()> {
+    let data = api.fetch_all_regions().await?;
+    *carbon_data.write().await = data;
+    debug!("Refreshed carbon intensity data");
+    Ok(())
+}
+
 /// Carbon statistics for dashboard
 #[derive(Clone, Debug)]
 pub struct CarbonStats {
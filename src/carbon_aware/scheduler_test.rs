@@ -0,0 +1,120 @@
+//! Tests for carbon-aware configuration and node scoring.
+
+#[cfg(test)]
+mod tests {
+    use crate::carbon_aware::{
+        CarbonAwareConfig, CarbonAwareScheduler, CarbonIntensityAPI, CarbonProvider,
+    };
+    use k8s_openapi::api::core::v1::{Node, Pod};
+    use kube::api::ObjectMeta;
+    use std::collections::BTreeMap;
+
+    fn node(name: &str, region: &str) -> Node {
+        Node {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                labels: Some(BTreeMap::from([(
+                    "topology.kubernetes.io/region".to_string(),
+                    region.to_string(),
+                )])),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn read_replica() -> Pod {
+        Pod {
+            metadata: ObjectMeta {
+                name: Some("horizon-read-0".to_string()),
+                labels: Some(BTreeMap::from([(
+                    "stellar.org/role".to_string(),
+                    "read-replica".to_string(),
+                )])),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn mock_scheduler(config: CarbonAwareConfig) -> CarbonAwareScheduler {
+        CarbonAwareScheduler::new(CarbonIntensityAPI::new(CarbonProvider::Mock), config)
+    }
+
+    #[test]
+    fn test_config_selects_provider_and_keeps_defaults() {
+        let config = CarbonAwareConfig::from_yaml(
+            r#"
+provider:
+  type: custom
+  url: https://carbon.example/regions
+  authHeader: Bearer abc
+refreshIntervalSeconds: 300
+regionZones:
+  us-west-2: US-NW-PACW
+"#,
+        )
+        .unwrap();
+
+        assert!(config.enabled);
+        assert!(matches!(
+            config.provider,
+            CarbonProvider::Custom { ref url, auth_header: Some(ref auth) }
+                if url == "https://carbon.example/regions" && auth == "Bearer abc"
+        ));
+        assert_eq!(config.refresh_interval_seconds, 300);
+        assert_eq!(config.max_data_age_minutes, 15);
+        assert_eq!(config.zone_for_region("us-west-2"), "US-NW-PACW");
+        assert_eq!(config.zone_for_region("DE"), "DE");
+
+        let mock = CarbonAwareConfig::from_yaml("provider:\n  type: mock\n").unwrap();
+        assert!(matches!(mock.provider, CarbonProvider::Mock));
+
+        assert!(CarbonAwareConfig::from_yaml("provider:\n  type: unknown\n").is_err());
+        assert!(!CarbonAwareConfig::disabled().enabled);
+    }
+
+    #[tokio::test]
+    async fn test_scoring_uses_refreshed_shared_data() {
+        let mut config = CarbonAwareConfig::default();
+        config
+            .region_zones
+            .insert("us-west-2".into(), "US-WA".into());
+        config
+            .region_zones
+            .insert("eu-central-1".into(), "DE".into());
+        let scheduler = mock_scheduler(config);
+        assert!(!scheduler.has_fresh_data().await);
+
+        scheduler.refresh().await.unwrap();
+        assert!(scheduler.has_fresh_data().await);
+        // The data is shared, not copied
+        assert!(scheduler
+            .carbon_data()
+            .read()
+            .await
+            .get_region("FR")
+            .is_some());
+
+        let frankfurt = node("node-a", "eu-central-1");
+        let oregon = node("node-b", "us-west-2");
+        let scored = scheduler
+            .score_nodes_carbon_aware(&read_replica(), &[&frankfurt, &oregon])
+            .await
+            .unwrap();
+
+        assert_eq!(scored[0].1.metadata.name.as_deref(), Some("node-b"));
+        assert!((scored[0].0 - 0.92).abs() < 1e-9);
+        assert!((scored[1].0 - 0.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_only_opted_in_pods_are_scheduled_carbon_aware() {
+        let scheduler = mock_scheduler(CarbonAwareConfig::default());
+        assert!(scheduler.should_schedule_carbon_aware(&read_replica()));
+        assert!(!scheduler.should_schedule_carbon_aware(&Pod::default()));
+
+        let disabled = mock_scheduler(CarbonAwareConfig::disabled());
+        assert!(!disabled.should_schedule_carbon_aware(&read_replica()));
+    }
+}
@@ -0,0 +1,161 @@
+//! Per-region carbon intensity time series
+//!
+//! Used by the file provider and by WattTime, whose forecasts arrive as a
+//! series of points. The latest point at or before now becomes the region's
+//! current intensity and later points become its forecast.
+
+use crate::carbon_aware::types::{CarbonForecast, CarbonIntensityData, RegionCarbonData};
+use crate::error::{Error, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::debug;
+
+/// One carbon intensity sample for a region
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntensityPoint {
+    /// Region identifier
+    pub region: String,
+    /// Time the intensity applies to
+    pub timestamp: DateTime<Utc>,
+    /// Carbon intensity in gCO2/kWh
+    pub carbon_intensity: f64,
+    /// Renewable energy percentage (0-100)
+    #[serde(default)]
+    pub renewable_percentage: Option<f64>,
+}
+
+/// Parse a time series file, choosing the format from its extension
+pub fn parse_file(path: &str, contents: &str) -> Result<Vec<IntensityPoint>> {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("csv") => parse_csv(contents),
+        Some("json") => parse_json(contents),
+        _ => Err(Error::ConfigError(format!(
+            "Carbon intensity file {path} must have a .csv or .json extension"
+        ))),
+    }
+}
+
+/// Parse a JSON array of `{region, timestamp, carbonIntensity, renewablePercentage}`
+pub fn parse_json(contents: &str) -> Result<Vec<IntensityPoint>> {
+    serde_json::from_str(contents)
+        .map_err(|e| Error::ConfigError(format!("Invalid carbon intensity JSON: {e}")))
+}
+
+/// Parse CSV with a `region,timestamp,carbon_intensity[,renewable_percentage]`
+/// header. Columns may be in any order; blank lines and `#` comments are
+/// skipped.
+pub fn parse_csv(contents: &str) -> Result<Vec<IntensityPoint>> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| Error::ConfigError("Carbon intensity CSV is empty".to_string()))?;
+    let columns: Vec<&str> = header.split(',').map(str::trim).collect();
+    let column = |name: &str| columns.iter().position(|c| *c == name);
+    let missing =
+        |name: &str| Error::ConfigError(format!("Carbon intensity CSV has no {name} column"));
+    let region_col = column("region").ok_or_else(|| missing("region"))?;
+    let timestamp_col = column("timestamp").ok_or_else(|| missing("timestamp"))?;
+    let intensity_col = column("carbon_intensity").ok_or_else(|| missing("carbon_intensity"))?;
+    let renewable_col = column("renewable_percentage");
+
+    lines
+        .map(|(line_no, line)| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let field = |col: usize| {
+                fields.get(col).copied().ok_or_else(|| {
+                    Error::ConfigError(format!(
+                        "Carbon intensity CSV line {line_no}: too few fields"
+                    ))
+                })
+            };
+            let invalid = |what: &str, value: &str| {
+                Error::ConfigError(format!(
+                    "Carbon intensity CSV line {line_no}: invalid {what} {value:?}"
+                ))
+            };
+
+            let timestamp = field(timestamp_col)?;
+            let intensity = field(intensity_col)?;
+            let renewable_percentage = match renewable_col.map(field).transpose()? {
+                Some("") | None => None,
+                Some(value) => Some(
+                    value
+                        .parse()
+                        .map_err(|_| invalid("renewable_percentage", value))?,
+                ),
+            };
+
+            Ok(IntensityPoint {
+                region: field(region_col)?.to_string(),
+                timestamp: DateTime::parse_from_rfc3339(timestamp)
+                    .map_err(|_| invalid("timestamp", timestamp))?
+                    .with_timezone(&Utc),
+                carbon_intensity: intensity
+                    .parse()
+                    .map_err(|_| invalid("carbon_intensity", intensity))?,
+                renewable_percentage,
+            })
+        })
+        .collect()
+}
+
+/// Group points by region into current intensity and forecast.
+///
+/// A region whose points all lie in the future uses its earliest point as
+/// the current intensity. A region whose points all lie more than `max_age`
+/// in the past is left out, since nothing says its last point still holds.
+/// Forecast points carry confidence 1.0, since neither source publishes one.
+pub fn to_region_data(
+    points: Vec<IntensityPoint>,
+    source: &str,
+    now: DateTime<Utc>,
+    max_age: Duration,
+) -> RegionCarbonData {
+    let mut by_region: HashMap<String, Vec<IntensityPoint>> = HashMap::new();
+    for point in points {
+        by_region
+            .entry(point.region.clone())
+            .or_default()
+            .push(point);
+    }
+
+    let mut region_data = RegionCarbonData::new();
+    for (region, mut series) in by_region {
+        series.sort_by_key(|p| p.timestamp);
+        let current_idx = series.iter().rposition(|p| p.timestamp <= now).unwrap_or(0);
+        if current_idx + 1 == series.len() && now - series[current_idx].timestamp > max_age {
+            debug!("Skipping region {} whose latest point is too old", region);
+            continue;
+        }
+        let forecast = series[current_idx + 1..]
+            .iter()
+            .map(|p| CarbonForecast {
+                timestamp: p.timestamp,
+                carbon_intensity: p.carbon_intensity,
+                confidence: 1.0,
+            })
+            .collect();
+        let current = &series[current_idx];
+
+        region_data.update_region(CarbonIntensityData {
+            region,
+            carbon_intensity: current.carbon_intensity,
+            timestamp: current.timestamp,
+            source: source.to_string(),
+            renewable_percentage: current.renewable_percentage,
+            forecast: Some(forecast),
+        });
+    }
+    region_data
+}
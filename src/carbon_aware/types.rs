@@ -33,7 +33,7 @@ pub struct CarbonForecast {
 }
 
 /// Regional carbon data mapping
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct RegionCarbonData {
     /// Map of region to carbon intensity data
     pub regions: HashMap<String, CarbonIntensityData>,
@@ -90,8 +90,12 @@ impl RegionCarbonData {
     }
 }
 
+/// Environment variable supplying the provider token when the config omits it
+pub const CARBON_API_TOKEN_ENV: &str = "CARBON_API_TOKEN";
+
 /// Carbon-aware scheduling configuration
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct CarbonAwareConfig {
     /// Enable carbon-aware scheduling
     pub enabled: bool,
@@ -103,6 +107,13 @@ pub struct CarbonAwareConfig {
     pub carbon_weight: f64,
     /// Minimum carbon intensity difference to consider migration
     pub migration_threshold: f64,
+    /// How often the leader refreshes carbon intensity data
+    pub refresh_interval_seconds: u64,
+    /// Provider zone for each cloud region (e.g. "us-west-2" -> "US-NW-PACW").
+    /// Regions without an entry are looked up under their own name.
+    pub region_zones: HashMap<String, String>,
+    /// Deferral of operator-initiated batch work into low-carbon windows
+    pub deferral: DeferralConfig,
 }
 
 impl Default for CarbonAwareConfig {
@@ -113,18 +124,154 @@ impl Default for CarbonAwareConfig {
             max_data_age_minutes: 15,
             carbon_weight: 0.7,
             migration_threshold: 50.0, // gCO2/kWh
+            refresh_interval_seconds: 60,
+            region_zones: HashMap::new(),
+            deferral: DeferralConfig::default(),
+        }
+    }
+}
+
+impl CarbonAwareConfig {
+    /// Configuration used when the operator is given none
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Default::default()
+        }
+    }
+
+    /// Parse the carbon-aware block of the operator configuration.
+    ///
+    /// An ElectricityMap token or WattTime password left empty is read from
+    /// `CARBON_API_TOKEN`, so it can come from a Secret rather than the
+    /// config file.
+    pub fn from_yaml(yaml: &str) -> crate::error::Result<Self> {
+        let mut config: Self = serde_yaml::from_str(yaml).map_err(|e| {
+            crate::error::Error::ConfigError(format!("Invalid carbon-aware configuration: {e}"))
+        })?;
+        match &mut config.provider {
+            CarbonProvider::ElectricityMap { token: secret, .. }
+            | CarbonProvider::WattTime {
+                password: secret, ..
+            } if secret.is_empty() => {
+                *secret = std::env::var(CARBON_API_TOKEN_ENV).unwrap_or_default();
+            }
+            _ => {}
+        }
+        Ok(config)
+    }
+
+    /// Provider zone for a cloud region
+    pub fn zone_for_region<'a>(&'a self, region: &'a str) -> &'a str {
+        self.region_zones
+            .get(region)
+            .map(String::as_str)
+            .unwrap_or(region)
+    }
+}
+
+/// Operator-initiated batch work that can be deferred into a low-carbon window
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchJobKind {
+    /// CSI VolumeSnapshot of a validator's data volume
+    CsiSnapshot,
+    /// OCI ledger snapshot push Job
+    OciSnapshotPush,
+    /// Decentralized history archive backup Job
+    HistoryArchiveBackup,
+    /// Horizon database maintenance window
+    DbMaintenance,
+}
+
+impl BatchJobKind {
+    /// Every deferrable kind
+    pub const ALL: [BatchJobKind; 4] = [
+        Self::CsiSnapshot,
+        Self::OciSnapshotPush,
+        Self::HistoryArchiveBackup,
+        Self::DbMaintenance,
+    ];
+
+    /// Rough energy use of one run in kWh, used to estimate CO2 saved
+    pub fn default_energy_kwh(self) -> f64 {
+        match self {
+            Self::CsiSnapshot => 0.1,
+            Self::OciSnapshotPush => 1.0,
+            Self::HistoryArchiveBackup => 0.5,
+            Self::DbMaintenance => 2.0,
+        }
+    }
+}
+
+impl std::fmt::Display for BatchJobKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::CsiSnapshot => "CSI snapshot",
+            Self::OciSnapshotPush => "OCI snapshot push",
+            Self::HistoryArchiveBackup => "history archive backup",
+            Self::DbMaintenance => "database maintenance",
+        };
+        f.write_str(name)
+    }
+}
+
+/// When and how far batch work may be deferred
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeferralConfig {
+    /// Defer batch work into the lowest-intensity forecast window
+    pub enabled: bool,
+    /// Cloud region (or provider zone) the operator's jobs run in
+    pub region: Option<String>,
+    /// Latest a job may start, in minutes after it was due
+    pub max_deferral_minutes: i64,
+    /// Only defer when the window is at least this much cleaner than now
+    pub min_savings_percent: f64,
+    /// Kinds of work that may be deferred
+    pub jobs: Vec<BatchJobKind>,
+    /// Energy per run in kWh, overriding the built-in estimates
+    pub estimated_energy_kwh: HashMap<BatchJobKind, f64>,
+}
+
+impl Default for DeferralConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            region: None,
+            max_deferral_minutes: 240,
+            min_savings_percent: 10.0,
+            jobs: BatchJobKind::ALL.to_vec(),
+            estimated_energy_kwh: HashMap::new(),
         }
     }
 }
 
+impl DeferralConfig {
+    /// Whether work of this kind is deferred at all
+    pub fn applies_to(&self, kind: BatchJobKind) -> bool {
+        self.enabled && self.region.is_some() && self.jobs.contains(&kind)
+    }
+
+    /// Energy of one run of this kind in kWh
+    pub fn energy_kwh(&self, kind: BatchJobKind) -> f64 {
+        self.estimated_energy_kwh
+            .get(&kind)
+            .copied()
+            .unwrap_or_else(|| kind.default_energy_kwh())
+    }
+}
+
 /// Carbon intensity data providers
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
 pub enum CarbonProvider {
     /// ElectricityMap API
     ElectricityMap {
         /// API base URL
         url: String,
         /// API token
+        #[serde(default)]
         token: String,
     },
     /// Custom API endpoint
@@ -132,12 +279,43 @@ pub enum CarbonProvider {
         /// API URL
         url: String,
         /// Authentication header
+        #[serde(default, rename = "authHeader")]
         auth_header: Option<String>,
     },
+    /// WattTime marginal emissions (`co2_moer`) and forecast
+    WattTime {
+        /// API base URL
+        #[serde(default = "default_watttime_url")]
+        url: String,
+        /// Account username
+        username: String,
+        /// Account password
+        #[serde(default)]
+        password: String,
+        /// WattTime grid regions to fetch (e.g. "CAISO_NORTH")
+        regions: Vec<String>,
+        /// Forecast horizon in hours
+        #[serde(default = "default_forecast_hours", rename = "forecastHours")]
+        forecast_hours: u32,
+    },
+    /// Per-region time series read from a CSV or JSON file, such as a
+    /// mounted ConfigMap
+    File {
+        /// Path of the file; the format follows its `.csv` or `.json` extension
+        path: String,
+    },
     /// Mock provider for testing
     Mock,
 }
 
+fn default_watttime_url() -> String {
+    "https://api.watttime.org".to_string()
+}
+
+fn default_forecast_hours() -> u32 {
+    24
+}
+
 impl Default for CarbonProvider {
     fn default() -> Self {
         Self::ElectricityMap {
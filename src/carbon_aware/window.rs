@@ -0,0 +1,282 @@
+//! Carbon windows for operator batch work
+//!
+//! CSI snapshots, OCI snapshot pushes, history archive backups and database
+//! maintenance consult the [`CarbonWindowService`] when they become due. If
+//! the forecast shows a clearly cleaner window before the job's deadline, the
+//! job is deferred until then. Decisions are kept in memory so the reconciler
+//! can emit Events and the sustainability API can report them.
+
+use crate::carbon_aware::scheduler::CarbonAwareScheduler;
+use crate::carbon_aware::types::{BatchJobKind, CarbonIntensityData};
+use crate::crd::StellarNode;
+use chrono::{DateTime, Duration, Utc};
+use kube::ResourceExt;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Number of finished deferrals kept for the sustainability API
+const HISTORY_LIMIT: usize = 100;
+
+/// Deferral decision for one job
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CarbonWindowDecision {
+    /// Namespace of the StellarNode the job belongs to
+    pub namespace: String,
+    /// Name of the StellarNode the job belongs to
+    pub node: String,
+    /// Kind of batch work
+    pub kind: BatchJobKind,
+    /// Provider zone whose intensity was used
+    pub zone: String,
+    /// When the job became due
+    pub due_at: DateTime<Utc>,
+    /// Latest time the job may start
+    pub deadline: DateTime<Utc>,
+    /// When the job is (or was) started
+    pub run_at: DateTime<Utc>,
+    /// Carbon intensity when the job became due (gCO2/kWh)
+    pub baseline_intensity: f64,
+    /// Forecast intensity at `run_at`, or the measured one once it ran (gCO2/kWh)
+    pub window_intensity: f64,
+    /// Estimated CO2 saved by running at `run_at` instead of `due_at` (grams)
+    pub estimated_co2_saved_grams: f64,
+}
+
+/// Outcome of consulting the carbon window
+#[derive(Clone, Debug)]
+pub enum CarbonWindowVerdict {
+    /// Run now; the job was not deferred
+    Run,
+    /// Run now; the job had been deferred and its window has come
+    RunAfterDeferral(CarbonWindowDecision),
+    /// Wait until `decision.run_at`. `changed` is set when the deferral is
+    /// new or was moved to another window.
+    Defer {
+        decision: CarbonWindowDecision,
+        changed: bool,
+    },
+}
+
+impl CarbonWindowVerdict {
+    /// Whether the job should start now
+    pub fn should_run(&self) -> bool {
+        !matches!(self, Self::Defer { .. })
+    }
+}
+
+/// Lowest-intensity start time before `deadline`.
+///
+/// Returns `(start, intensity)`; the start is `now` unless a forecast point
+/// is at least `min_savings_percent` cleaner than the current intensity.
+pub fn best_window(
+    data: &CarbonIntensityData,
+    now: DateTime<Utc>,
+    deadline: DateTime<Utc>,
+    min_savings_percent: f64,
+) -> (DateTime<Utc>, f64) {
+    let current = data.carbon_intensity;
+    let best = data
+        .forecast
+        .iter()
+        .flatten()
+        .filter(|p| p.timestamp > now && p.timestamp <= deadline)
+        .fold(None::<(DateTime<Utc>, f64)>, |best, p| match best {
+            Some((_, intensity)) if intensity <= p.carbon_intensity => best,
+            _ => Some((p.timestamp, p.carbon_intensity)),
+        });
+
+    match best {
+        Some((start, intensity))
+            if current > 0.0 && (current - intensity) / current * 100.0 >= min_savings_percent =>
+        {
+            (start, intensity)
+        }
+        _ => (now, current),
+    }
+}
+
+/// Next time a cron schedule fires after `after`.
+///
+/// Accepts the 5-field Kubernetes format as well as the 6/7-field format
+/// with seconds.
+pub fn next_cron_fire(schedule: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    parse_cron(schedule)?.after(&after).next()
+}
+
+/// Latest time a cron schedule fired at or before `at`, found without
+/// stepping through the runs in between
+pub fn previous_cron_fire(schedule: &str, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    parse_cron(schedule)?
+        .after(&(at + Duration::seconds(1)))
+        .next_back()
+        .filter(|fired| *fired <= at)
+}
+
+fn parse_cron(schedule: &str) -> Option<cron::Schedule> {
+    let schedule = schedule.trim();
+    let expression = if schedule.split_whitespace().count() == 5 {
+        format!("0 {schedule}")
+    } else {
+        schedule.to_string()
+    };
+    cron::Schedule::from_str(&expression).ok()
+}
+
+/// Decides when deferrable batch work runs, from the carbon data refreshed
+/// by the [`CarbonAwareScheduler`]
+pub struct CarbonWindowService {
+    scheduler: Arc<CarbonAwareScheduler>,
+    /// Deferred jobs keyed by namespace, node and kind
+    pending: RwLock<HashMap<(String, String, BatchJobKind), CarbonWindowDecision>>,
+    /// Deferred jobs that have started, newest last
+    history: RwLock<VecDeque<CarbonWindowDecision>>,
+}
+
+impl CarbonWindowService {
+    pub fn new(scheduler: Arc<CarbonAwareScheduler>) -> Self {
+        Self {
+            scheduler,
+            pending: RwLock::new(HashMap::new()),
+            history: RwLock::new(VecDeque::new()),
+        }
+    }
+
+    /// Scheduler holding the shared carbon data
+    pub fn scheduler(&self) -> &Arc<CarbonAwareScheduler> {
+        &self.scheduler
+    }
+
+    /// Whether work of this kind is subject to deferral
+    pub fn defers(&self, kind: BatchJobKind) -> bool {
+        let config = self.scheduler.config();
+        config.enabled && config.deferral.applies_to(kind)
+    }
+
+    /// Jobs currently waiting for a cleaner window
+    pub async fn pending(&self) -> Vec<CarbonWindowDecision> {
+        let mut pending: Vec<_> = self.pending.read().await.values().cloned().collect();
+        pending.sort_by_key(|d| d.run_at);
+        pending
+    }
+
+    /// Deferred jobs that have started since the operator started, newest first
+    pub async fn history(&self) -> Vec<CarbonWindowDecision> {
+        self.history.read().await.iter().rev().cloned().collect()
+    }
+
+    /// Decide whether a job that became due at `due_at` starts now.
+    ///
+    /// `latest` caps the deadline below `due_at + maxDeferralMinutes`, for
+    /// work that must finish inside its own window. Jobs run right away when
+    /// deferral does not apply to them or the carbon data is missing or stale.
+    pub async fn evaluate(
+        &self,
+        node: &StellarNode,
+        kind: BatchJobKind,
+        due_at: DateTime<Utc>,
+        latest: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> CarbonWindowVerdict {
+        let config = self.scheduler.config();
+        let deferral = &config.deferral;
+        let key = (
+            node.namespace().unwrap_or_else(|| "default".to_string()),
+            node.name_any(),
+            kind,
+        );
+        let mut pending = self.pending.write().await;
+        let previous = pending.get(&key).cloned();
+
+        let region = match &deferral.region {
+            Some(region) if deferral.applies_to(kind) => region,
+            _ => return self.release(&mut pending, &key, previous, None, now).await,
+        };
+        let zone = config.zone_for_region(region).to_string();
+        let current = if self.scheduler.has_fresh_data().await {
+            self.scheduler
+                .carbon_data()
+                .read()
+                .await
+                .get_region(&zone)
+                .cloned()
+        } else {
+            None
+        };
+        let Some(current) = current else {
+            return self.release(&mut pending, &key, previous, None, now).await;
+        };
+
+        // A deferred job keeps the due time and baseline it was deferred with
+        let (due_at, baseline) = previous
+            .as_ref()
+            .map(|d| (d.due_at, d.baseline_intensity))
+            .unwrap_or((due_at, current.carbon_intensity));
+        let mut deadline = due_at + Duration::minutes(deferral.max_deferral_minutes);
+        if let Some(latest) = latest {
+            deadline = deadline.min(latest);
+        }
+        let window_reached = previous.as_ref().is_some_and(|d| now >= d.run_at);
+        if window_reached || now >= deadline {
+            return self
+                .release(&mut pending, &key, previous, Some(&current), now)
+                .await;
+        }
+
+        let (run_at, window_intensity) =
+            best_window(&current, now, deadline, deferral.min_savings_percent);
+        if run_at <= now {
+            return self
+                .release(&mut pending, &key, previous, Some(&current), now)
+                .await;
+        }
+
+        let decision = CarbonWindowDecision {
+            namespace: key.0.clone(),
+            node: key.1.clone(),
+            kind,
+            zone,
+            due_at,
+            deadline,
+            run_at,
+            baseline_intensity: baseline,
+            window_intensity,
+            estimated_co2_saved_grams: deferral.energy_kwh(kind) * (baseline - window_intensity),
+        };
+        let changed = previous.is_none_or(|d| d.run_at != run_at);
+        pending.insert(key, decision.clone());
+        CarbonWindowVerdict::Defer { decision, changed }
+    }
+
+    /// Let a job run, recording the saving of a deferral that ends here
+    async fn release(
+        &self,
+        pending: &mut HashMap<(String, String, BatchJobKind), CarbonWindowDecision>,
+        key: &(String, String, BatchJobKind),
+        previous: Option<CarbonWindowDecision>,
+        current: Option<&CarbonIntensityData>,
+        now: DateTime<Utc>,
+    ) -> CarbonWindowVerdict {
+        let Some(mut decision) = previous else {
+            return CarbonWindowVerdict::Run;
+        };
+        pending.remove(key);
+
+        decision.run_at = now;
+        if let Some(current) = current {
+            decision.window_intensity = current.carbon_intensity;
+        }
+        decision.estimated_co2_saved_grams = self.scheduler.config().deferral.energy_kwh(key.2)
+            * (decision.baseline_intensity - decision.window_intensity);
+
+        let mut history = self.history.write().await;
+        if history.len() == HISTORY_LIMIT {
+            history.pop_front();
+        }
+        history.push_back(decision.clone());
+        CarbonWindowVerdict::RunAfterDeferral(decision)
+    }
+}
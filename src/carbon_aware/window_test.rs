@@ -0,0 +1,190 @@
+//! Tests for carbon-window deferral of batch work.
+
+#[cfg(test)]
+mod tests {
+    use crate::carbon_aware::types::CarbonForecast;
+    use crate::carbon_aware::window::{best_window, next_cron_fire, previous_cron_fire};
+    use crate::carbon_aware::{
+        BatchJobKind, CarbonAwareConfig, CarbonAwareScheduler, CarbonIntensityAPI,
+        CarbonIntensityData, CarbonProvider, CarbonWindowService, CarbonWindowVerdict,
+    };
+    use crate::crd::StellarNode;
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use std::sync::Arc;
+
+    fn intensity(now: DateTime<Utc>, current: f64, forecast: &[(i64, f64)]) -> CarbonIntensityData {
+        CarbonIntensityData {
+            region: "DE".to_string(),
+            carbon_intensity: current,
+            timestamp: now,
+            source: "Test".to_string(),
+            renewable_percentage: None,
+            forecast: Some(
+                forecast
+                    .iter()
+                    .map(|&(hours, carbon_intensity)| CarbonForecast {
+                        timestamp: now + Duration::hours(hours),
+                        carbon_intensity,
+                        confidence: 1.0,
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn validator() -> StellarNode {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "stellar.org/v1alpha1",
+            "kind": "StellarNode",
+            "metadata": { "name": "validator-1", "namespace": "stellar" },
+            "spec": { "nodeType": "Validator", "network": "Testnet", "version": "v21.0.0" },
+        }))
+        .unwrap()
+    }
+
+    async fn service(yaml: &str, data: CarbonIntensityData) -> CarbonWindowService {
+        let config = CarbonAwareConfig::from_yaml(yaml).unwrap();
+        let scheduler = Arc::new(CarbonAwareScheduler::new(
+            CarbonIntensityAPI::new(CarbonProvider::Mock),
+            config,
+        ));
+        scheduler.carbon_data().write().await.update_region(data);
+        CarbonWindowService::new(scheduler)
+    }
+
+    const DEFERRAL: &str = "
+deferral:
+  enabled: true
+  region: eu-central-1
+  maxDeferralMinutes: 360
+  estimatedEnergyKwh:
+    csiSnapshot: 2.0
+regionZones:
+  eu-central-1: DE
+";
+
+    #[test]
+    fn test_best_window_respects_deadline_and_savings() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let data = intensity(now, 300.0, &[(1, 290.0), (2, 150.0), (3, 150.0), (8, 50.0)]);
+
+        // The earliest of equally clean points, within the deadline
+        let (start, value) = best_window(&data, now, now + Duration::hours(4), 10.0);
+        assert_eq!(start, now + Duration::hours(2));
+        assert_eq!(value, 150.0);
+
+        // Too small a saving runs now
+        let (start, value) = best_window(&data, now, now + Duration::hours(1), 10.0);
+        assert_eq!((start, value), (now, 300.0));
+        let (start, _) = best_window(&data, now, now + Duration::hours(4), 60.0);
+        assert_eq!(start, now);
+    }
+
+    #[test]
+    fn test_next_cron_fire_accepts_kubernetes_schedules() {
+        let after = Utc.with_ymd_and_hms(2026, 3, 1, 7, 30, 0).unwrap();
+        assert_eq!(
+            next_cron_fire("0 */6 * * *", after),
+            Some(Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap())
+        );
+        assert_eq!(
+            next_cron_fire("0 0 2 * * *", after),
+            Some(Utc.with_ymd_and_hms(2026, 3, 2, 2, 0, 0).unwrap())
+        );
+        assert_eq!(next_cron_fire("not a cron", after), None);
+
+        let at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        assert_eq!(previous_cron_fire("0 */6 * * *", at), Some(at));
+        assert_eq!(
+            previous_cron_fire("0 */6 * * *", at + Duration::milliseconds(1500)),
+            Some(at)
+        );
+        assert_eq!(
+            previous_cron_fire("0 */6 * * *", at - Duration::seconds(1)),
+            Some(Utc.with_ymd_and_hms(2026, 3, 1, 6, 0, 0).unwrap())
+        );
+        assert_eq!(previous_cron_fire("not a cron", at), None);
+    }
+
+    #[tokio::test]
+    async fn test_job_is_deferred_then_released_with_savings() {
+        let now = Utc::now();
+        let service = service(DEFERRAL, intensity(now, 300.0, &[(2, 100.0)])).await;
+        let node = validator();
+        let kind = BatchJobKind::CsiSnapshot;
+
+        let verdict = service.evaluate(&node, kind, now, None, now).await;
+        let CarbonWindowVerdict::Defer { decision, changed } = verdict else {
+            panic!("expected a deferral, got {verdict:?}");
+        };
+        assert!(changed);
+        assert_eq!(decision.zone, "DE");
+        assert_eq!(decision.run_at, now + Duration::hours(2));
+        assert_eq!(decision.deadline, now + Duration::hours(6));
+        assert_eq!(decision.estimated_co2_saved_grams, 400.0);
+
+        // Re-evaluating keeps the deferral without reporting it again
+        let later = now + Duration::minutes(30);
+        let verdict = service.evaluate(&node, kind, later, None, later).await;
+        assert!(
+            matches!(verdict, CarbonWindowVerdict::Defer { changed: false, ref decision } if decision.due_at == now)
+        );
+        assert_eq!(service.pending().await.len(), 1);
+
+        // The window comes: the job runs and its saving is recorded
+        let window = now + Duration::hours(2);
+        service
+            .scheduler()
+            .carbon_data()
+            .write()
+            .await
+            .update_region(intensity(window, 120.0, &[]));
+        let verdict = service.evaluate(&node, kind, window, None, window).await;
+        let CarbonWindowVerdict::RunAfterDeferral(decision) = verdict else {
+            panic!("expected the deferred job to run, got {verdict:?}");
+        };
+        assert_eq!(decision.window_intensity, 120.0);
+        assert_eq!(decision.estimated_co2_saved_grams, 360.0);
+        assert!(service.pending().await.is_empty());
+        assert_eq!(service.history().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_jobs_run_when_deferral_does_not_apply() {
+        let now = Utc::now();
+        let data = || intensity(now, 300.0, &[(2, 100.0)]);
+        let node = validator();
+
+        // The deadline caps the deferral
+        let service = service(DEFERRAL, data()).await;
+        let verdict = service
+            .evaluate(
+                &node,
+                BatchJobKind::DbMaintenance,
+                now,
+                Some(now + Duration::hours(1)),
+                now,
+            )
+            .await;
+        assert!(matches!(verdict, CarbonWindowVerdict::Run));
+
+        // Kinds that are not listed, and configs without a region
+        let only_backups =
+            "deferral:\n  enabled: true\n  region: DE\n  jobs: [historyArchiveBackup]\n";
+        let service = self::service(only_backups, data()).await;
+        let snapshot = service
+            .evaluate(&node, BatchJobKind::CsiSnapshot, now, None, now)
+            .await;
+        assert!(snapshot.should_run());
+        let backup = service
+            .evaluate(&node, BatchJobKind::HistoryArchiveBackup, now, None, now)
+            .await;
+        assert!(!backup.should_run());
+
+        let service = self::service("deferral:\n  enabled: true\n", data()).await;
+        assert!(service
+            .evaluate(&node, BatchJobKind::CsiSnapshot, now, None, now)
+            .await
+            .should_run());
+    }
+}
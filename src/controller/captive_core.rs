@@ -254,6 +254,7 @@ mod tests {
                 read_replica_config: None,
                 db_maintenance_config: None,
                 oci_snapshot: None,
+                decentralized_backup: None,
                 service_mesh: None,
                 resource_meta: None,
                 vpa_config: None,
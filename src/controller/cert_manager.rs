@@ -0,0 +1,264 @@
+//! cert-manager as an alternative mTLS issuer
+//!
+//! By default the operator runs its own CA (see [`super::mtls`]). When an
+//! issuer is configured, server and node certificates are instead requested
+//! through cert-manager `Certificate` resources referencing that
+//! `Issuer`/`ClusterIssuer`. cert-manager writes the same Secrets the
+//! built-in CA would (`stellar-operator-server-cert`, `<node>-client-cert`)
+//! with `tls.crt`, `tls.key` and `ca.crt`, and renews them on its own.
+//!
+//! The issuer must populate `ca.crt` (CA and Vault issuers do), since both
+//! the REST API and the node health checks verify peers against it. A
+//! namespaced `Issuer` must exist in every namespace running StellarNodes;
+//! a `ClusterIssuer` avoids that.
+
+use std::time::Duration;
+
+use k8s_openapi::api::core::v1::Secret;
+use kube::{
+    api::{Api, ApiResource, DynamicObject, GroupVersionKind, Patch, PatchParams},
+    core::ObjectMeta,
+    Client, Resource, ResourceExt,
+};
+use serde_json::json;
+use tracing::{debug, info};
+
+use super::mtls::SERVER_CERT_SECRET_NAME;
+use crate::crd::StellarNode;
+use crate::error::{Error, Result};
+use crate::MtlsConfig;
+
+// ---------------------------------------------------------------------------
+// Constants
+// ---------------------------------------------------------------------------
+
+const CERT_MANAGER_GROUP: &str = "cert-manager.io";
+const CERT_MANAGER_VERSION: &str = "v1";
+const CERTIFICATE_KIND: &str = "Certificate";
+const FIELD_MANAGER: &str = "stellar-operator";
+
+/// Validity requested for issued certificates
+const CERT_DURATION: &str = "8760h";
+/// Renew this long before expiry, matching the built-in CA's rotation threshold
+const CERT_RENEW_BEFORE: &str = "720h";
+
+// ---------------------------------------------------------------------------
+// Issuer reference
+// ---------------------------------------------------------------------------
+
+/// Kind of the referenced cert-manager issuer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IssuerKind {
+    Issuer,
+    ClusterIssuer,
+}
+
+impl std::str::FromStr for IssuerKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "Issuer" => Ok(Self::Issuer),
+            "ClusterIssuer" => Ok(Self::ClusterIssuer),
+            other => Err(Error::ConfigError(format!(
+                "Invalid cert-manager issuer kind '{other}', expected Issuer or ClusterIssuer"
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for IssuerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Issuer => write!(f, "Issuer"),
+            Self::ClusterIssuer => write!(f, "ClusterIssuer"),
+        }
+    }
+}
+
+/// The cert-manager issuer mTLS certificates are delegated to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IssuerRef {
+    pub name: String,
+    pub kind: IssuerKind,
+}
+
+// ---------------------------------------------------------------------------
+// Certificate builder
+// ---------------------------------------------------------------------------
+
+/// Returns the `ApiResource` descriptor for cert-manager `Certificate`s.
+fn certificate_api_resource() -> ApiResource {
+    ApiResource::from_gvk(&GroupVersionKind {
+        group: CERT_MANAGER_GROUP.to_string(),
+        version: CERT_MANAGER_VERSION.to_string(),
+        kind: CERTIFICATE_KIND.to_string(),
+    })
+}
+
+/// Name of the `Certificate` requesting a node's client certificate.
+/// Convention: `<node-name>-client-cert`, the same as the Secret.
+pub fn node_certificate_name(node: &StellarNode) -> String {
+    format!("{}-client-cert", node.name_any())
+}
+
+/// Builds a `Certificate` usable for both server and client authentication.
+pub fn build_certificate(
+    name: &str,
+    namespace: &str,
+    common_name: &str,
+    dns_names: &[String],
+    issuer: &IssuerRef,
+) -> DynamicObject {
+    let mut spec = json!({
+        "secretName": name,
+        "commonName": common_name,
+        "duration": CERT_DURATION,
+        "renewBefore": CERT_RENEW_BEFORE,
+        "usages": ["digital signature", "key encipherment", "server auth", "client auth"],
+        "privateKey": {
+            "algorithm": "ECDSA",
+            "size": 256,
+            "rotationPolicy": "Always",
+        },
+        "issuerRef": {
+            "name": issuer.name,
+            "kind": issuer.kind.to_string(),
+            "group": CERT_MANAGER_GROUP,
+        },
+    });
+    if !dns_names.is_empty() {
+        spec["dnsNames"] = json!(dns_names);
+    }
+
+    let mut obj = DynamicObject::new(name, &certificate_api_resource())
+        .within(namespace)
+        .data(json!({ "spec": spec }));
+    obj.metadata.labels = Some(
+        [(
+            "app.kubernetes.io/managed-by".to_string(),
+            "stellar-operator".to_string(),
+        )]
+        .into(),
+    );
+    obj
+}
+
+/// Builds the `Certificate` for a node's client certificate, owned by the node.
+pub fn build_node_certificate(node: &StellarNode, issuer: &IssuerRef) -> DynamicObject {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let name = node_certificate_name(node);
+    let mut obj = build_certificate(
+        &name,
+        &namespace,
+        &format!("stellar-node-{}", node.name_any()),
+        &[],
+        issuer,
+    );
+    obj.metadata = ObjectMeta {
+        owner_references: node.controller_owner_ref(&()).map(|r| vec![r]),
+        ..obj.metadata
+    };
+    obj
+}
+
+// ---------------------------------------------------------------------------
+// Reconciliation
+// ---------------------------------------------------------------------------
+
+async fn apply_certificate(client: &Client, namespace: &str, cert: &DynamicObject) -> Result<()> {
+    let api: Api<DynamicObject> =
+        Api::namespaced_with(client.clone(), namespace, &certificate_api_resource());
+    let name = cert.name_any();
+    api.patch(
+        &name,
+        &PatchParams::apply(FIELD_MANAGER).force(),
+        &Patch::Apply(cert),
+    )
+    .await
+    .map_err(Error::KubeError)?;
+    debug!("cert-manager Certificate {}/{} applied", namespace, name);
+    Ok(())
+}
+
+/// Ensure the `Certificate` for the operator's REST API server exists.
+pub async fn ensure_server_certificate(
+    client: &Client,
+    namespace: &str,
+    dns_names: &[String],
+    issuer: &IssuerRef,
+) -> Result<()> {
+    let cert = build_certificate(
+        SERVER_CERT_SECRET_NAME,
+        namespace,
+        "stellar-operator",
+        dns_names,
+        issuer,
+    );
+    apply_certificate(client, namespace, &cert).await
+}
+
+/// Ensure the `Certificate` for a node's client certificate exists.
+pub async fn ensure_node_certificate(
+    client: &Client,
+    node: &StellarNode,
+    issuer: &IssuerRef,
+) -> Result<()> {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    apply_certificate(client, &namespace, &build_node_certificate(node, issuer)).await
+}
+
+/// Wait until cert-manager has issued `secret_name` and load it.
+pub async fn wait_for_certificate_secret(
+    client: &Client,
+    namespace: &str,
+    secret_name: &str,
+    timeout: Duration,
+) -> Result<MtlsConfig> {
+    let secrets: Api<Secret> = Api::namespaced(client.clone(), namespace);
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let last_error = match secrets.get_opt(secret_name).await? {
+            Some(secret) => match mtls_config_from_secret(&secret) {
+                Ok(config) => {
+                    info!(
+                        "cert-manager issued certificate {}/{}",
+                        namespace, secret_name
+                    );
+                    return Ok(config);
+                }
+                Err(e) => e.to_string(),
+            },
+            None => "Secret not created yet".to_string(),
+        };
+        if tokio::time::Instant::now() >= deadline {
+            return Err(Error::CertificateError(format!(
+                "cert-manager did not issue {namespace}/{secret_name} within {}s: {last_error}",
+                timeout.as_secs()
+            )));
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+}
+
+/// Load the mTLS material from a certificate Secret.
+///
+/// All three keys are required; issuers that do not populate `ca.crt`
+/// cannot be used for mTLS.
+pub fn mtls_config_from_secret(secret: &Secret) -> Result<MtlsConfig> {
+    let name = secret.name_any();
+    let get = |key: &str| {
+        secret
+            .data
+            .as_ref()
+            .and_then(|d| d.get(key))
+            .filter(|v| !v.0.is_empty())
+            .map(|v| v.0.clone())
+            .ok_or_else(|| Error::CertificateError(format!("Secret {name} has no {key}")))
+    };
+    Ok(MtlsConfig {
+        cert_pem: get("tls.crt")?,
+        key_pem: get("tls.key")?,
+        ca_pem: get("ca.crt")?,
+    })
+}
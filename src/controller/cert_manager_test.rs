@@ -0,0 +1,131 @@
+//! Tests for cert-manager mTLS certificate delegation.
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::cert_manager::*;
+    use crate::crd::StellarNode;
+    use crate::error::Error;
+
+    use k8s_openapi::api::core::v1::Secret;
+    use k8s_openapi::ByteString;
+    use kube::core::ObjectMeta;
+
+    fn cluster_issuer() -> IssuerRef {
+        IssuerRef {
+            name: "platform-ca".to_string(),
+            kind: IssuerKind::ClusterIssuer,
+        }
+    }
+
+    fn node() -> StellarNode {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "stellar.org/v1alpha1",
+            "kind": "StellarNode",
+            "metadata": { "name": "validator-1", "namespace": "stellar", "uid": "abc-123" },
+            "spec": {
+                "nodeType": "Validator",
+                "network": "Testnet",
+                "version": "v21.0.0",
+                "validatorConfig": { "seedSecretRef": "validator-seed" },
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_issuer_kind_parsing() {
+        assert_eq!("Issuer".parse::<IssuerKind>().unwrap(), IssuerKind::Issuer);
+        assert_eq!(
+            "ClusterIssuer".parse::<IssuerKind>().unwrap(),
+            IssuerKind::ClusterIssuer
+        );
+        let err = "clusterissuer".parse::<IssuerKind>().unwrap_err();
+        assert!(err.to_string().contains("expected Issuer or ClusterIssuer"));
+    }
+
+    #[test]
+    fn test_server_certificate_spec() {
+        let dns = vec![
+            "stellar-operator".to_string(),
+            "stellar-operator.stellar-system".to_string(),
+        ];
+        let cert = build_certificate(
+            "stellar-operator-server-cert",
+            "stellar-system",
+            "stellar-operator",
+            &dns,
+            &cluster_issuer(),
+        );
+        assert_eq!(
+            cert.types.as_ref().unwrap().api_version,
+            "cert-manager.io/v1"
+        );
+        assert_eq!(cert.types.as_ref().unwrap().kind, "Certificate");
+        assert_eq!(cert.metadata.namespace.as_deref(), Some("stellar-system"));
+
+        let spec = &cert.data["spec"];
+        assert_eq!(spec["secretName"], "stellar-operator-server-cert");
+        assert_eq!(spec["commonName"], "stellar-operator");
+        assert_eq!(spec["dnsNames"][1], "stellar-operator.stellar-system");
+        assert_eq!(spec["issuerRef"]["name"], "platform-ca");
+        assert_eq!(spec["issuerRef"]["kind"], "ClusterIssuer");
+        assert_eq!(spec["issuerRef"]["group"], "cert-manager.io");
+        let usages: Vec<&str> = spec["usages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|u| u.as_str().unwrap())
+            .collect();
+        assert!(usages.contains(&"server auth") && usages.contains(&"client auth"));
+    }
+
+    #[test]
+    fn test_node_certificate_is_owned_by_node() {
+        let issuer = IssuerRef {
+            name: "stellar-ca".to_string(),
+            kind: IssuerKind::Issuer,
+        };
+        let cert = build_node_certificate(&node(), &issuer);
+        assert_eq!(
+            cert.metadata.name.as_deref(),
+            Some("validator-1-client-cert")
+        );
+        assert_eq!(cert.metadata.namespace.as_deref(), Some("stellar"));
+        // Same Secret name as the built-in CA, so pod volumes are unchanged
+        assert_eq!(cert.data["spec"]["secretName"], "validator-1-client-cert");
+        assert_eq!(cert.data["spec"]["commonName"], "stellar-node-validator-1");
+        assert!(cert.data["spec"].get("dnsNames").is_none());
+        assert_eq!(cert.data["spec"]["issuerRef"]["kind"], "Issuer");
+
+        let owner = &cert.metadata.owner_references.as_ref().unwrap()[0];
+        assert_eq!(owner.kind, "StellarNode");
+        assert_eq!(owner.name, "validator-1");
+        assert_eq!(owner.uid, "abc-123");
+        assert_eq!(owner.controller, Some(true));
+    }
+
+    #[test]
+    fn test_mtls_config_requires_ca() {
+        let secret = |keys: &[&str]| Secret {
+            metadata: ObjectMeta {
+                name: Some("stellar-operator-server-cert".to_string()),
+                ..Default::default()
+            },
+            data: Some(
+                keys.iter()
+                    .map(|k| (k.to_string(), ByteString(format!("{k}-pem").into_bytes())))
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+
+        let config = mtls_config_from_secret(&secret(&["tls.crt", "tls.key", "ca.crt"])).unwrap();
+        assert_eq!(config.cert_pem, b"tls.crt-pem");
+        assert_eq!(config.ca_pem, b"ca.crt-pem");
+
+        // e.g. an ACME issuer, which does not provide ca.crt
+        let err = mtls_config_from_secret(&secret(&["tls.crt", "tls.key"])).unwrap_err();
+        assert!(matches!(err, Error::CertificateError(_)));
+        assert!(err.to_string().contains("has no ca.crt"));
+    }
+}
@@ -9,6 +9,10 @@ pub const CONDITION_TYPE_READY: &str = "Ready";
 pub const CONDITION_TYPE_PROGRESSING: &str = "Progressing";
 pub const CONDITION_TYPE_DEGRADED: &str = "Degraded";
 pub const CONDITION_TYPE_AVAILABLE: &str = "Available";
+/// Local ledger history disagrees with the DR peer or history archive
+pub const CONDITION_TYPE_DIVERGED: &str = "Diverged";
+/// The operator is running VACUUM FULL/REINDEX on the node's database
+pub const CONDITION_TYPE_MAINTENANCE_IN_PROGRESS: &str = "MaintenanceInProgress";
 
 /// Standard condition statuses
 pub const CONDITION_STATUS_TRUE: &str = "True";
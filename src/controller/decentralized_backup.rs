@@ -0,0 +1,587 @@
+//! Decentralized history archive backups
+//!
+//! `spec.decentralizedBackup` on a Validator is turned into a CronJob
+//! (`<node>-backup`) that runs the operator image's `backup` subcommand on the
+//! configured schedule. Each run mounts the node's data PVC read-only, uploads
+//! new history archive segments through [`crate::backup::worker`] and writes
+//! the outcome to `status.backup`.
+//!
+//! # Resources
+//! - **ServiceAccount, Role and RoleBinding** (`<node>-backup`) allowing the
+//!   worker to read its StellarNode, patch its status and persist the backup
+//!   manifest ConfigMap.
+//! - **CronJob** (`<node>-backup`) with `concurrencyPolicy: Forbid`. The pod is
+//!   scheduled next to the validator pod, since the data PVC is usually
+//!   `ReadWriteOnce`. Provider credentials are injected from the Secrets named
+//!   in the spec, so they never pass through the operator.
+//!
+//! When backups are deferred into low-carbon windows (see
+//! [`crate::carbon_aware::window`]), the CronJob is suspended and only serves
+//! as the Job template. The operator creates a Job from it once the schedule
+//! has fired and the carbon window allows, and records the schedule time it
+//! ran for on the CronJob.
+//!
+//! All of them are owned by the StellarNode and removed with it or when
+//! `spec.decentralizedBackup` is dropped. The manifest ConfigMap is kept so a
+//! re-enabled backup does not upload the archive again.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::batch::v1::{CronJob, CronJobSpec, Job, JobSpec, JobTemplateSpec};
+use k8s_openapi::api::core::v1::{
+    Affinity, Container, EnvVar, EnvVarSource, PersistentVolumeClaimVolumeSource, PodAffinity,
+    PodAffinityTerm, PodSpec, PodTemplateSpec, SecretKeySelector, ServiceAccount, Volume,
+    VolumeMount,
+};
+use k8s_openapi::api::rbac::v1::{PolicyRule, Role, RoleBinding, RoleRef, Subject};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, ObjectMeta, OwnerReference};
+use kube::api::{Api, DeleteParams, ListParams, Patch, PatchParams, PostParams, PropagationPolicy};
+use kube::{Client, ResourceExt};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{debug, info};
+
+use crate::backup::manifest::manifest_shard_names;
+use crate::backup::worker::{
+    manifest_configmap_name, ARWEAVE_WALLET_KEY, ENV_ARWEAVE_WALLET, ENV_PINNING_API_KEY,
+    ENV_S3_ACCESS_KEY_ID, ENV_S3_SECRET_ACCESS_KEY, PINNING_API_KEY_KEY, S3_ACCESS_KEY_ID_KEY,
+    S3_SECRET_ACCESS_KEY_KEY,
+};
+use crate::backup::{DecentralizedBackupConfig, StorageProvider};
+use crate::carbon_aware::window::previous_cron_fire;
+use crate::controller::resources::{owner_reference, resource_name, standard_labels};
+use crate::crd::StellarNode;
+use crate::error::{Error, Result};
+
+const FIELD_MANAGER: &str = "stellar-operator";
+
+/// Where the node's data PVC is mounted in the backup pod
+pub const DATA_MOUNT_PATH: &str = "/archive";
+
+/// Finished Jobs kept by the CronJob for inspection
+const SUCCESSFUL_JOBS_HISTORY: i32 = 3;
+const FAILED_JOBS_HISTORY: i32 = 3;
+
+/// Retries of a failed run before waiting for the next schedule
+const BACKOFF_LIMIT: i32 = 2;
+
+/// Schedule time of the last Job the operator created from the CronJob
+const LAST_TRIGGERED_ANNOTATION: &str = "stellar.org/last-triggered-schedule";
+
+/// Image used when `--backup-image` is not set
+pub fn default_worker_image() -> String {
+    format!("ghcr.io/stellar/stellar-k8s:{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Name shared by the backup CronJob, ServiceAccount, Role and RoleBinding
+pub fn backup_resource_name(node: &StellarNode) -> String {
+    resource_name(node, "backup")
+}
+
+fn backup_labels(node: &StellarNode) -> BTreeMap<String, String> {
+    let mut labels = standard_labels(node);
+    labels.insert(
+        "app.kubernetes.io/component".to_string(),
+        "backup".to_string(),
+    );
+    labels
+}
+
+fn backup_metadata(node: &StellarNode) -> ObjectMeta {
+    ObjectMeta {
+        name: Some(backup_resource_name(node)),
+        namespace: node.namespace(),
+        labels: Some(backup_labels(node)),
+        owner_references: Some(vec![owner_reference(node)]),
+        ..Default::default()
+    }
+}
+
+fn secret_env(name: &str, secret: &str, key: &str) -> EnvVar {
+    EnvVar {
+        name: name.to_string(),
+        value_from: Some(EnvVarSource {
+            secret_key_ref: Some(SecretKeySelector {
+                name: Some(secret.to_string()),
+                key: key.to_string(),
+                optional: Some(false),
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Environment variables resolving the provider's credentials from Secrets
+pub fn credential_env(provider: &StorageProvider) -> Vec<EnvVar> {
+    match provider {
+        StorageProvider::Arweave { wallet_secret, .. } => vec![secret_env(
+            ENV_ARWEAVE_WALLET,
+            wallet_secret,
+            ARWEAVE_WALLET_KEY,
+        )],
+        StorageProvider::IPFS {
+            pinning_service: Some(pinning),
+            ..
+        } => vec![secret_env(
+            ENV_PINNING_API_KEY,
+            &pinning.api_key_secret,
+            PINNING_API_KEY_KEY,
+        )],
+        StorageProvider::S3 {
+            credentials_secret, ..
+        } => vec![
+            secret_env(
+                ENV_S3_ACCESS_KEY_ID,
+                credentials_secret,
+                S3_ACCESS_KEY_ID_KEY,
+            ),
+            secret_env(
+                ENV_S3_SECRET_ACCESS_KEY,
+                credentials_secret,
+                S3_SECRET_ACCESS_KEY_KEY,
+            ),
+        ],
+        StorageProvider::IPFS { .. }
+        | StorageProvider::Filecoin { .. }
+        | StorageProvider::Filesystem { .. } => vec![],
+    }
+}
+
+/// ServiceAccount the backup pods run as
+pub fn build_service_account(node: &StellarNode) -> ServiceAccount {
+    ServiceAccount {
+        metadata: backup_metadata(node),
+        automount_service_account_token: Some(true),
+        ..Default::default()
+    }
+}
+
+/// Role limited to the node itself and its manifest ConfigMap
+pub fn build_role(node: &StellarNode) -> Role {
+    let node_name = vec![node.name_any()];
+    let manifest_name = manifest_configmap_name(&node.name_any());
+    let mut manifest = manifest_shard_names(&manifest_name);
+    manifest.insert(0, manifest_name);
+    let rule =
+        |group: &str, resource: &str, verbs: &[&str], names: Option<&Vec<String>>| PolicyRule {
+            api_groups: Some(vec![group.to_string()]),
+            resources: Some(vec![resource.to_string()]),
+            verbs: verbs.iter().map(|v| v.to_string()).collect(),
+            resource_names: names.cloned(),
+            ..Default::default()
+        };
+
+    Role {
+        metadata: backup_metadata(node),
+        rules: Some(vec![
+            rule("stellar.org", "stellarnodes", &["get"], Some(&node_name)),
+            rule(
+                "stellar.org",
+                "stellarnodes/status",
+                &["get", "patch"],
+                Some(&node_name),
+            ),
+            rule("", "configmaps", &["get", "patch"], Some(&manifest)),
+            // `create` cannot be restricted by name
+            rule("", "configmaps", &["create"], None),
+        ]),
+    }
+}
+
+pub fn build_role_binding(node: &StellarNode) -> RoleBinding {
+    let name = backup_resource_name(node);
+    RoleBinding {
+        metadata: backup_metadata(node),
+        role_ref: RoleRef {
+            api_group: "rbac.authorization.k8s.io".to_string(),
+            kind: "Role".to_string(),
+            name: name.clone(),
+        },
+        subjects: Some(vec![Subject {
+            kind: "ServiceAccount".to_string(),
+            name,
+            namespace: node.namespace(),
+            ..Default::default()
+        }]),
+    }
+}
+
+/// CronJob running the backup worker against the node's data PVC.
+///
+/// With `operator_triggered` the CronJob stays suspended and the operator
+/// starts its Jobs through [`trigger_backup_job`].
+pub fn build_cronjob(
+    node: &StellarNode,
+    config: &DecentralizedBackupConfig,
+    image: &str,
+    operator_triggered: bool,
+) -> CronJob {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let labels = backup_labels(node);
+
+    // Run on the same host as the validator, which holds the RWO data volume
+    let validator_selector = BTreeMap::from([
+        ("app.kubernetes.io/instance".to_string(), node.name_any()),
+        (
+            "app.kubernetes.io/component".to_string(),
+            node.spec.node_type.to_string().to_lowercase(),
+        ),
+    ]);
+
+    let mut volume_mounts = vec![VolumeMount {
+        name: "node-data".to_string(),
+        mount_path: DATA_MOUNT_PATH.to_string(),
+        read_only: Some(true),
+        ..Default::default()
+    }];
+    let mut volumes = vec![Volume {
+        name: "node-data".to_string(),
+        persistent_volume_claim: Some(PersistentVolumeClaimVolumeSource {
+            claim_name: resource_name(node, "data"),
+            read_only: Some(true),
+        }),
+        ..Default::default()
+    }];
+    // The filesystem provider writes to its own PVC
+    if let StorageProvider::Filesystem {
+        path,
+        claim_name: Some(claim_name),
+    } = &config.provider
+    {
+        volume_mounts.push(VolumeMount {
+            name: "backup-target".to_string(),
+            mount_path: path.clone(),
+            ..Default::default()
+        });
+        volumes.push(Volume {
+            name: "backup-target".to_string(),
+            persistent_volume_claim: Some(PersistentVolumeClaimVolumeSource {
+                claim_name: claim_name.clone(),
+                read_only: Some(false),
+            }),
+            ..Default::default()
+        });
+    }
+
+    let container = Container {
+        name: "backup".to_string(),
+        image: Some(image.to_string()),
+        args: Some(vec![
+            "backup".to_string(),
+            format!("--node={}", node.name_any()),
+            format!("--namespace={namespace}"),
+            format!("--data-dir={DATA_MOUNT_PATH}"),
+        ]),
+        env: Some(credential_env(&config.provider)),
+        volume_mounts: Some(volume_mounts),
+        ..Default::default()
+    };
+
+    let pod_spec = PodSpec {
+        service_account_name: Some(backup_resource_name(node)),
+        restart_policy: Some("OnFailure".to_string()),
+        containers: vec![container],
+        volumes: Some(volumes),
+        affinity: Some(Affinity {
+            pod_affinity: Some(PodAffinity {
+                required_during_scheduling_ignored_during_execution: Some(vec![PodAffinityTerm {
+                    label_selector: Some(LabelSelector {
+                        match_labels: Some(validator_selector),
+                        ..Default::default()
+                    }),
+                    topology_key: "kubernetes.io/hostname".to_string(),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    CronJob {
+        metadata: backup_metadata(node),
+        spec: Some(CronJobSpec {
+            schedule: config.schedule.clone(),
+            suspend: Some(!config.enabled || node.spec.suspended || operator_triggered),
+            concurrency_policy: Some("Forbid".to_string()),
+            successful_jobs_history_limit: Some(SUCCESSFUL_JOBS_HISTORY),
+            failed_jobs_history_limit: Some(FAILED_JOBS_HISTORY),
+            job_template: JobTemplateSpec {
+                metadata: Some(ObjectMeta {
+                    labels: Some(labels.clone()),
+                    ..Default::default()
+                }),
+                spec: Some(JobSpec {
+                    backoff_limit: Some(BACKOFF_LIMIT),
+                    template: PodTemplateSpec {
+                        metadata: Some(ObjectMeta {
+                            labels: Some(labels),
+                            ..Default::default()
+                        }),
+                        spec: Some(pod_spec),
+                    },
+                    ..Default::default()
+                }),
+            },
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+async fn apply<K>(client: &Client, namespace: &str, resource: &K) -> Result<()>
+where
+    K: kube::Resource<Scope = k8s_openapi::NamespaceResourceScope>
+        + Clone
+        + std::fmt::Debug
+        + Serialize
+        + DeserializeOwned,
+    K::DynamicType: Default,
+{
+    let api: Api<K> = Api::namespaced(client.clone(), namespace);
+    api.patch(
+        &resource.name_any(),
+        &PatchParams::apply(FIELD_MANAGER).force(),
+        &Patch::Apply(resource),
+    )
+    .await
+    .map_err(Error::KubeError)?;
+    Ok(())
+}
+
+async fn delete<K>(client: &Client, namespace: &str, name: &str) -> Result<()>
+where
+    K: kube::Resource<Scope = k8s_openapi::NamespaceResourceScope>
+        + Clone
+        + std::fmt::Debug
+        + DeserializeOwned,
+    K::DynamicType: Default,
+{
+    let api: Api<K> = Api::namespaced(client.clone(), namespace);
+    let params = DeleteParams {
+        propagation_policy: Some(PropagationPolicy::Background),
+        ..Default::default()
+    };
+    match api.delete(name, &params).await {
+        Ok(_) => Ok(()),
+        Err(kube::Error::Api(ae)) if ae.code == 404 => Ok(()),
+        Err(e) => Err(Error::KubeError(e)),
+    }
+}
+
+/// Create or update the backup CronJob and the worker's RBAC
+pub async fn ensure_backup(
+    client: &Client,
+    node: &StellarNode,
+    config: &DecentralizedBackupConfig,
+    image: &str,
+    operator_triggered: bool,
+) -> Result<()> {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+
+    apply(client, &namespace, &build_service_account(node)).await?;
+    apply(client, &namespace, &build_role(node)).await?;
+    apply(client, &namespace, &build_role_binding(node)).await?;
+    apply(
+        client,
+        &namespace,
+        &build_cronjob(node, config, image, operator_triggered),
+    )
+    .await?;
+
+    debug!(
+        "Backup CronJob {}/{} applied (schedule {})",
+        namespace,
+        backup_resource_name(node),
+        config.schedule
+    );
+    Ok(())
+}
+
+/// Latest schedule time of an operator-triggered CronJob that has passed
+/// since the last Job was started. Like the CronJob controller, missed runs
+/// collapse into one. Nothing is due while a Job of the CronJob is still
+/// running: the suspended CronJob no longer applies `concurrencyPolicy:
+/// Forbid`, so the operator does.
+pub async fn backup_due_at(
+    client: &Client,
+    node: &StellarNode,
+    config: &DecentralizedBackupConfig,
+) -> Result<Option<DateTime<Utc>>> {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let cronjobs: Api<CronJob> = Api::namespaced(client.clone(), &namespace);
+    let Some(cronjob) = cronjobs
+        .get_opt(&backup_resource_name(node))
+        .await
+        .map_err(Error::KubeError)?
+    else {
+        return Ok(None);
+    };
+
+    let last = last_triggered(&cronjob).unwrap_or_else(Utc::now);
+    let Some(due_at) = due_since(&config.schedule, last, Utc::now()) else {
+        return Ok(None);
+    };
+
+    let jobs: Api<Job> = Api::namespaced(client.clone(), &namespace);
+    let selector = backup_labels(node)
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join(",");
+    let jobs = jobs
+        .list(&ListParams::default().labels(&selector))
+        .await
+        .map_err(Error::KubeError)?;
+    if let Some(job) = active_job(&cronjob, &jobs.items) {
+        debug!(
+            "Backup due at {} waits for Job {}/{} to finish",
+            due_at,
+            namespace,
+            job.name_any()
+        );
+        return Ok(None);
+    }
+    Ok(Some(due_at))
+}
+
+/// Latest time `schedule` fired after `last` and at or before `now`
+pub fn due_since(schedule: &str, last: DateTime<Utc>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    previous_cron_fire(schedule, now).filter(|fired| *fired > last)
+}
+
+/// A Job owned by `cronjob` that has neither completed nor failed
+pub fn active_job<'a>(cronjob: &CronJob, jobs: &'a [Job]) -> Option<&'a Job> {
+    let uid = cronjob.uid()?;
+    jobs.iter().find(|job| {
+        let owned = job
+            .owner_references()
+            .iter()
+            .any(|owner| owner.kind == "CronJob" && owner.uid == uid);
+        let finished = job
+            .status
+            .as_ref()
+            .and_then(|s| s.conditions.as_ref())
+            .is_some_and(|conditions| {
+                conditions
+                    .iter()
+                    .any(|c| (c.type_ == "Complete" || c.type_ == "Failed") && c.status == "True")
+            });
+        owned && !finished
+    })
+}
+
+/// When the operator last started a Job from the CronJob, falling back to
+/// the CronJob's creation
+fn last_triggered(cronjob: &CronJob) -> Option<DateTime<Utc>> {
+    cronjob
+        .annotations()
+        .get(LAST_TRIGGERED_ANNOTATION)
+        .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+        .map(|t| t.with_timezone(&Utc))
+        .or_else(|| cronjob.metadata.creation_timestamp.as_ref().map(|t| t.0))
+}
+
+/// Job for the run of `cronjob` scheduled at `due_at`, owned by the CronJob
+/// like the Jobs it creates itself
+pub fn build_triggered_job(cronjob: &CronJob, due_at: DateTime<Utc>) -> Job {
+    let template = cronjob
+        .spec
+        .as_ref()
+        .map(|s| s.job_template.clone())
+        .unwrap_or_default();
+    let name = cronjob.name_any();
+
+    Job {
+        metadata: ObjectMeta {
+            // Scheduled time in minutes, as the CronJob controller names Jobs
+            name: Some(format!("{}-{}", name, due_at.timestamp() / 60)),
+            namespace: cronjob.namespace(),
+            labels: template.metadata.as_ref().and_then(|m| m.labels.clone()),
+            annotations: Some(BTreeMap::from([(
+                "cronjob.kubernetes.io/instantiate".to_string(),
+                "manual".to_string(),
+            )])),
+            owner_references: Some(vec![OwnerReference {
+                api_version: "batch/v1".to_string(),
+                kind: "CronJob".to_string(),
+                name,
+                uid: cronjob.uid().unwrap_or_default(),
+                controller: Some(true),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        },
+        spec: template.spec,
+        ..Default::default()
+    }
+}
+
+/// Start the backup run scheduled at `due_at` and record it on the CronJob.
+///
+/// Returns the Job name; an existing Job for the same run is left as is.
+pub async fn trigger_backup_job(
+    client: &Client,
+    node: &StellarNode,
+    due_at: DateTime<Utc>,
+) -> Result<String> {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let cronjobs: Api<CronJob> = Api::namespaced(client.clone(), &namespace);
+    let cronjob = cronjobs
+        .get(&backup_resource_name(node))
+        .await
+        .map_err(Error::KubeError)?;
+
+    let job = build_triggered_job(&cronjob, due_at);
+    let job_name = job.name_any();
+    let jobs: Api<Job> = Api::namespaced(client.clone(), &namespace);
+    match jobs.create(&PostParams::default(), &job).await {
+        Ok(_) => info!("Created backup Job {}/{}", namespace, job_name),
+        Err(kube::Error::Api(ae)) if ae.code == 409 => {
+            debug!("Backup Job {}/{} already exists", namespace, job_name)
+        }
+        Err(e) => return Err(Error::KubeError(e)),
+    }
+
+    let patch = serde_json::json!({
+        "metadata": { "annotations": { LAST_TRIGGERED_ANNOTATION: due_at.to_rfc3339() } }
+    });
+    cronjobs
+        .patch(
+            &cronjob.name_any(),
+            &PatchParams::default(),
+            &Patch::Merge(&patch),
+        )
+        .await
+        .map_err(Error::KubeError)?;
+    Ok(job_name)
+}
+
+/// Remove the backup CronJob and RBAC; a no-op if they do not exist
+pub async fn delete_backup(client: &Client, node: &StellarNode) -> Result<()> {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let name = backup_resource_name(node);
+
+    let cronjobs: Api<CronJob> = Api::namespaced(client.clone(), &namespace);
+    if cronjobs
+        .get_opt(&name)
+        .await
+        .map_err(Error::KubeError)?
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    delete::<CronJob>(client, &namespace, &name).await?;
+    delete::<RoleBinding>(client, &namespace, &name).await?;
+    delete::<Role>(client, &namespace, &name).await?;
+    delete::<ServiceAccount>(client, &namespace, &name).await?;
+
+    info!(
+        "Deleted decentralized backup CronJob {}/{}",
+        namespace, name
+    );
+    Ok(())
+}
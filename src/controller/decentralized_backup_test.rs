@@ -0,0 +1,311 @@
+//! Tests for the decentralized backup CronJob and worker RBAC.
+
+#[cfg(test)]
+mod tests {
+    use crate::backup::DecentralizedBackupConfig;
+    use crate::controller::decentralized_backup::*;
+    use crate::crd::StellarNode;
+
+    fn node() -> StellarNode {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "stellar.org/v1alpha1",
+            "kind": "StellarNode",
+            "metadata": { "name": "validator-1", "namespace": "stellar", "uid": "abc-123" },
+            "spec": {
+                "nodeType": "Validator",
+                "network": "Testnet",
+                "version": "v21.0.0",
+                "validatorConfig": { "seedSecretRef": "validator-seed" },
+                "decentralizedBackup": {
+                    "enabled": true,
+                    "schedule": "30 */4 * * *",
+                    "provider": {
+                        "type": "s3",
+                        "bucket": "stellar-history",
+                        "credentials_secret": "backup-s3",
+                    },
+                },
+            },
+        }))
+        .unwrap()
+    }
+
+    fn config(node: &StellarNode) -> DecentralizedBackupConfig {
+        node.spec.decentralized_backup.clone().unwrap()
+    }
+
+    #[test]
+    fn test_cronjob_runs_worker_against_data_pvc() {
+        let node = node();
+        let cronjob = build_cronjob(&node, &config(&node), "stellar-operator:v1", false);
+
+        assert_eq!(cronjob.metadata.name.as_deref(), Some("validator-1-backup"));
+        assert_eq!(
+            cronjob.metadata.owner_references.as_ref().unwrap()[0].uid,
+            "abc-123"
+        );
+
+        let spec = cronjob.spec.unwrap();
+        assert_eq!(spec.schedule, "30 */4 * * *");
+        assert_eq!(spec.suspend, Some(false));
+        assert_eq!(spec.concurrency_policy.as_deref(), Some("Forbid"));
+
+        let pod = spec.job_template.spec.unwrap().template.spec.unwrap();
+        assert_eq!(
+            pod.service_account_name.as_deref(),
+            Some("validator-1-backup")
+        );
+
+        let volume = &pod.volumes.as_ref().unwrap()[0];
+        let pvc = volume.persistent_volume_claim.as_ref().unwrap();
+        assert_eq!(pvc.claim_name, "validator-1-data");
+        assert_eq!(pvc.read_only, Some(true));
+
+        let container = &pod.containers[0];
+        assert_eq!(container.image.as_deref(), Some("stellar-operator:v1"));
+        assert_eq!(
+            container.args.as_ref().unwrap(),
+            &vec![
+                "backup".to_string(),
+                "--node=validator-1".to_string(),
+                "--namespace=stellar".to_string(),
+                "--data-dir=/archive".to_string(),
+            ]
+        );
+        assert_eq!(
+            container.volume_mounts.as_ref().unwrap()[0].read_only,
+            Some(true)
+        );
+
+        // Scheduled next to the validator, which holds the RWO volume
+        let term = &pod
+            .affinity
+            .unwrap()
+            .pod_affinity
+            .unwrap()
+            .required_during_scheduling_ignored_during_execution
+            .unwrap()[0];
+        assert_eq!(term.topology_key, "kubernetes.io/hostname");
+        let selector = term
+            .label_selector
+            .as_ref()
+            .unwrap()
+            .match_labels
+            .as_ref()
+            .unwrap();
+        assert_eq!(selector["app.kubernetes.io/instance"], "validator-1");
+        assert_eq!(selector["app.kubernetes.io/component"], "validator");
+    }
+
+    #[test]
+    fn test_cronjob_mounts_filesystem_target_pvc() {
+        let node = node();
+        let mut config = config(&node);
+        config.provider = crate::backup::StorageProvider::Filesystem {
+            path: "/backups".to_string(),
+            claim_name: Some("history-backups".to_string()),
+        };
+        let cronjob = build_cronjob(&node, &config, "stellar-operator:v1", false);
+        let pod = cronjob
+            .spec
+            .unwrap()
+            .job_template
+            .spec
+            .unwrap()
+            .template
+            .spec
+            .unwrap();
+
+        let volume = &pod.volumes.as_ref().unwrap()[1];
+        let pvc = volume.persistent_volume_claim.as_ref().unwrap();
+        assert_eq!(pvc.claim_name, "history-backups");
+        assert_eq!(pvc.read_only, Some(false));
+
+        let mount = &pod.containers[0].volume_mounts.as_ref().unwrap()[1];
+        assert_eq!(mount.name, volume.name);
+        assert_eq!(mount.mount_path, "/backups");
+        assert_ne!(mount.read_only, Some(true));
+    }
+
+    #[test]
+    fn test_cronjob_suspended_when_disabled_or_node_suspended() {
+        let mut node = node();
+        let mut cfg = config(&node);
+        cfg.enabled = false;
+        let spec = build_cronjob(&node, &cfg, "img", false).spec.unwrap();
+        assert_eq!(spec.suspend, Some(true));
+
+        let spec = build_cronjob(&node, &config(&node), "img", true)
+            .spec
+            .unwrap();
+        assert_eq!(spec.suspend, Some(true));
+
+        node.spec.suspended = true;
+        let spec = build_cronjob(&node, &config(&node), "img", false)
+            .spec
+            .unwrap();
+        assert_eq!(spec.suspend, Some(true));
+    }
+
+    #[test]
+    fn test_triggered_job_is_built_from_cronjob_template() {
+        let node = node();
+        let mut cronjob = build_cronjob(&node, &config(&node), "img", true);
+        cronjob.metadata.uid = Some("cron-uid".to_string());
+        let due_at = chrono::DateTime::parse_from_rfc3339("2026-03-01T04:30:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        let job = build_triggered_job(&cronjob, due_at);
+        assert_eq!(
+            job.metadata.name.as_deref(),
+            Some("validator-1-backup-29538990")
+        );
+        let owner = &job.metadata.owner_references.as_ref().unwrap()[0];
+        assert_eq!(
+            (owner.kind.as_str(), owner.uid.as_str()),
+            ("CronJob", "cron-uid")
+        );
+        assert_eq!(
+            job.spec
+                .unwrap()
+                .template
+                .spec
+                .unwrap()
+                .service_account_name
+                .as_deref(),
+            Some("validator-1-backup")
+        );
+    }
+
+    #[test]
+    fn test_due_backup_collapses_missed_runs() {
+        let at = |s: &str| {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .unwrap()
+                .with_timezone(&chrono::Utc)
+        };
+        let now = at("2026-03-01T13:00:00Z");
+
+        assert_eq!(
+            due_since("30 */4 * * *", at("2026-03-01T04:30:00Z"), now),
+            Some(at("2026-03-01T12:30:00Z"))
+        );
+        assert_eq!(
+            due_since("30 */4 * * *", at("2026-03-01T12:30:00Z"), now),
+            None
+        );
+        // Years of missed per-second runs do not step through each of them
+        assert_eq!(
+            due_since("* * * * * *", at("2020-01-01T00:00:00Z"), now),
+            Some(now)
+        );
+    }
+
+    #[test]
+    fn test_active_job_blocks_next_run() {
+        let node = node();
+        let mut cronjob = build_cronjob(&node, &config(&node), "img", true);
+        cronjob.metadata.uid = Some("cron-uid".to_string());
+        let due_at = chrono::DateTime::parse_from_rfc3339("2026-03-01T04:30:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        let running = build_triggered_job(&cronjob, due_at);
+        let mut finished = running.clone();
+        finished.status = Some(k8s_openapi::api::batch::v1::JobStatus {
+            conditions: Some(vec![k8s_openapi::api::batch::v1::JobCondition {
+                type_: "Complete".to_string(),
+                status: "True".to_string(),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        });
+        let mut foreign = running.clone();
+        foreign.metadata.owner_references.as_mut().unwrap()[0].uid = "other".to_string();
+
+        assert!(active_job(&cronjob, &[finished.clone(), foreign]).is_none());
+        assert_eq!(
+            active_job(&cronjob, &[finished, running]).and_then(|j| j.metadata.name.clone()),
+            Some("validator-1-backup-29538990".to_string())
+        );
+    }
+
+    #[test]
+    fn test_credentials_come_from_secrets() {
+        let node = node();
+        let env = credential_env(&config(&node).provider);
+
+        let refs: Vec<_> = env
+            .iter()
+            .map(|e| {
+                let secret = e
+                    .value_from
+                    .as_ref()
+                    .unwrap()
+                    .secret_key_ref
+                    .as_ref()
+                    .unwrap();
+                (
+                    e.name.as_str(),
+                    secret.name.as_deref().unwrap(),
+                    secret.key.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            refs,
+            vec![
+                ("BACKUP_S3_ACCESS_KEY_ID", "backup-s3", "accessKeyId"),
+                (
+                    "BACKUP_S3_SECRET_ACCESS_KEY",
+                    "backup-s3",
+                    "secretAccessKey"
+                ),
+            ]
+        );
+        assert!(env.iter().all(|e| e.value.is_none()));
+    }
+
+    #[test]
+    fn test_role_is_scoped_to_node_and_manifest() {
+        let node = node();
+        let role = build_role(&node);
+        let rules = role.rules.unwrap();
+
+        let status = rules
+            .iter()
+            .find(|r| r.resources.as_ref().unwrap()[0] == "stellarnodes/status")
+            .unwrap();
+        assert_eq!(
+            status.resource_names.as_ref().unwrap(),
+            &vec!["validator-1".to_string()]
+        );
+        assert!(status.verbs.contains(&"patch".to_string()));
+
+        let manifest = rules
+            .iter()
+            .find(|r| {
+                r.verbs.contains(&"patch".to_string())
+                    && r.api_groups.as_ref().unwrap()[0].is_empty()
+            })
+            .unwrap();
+        let names = manifest.resource_names.as_ref().unwrap();
+        assert_eq!(names[0], "validator-1-backup-manifest");
+        assert!(names.contains(&"validator-1-backup-manifest-a0".to_string()));
+        assert!(names.contains(&"validator-1-backup-manifest-b15".to_string()));
+        assert_eq!(names.len(), 33);
+        // Nothing grants access to Secrets; credentials arrive through env
+        assert!(rules.iter().all(|r| !r
+            .resources
+            .as_ref()
+            .unwrap()
+            .contains(&"secrets".to_string())));
+
+        let binding = build_role_binding(&node);
+        assert_eq!(binding.role_ref.name, "validator-1-backup");
+        let subject = &binding.subjects.unwrap()[0];
+        assert_eq!(subject.kind, "ServiceAccount");
+        assert_eq!(subject.namespace.as_deref(), Some("stellar"));
+    }
+}
@@ -2,30 +2,105 @@
 //!
 //! This module handles synchronization between primary and standby clusters,
 //! detects regional failures, and performs automated failover using external DNS.
+//!
+//! # DNS failover
+//!
+//! On failover the Standby publishes an external-dns `DNSEndpoint`
+//! (`externaldns.k8s.io/v1alpha1`) pointing `failoverDns.hostname` at this
+//! cluster's load balancer. Failback is operator-driven: annotate the
+//! StellarNode with `stellar.org/dr-failback=requested` and, once the peer is
+//! healthy again, the Standby deletes its `DNSEndpoint` and returns to standby.
+//! `status.drStatus.dnsRecordHolder` records which cluster holds the record.
+//!
+//! # Consistency
+//!
+//! Promotion and failback are blocked while the node's recent ledger hashes
+//! disagree with the peer or the history archive (see [`super::ledger_consistency`]).
 
 use chrono::Utc;
-use kube::{Client, ResourceExt};
-use tracing::{info, instrument, warn};
+use k8s_openapi::api::core::v1::Service;
+use kube::{
+    api::{Api, ApiResource, DeleteParams, DynamicObject, GroupVersionKind, Patch, PatchParams},
+    core::ObjectMeta,
+    Client, Resource, ResourceExt,
+};
+use serde_json::json;
+use tracing::{debug, info, instrument, warn};
 
-use crate::crd::{DRRole, DRSyncStrategy, DisasterRecoveryStatus, StellarNode};
-use crate::error::Result;
+use crate::crd::{
+    Condition, DRRole, DRSyncStrategy, DisasterRecoveryStatus, ExternalDNSConfig, LedgerHashSample,
+    StellarNode,
+};
+use crate::error::{Error, Result};
+
+use super::conditions;
+use super::dr_probe;
+use super::health::HealthCheckResult;
+use super::ledger_consistency;
 
 /// Key for the annotation that tracks the current failover state
 pub const DR_FAILOVER_ANNOTATION: &str = "stellar.org/dr-failover-active";
 pub const DR_LAST_SYNC_ANNOTATION: &str = "stellar.org/dr-last-sync-time";
+/// Annotation an operator sets (to `requested`) to fail back to the original primary
+pub const DR_FAILBACK_ANNOTATION: &str = "stellar.org/dr-failback";
+
+/// Cluster identifier recorded as DNS holder when `localClusterId` is not set
+const DEFAULT_LOCAL_CLUSTER_ID: &str = "local";
+
+const DNS_ENDPOINT_GROUP: &str = "externaldns.k8s.io";
+const DNS_ENDPOINT_VERSION: &str = "v1alpha1";
+const DNS_ENDPOINT_KIND: &str = "DNSEndpoint";
+const FIELD_MANAGER: &str = "stellar-operator";
+
+/// Failover state transition decided for a reconciliation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailoverAction {
+    /// Nothing to change
+    None,
+    /// Promote this Standby and take over the DNS record
+    Failover,
+    /// Hand the DNS record back to the peer and return to Standby
+    Failback,
+    /// Failback was requested but the peer is not healthy yet
+    FailbackBlocked,
+}
+
+/// Decide the failover transition for a node
+///
+/// Failover is automatic when a Standby's peer is unreachable. Failback only
+/// happens when explicitly requested and the peer is healthy again.
+pub fn evaluate_failover(
+    role: &DRRole,
+    peer_healthy: bool,
+    failback_requested: bool,
+    status: &DisasterRecoveryStatus,
+) -> FailoverAction {
+    if *role != DRRole::Standby {
+        return FailoverAction::None;
+    }
+
+    match (status.failover_active, peer_healthy, failback_requested) {
+        (false, false, _) => FailoverAction::Failover,
+        (true, true, true) => FailoverAction::Failback,
+        (true, false, true) => FailoverAction::FailbackBlocked,
+        _ => FailoverAction::None,
+    }
+}
 
 /// Handle DR reconciliation for a node
-#[instrument(skip(client, node), fields(name = %node.name_any()))]
+#[instrument(skip(client, node, health, mtls_config), fields(name = %node.name_any()))]
 pub async fn reconcile_dr(
     client: &Client,
     node: &StellarNode,
+    health: &HealthCheckResult,
+    mtls_config: Option<&crate::MtlsConfig>,
 ) -> Result<Option<DisasterRecoveryStatus>> {
     let dr_config = match &node.spec.dr_config {
         Some(config) if config.enabled => config,
         _ => return Ok(None),
     };
 
-    let _namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
     let name = node.name_any();
 
     info!("Processing DR for {} in role {:?}", name, dr_config.role);
@@ -36,60 +111,137 @@ pub async fn reconcile_dr(
         .and_then(|s| s.dr_status.clone())
         .unwrap_or_default();
 
-    // 1. Check peer health
-    // In a real implementation, this would call the peer cluster's API
-    // For this task, we'll simulate the peer health check
-    let peer_healthy = simulate_peer_health_check(&dr_config.peer_cluster_id).await;
-
-    status.peer_health = Some(if peer_healthy {
-        "Healthy".to_string()
-    } else {
-        "Unreachable".to_string()
-    });
-    if peer_healthy {
-        status.last_peer_contact = Some(Utc::now().to_rfc3339());
+    // 0. Record the local ledger hash for later consistency checks
+    if let (Some(sequence), Some(hash)) = (
+        health.ledger_sequence,
+        health
+            .validator
+            .as_ref()
+            .and_then(|v| v.ledger_hash.clone()),
+    ) {
+        ledger_consistency::record_sample(
+            &mut status.recent_ledger_hashes,
+            LedgerHashSample { sequence, hash },
+            dr_config.consistency_window.max(1) as usize,
+        );
     }
 
-    // 2. Automated Failover Logic
-    if dr_config.role == DRRole::Standby && !peer_healthy {
+    // 1. Check peer health
+    let peer_was_down = matches!(
+        status.peer_health.as_deref(),
+        Some(dr_probe::PEER_HEALTH_UNREACHABLE) | Some(dr_probe::PEER_HEALTH_DEGRADED)
+    );
+    let probes = dr_probe::build_probes(dr_config, &namespace, &name, mtls_config)?;
+    let now = Utc::now();
+    let probed = !probes.is_empty() && dr_probe::probe_due(&status, dr_config, now);
+    let peer_healthy = if probes.is_empty() {
+        // Without probes we have no evidence the peer is down, so never fail over
         warn!(
-            "Primary cluster {} is unreachable. Evaluating failover...",
-            dr_config.peer_cluster_id
+            "No DR peer probes configured for {}; peer {} health is unknown",
+            name, dr_config.peer_cluster_id
         );
+        status.peer_health = Some(dr_probe::PEER_HEALTH_UNKNOWN.to_string());
+        true
+    } else if !probed {
+        status.peer_health.as_deref() != Some(dr_probe::PEER_HEALTH_UNREACHABLE)
+    } else {
+        let round = dr_probe::run_probe_round(&probes, dr_config.probe_quorum).await;
+        status.last_probe_time = Some(now.to_rfc3339());
+        if !round.peer_down {
+            status.last_peer_contact = Some(now.to_rfc3339());
+        }
+        !dr_probe::apply_probe_round(&mut status, &round, dr_config)
+    };
 
-        // Trigger failover if not already active
-        if !status.failover_active {
-            info!("Initiating automated failover for {}", name);
-            status.failover_active = true;
-            status.current_role = Some(DRRole::Primary);
+    // 2. Automated failover and operator-driven failback
+    let failback_requested = node
+        .annotations()
+        .get(DR_FAILBACK_ANNOTATION)
+        .is_some_and(|v| v == "requested");
+    let local_cluster_id = dr_config
+        .local_cluster_id
+        .as_deref()
+        .unwrap_or(DEFAULT_LOCAL_CLUSTER_ID);
+
+    let mut action = evaluate_failover(&dr_config.role, peer_healthy, failback_requested, &status);
+
+    // 2a. Verify ledger consistency when a partition heals, before any role
+    // change, and while the node is known to be diverged
+    let partition_healed = probed && peer_was_down && peer_healthy;
+    if partition_healed
+        || status.diverged
+        || matches!(action, FailoverAction::Failover | FailoverAction::Failback)
+    {
+        let diverged = verify_consistency_partition(node, &mut status, mtls_config).await?;
+        if diverged && action != FailoverAction::None {
+            warn!(
+                "Blocking {:?} for {}: ledger history has diverged ({})",
+                action,
+                name,
+                status.divergence_message.as_deref().unwrap_or("unknown")
+            );
+            action = FailoverAction::None;
+        }
+    }
+
+    match action {
+        FailoverAction::Failover => {
+            warn!(
+                "Primary cluster {} is unreachable. Initiating automated failover for {}",
+                dr_config.peer_cluster_id, name
+            );
 
-            // Perform DNS update
             if let Some(dns_config) = &dr_config.failover_dns {
                 update_failover_dns(client, node, dns_config).await?;
+                set_dns_holder(&mut status, local_cluster_id);
+            }
+            status.failover_active = true;
+            status.current_role = Some(DRRole::Primary);
+        }
+        FailoverAction::Failback => {
+            info!(
+                "Failback requested for {}: returning DNS to primary cluster {}",
+                name, dr_config.peer_cluster_id
+            );
+
+            if dr_config.failover_dns.is_some() {
+                remove_failover_dns(client, node).await?;
+                set_dns_holder(&mut status, &dr_config.peer_cluster_id);
+            }
+            status.failover_active = false;
+            status.current_role = Some(DRRole::Standby);
+            clear_failback_request(client, node).await?;
+        }
+        FailoverAction::FailbackBlocked => {
+            warn!(
+                "Failback requested for {} but primary cluster {} is not healthy yet",
+                name, dr_config.peer_cluster_id
+            );
+        }
+        FailoverAction::None => {
+            if !status.failover_active {
+                status.current_role = Some(dr_config.role.clone());
+                if dr_config.role == DRRole::Standby
+                    && dr_config.failover_dns.is_some()
+                    && status.dns_record_holder.is_none()
+                {
+                    set_dns_holder(&mut status, &dr_config.peer_cluster_id);
+                }
             }
         }
-    } else if dr_config.role == DRRole::Standby && peer_healthy && status.failover_active {
-        // Optional: Failback logic could go here
-        info!(
-            "Primary cluster {} is healthy again. Failback would be manual.",
-            dr_config.peer_cluster_id
-        );
-    } else {
-        status.current_role = Some(dr_config.role.clone());
     }
 
     // 3. State Synchronization logic
     if dr_config.role == DRRole::Standby && !status.failover_active {
         match dr_config.sync_strategy {
             DRSyncStrategy::PeerTracking => {
-                let peer_ledger = fetch_peer_ledger_sequence(&dr_config.peer_cluster_id)
-                    .await
-                    .ok();
+                let peer_ledger = status.peer_ledger_sequence;
                 let local_ledger = node.status.as_ref().and_then(|s| s.ledger_sequence);
 
-                if let (Some(p), Some(l)) = (peer_ledger, local_ledger) {
-                    status.sync_lag = Some(p.saturating_sub(l));
-                }
+                status.sync_lag = match (peer_ledger, local_ledger) {
+                    (Some(p), Some(l)) => Some(p.saturating_sub(l)),
+                    _ => None,
+                };
             }
             DRSyncStrategy::ArchiveSync => {
                 // Logic for ensuring history archives are being consumed/synced
@@ -104,44 +256,272 @@ pub async fn reconcile_dr(
     Ok(Some(status))
 }
 
-/// Simulate checking health of a peer cluster
-async fn simulate_peer_health_check(_peer_id: &str) -> bool {
-    // In production, this would be a real network check
-    // For verification, we assume it's healthy unless a failure is simulated
-    true
+/// Record which cluster currently holds the failover DNS record
+fn set_dns_holder(status: &mut DisasterRecoveryStatus, cluster_id: &str) {
+    if status.dns_record_holder.as_deref() != Some(cluster_id) {
+        status.dns_record_holder = Some(cluster_id.to_string());
+        status.dns_record_since = Some(Utc::now().to_rfc3339());
+    }
+}
+
+fn dns_endpoint_api_resource() -> ApiResource {
+    ApiResource::from_gvk(&GroupVersionKind {
+        group: DNS_ENDPOINT_GROUP.to_string(),
+        version: DNS_ENDPOINT_VERSION.to_string(),
+        kind: DNS_ENDPOINT_KIND.to_string(),
+    })
+}
+
+/// Name of the DNSEndpoint published on failover: `<node-name>-dr-failover`
+pub fn dns_endpoint_name(node: &StellarNode) -> String {
+    format!("{}-dr-failover", node.name_any())
+}
+
+/// Build the external-dns `DNSEndpoint` that points the failover hostname at `target`
+///
+/// IP targets produce an `A` record, anything else a `CNAME`.
+pub fn build_dns_endpoint(
+    node: &StellarNode,
+    dns_config: &ExternalDNSConfig,
+    target: &str,
+) -> DynamicObject {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let name = dns_endpoint_name(node);
+    let record_type = if target.parse::<std::net::IpAddr>().is_ok() {
+        "A"
+    } else {
+        "CNAME"
+    };
+
+    let mut annotations = dns_config.annotations.clone().unwrap_or_default();
+    if let Some(provider) = &dns_config.provider {
+        annotations.insert("stellar.org/dns-provider".to_string(), provider.clone());
+    }
+
+    let mut obj = DynamicObject::new(&name, &dns_endpoint_api_resource());
+    obj.metadata = ObjectMeta {
+        name: Some(name),
+        namespace: Some(namespace),
+        labels: Some(
+            [
+                (
+                    "app.kubernetes.io/managed-by".to_string(),
+                    FIELD_MANAGER.to_string(),
+                ),
+                ("app.kubernetes.io/instance".to_string(), node.name_any()),
+            ]
+            .into_iter()
+            .collect(),
+        ),
+        annotations: if annotations.is_empty() {
+            None
+        } else {
+            Some(annotations)
+        },
+        owner_references: node.controller_owner_ref(&()).map(|r| vec![r]),
+        ..Default::default()
+    };
+    obj.data = json!({
+        "spec": {
+            "endpoints": [{
+                "dnsName": dns_config.hostname,
+                "recordTTL": dns_config.ttl,
+                "recordType": record_type,
+                "targets": [target],
+            }]
+        }
+    });
+    obj
 }
 
-/// Simulation of fetching the latest ledger sequence from the peer
-async fn fetch_peer_ledger_sequence(_peer_id: &str) -> Result<u64> {
-    // Simulated peer ledger sequence
-    Ok(1234567)
+/// Find the externally reachable address of this cluster's node
+///
+/// Prefers the MetalLB IP recorded in status, then the node Service's
+/// load balancer ingress.
+async fn resolve_failover_target(client: &Client, node: &StellarNode) -> Result<String> {
+    if let Some(ip) = node.status.as_ref().and_then(|s| s.external_ip.clone()) {
+        return Ok(ip);
+    }
+
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let api: Api<Service> = Api::namespaced(client.clone(), &namespace);
+    let service = api.get(&node.name_any()).await.map_err(Error::KubeError)?;
+
+    service
+        .status
+        .and_then(|s| s.load_balancer)
+        .and_then(|lb| lb.ingress)
+        .and_then(|ingress| ingress.into_iter().find_map(|i| i.ip.or(i.hostname)))
+        .ok_or_else(|| {
+            Error::ConfigError(format!(
+                "No external address found for {namespace}/{} to publish failover DNS",
+                node.name_any()
+            ))
+        })
 }
 
-/// Update external DNS for failover
+/// Publish the failover DNS record for this cluster
 async fn update_failover_dns(
-    _client: &Client,
-    _node: &StellarNode,
-    dns_config: &crate::crd::ExternalDNSConfig,
+    client: &Client,
+    node: &StellarNode,
+    dns_config: &ExternalDNSConfig,
 ) -> Result<()> {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let target = resolve_failover_target(client, node).await?;
+
     info!(
-        "Updating external DNS ({}) for failover: {} -> this cluster",
+        "Updating external DNS ({}) for failover: {} -> {}",
         dns_config.provider.as_deref().unwrap_or("default"),
-        dns_config.hostname
+        dns_config.hostname,
+        target
     );
 
-    // In a real implementation, this would create/patch a Service or DNSEndpoint resource
-    // that external-dns watches to update Route53/Cloudflare.
+    let api: Api<DynamicObject> =
+        Api::namespaced_with(client.clone(), &namespace, &dns_endpoint_api_resource());
+    let endpoint = build_dns_endpoint(node, dns_config, &target);
+
+    api.patch(
+        &dns_endpoint_name(node),
+        &PatchParams::apply(FIELD_MANAGER).force(),
+        &Patch::Apply(&endpoint),
+    )
+    .await
+    .map_err(|e| {
+        if let kube::Error::Api(ref ae) = e {
+            if ae.code == 404 {
+                return Error::ConfigError(format!(
+                    "DNSEndpoint CRD not installed ({}). Deploy external-dns with the CRD source \
+                     before enabling failoverDns.",
+                    ae.message
+                ));
+            }
+        }
+        Error::KubeError(e)
+    })?;
 
     Ok(())
 }
 
-/// Verify data consistency during regional partition
-pub async fn verify_consistency_partition(node: &StellarNode) -> bool {
-    // Logic to verify that the node hasn't diverged during a partition
-    // e.g. checking that most recent ledgers match known hashes
+/// Remove the failover DNS record published by this cluster
+async fn remove_failover_dns(client: &Client, node: &StellarNode) -> Result<()> {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let api: Api<DynamicObject> =
+        Api::namespaced_with(client.clone(), &namespace, &dns_endpoint_api_resource());
+
+    match api
+        .delete(&dns_endpoint_name(node), &DeleteParams::default())
+        .await
+    {
+        Ok(_) => {
+            info!(
+                "Deleted failover DNSEndpoint {}/{}",
+                namespace,
+                dns_endpoint_name(node)
+            );
+            Ok(())
+        }
+        Err(kube::Error::Api(e)) if e.code == 404 => {
+            debug!("Failover DNSEndpoint already absent");
+            Ok(())
+        }
+        Err(e) => Err(Error::KubeError(e)),
+    }
+}
+
+/// Remove the failback request annotation once failback has completed
+async fn clear_failback_request(client: &Client, node: &StellarNode) -> Result<()> {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let api: Api<StellarNode> = Api::namespaced(client.clone(), &namespace);
+    let patch = json!({
+        "metadata": {
+            "annotations": {
+                DR_FAILBACK_ANNOTATION: null
+            }
+        }
+    });
+
+    api.patch(
+        &node.name_any(),
+        &PatchParams::default(),
+        &Patch::Merge(&patch),
+    )
+    .await
+    .map_err(Error::KubeError)?;
+
+    Ok(())
+}
+
+/// Verify that the node's recent ledger history matches the peer and archives
+///
+/// Updates the consistency fields of `status` and returns whether the node
+/// has diverged. Unreachable sources are skipped.
+pub async fn verify_consistency_partition(
+    node: &StellarNode,
+    status: &mut DisasterRecoveryStatus,
+    mtls_config: Option<&crate::MtlsConfig>,
+) -> Result<bool> {
+    let Some(dr_config) = node.spec.dr_config.as_ref() else {
+        return Ok(false);
+    };
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let name = node.name_any();
+    let archive_urls = node
+        .spec
+        .validator_config
+        .as_ref()
+        .map(|v| v.history_archive_urls.clone())
+        .unwrap_or_default();
+
     info!(
-        "Verifying consistency for {} during partition...",
-        node.name_any()
+        "Verifying ledger consistency for {} against {} peer probe(s) and {} archive(s)",
+        name,
+        dr_config.peer_probes.len(),
+        archive_urls.len()
     );
-    true
+
+    let report = ledger_consistency::verify_consistency(
+        &status.recent_ledger_hashes,
+        &dr_config.peer_probes,
+        &archive_urls,
+        &namespace,
+        &name,
+        mtls_config,
+    )
+    .await?;
+
+    if report.diverged {
+        warn!(
+            "Ledger divergence detected for {}: {}",
+            name, report.message
+        );
+    } else {
+        debug!("Ledger consistency for {}: {}", name, report.message);
+    }
+
+    status.diverged = report.diverged;
+    status.divergence_message = report.diverged.then(|| report.message.clone());
+    status.last_consistency_check = Some(Utc::now().to_rfc3339());
+
+    Ok(report.diverged)
+}
+
+/// Set or clear the `Diverged` condition from the DR status
+pub fn apply_divergence_condition(
+    conditions_list: &mut Vec<Condition>,
+    dr_status: &DisasterRecoveryStatus,
+) {
+    if dr_status.diverged {
+        conditions::set_condition(
+            conditions_list,
+            conditions::CONDITION_TYPE_DIVERGED,
+            conditions::CONDITION_STATUS_TRUE,
+            "LedgerHashMismatch",
+            dr_status
+                .divergence_message
+                .as_deref()
+                .unwrap_or("Ledger history disagrees with peer or archive"),
+        );
+    } else {
+        conditions::remove_condition(conditions_list, conditions::CONDITION_TYPE_DIVERGED);
+    }
 }
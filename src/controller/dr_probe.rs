@@ -0,0 +1,416 @@
+//! Peer cluster health probing for Disaster Recovery
+//!
+//! A Standby node decides whether its Primary is gone by running a set of
+//! [`PeerProbe`]s every reconciliation. Each probe reaches the peer over HTTP(S)
+//! (with mTLS when configured) and reports reachability and the peer's ledger.
+//!
+//! A probe round counts as failed only when a quorum of probes fail, and the
+//! peer is declared unreachable only after `failure_threshold` consecutive
+//! failed rounds. It is declared healthy again after `recovery_threshold`
+//! consecutive good rounds, which prevents flapping between roles.
+//!
+//! A probe refused with 401 or 403 is inconclusive: the peer answered, so it
+//! is neither counted as down nor as healthy. A round in which every probe is
+//! inconclusive leaves the counters as they were.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use crate::crd::{DRPeerProbe, DRProbeKind, DisasterRecoveryConfig, DisasterRecoveryStatus};
+use crate::error::Result;
+
+use super::health;
+
+/// Peer health reported while the peer is up
+pub const PEER_HEALTH_HEALTHY: &str = "Healthy";
+/// Peer health reported while failures are accumulating but below the threshold
+pub const PEER_HEALTH_DEGRADED: &str = "Degraded";
+/// Peer health reported once the failure threshold has been reached
+pub const PEER_HEALTH_UNREACHABLE: &str = "Unreachable";
+/// Peer health reported when no probes are configured
+pub const PEER_HEALTH_UNKNOWN: &str = "Unknown";
+
+/// Outcome of a single probe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerProbeResult {
+    /// Whether the peer answered and reported itself healthy
+    pub healthy: bool,
+    /// Whether the probe could not tell, e.g. because it was not authorized
+    pub inconclusive: bool,
+    /// Ledger sequence reported by the peer (if any)
+    pub ledger_sequence: Option<u64>,
+    /// Human-readable detail
+    pub message: String,
+}
+
+impl PeerProbeResult {
+    pub fn up(ledger_sequence: Option<u64>) -> Self {
+        Self {
+            healthy: true,
+            inconclusive: false,
+            ledger_sequence,
+            message: "Peer is healthy".to_string(),
+        }
+    }
+
+    pub fn down(message: String) -> Self {
+        Self {
+            healthy: false,
+            inconclusive: false,
+            ledger_sequence: None,
+            message,
+        }
+    }
+
+    pub fn unknown(message: String) -> Self {
+        Self {
+            healthy: false,
+            inconclusive: true,
+            ledger_sequence: None,
+            message,
+        }
+    }
+
+    /// Result for a response with a non-success status
+    fn from_status(status: reqwest::StatusCode) -> Self {
+        match status {
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => Self::unknown(
+                format!("HTTP {status}; check the probe's credentials or client certificate"),
+            ),
+            _ => Self::down(format!("HTTP {status}")),
+        }
+    }
+}
+
+/// A way of checking whether the DR peer is alive
+#[async_trait]
+pub trait PeerProbe: Send + Sync {
+    /// Short description used in logs
+    fn describe(&self) -> String;
+
+    /// Run the probe. Transport failures are reported as an unhealthy result.
+    async fn probe(&self) -> PeerProbeResult;
+}
+
+/// Probe the peer cluster's operator REST API for the peer StellarNode
+pub struct OperatorApiProbe {
+    client: reqwest::Client,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OperatorNodeResponse {
+    #[serde(default)]
+    status: OperatorNodeStatus,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OperatorNodeStatus {
+    #[serde(default)]
+    ledger_sequence: Option<u64>,
+    #[serde(default)]
+    conditions: Vec<OperatorNodeCondition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OperatorNodeCondition {
+    #[serde(rename = "type")]
+    type_: String,
+    status: String,
+}
+
+impl OperatorApiProbe {
+    pub fn new(client: reqwest::Client, endpoint: &str, namespace: &str, name: &str) -> Self {
+        Self {
+            client,
+            url: format!(
+                "{}/api/v1/nodes/{namespace}/{name}",
+                endpoint.trim_end_matches('/')
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl PeerProbe for OperatorApiProbe {
+    fn describe(&self) -> String {
+        format!("operator API {}", self.url)
+    }
+
+    async fn probe(&self) -> PeerProbeResult {
+        let response = match self.client.get(&self.url).send().await {
+            Ok(r) if r.status().is_success() => r,
+            Ok(r) => return PeerProbeResult::from_status(r.status()),
+            Err(e) => return PeerProbeResult::down(format!("Request failed: {e}")),
+        };
+
+        match response.json::<OperatorNodeResponse>().await {
+            Ok(node) => {
+                let not_ready = node
+                    .status
+                    .conditions
+                    .iter()
+                    .any(|c| c.type_ == "Ready" && c.status == "False");
+                if not_ready {
+                    PeerProbeResult::down("Peer node reports Ready=False".to_string())
+                } else {
+                    PeerProbeResult::up(node.status.ledger_sequence)
+                }
+            }
+            Err(e) => PeerProbeResult::down(format!("Unparseable response: {e}")),
+        }
+    }
+}
+
+/// Probe the peer node's Horizon root endpoint
+pub struct HorizonProbe {
+    client: reqwest::Client,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct HorizonRootResponse {
+    #[serde(default)]
+    history_latest_ledger: Option<u64>,
+}
+
+impl HorizonProbe {
+    pub fn new(client: reqwest::Client, endpoint: &str) -> Self {
+        Self {
+            client,
+            url: format!("{}/", endpoint.trim_end_matches('/')),
+        }
+    }
+}
+
+#[async_trait]
+impl PeerProbe for HorizonProbe {
+    fn describe(&self) -> String {
+        format!("Horizon {}", self.url)
+    }
+
+    async fn probe(&self) -> PeerProbeResult {
+        let response = match self.client.get(&self.url).send().await {
+            Ok(r) if r.status().is_success() => r,
+            Ok(r) => return PeerProbeResult::from_status(r.status()),
+            Err(e) => return PeerProbeResult::down(format!("Request failed: {e}")),
+        };
+
+        match response.json::<HorizonRootResponse>().await {
+            Ok(root) => PeerProbeResult::up(root.history_latest_ledger),
+            Err(e) => PeerProbeResult::down(format!("Unparseable response: {e}")),
+        }
+    }
+}
+
+/// Probe the peer node's Stellar Core `/info` endpoint
+pub struct CoreProbe {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl CoreProbe {
+    pub fn new(client: reqwest::Client, endpoint: &str) -> Self {
+        Self {
+            client,
+            url: format!("{}/info", endpoint.trim_end_matches('/')),
+        }
+    }
+}
+
+#[async_trait]
+impl PeerProbe for CoreProbe {
+    fn describe(&self) -> String {
+        format!("Stellar Core {}", self.url)
+    }
+
+    async fn probe(&self) -> PeerProbeResult {
+        let response = match self.client.get(&self.url).send().await {
+            Ok(r) if r.status().is_success() => r,
+            Ok(r) => return PeerProbeResult::from_status(r.status()),
+            Err(e) => return PeerProbeResult::down(format!("Request failed: {e}")),
+        };
+
+        match response.json::<health::CoreInfoResponse>().await {
+            Ok(core) => {
+                let result = health::evaluate_core_info(&core.info);
+                if result.synced {
+                    PeerProbeResult::up(result.ledger_sequence)
+                } else {
+                    PeerProbeResult {
+                        healthy: false,
+                        inconclusive: false,
+                        ledger_sequence: result.ledger_sequence,
+                        message: result.message,
+                    }
+                }
+            }
+            Err(e) => PeerProbeResult::down(format!("Unparseable response: {e}")),
+        }
+    }
+}
+
+/// Build the probes configured for a DR setup
+///
+/// `namespace` and `name` identify the local StellarNode and are used as the
+/// defaults for operator API probes, since DR peers usually mirror each other.
+pub fn build_probes(
+    config: &DisasterRecoveryConfig,
+    namespace: &str,
+    name: &str,
+    mtls_config: Option<&crate::MtlsConfig>,
+) -> Result<Vec<Box<dyn PeerProbe>>> {
+    let client = health::build_http_client(mtls_config)?;
+
+    Ok(config
+        .peer_probes
+        .iter()
+        .map(|p| build_probe(p, client.clone(), namespace, name))
+        .collect())
+}
+
+fn build_probe(
+    probe: &DRPeerProbe,
+    client: reqwest::Client,
+    namespace: &str,
+    name: &str,
+) -> Box<dyn PeerProbe> {
+    match probe.kind {
+        DRProbeKind::OperatorApi => Box::new(OperatorApiProbe::new(
+            client,
+            &probe.endpoint,
+            probe.node_namespace.as_deref().unwrap_or(namespace),
+            probe.node_name.as_deref().unwrap_or(name),
+        )),
+        DRProbeKind::Horizon => Box::new(HorizonProbe::new(client, &probe.endpoint)),
+        DRProbeKind::Core => Box::new(CoreProbe::new(client, &probe.endpoint)),
+    }
+}
+
+/// Aggregated result of one probe round
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeRound {
+    /// Whether the round counts as the peer being down
+    pub peer_down: bool,
+    /// No probe gave a conclusive answer
+    pub inconclusive: bool,
+    /// Highest ledger sequence reported by any probe
+    pub peer_ledger: Option<u64>,
+}
+
+/// Run all probes concurrently and aggregate the results
+pub async fn run_probe_round(probes: &[Box<dyn PeerProbe>], quorum: Option<u32>) -> ProbeRound {
+    let results = futures::future::join_all(probes.iter().map(|p| async move {
+        let result = p.probe().await;
+        if result.inconclusive {
+            warn!(
+                "DR peer probe {} inconclusive: {}",
+                p.describe(),
+                result.message
+            );
+        } else if !result.healthy {
+            warn!("DR peer probe {} failed: {}", p.describe(), result.message);
+        } else {
+            debug!(
+                "DR peer probe {} ok (ledger {:?})",
+                p.describe(),
+                result.ledger_sequence
+            );
+        }
+        result
+    }))
+    .await;
+
+    evaluate_probe_round(&results, quorum)
+}
+
+/// Decide whether a round of probe results means the peer is down
+///
+/// The peer is down when at least `quorum` probes failed. Without an explicit
+/// quorum a strict majority of the conclusive probes must fail, so a single
+/// broken network path cannot trigger a failover on its own. Inconclusive
+/// probes count neither way.
+pub fn evaluate_probe_round(results: &[PeerProbeResult], quorum: Option<u32>) -> ProbeRound {
+    let conclusive: Vec<&PeerProbeResult> = results.iter().filter(|r| !r.inconclusive).collect();
+    let failed = conclusive.iter().filter(|r| !r.healthy).count();
+    let required = quorum
+        .map(|q| q.max(1) as usize)
+        .unwrap_or(conclusive.len() / 2 + 1);
+
+    ProbeRound {
+        peer_down: !conclusive.is_empty() && failed >= required,
+        inconclusive: !results.is_empty() && conclusive.is_empty(),
+        peer_ledger: results.iter().filter_map(|r| r.ledger_sequence).max(),
+    }
+}
+
+/// Apply a probe round to the DR status with hysteresis
+///
+/// Whether a probe round is due: `health_check_interval` seconds after the
+/// last one. Between rounds the previous verdict stands, so the failure and
+/// recovery thresholds count rounds rather than reconciles.
+pub fn probe_due(
+    status: &DisasterRecoveryStatus,
+    config: &DisasterRecoveryConfig,
+    now: DateTime<Utc>,
+) -> bool {
+    status
+        .last_probe_time
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .is_none_or(|last| {
+            now.signed_duration_since(last)
+                >= chrono::Duration::seconds(i64::from(config.health_check_interval))
+        })
+}
+
+/// Updates the consecutive counters and `peer_health`, and returns whether the
+/// peer should now be treated as unreachable. The peer's ledger is cleared
+/// when no probe reported one, so a stale sequence is never used for the sync
+/// lag.
+pub fn apply_probe_round(
+    status: &mut DisasterRecoveryStatus,
+    round: &ProbeRound,
+    config: &DisasterRecoveryConfig,
+) -> bool {
+    status.peer_ledger_sequence = round.peer_ledger;
+
+    let was_unreachable = status.peer_health.as_deref() == Some(PEER_HEALTH_UNREACHABLE);
+    if round.inconclusive {
+        if status.peer_health.is_none() {
+            status.peer_health = Some(PEER_HEALTH_UNKNOWN.to_string());
+        }
+        return was_unreachable;
+    }
+
+    if round.peer_down {
+        status.consecutive_failures = status.consecutive_failures.saturating_add(1);
+        status.consecutive_successes = 0;
+    } else {
+        status.consecutive_successes = status.consecutive_successes.saturating_add(1);
+        status.consecutive_failures = 0;
+    }
+
+    let unreachable = if was_unreachable {
+        status.consecutive_successes < config.recovery_threshold.max(1)
+    } else {
+        status.consecutive_failures >= config.failure_threshold.max(1)
+    };
+
+    status.peer_health = Some(
+        if unreachable {
+            PEER_HEALTH_UNREACHABLE
+        } else if round.peer_down {
+            PEER_HEALTH_DEGRADED
+        } else {
+            PEER_HEALTH_HEALTHY
+        }
+        .to_string(),
+    );
+
+    unreachable
+}
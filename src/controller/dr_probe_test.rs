@@ -0,0 +1,336 @@
+//! Tests for DR peer probing
+//!
+//! Covers probe-round quorum evaluation, failure/recovery hysteresis, and the
+//! HTTP probes against local axum stand-ins for the peer cluster.
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::dr_probe::*;
+    use crate::crd::{DRRole, DRSyncStrategy, DisasterRecoveryConfig, DisasterRecoveryStatus};
+
+    fn dr_config(failure_threshold: u32, recovery_threshold: u32) -> DisasterRecoveryConfig {
+        DisasterRecoveryConfig {
+            enabled: true,
+            role: DRRole::Standby,
+            peer_cluster_id: "us-west-2".to_string(),
+            sync_strategy: DRSyncStrategy::PeerTracking,
+            failover_dns: None,
+            health_check_interval: 30,
+            peer_probes: Vec::new(),
+            failure_threshold,
+            recovery_threshold,
+            probe_quorum: None,
+            local_cluster_id: None,
+            consistency_window: 10,
+        }
+    }
+
+    fn down_round() -> ProbeRound {
+        ProbeRound {
+            peer_down: true,
+            inconclusive: false,
+            peer_ledger: None,
+        }
+    }
+
+    fn up_round(ledger: u64) -> ProbeRound {
+        ProbeRound {
+            peer_down: false,
+            inconclusive: false,
+            peer_ledger: Some(ledger),
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Probe round evaluation
+    // -------------------------------------------------------------------------
+
+    #[test]
+    fn test_round_requires_majority_by_default() {
+        let results = vec![
+            PeerProbeResult::down("timeout".to_string()),
+            PeerProbeResult::up(Some(100)),
+            PeerProbeResult::up(Some(102)),
+        ];
+        let round = evaluate_probe_round(&results, None);
+        assert!(!round.peer_down);
+        assert_eq!(round.peer_ledger, Some(102));
+
+        let results = vec![
+            PeerProbeResult::down("timeout".to_string()),
+            PeerProbeResult::down("refused".to_string()),
+            PeerProbeResult::up(Some(100)),
+        ];
+        assert!(evaluate_probe_round(&results, None).peer_down);
+    }
+
+    #[test]
+    fn test_round_respects_explicit_quorum() {
+        let results = vec![
+            PeerProbeResult::down("timeout".to_string()),
+            PeerProbeResult::up(Some(100)),
+        ];
+        assert!(evaluate_probe_round(&results, Some(1)).peer_down);
+        assert!(!evaluate_probe_round(&results, Some(2)).peer_down);
+    }
+
+    #[test]
+    fn test_inconclusive_probes_count_neither_way() {
+        // One failure out of two conclusive probes is not a majority
+        let results = vec![
+            PeerProbeResult::unknown("HTTP 403".to_string()),
+            PeerProbeResult::unknown("HTTP 401".to_string()),
+            PeerProbeResult::down("timeout".to_string()),
+            PeerProbeResult::up(Some(100)),
+        ];
+        let round = evaluate_probe_round(&results, None);
+        assert!(!round.peer_down && !round.inconclusive);
+
+        // Only inconclusive probes: the round says nothing
+        let round = evaluate_probe_round(&results[..2], None);
+        assert!(!round.peer_down && round.inconclusive);
+        assert!(!evaluate_probe_round(&results[..2], Some(1)).peer_down);
+    }
+
+    #[test]
+    fn test_inconclusive_round_keeps_counters_and_clears_ledger() {
+        let config = dr_config(2, 2);
+        let mut status = DisasterRecoveryStatus::default();
+        let inconclusive = ProbeRound {
+            peer_down: false,
+            inconclusive: true,
+            peer_ledger: None,
+        };
+
+        assert!(!apply_probe_round(&mut status, &inconclusive, &config));
+        assert_eq!(status.peer_health.as_deref(), Some(PEER_HEALTH_UNKNOWN));
+
+        apply_probe_round(&mut status, &up_round(10), &config);
+        assert_eq!(status.peer_ledger_sequence, Some(10));
+        apply_probe_round(&mut status, &down_round(), &config);
+        // A failed round drops the last known ledger
+        assert_eq!(status.peer_ledger_sequence, None);
+        apply_probe_round(&mut status, &inconclusive, &config);
+        assert_eq!(status.consecutive_failures, 1);
+        assert_eq!(status.peer_health.as_deref(), Some(PEER_HEALTH_DEGRADED));
+        assert!(apply_probe_round(&mut status, &down_round(), &config));
+    }
+
+    #[test]
+    fn test_empty_round_is_not_down() {
+        assert!(!evaluate_probe_round(&[], None).peer_down);
+    }
+
+    // -------------------------------------------------------------------------
+    // Hysteresis
+    // -------------------------------------------------------------------------
+
+    #[test]
+    fn test_failure_threshold_before_unreachable() {
+        let config = dr_config(3, 2);
+        let mut status = DisasterRecoveryStatus::default();
+
+        assert!(!apply_probe_round(&mut status, &down_round(), &config));
+        assert_eq!(status.peer_health.as_deref(), Some(PEER_HEALTH_DEGRADED));
+        assert!(!apply_probe_round(&mut status, &down_round(), &config));
+        assert!(apply_probe_round(&mut status, &down_round(), &config));
+        assert_eq!(status.peer_health.as_deref(), Some(PEER_HEALTH_UNREACHABLE));
+        assert_eq!(status.consecutive_failures, 3);
+    }
+
+    #[test]
+    fn test_single_success_resets_failures() {
+        let config = dr_config(2, 2);
+        let mut status = DisasterRecoveryStatus::default();
+
+        apply_probe_round(&mut status, &down_round(), &config);
+        apply_probe_round(&mut status, &up_round(10), &config);
+        assert_eq!(status.consecutive_failures, 0);
+        assert!(!apply_probe_round(&mut status, &down_round(), &config));
+        assert_eq!(status.peer_health.as_deref(), Some(PEER_HEALTH_DEGRADED));
+    }
+
+    #[test]
+    fn test_recovery_threshold_before_healthy() {
+        let config = dr_config(1, 3);
+        let mut status = DisasterRecoveryStatus::default();
+
+        assert!(apply_probe_round(&mut status, &down_round(), &config));
+        assert!(apply_probe_round(&mut status, &up_round(10), &config));
+        assert!(apply_probe_round(&mut status, &up_round(11), &config));
+        assert_eq!(status.peer_health.as_deref(), Some(PEER_HEALTH_UNREACHABLE));
+        assert!(!apply_probe_round(&mut status, &up_round(12), &config));
+        assert_eq!(status.peer_health.as_deref(), Some(PEER_HEALTH_HEALTHY));
+        assert_eq!(status.peer_ledger_sequence, Some(12));
+    }
+
+    #[test]
+    fn test_flap_during_recovery_stays_unreachable() {
+        let config = dr_config(1, 2);
+        let mut status = DisasterRecoveryStatus::default();
+
+        apply_probe_round(&mut status, &down_round(), &config);
+        apply_probe_round(&mut status, &up_round(10), &config);
+        assert!(apply_probe_round(&mut status, &down_round(), &config));
+        assert!(apply_probe_round(&mut status, &up_round(11), &config));
+        assert!(!apply_probe_round(&mut status, &up_round(12), &config));
+    }
+
+    #[test]
+    fn test_probe_rounds_follow_health_check_interval() {
+        let config = dr_config(3, 1);
+        let now = chrono::Utc::now();
+        let mut status = DisasterRecoveryStatus::default();
+        assert!(probe_due(&status, &config, now));
+
+        status.last_probe_time = Some(now.to_rfc3339());
+        assert!(!probe_due(&status, &config, now));
+        assert!(!probe_due(
+            &status,
+            &config,
+            now + chrono::Duration::seconds(29)
+        ));
+        assert!(probe_due(
+            &status,
+            &config,
+            now + chrono::Duration::seconds(30)
+        ));
+
+        // An unparsable timestamp never blocks probing
+        status.last_probe_time = Some("yesterday".to_string());
+        assert!(probe_due(&status, &config, now));
+    }
+
+    // -------------------------------------------------------------------------
+    // HTTP probes against local axum stand-ins
+    // -------------------------------------------------------------------------
+
+    #[cfg(feature = "axum")]
+    mod http {
+        use crate::controller::dr_probe::*;
+        use axum::{http::StatusCode, routing::get, Json, Router};
+        use serde_json::json;
+
+        async fn serve(app: Router) -> String {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(async move {
+                axum::serve(listener, app).await.unwrap();
+            });
+            format!("http://{addr}")
+        }
+
+        fn client() -> reqwest::Client {
+            reqwest::Client::new()
+        }
+
+        #[tokio::test]
+        async fn test_operator_api_probe_ready_node() {
+            let app = Router::new().route(
+                "/api/v1/nodes/stellar/validator-1",
+                get(|| async {
+                    Json(json!({
+                        "name": "validator-1",
+                        "namespace": "stellar",
+                        "status": {
+                            "ledgerSequence": 4242,
+                            "conditions": [{ "type": "Ready", "status": "True" }]
+                        }
+                    }))
+                }),
+            );
+            let base = serve(app).await;
+
+            let probe = OperatorApiProbe::new(client(), &base, "stellar", "validator-1");
+            let result = probe.probe().await;
+            assert!(result.healthy);
+            assert_eq!(result.ledger_sequence, Some(4242));
+        }
+
+        #[tokio::test]
+        async fn test_operator_api_probe_not_ready_node() {
+            let app = Router::new().route(
+                "/api/v1/nodes/stellar/validator-1",
+                get(|| async {
+                    Json(json!({
+                        "status": {
+                            "ledgerSequence": 4242,
+                            "conditions": [{ "type": "Ready", "status": "False" }]
+                        }
+                    }))
+                }),
+            );
+            let base = serve(app).await;
+
+            let probe = OperatorApiProbe::new(client(), &base, "stellar", "validator-1");
+            assert!(!probe.probe().await.healthy);
+        }
+
+        #[tokio::test]
+        async fn test_horizon_probe() {
+            let app = Router::new().route(
+                "/",
+                get(|| async { Json(json!({ "history_latest_ledger": 777 })) }),
+            );
+            let base = serve(app).await;
+
+            let result = HorizonProbe::new(client(), &base).probe().await;
+            assert!(result.healthy);
+            assert_eq!(result.ledger_sequence, Some(777));
+        }
+
+        #[tokio::test]
+        async fn test_core_probe_catching_up_is_down() {
+            let app = Router::new().route(
+                "/info",
+                get(|| async {
+                    Json(json!({
+                        "info": { "state": "Catching up", "ledger": { "num": 90, "age": 400 } }
+                    }))
+                }),
+            );
+            let base = serve(app).await;
+
+            let result = CoreProbe::new(client(), &base).probe().await;
+            assert!(!result.healthy);
+            assert_eq!(result.ledger_sequence, Some(90));
+        }
+
+        #[tokio::test]
+        async fn test_probe_round_with_failing_peer() {
+            let app = Router::new()
+                .route(
+                    "/info",
+                    get(|| async { (StatusCode::SERVICE_UNAVAILABLE, "down") }),
+                )
+                .route("/", get(|| async { (StatusCode::BAD_GATEWAY, "down") }));
+            let base = serve(app).await;
+
+            let probes: Vec<Box<dyn PeerProbe>> = vec![
+                Box::new(CoreProbe::new(client(), &base)),
+                Box::new(HorizonProbe::new(client(), &base)),
+                Box::new(HorizonProbe::new(client(), "http://localhost:1")),
+            ];
+            let round = run_probe_round(&probes, None).await;
+            assert!(round.peer_down);
+            assert_eq!(round.peer_ledger, None);
+        }
+
+        #[tokio::test]
+        async fn test_unauthorized_probe_is_inconclusive() {
+            let app = Router::new()
+                .route("/info", get(|| async { (StatusCode::FORBIDDEN, "no") }))
+                .route("/", get(|| async { (StatusCode::UNAUTHORIZED, "no") }));
+            let base = serve(app).await;
+
+            let core = CoreProbe::new(client(), &base).probe().await;
+            assert!(core.inconclusive && !core.healthy);
+            let probes: Vec<Box<dyn PeerProbe>> = vec![
+                Box::new(CoreProbe::new(client(), &base)),
+                Box::new(HorizonProbe::new(client(), &base)),
+            ];
+            let round = run_probe_round(&probes, None).await;
+            assert!(!round.peer_down && round.inconclusive);
+        }
+    }
+}
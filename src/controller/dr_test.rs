@@ -2,7 +2,7 @@
 //!
 //! Covers: DR config enabled/disabled, Primary/Standby role assignment,
 //! failover state transitions, sync lag computation, backup target priority
-//! ordering, and the consistency partition check.
+//! ordering, DNS failover/failback transitions, and the consistency partition check.
 
 #[cfg(test)]
 mod tests {
@@ -22,6 +22,12 @@ mod tests {
             sync_strategy: sync,
             failover_dns: None,
             health_check_interval: 30,
+            peer_probes: Vec::new(),
+            failure_threshold: 3,
+            recovery_threshold: 2,
+            probe_quorum: None,
+            local_cluster_id: None,
+            consistency_window: 10,
         }
     }
 
@@ -42,6 +48,12 @@ mod tests {
             sync_strategy: DRSyncStrategy::Consensus,
             failover_dns: None,
             health_check_interval: 30,
+            peer_probes: Vec::new(),
+            failure_threshold: 3,
+            recovery_threshold: 2,
+            probe_quorum: None,
+            local_cluster_id: None,
+            consistency_window: 10,
         };
         // When enabled is false the reconciler returns Ok(None).
         // We verify the shape of the config to confirm the guard would fire.
@@ -263,4 +275,122 @@ mod tests {
         assert_eq!(DR_FAILOVER_ANNOTATION, "stellar.org/dr-failover-active");
         assert_eq!(DR_LAST_SYNC_ANNOTATION, "stellar.org/dr-last-sync-time");
     }
+
+    // -------------------------------------------------------------------------
+    // Failover / failback transitions
+    // -------------------------------------------------------------------------
+
+    #[test]
+    fn test_evaluate_failover_transitions() {
+        use crate::controller::dr::{evaluate_failover, FailoverAction};
+
+        let mut status = fresh_status();
+        let standby = DRRole::Standby;
+
+        assert_eq!(
+            evaluate_failover(&standby, true, false, &status),
+            FailoverAction::None
+        );
+        assert_eq!(
+            evaluate_failover(&standby, false, false, &status),
+            FailoverAction::Failover
+        );
+        assert_eq!(
+            evaluate_failover(&DRRole::Primary, false, false, &status),
+            FailoverAction::None
+        );
+
+        status.failover_active = true;
+        // Peer healthy again: failback is never automatic
+        assert_eq!(
+            evaluate_failover(&standby, true, false, &status),
+            FailoverAction::None
+        );
+        assert_eq!(
+            evaluate_failover(&standby, true, true, &status),
+            FailoverAction::Failback
+        );
+        assert_eq!(
+            evaluate_failover(&standby, false, true, &status),
+            FailoverAction::FailbackBlocked
+        );
+    }
+
+    // -------------------------------------------------------------------------
+    // DNSEndpoint generation
+    // -------------------------------------------------------------------------
+
+    fn dns_test_node() -> crate::crd::StellarNode {
+        use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+
+        crate::crd::StellarNode {
+            metadata: ObjectMeta {
+                name: Some("validator-1".to_string()),
+                namespace: Some("stellar".to_string()),
+                uid: Some("uid-1".to_string()),
+                ..Default::default()
+            },
+            spec: Default::default(),
+            status: None,
+        }
+    }
+
+    #[test]
+    fn test_build_dns_endpoint_a_record() {
+        use crate::controller::dr::{build_dns_endpoint, dns_endpoint_name};
+        use crate::crd::ExternalDNSConfig;
+
+        let node = dns_test_node();
+        let dns = ExternalDNSConfig {
+            hostname: "core.example.com".to_string(),
+            ttl: 60,
+            provider: Some("route53".to_string()),
+            annotations: None,
+        };
+
+        let obj = build_dns_endpoint(&node, &dns, "203.0.113.10");
+        assert_eq!(
+            obj.metadata.name.as_deref(),
+            Some("validator-1-dr-failover")
+        );
+        assert_eq!(dns_endpoint_name(&node), "validator-1-dr-failover");
+        assert_eq!(obj.metadata.namespace.as_deref(), Some("stellar"));
+
+        let endpoint = &obj.data["spec"]["endpoints"][0];
+        assert_eq!(endpoint["dnsName"], "core.example.com");
+        assert_eq!(endpoint["recordTTL"], 60);
+        assert_eq!(endpoint["recordType"], "A");
+        assert_eq!(endpoint["targets"][0], "203.0.113.10");
+        assert_eq!(
+            obj.metadata
+                .annotations
+                .as_ref()
+                .and_then(|a| a.get("stellar.org/dns-provider"))
+                .map(String::as_str),
+            Some("route53")
+        );
+    }
+
+    #[test]
+    fn test_build_dns_endpoint_cname_for_hostname_target() {
+        use crate::controller::dr::build_dns_endpoint;
+        use crate::crd::ExternalDNSConfig;
+
+        let dns = ExternalDNSConfig {
+            hostname: "core.example.com".to_string(),
+            ttl: 300,
+            provider: None,
+            annotations: None,
+        };
+
+        let obj = build_dns_endpoint(&dns_test_node(), &dns, "lb-123.elb.amazonaws.com");
+        assert_eq!(obj.data["spec"]["endpoints"][0]["recordType"], "CNAME");
+        assert!(obj.metadata.annotations.is_none());
+    }
+
+    #[test]
+    fn test_dr_failback_annotation_constant() {
+        use crate::controller::dr::DR_FAILBACK_ANNOTATION;
+        assert_eq!(DR_FAILBACK_ANNOTATION, "stellar.org/dr-failback");
+    }
 }
@@ -3,7 +3,7 @@
 //! Queries node endpoints to verify they are fully synced and operational.
 //! The health check logic differs by node type:
 //!
-//! - **Validators**: Check ledger progression via Stellar Core HTTP endpoint
+//! - **Validators**: Check state, ledger age, peers and quorum via Stellar Core `/info`
 //! - **Horizon**: Check database synchronization and ingestion status
 //! - **Soroban RPC**: Check RPC endpoint availability and ledger sync
 //!
@@ -15,6 +15,7 @@
 //! - `message` - Human-readable status message
 //! - `ledger_sequence` - Current ledger number (if available)
 
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 use k8s_openapi::api::core::v1::Pod;
@@ -61,6 +62,117 @@ struct SorobanHealthResponse {
     pub ledger: u64,
 }
 
+/// Stellar Core `/info` response
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct CoreInfoResponse {
+    pub info: CoreInfo,
+}
+
+/// The `info` object returned by Stellar Core's HTTP admin endpoint
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct CoreInfo {
+    /// Node state ("Booting", "Joining SCP", "Catching up", "Synced!")
+    #[serde(default)]
+    pub state: String,
+
+    /// Last closed ledger
+    pub ledger: CoreLedgerInfo,
+
+    /// Overlay peer counts
+    #[serde(default)]
+    pub peers: CorePeerInfo,
+
+    /// Quorum summary keyed by "qset" (or by ledger number on older cores)
+    #[serde(default)]
+    pub quorum: BTreeMap<String, CoreQuorumInfo>,
+}
+
+/// Last closed ledger as reported by Stellar Core
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct CoreLedgerInfo {
+    /// Ledger sequence number
+    pub num: u64,
+
+    /// Seconds since the ledger closed
+    #[serde(default)]
+    pub age: u64,
+
+    /// Ledger header hash (hex)
+    #[serde(default)]
+    pub hash: Option<String>,
+}
+
+/// Overlay peer counts as reported by Stellar Core
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct CorePeerInfo {
+    #[serde(default)]
+    pub authenticated_count: u32,
+
+    #[serde(default)]
+    pub pending_count: u32,
+}
+
+/// Quorum agreement summary as reported by Stellar Core
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct CoreQuorumInfo {
+    #[serde(default)]
+    pub agree: u32,
+
+    #[serde(default)]
+    pub disagree: u32,
+
+    #[serde(default)]
+    pub missing: u32,
+
+    #[serde(default)]
+    pub delayed: u32,
+
+    /// Number of additional node failures that would cause a loss of quorum
+    #[serde(default)]
+    pub fail_at: u32,
+}
+
+/// Stellar Core state string reported once the node is in consensus
+const CORE_SYNCED_STATE: &str = "Synced!";
+
+/// Maximum age (seconds) of the last closed ledger before a "Synced!" validator
+/// is considered to have lost consensus. Ledgers close roughly every 5 seconds.
+pub const MAX_VALIDATOR_LEDGER_AGE_SECS: u64 = 90;
+
+/// Stellar Core HTTP admin port
+const CORE_HTTP_PORT: u16 = 11626;
+
+/// Validator-specific details reported by Stellar Core
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidatorHealthDetails {
+    /// Stellar Core state string
+    pub state: String,
+
+    /// Seconds since the last ledger closed
+    pub ledger_age: u64,
+
+    /// Hash of the last closed ledger header (hex)
+    pub ledger_hash: Option<String>,
+
+    /// Number of authenticated overlay peers
+    pub authenticated_peers: u32,
+
+    /// Number of pending overlay peers
+    pub pending_peers: u32,
+
+    /// Validators in the quorum set agreeing with this node
+    pub quorum_agree: u32,
+
+    /// Validators in the quorum set disagreeing with this node
+    pub quorum_disagree: u32,
+
+    /// Validators in the quorum set that are missing
+    pub quorum_missing: u32,
+
+    /// Additional failures that would cause a loss of quorum
+    pub quorum_fail_at: u32,
+}
+
 /// Result of a health check
 ///
 /// Contains the outcome of a health check operation, including:
@@ -89,6 +201,9 @@ pub struct HealthCheckResult {
 
     /// Current ledger sequence (if available)
     pub ledger_sequence: Option<u64>,
+
+    /// Stellar Core details (validators only)
+    pub validator: Option<ValidatorHealthDetails>,
 }
 
 impl HealthCheckResult {
@@ -99,6 +214,7 @@ impl HealthCheckResult {
             synced: true,
             message: "Node is healthy and synced".to_string(),
             ledger_sequence: ledger,
+            validator: None,
         }
     }
 
@@ -109,6 +225,7 @@ impl HealthCheckResult {
             synced: false,
             message,
             ledger_sequence: ledger,
+            validator: None,
         }
     }
 
@@ -119,6 +236,7 @@ impl HealthCheckResult {
             synced: false,
             message,
             ledger_sequence: None,
+            validator: None,
         }
     }
 
@@ -129,8 +247,15 @@ impl HealthCheckResult {
             synced: false,
             message,
             ledger_sequence: None,
+            validator: None,
         }
     }
+
+    /// Attach Stellar Core details to the result
+    pub fn with_validator_details(mut self, details: ValidatorHealthDetails) -> Self {
+        self.validator = Some(details);
+        self
+    }
 }
 
 /// Check the health of a StellarNode
@@ -196,12 +321,39 @@ pub async fn check_node_health(
     match node.spec.node_type {
         NodeType::Horizon => check_horizon_health(pod_ip, mtls_config).await,
         NodeType::SorobanRpc => check_soroban_health(pod_ip, mtls_config).await,
-        NodeType::Validator => {
-            // Validators don't have a standard health endpoint
-            // We consider them healthy if the pod is running
-            Ok(HealthCheckResult::synced(None))
+        NodeType::Validator => check_validator_health(pod_ip, mtls_config).await,
+    }
+}
+
+/// Build the HTTP client used for node health checks, with mTLS if enabled
+pub(crate) fn build_http_client(
+    mtls_config: Option<&crate::MtlsConfig>,
+) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(5));
+
+    if let Some(config) = mtls_config {
+        let mut identity_pem = config.cert_pem.clone();
+        identity_pem.extend_from_slice(&config.key_pem);
+
+        let identity = reqwest::Identity::from_pem(&identity_pem)
+            .map_err(|e| Error::ConfigError(format!("Failed to create identity: {e}")))?;
+
+        // Trust every CA in the bundle: the built-in CA during a rotation
+        // overlap, or the cert-manager issuer's chain
+        let ca_certs = reqwest::Certificate::from_pem_bundle(&config.ca_pem)
+            .map_err(|e| Error::ConfigError(format!("Failed to parse CA cert: {e}")))?;
+
+        builder = builder
+            .identity(identity)
+            .danger_accept_invalid_hostnames(true);
+        for ca_cert in ca_certs {
+            builder = builder.add_root_certificate(ca_cert);
         }
     }
+
+    builder
+        .build()
+        .map_err(|e| Error::ConfigError(format!("Failed to create HTTP client: {e}")))
 }
 
 /// Check if a pod is ready
@@ -230,28 +382,7 @@ async fn check_horizon_health(
 
     debug!("Querying Horizon health endpoint: {}", url);
 
-    // Create HTTP client with mTLS if enabled
-    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(5));
-
-    if let Some(config) = mtls_config {
-        let mut identity_pem = config.cert_pem.clone();
-        identity_pem.extend_from_slice(&config.key_pem);
-
-        let identity = reqwest::Identity::from_pem(&identity_pem)
-            .map_err(|e| Error::ConfigError(format!("Failed to create identity: {e}")))?;
-
-        let ca_cert = reqwest::Certificate::from_pem(&config.ca_pem)
-            .map_err(|e| Error::ConfigError(format!("Failed to parse CA cert: {e}")))?;
-
-        builder = builder
-            .identity(identity)
-            .add_root_certificate(ca_cert)
-            .danger_accept_invalid_hostnames(true);
-    }
-
-    let client = builder
-        .build()
-        .map_err(|e| Error::ConfigError(format!("Failed to create HTTP client: {e}")))?;
+    let client = build_http_client(mtls_config)?;
 
     match client.get(&url).send().await {
         Ok(response) => {
@@ -330,27 +461,7 @@ async fn check_soroban_health(
 
     debug!("Querying Soroban RPC health endpoint: {}", url);
 
-    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(5));
-
-    if let Some(config) = mtls_config {
-        let mut identity_pem = config.cert_pem.clone();
-        identity_pem.extend_from_slice(&config.key_pem);
-
-        let identity = reqwest::Identity::from_pem(&identity_pem)
-            .map_err(|e| Error::ConfigError(format!("Failed to create identity: {e}")))?;
-
-        let ca_cert = reqwest::Certificate::from_pem(&config.ca_pem)
-            .map_err(|e| Error::ConfigError(format!("Failed to parse CA cert: {e}")))?;
-
-        builder = builder
-            .identity(identity)
-            .add_root_certificate(ca_cert)
-            .danger_accept_invalid_hostnames(true);
-    }
-
-    let client = builder
-        .build()
-        .map_err(|e| Error::ConfigError(format!("Failed to create HTTP client: {e}")))?;
+    let client = build_http_client(mtls_config)?;
 
     match client.get(&url).send().await {
         Ok(response) => {
@@ -396,3 +507,113 @@ async fn check_soroban_health(
         }
     }
 }
+
+/// Check validator health via Stellar Core's `/info` admin endpoint
+async fn check_validator_health(
+    pod_ip: &str,
+    mtls_config: Option<&crate::MtlsConfig>,
+) -> Result<HealthCheckResult> {
+    let scheme = if mtls_config.is_some() {
+        "https"
+    } else {
+        "http"
+    };
+    let base_url = format!("{scheme}://{pod_ip}:{CORE_HTTP_PORT}");
+    check_core_info(&base_url, mtls_config).await
+}
+
+/// Query `<base_url>/info` on a Stellar Core node and evaluate the response
+pub(crate) async fn check_core_info(
+    base_url: &str,
+    mtls_config: Option<&crate::MtlsConfig>,
+) -> Result<HealthCheckResult> {
+    let url = format!("{}/info", base_url.trim_end_matches('/'));
+
+    debug!("Querying Stellar Core info endpoint: {}", url);
+
+    let client = build_http_client(mtls_config)?;
+
+    match client.get(&url).send().await {
+        Ok(response) => {
+            if !response.status().is_success() {
+                warn!(
+                    "Stellar Core info endpoint returned status: {}",
+                    response.status()
+                );
+                return Ok(HealthCheckResult::unhealthy(format!(
+                    "Info endpoint returned status {}",
+                    response.status()
+                )));
+            }
+
+            match response.json::<CoreInfoResponse>().await {
+                Ok(core) => {
+                    debug!("Stellar Core info response: {:?}", core);
+                    Ok(evaluate_core_info(&core.info))
+                }
+                Err(e) => {
+                    warn!("Failed to parse Stellar Core info response: {}", e);
+                    Ok(HealthCheckResult::syncing(
+                        "Info endpoint returned unparseable response".to_string(),
+                        None,
+                    ))
+                }
+            }
+        }
+        Err(e) => {
+            warn!("Failed to query Stellar Core info endpoint: {}", e);
+            Ok(HealthCheckResult::pending(format!(
+                "Cannot reach Stellar Core info endpoint: {e}"
+            )))
+        }
+    }
+}
+
+/// Turn a Stellar Core `info` object into a health check result
+///
+/// A validator is synced only when Core reports `Synced!` and the last closed
+/// ledger is younger than [`MAX_VALIDATOR_LEDGER_AGE_SECS`]. A stale ledger
+/// while "Synced!" means the node has lost consensus.
+pub(crate) fn evaluate_core_info(info: &CoreInfo) -> HealthCheckResult {
+    let quorum = info.quorum.values().next();
+    let details = ValidatorHealthDetails {
+        state: info.state.clone(),
+        ledger_age: info.ledger.age,
+        ledger_hash: info.ledger.hash.clone(),
+        authenticated_peers: info.peers.authenticated_count,
+        pending_peers: info.peers.pending_count,
+        quorum_agree: quorum.map(|q| q.agree).unwrap_or(0),
+        quorum_disagree: quorum.map(|q| q.disagree).unwrap_or(0),
+        quorum_missing: quorum.map(|q| q.missing).unwrap_or(0),
+        quorum_fail_at: quorum.map(|q| q.fail_at).unwrap_or(0),
+    };
+    let ledger = Some(info.ledger.num);
+
+    let result = if info.state != CORE_SYNCED_STATE {
+        let message = format!(
+            "Stellar Core is {} at ledger {} ({} peers)",
+            info.state, info.ledger.num, info.peers.authenticated_count
+        );
+        info!("{}", message);
+        HealthCheckResult::syncing(message, ledger)
+    } else if info.ledger.age > MAX_VALIDATOR_LEDGER_AGE_SECS {
+        let message = format!(
+            "Stellar Core lost consensus: ledger {} closed {}s ago (quorum agree={}, disagree={}, missing={})",
+            info.ledger.num,
+            info.ledger.age,
+            details.quorum_agree,
+            details.quorum_disagree,
+            details.quorum_missing
+        );
+        warn!("{}", message);
+        HealthCheckResult::syncing(message, ledger)
+    } else {
+        info!(
+            "Stellar Core is synced at ledger {} (quorum agree={}, fail_at={})",
+            info.ledger.num, details.quorum_agree, details.quorum_fail_at
+        );
+        HealthCheckResult::synced(ledger)
+    };
+
+    result.with_validator_details(details)
+}
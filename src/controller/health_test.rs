@@ -3,6 +3,29 @@
 #[cfg(test)]
 mod tests {
     use super::super::health::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn core_info_body(state: &str, ledger: u64, age: u64) -> serde_json::Value {
+        serde_json::json!({
+            "info": {
+                "build": "stellar-core 21.0.0",
+                "ledger": { "num": ledger, "age": age, "hash": "abc", "version": 21 },
+                "peers": { "authenticated_count": 8, "pending_count": 1 },
+                "quorum": {
+                    "qset": {
+                        "agree": 5,
+                        "disagree": 0,
+                        "missing": 1,
+                        "delayed": 0,
+                        "fail_at": 2,
+                        "phase": "EXTERNALIZE"
+                    }
+                },
+                "state": state
+            }
+        })
+    }
 
     #[test]
     fn test_health_check_result_synced() {
@@ -35,4 +58,90 @@ mod tests {
         assert!(!result.synced);
         assert_eq!(result.ledger_sequence, None);
     }
+
+    #[tokio::test]
+    async fn test_core_info_synced() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/info"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(core_info_body("Synced!", 51234, 3)),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let result = check_core_info(&mock_server.uri(), None).await.unwrap();
+        assert!(result.healthy);
+        assert!(result.synced);
+        assert_eq!(result.ledger_sequence, Some(51234));
+
+        let details = result.validator.unwrap();
+        assert_eq!(details.state, "Synced!");
+        assert_eq!(details.authenticated_peers, 8);
+        assert_eq!(details.pending_peers, 1);
+        assert_eq!(details.quorum_agree, 5);
+        assert_eq!(details.quorum_missing, 1);
+        assert_eq!(details.quorum_fail_at, 2);
+    }
+
+    #[tokio::test]
+    async fn test_core_info_catching_up() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/info"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(core_info_body(
+                "Catching up",
+                100,
+                0,
+            )))
+            .mount(&mock_server)
+            .await;
+
+        let result = check_core_info(&mock_server.uri(), None).await.unwrap();
+        assert!(result.healthy);
+        assert!(!result.synced);
+        assert_eq!(result.ledger_sequence, Some(100));
+        assert!(result.message.contains("Catching up"));
+    }
+
+    #[tokio::test]
+    async fn test_core_info_stale_ledger_is_not_synced() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/info"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(core_info_body(
+                "Synced!",
+                51234,
+                MAX_VALIDATOR_LEDGER_AGE_SECS + 1,
+            )))
+            .mount(&mock_server)
+            .await;
+
+        let result = check_core_info(&mock_server.uri(), None).await.unwrap();
+        assert!(result.healthy);
+        assert!(!result.synced);
+        assert_eq!(result.ledger_sequence, Some(51234));
+        assert!(result.message.contains("lost consensus"));
+    }
+
+    #[tokio::test]
+    async fn test_core_info_error_status() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/info"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&mock_server)
+            .await;
+
+        let result = check_core_info(&mock_server.uri(), None).await.unwrap();
+        assert!(!result.healthy);
+        assert_eq!(result.ledger_sequence, None);
+    }
+
+    #[tokio::test]
+    async fn test_core_info_unreachable() {
+        let result = check_core_info("http://localhost:1", None).await.unwrap();
+        assert!(!result.healthy);
+        assert!(!result.synced);
+    }
 }
@@ -0,0 +1,437 @@
+//! Ledger hash consistency verification
+//!
+//! After a regional partition heals (or before a DR promotion/failback) the
+//! operator compares recently observed local ledger header hashes against:
+//!
+//! - the DR peer, via its operator REST API, Horizon `/ledgers/:seq`, or Core `/info`
+//! - the history archive, by reading `ledger-XXXXXXXX.xdr.gz` checkpoint files
+//!
+//! Any sequence for which two sources report different hashes marks the node
+//! as diverged.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Read;
+use std::time::Duration;
+
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+use crate::crd::{DRPeerProbe, DRProbeKind, LedgerHashSample};
+use crate::error::{Error, Result};
+
+use super::health;
+
+/// Number of ledgers per history archive checkpoint
+pub const CHECKPOINT_FREQUENCY: u64 = 64;
+
+/// Outcome of comparing local hashes with one reference source
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HashComparison {
+    /// Number of sequences present in both sets with the same hash
+    pub matched: usize,
+    /// Sequences whose hashes disagree: (sequence, local, reference)
+    pub mismatches: Vec<(u64, String, String)>,
+}
+
+impl HashComparison {
+    pub fn diverged(&self) -> bool {
+        !self.mismatches.is_empty()
+    }
+}
+
+/// Result of a full consistency check across all reference sources
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    /// Whether any source disagreed with the local history
+    pub diverged: bool,
+    /// Number of ledger hashes that were confirmed by at least one source
+    pub verified: usize,
+    /// Human-readable summary
+    pub message: String,
+}
+
+/// Add a sample to the rolling window of local ledger hashes
+///
+/// Keeps samples sorted by sequence, replaces an existing sample for the same
+/// sequence and drops the oldest samples beyond `window`.
+pub fn record_sample(samples: &mut Vec<LedgerHashSample>, sample: LedgerHashSample, window: usize) {
+    samples.retain(|s| s.sequence != sample.sequence);
+    samples.push(sample);
+    samples.sort_by_key(|s| s.sequence);
+    if samples.len() > window {
+        let excess = samples.len() - window;
+        samples.drain(..excess);
+    }
+}
+
+/// Compare local hashes against reference hashes for overlapping sequences
+pub fn compare_hashes(
+    local: &[LedgerHashSample],
+    reference: &BTreeMap<u64, String>,
+) -> HashComparison {
+    let mut result = HashComparison::default();
+    for sample in local {
+        if let Some(remote) = reference.get(&sample.sequence) {
+            if remote.eq_ignore_ascii_case(&sample.hash) {
+                result.matched += 1;
+            } else {
+                result
+                    .mismatches
+                    .push((sample.sequence, sample.hash.clone(), remote.clone()));
+            }
+        }
+    }
+    result
+}
+
+/// Checkpoint ledger that contains `sequence`
+pub fn checkpoint_containing(sequence: u64) -> u64 {
+    (sequence / CHECKPOINT_FREQUENCY + 1) * CHECKPOINT_FREQUENCY - 1
+}
+
+/// Path of the ledger header file for a checkpoint, relative to the archive root
+pub fn ledger_checkpoint_path(checkpoint: u64) -> String {
+    let hex = format!("{checkpoint:08x}");
+    format!(
+        "ledger/{}/{}/{}/ledger-{hex}.xdr.gz",
+        &hex[0..2],
+        &hex[2..4],
+        &hex[4..6]
+    )
+}
+
+/// Minimal big-endian XDR reader over a single record
+struct XdrReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> XdrReader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.buf.len())
+            .ok_or_else(|| Error::ArchiveHealthCheckError("truncated XDR record".to_string()))?;
+        let out = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn skip(&mut self, n: usize) -> Result<()> {
+        self.take(n).map(|_| ())
+    }
+
+    /// Skip a variable-length opaque, including its padding to 4 bytes
+    fn skip_var_opaque(&mut self) -> Result<()> {
+        let len = self.u32()? as usize;
+        self.skip(len.div_ceil(4) * 4)
+    }
+}
+
+/// Parse the hash and sequence out of one `LedgerHeaderHistoryEntry` record
+fn parse_ledger_header_entry(record: &[u8]) -> Result<LedgerHashSample> {
+    let mut r = XdrReader::new(record);
+
+    let hash: String = r.take(32)?.iter().map(|b| format!("{b:02x}")).collect();
+
+    // LedgerHeader
+    r.skip(4)?; // ledgerVersion
+    r.skip(32)?; // previousLedgerHash
+
+    // StellarValue
+    r.skip(32)?; // txSetHash
+    r.skip(8)?; // closeTime
+    let upgrades = r.u32()?;
+    for _ in 0..upgrades {
+        r.skip_var_opaque()?;
+    }
+    match r.u32()? {
+        0 => {} // STELLAR_VALUE_BASIC
+        1 => {
+            // STELLAR_VALUE_SIGNED: NodeID (type + ed25519) and signature
+            r.skip(4 + 32)?;
+            r.skip_var_opaque()?;
+        }
+        other => {
+            return Err(Error::ArchiveHealthCheckError(format!(
+                "unknown StellarValue ext {other}"
+            )))
+        }
+    }
+
+    r.skip(32)?; // txSetResultHash
+    r.skip(32)?; // bucketListHash
+    let sequence = u64::from(r.u32()?);
+
+    Ok(LedgerHashSample { sequence, hash })
+}
+
+/// Parse an uncompressed XDR stream of record-marked `LedgerHeaderHistoryEntry`s
+pub fn parse_ledger_headers(data: &[u8]) -> Result<Vec<LedgerHashSample>> {
+    let mut entries = Vec::new();
+    let mut pos = 0;
+
+    while pos < data.len() {
+        let mark = data
+            .get(pos..pos + 4)
+            .ok_or_else(|| Error::ArchiveHealthCheckError("truncated record mark".to_string()))?;
+        let len = (u32::from_be_bytes([mark[0], mark[1], mark[2], mark[3]]) & 0x7fff_ffff) as usize;
+        pos += 4;
+
+        let record = data.get(pos..pos + len).ok_or_else(|| {
+            Error::ArchiveHealthCheckError("truncated ledger header record".to_string())
+        })?;
+        entries.push(parse_ledger_header_entry(record)?);
+        pos += len;
+    }
+
+    Ok(entries)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryArchiveState {
+    current_ledger: u64,
+}
+
+/// Fetch archive hashes for every local sample already published to the archive
+pub async fn fetch_archive_hashes(
+    client: &reqwest::Client,
+    archive_url: &str,
+    samples: &[LedgerHashSample],
+) -> Result<BTreeMap<u64, String>> {
+    let base = archive_url.trim_end_matches('/');
+
+    let has: HistoryArchiveState = client
+        .get(format!("{base}/.well-known/stellar-history.json"))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let wanted: BTreeSet<u64> = samples
+        .iter()
+        .map(|s| s.sequence)
+        .filter(|seq| checkpoint_containing(*seq) <= has.current_ledger)
+        .collect();
+    let checkpoints: BTreeSet<u64> = wanted.iter().map(|s| checkpoint_containing(*s)).collect();
+
+    let mut hashes = BTreeMap::new();
+    for checkpoint in checkpoints {
+        let url = format!("{base}/{}", ledger_checkpoint_path(checkpoint));
+        debug!("Fetching ledger headers from {}", url);
+
+        let compressed = client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let mut data = Vec::new();
+        GzDecoder::new(compressed.as_ref())
+            .read_to_end(&mut data)
+            .map_err(|e| Error::ArchiveHealthCheckError(format!("invalid gzip in {url}: {e}")))?;
+
+        for entry in parse_ledger_headers(&data)? {
+            if wanted.contains(&entry.sequence) {
+                hashes.insert(entry.sequence, entry.hash);
+            }
+        }
+    }
+
+    Ok(hashes)
+}
+
+#[derive(Debug, Deserialize)]
+struct HorizonLedger {
+    hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PeerNodeResponse {
+    #[serde(default)]
+    status: PeerNodeStatus,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PeerNodeStatus {
+    #[serde(default)]
+    dr_status: Option<PeerDrStatus>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PeerDrStatus {
+    #[serde(default)]
+    recent_ledger_hashes: Vec<LedgerHashSample>,
+}
+
+/// Fetch the peer's ledger hashes for the local samples through one probe endpoint
+pub async fn fetch_peer_hashes(
+    client: &reqwest::Client,
+    probe: &DRPeerProbe,
+    namespace: &str,
+    name: &str,
+    samples: &[LedgerHashSample],
+) -> Result<BTreeMap<u64, String>> {
+    let base = probe.endpoint.trim_end_matches('/');
+    let mut hashes = BTreeMap::new();
+
+    match probe.kind {
+        DRProbeKind::OperatorApi => {
+            let url = format!(
+                "{base}/api/v1/nodes/{}/{}",
+                probe.node_namespace.as_deref().unwrap_or(namespace),
+                probe.node_name.as_deref().unwrap_or(name)
+            );
+            let node: PeerNodeResponse = client
+                .get(&url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            for sample in node
+                .status
+                .dr_status
+                .map(|d| d.recent_ledger_hashes)
+                .unwrap_or_default()
+            {
+                hashes.insert(sample.sequence, sample.hash);
+            }
+        }
+        DRProbeKind::Horizon => {
+            for sample in samples {
+                let url = format!("{base}/ledgers/{}", sample.sequence);
+                match client.get(&url).send().await?.error_for_status() {
+                    Ok(resp) => {
+                        let ledger: HorizonLedger = resp.json().await?;
+                        hashes.insert(sample.sequence, ledger.hash);
+                    }
+                    Err(e) => debug!("Peer Horizon has no ledger {}: {}", sample.sequence, e),
+                }
+            }
+        }
+        DRProbeKind::Core => {
+            let info: health::CoreInfoResponse = client
+                .get(format!("{base}/info"))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            if let Some(hash) = info.info.ledger.hash {
+                hashes.insert(info.info.ledger.num, hash);
+            }
+        }
+    }
+
+    Ok(hashes)
+}
+
+/// Compare local ledger hashes against the DR peer and the history archives
+///
+/// Sources that cannot be reached are skipped; only an actual hash mismatch
+/// marks the node as diverged.
+pub async fn verify_consistency(
+    samples: &[LedgerHashSample],
+    peer_probes: &[DRPeerProbe],
+    archive_urls: &[String],
+    namespace: &str,
+    name: &str,
+    mtls_config: Option<&crate::MtlsConfig>,
+) -> Result<ConsistencyReport> {
+    if samples.is_empty() {
+        return Ok(ConsistencyReport {
+            diverged: false,
+            verified: 0,
+            message: "No local ledger hashes recorded yet".to_string(),
+        });
+    }
+
+    let peer_client = health::build_http_client(mtls_config)?;
+    let archive_client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .user_agent("stellar-k8s-operator/0.1.0")
+        .build()
+        .map_err(Error::HttpError)?;
+
+    let mut sources: Vec<(String, BTreeMap<u64, String>)> = Vec::new();
+    for probe in peer_probes {
+        match fetch_peer_hashes(&peer_client, probe, namespace, name, samples).await {
+            Ok(h) => sources.push((format!("peer {}", probe.endpoint), h)),
+            Err(e) => warn!(
+                "Could not fetch peer ledger hashes from {}: {}",
+                probe.endpoint, e
+            ),
+        }
+    }
+    for url in archive_urls {
+        match fetch_archive_hashes(&archive_client, url, samples).await {
+            Ok(h) => sources.push((format!("archive {url}"), h)),
+            Err(e) => warn!("Could not fetch archive ledger hashes from {}: {}", url, e),
+        }
+    }
+
+    Ok(summarize(samples, &sources))
+}
+
+/// Build a report from per-source reference hashes
+pub fn summarize(
+    samples: &[LedgerHashSample],
+    sources: &[(String, BTreeMap<u64, String>)],
+) -> ConsistencyReport {
+    let mut verified = BTreeSet::new();
+    let mut problems = Vec::new();
+
+    for (source, reference) in sources {
+        let comparison = compare_hashes(samples, reference);
+        for sample in samples {
+            if reference
+                .get(&sample.sequence)
+                .is_some_and(|h| h.eq_ignore_ascii_case(&sample.hash))
+            {
+                verified.insert(sample.sequence);
+            }
+        }
+        if let Some((seq, local, remote)) = comparison.mismatches.first() {
+            problems.push(format!(
+                "{source} disagrees at ledger {seq} (local {local}, remote {remote})"
+            ));
+        }
+    }
+
+    if problems.is_empty() {
+        ConsistencyReport {
+            diverged: false,
+            verified: verified.len(),
+            message: format!(
+                "{} of {} recent ledger hashes confirmed by {} source(s)",
+                verified.len(),
+                samples.len(),
+                sources.len()
+            ),
+        }
+    } else {
+        ConsistencyReport {
+            diverged: true,
+            verified: verified.len(),
+            message: problems.join("; "),
+        }
+    }
+}
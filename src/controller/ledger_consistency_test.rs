@@ -0,0 +1,178 @@
+//! Tests for ledger hash consistency verification
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::controller::conditions;
+    use crate::controller::dr::apply_divergence_condition;
+    use crate::controller::ledger_consistency::*;
+    use crate::crd::{DisasterRecoveryStatus, LedgerHashSample};
+
+    fn sample(sequence: u64, hash: &str) -> LedgerHashSample {
+        LedgerHashSample {
+            sequence,
+            hash: hash.to_string(),
+        }
+    }
+
+    /// Encode a record-marked `LedgerHeaderHistoryEntry` with a basic StellarValue
+    fn encode_header_entry(sequence: u32, hash_byte: u8) -> Vec<u8> {
+        let mut record = Vec::new();
+        record.extend_from_slice(&[hash_byte; 32]); // hash
+        record.extend_from_slice(&21u32.to_be_bytes()); // ledgerVersion
+        record.extend_from_slice(&[0u8; 32]); // previousLedgerHash
+        record.extend_from_slice(&[0u8; 32]); // txSetHash
+        record.extend_from_slice(&0u64.to_be_bytes()); // closeTime
+        record.extend_from_slice(&1u32.to_be_bytes()); // one upgrade
+        record.extend_from_slice(&5u32.to_be_bytes()); // upgrade length (padded to 8)
+        record.extend_from_slice(&[1, 2, 3, 4, 5, 0, 0, 0]);
+        record.extend_from_slice(&0u32.to_be_bytes()); // STELLAR_VALUE_BASIC
+        record.extend_from_slice(&[0u8; 32]); // txSetResultHash
+        record.extend_from_slice(&[0u8; 32]); // bucketListHash
+        record.extend_from_slice(&sequence.to_be_bytes()); // ledgerSeq
+        record.extend_from_slice(&[0u8; 64]); // remainder of the header, ignored
+
+        let mut out = ((record.len() as u32) | 0x8000_0000).to_be_bytes().to_vec();
+        out.extend_from_slice(&record);
+        out
+    }
+
+    #[test]
+    fn test_record_sample_keeps_window() {
+        let mut samples = Vec::new();
+        for seq in 1..=5 {
+            record_sample(&mut samples, sample(seq, "aa"), 3);
+        }
+        let seqs: Vec<u64> = samples.iter().map(|s| s.sequence).collect();
+        assert_eq!(seqs, vec![3, 4, 5]);
+
+        // Re-recording the same ledger does not duplicate it
+        record_sample(&mut samples, sample(5, "aa"), 3);
+        assert_eq!(samples.len(), 3);
+    }
+
+    #[test]
+    fn test_compare_hashes_detects_mismatch() {
+        let local = vec![sample(10, "aa"), sample(11, "bb"), sample(12, "cc")];
+        let reference = BTreeMap::from([(10, "AA".to_string()), (11, "ff".to_string())]);
+
+        let comparison = compare_hashes(&local, &reference);
+        assert_eq!(comparison.matched, 1);
+        assert!(comparison.diverged());
+        assert_eq!(comparison.mismatches[0].0, 11);
+    }
+
+    #[test]
+    fn test_summarize_reports_diverged_source() {
+        let local = vec![sample(10, "aa"), sample(11, "bb")];
+        let sources = vec![
+            (
+                "peer a".to_string(),
+                BTreeMap::from([(10, "aa".to_string()), (11, "bb".to_string())]),
+            ),
+            (
+                "archive b".to_string(),
+                BTreeMap::from([(11, "00".to_string())]),
+            ),
+        ];
+
+        let report = summarize(&local, &sources);
+        assert!(report.diverged);
+        assert!(report.message.contains("archive b"));
+
+        let report = summarize(&local, &sources[..1]);
+        assert!(!report.diverged);
+        assert_eq!(report.verified, 2);
+    }
+
+    #[test]
+    fn test_checkpoint_layout() {
+        assert_eq!(checkpoint_containing(1), 63);
+        assert_eq!(checkpoint_containing(63), 63);
+        assert_eq!(checkpoint_containing(64), 127);
+        assert_eq!(
+            ledger_checkpoint_path(63),
+            "ledger/00/00/00/ledger-0000003f.xdr.gz"
+        );
+        assert_eq!(
+            ledger_checkpoint_path(0x0012_34bf),
+            "ledger/00/12/34/ledger-001234bf.xdr.gz"
+        );
+    }
+
+    #[test]
+    fn test_parse_ledger_headers() {
+        let mut data = encode_header_entry(62, 0xab);
+        data.extend(encode_header_entry(63, 0xcd));
+
+        let entries = parse_ledger_headers(&data).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].sequence, 62);
+        assert_eq!(entries[0].hash, "ab".repeat(32));
+        assert_eq!(entries[1].sequence, 63);
+    }
+
+    #[test]
+    fn test_parse_ledger_headers_truncated() {
+        let data = encode_header_entry(62, 0xab);
+        assert!(parse_ledger_headers(&data[..data.len() - 10]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_archive_hashes() {
+        let server = MockServer::start().await;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&encode_header_entry(62, 0x11)).unwrap();
+        encoder.write_all(&encode_header_entry(63, 0x22)).unwrap();
+        let checkpoint = encoder.finish().unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/.well-known/stellar-history.json"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "currentLedger": 63 })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/ledger/00/00/00/ledger-0000003f.xdr.gz"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(checkpoint))
+            .mount(&server)
+            .await;
+
+        // Ledger 70 has not been published yet and must not be requested
+        let samples = vec![sample(63, "22".repeat(32).as_str()), sample(70, "33")];
+        let hashes = fetch_archive_hashes(&reqwest::Client::new(), &server.uri(), &samples)
+            .await
+            .unwrap();
+
+        assert_eq!(hashes.len(), 1);
+        assert_eq!(hashes.get(&63), Some(&"22".repeat(32)));
+    }
+
+    #[test]
+    fn test_divergence_condition() {
+        let mut conds = Vec::new();
+        let mut status = DisasterRecoveryStatus {
+            diverged: true,
+            divergence_message: Some("archive disagrees at ledger 63".to_string()),
+            ..Default::default()
+        };
+
+        apply_divergence_condition(&mut conds, &status);
+        let cond = conditions::find_condition(&conds, conditions::CONDITION_TYPE_DIVERGED).unwrap();
+        assert_eq!(cond.status, conditions::CONDITION_STATUS_TRUE);
+
+        status.diverged = false;
+        apply_divergence_condition(&mut conds, &status);
+        assert!(conditions::find_condition(&conds, conditions::CONDITION_TYPE_DIVERGED).is_none());
+    }
+}
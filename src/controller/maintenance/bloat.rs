@@ -1,11 +1,52 @@
 //! Database bloat detection for Postgres
 //!
-//! Queries Postgres statistics tables to estimate table and index bloat.
+//! Estimates table bloat from the planner statistics: the pages a table
+//! would need for its live rows are compared with the pages it occupies.
 
 use crate::error::Result;
-use sqlx::postgres::PgRow;
 use sqlx::{PgPool, Row};
 
+/// Tables smaller than this many pages are never reported as bloated
+pub const MIN_TABLE_PAGES: i32 = 128;
+
+/// Estimated bloat of a single table
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableBloat {
+    pub schema: String,
+    pub table: String,
+    pub bloat_percent: f64,
+    pub size_bytes: i64,
+}
+
+impl TableBloat {
+    /// Schema-qualified name for display
+    pub fn display_name(&self) -> String {
+        format!("{}.{}", self.schema, self.table)
+    }
+
+    /// Schema-qualified name, quoted for use in SQL statements
+    pub fn quoted_name(&self) -> String {
+        format!(
+            "{}.{}",
+            quote_identifier(&self.schema),
+            quote_identifier(&self.table)
+        )
+    }
+}
+
+/// Quote a Postgres identifier, doubling embedded quotes
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Share of `actual_pages` not needed for the live rows, in percent
+pub fn bloat_percent(actual_pages: f64, expected_pages: f64) -> f64 {
+    if actual_pages <= 0.0 {
+        return 0.0;
+    }
+    ((actual_pages - expected_pages) / actual_pages * 100.0).clamp(0.0, 100.0)
+}
+
 pub struct BloatDetector {
     pool: PgPool,
 }
@@ -15,53 +56,73 @@ impl BloatDetector {
         Self { pool }
     }
 
-    /// Estimate bloat percentage for a specific table
-    pub async fn estimate_table_bloat(&self, table_name: &str) -> Result<f64> {
-        // Standard bloat estimation query for Postgres
+    /// Estimate bloat for every user table of at least `MIN_TABLE_PAGES`
+    pub async fn estimate_table_bloat(&self) -> Result<Vec<TableBloat>> {
+        // Expected size: live rows times their average width plus the tuple
+        // header and line pointer, packed into pages minus the page header.
+        // Tables without statistics (never analyzed) are skipped.
         let query = r#"
+            WITH row_widths AS (
+              SELECT schemaname, tablename,
+                     SUM((1 - null_frac::float8) * avg_width::float8) AS row_width
+              FROM pg_catalog.pg_stats
+              GROUP BY schemaname, tablename
+            )
             SELECT
-              current_database(), schemaname, relname, 
-              ROUND(CASE WHEN otta=0 THEN 0 ELSE sml.relpages/otta::numeric END,1) AS tbloat
-            FROM (
-              SELECT
-                schemaname, tablename, relpages, est_pgfree,
-                CEIL((reltuples*slot_size*(1-fillfactor/100.0))/bs) AS otta
-              FROM (
-                SELECT
-                  schemaname, tablename, reltuples, relpages, fillfactor, bs,
-                  CASE WHEN reltuples=0 THEN 0 ELSE (relpages*bs)/reltuples END AS slot_size,
-                  (SELECT (regexp_matches(current_setting('block_size'), E'\\d+'))[1]::int) AS est_pgfree
-                FROM pg_catalog.pg_tables
-                JOIN pg_catalog.pg_class ON (relname = tablename)
-                JOIN pg_catalog.pg_namespace ON (pg_namespace.oid = relnamespace)
-                WHERE schemaname NOT IN ('pg_catalog', 'information_schema')
-              ) AS storage_info
-            ) AS sml
-            WHERE relname = $1
+              n.nspname AS schema_name,
+              c.relname AS table_name,
+              pg_catalog.pg_table_size(c.oid) AS size_bytes,
+              c.relpages::float8 AS actual_pages,
+              CEIL(c.reltuples::float8 * (w.row_width + 28)
+                   / (current_setting('block_size')::float8 - 24)) AS expected_pages
+            FROM pg_catalog.pg_class c
+            JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+            JOIN row_widths w ON w.schemaname = n.nspname AND w.tablename = c.relname
+            WHERE c.relkind = 'r'
+              AND n.nspname NOT IN ('pg_catalog', 'information_schema')
+              AND n.nspname NOT LIKE 'pg_toast%'
+              AND c.reltuples >= 0
+              AND c.relpages >= $1
         "#;
 
-        let row: PgRow = sqlx::query(query)
-            .bind(table_name)
-            .fetch_one(&self.pool)
+        let rows = sqlx::query(query)
+            .bind(MIN_TABLE_PAGES)
+            .fetch_all(&self.pool)
             .await?;
 
-        let bloat: f64 = row.try_get("tbloat")?;
-        Ok(bloat)
+        rows.iter()
+            .map(|row| {
+                Ok(TableBloat {
+                    schema: row.try_get("schema_name")?,
+                    table: row.try_get("table_name")?,
+                    bloat_percent: bloat_percent(
+                        row.try_get("actual_pages")?,
+                        row.try_get("expected_pages")?,
+                    ),
+                    size_bytes: row.try_get("size_bytes")?,
+                })
+            })
+            .collect()
     }
 
-    /// List bloated tables exceeding a threshold
-    pub async fn get_bloated_tables(&self, threshold_percent: u32) -> Result<Vec<String>> {
-        // Simplistic implementation for now
-        let query = "SELECT relname FROM pg_class WHERE relkind = 'r'";
-        let rows: Vec<PgRow> = sqlx::query(query).fetch_all(&self.pool).await?;
-
-        let mut bloated = Vec::new();
-        for row in rows {
-            let table: String = Row::get::<String, usize>(&row, 0);
-            if self.estimate_table_bloat(&table).await? > threshold_percent as f64 {
-                bloated.push(table);
-            }
-        }
+    /// List tables whose bloat exceeds a threshold, most bloated first
+    pub async fn get_bloated_tables(&self, threshold_percent: u32) -> Result<Vec<TableBloat>> {
+        let mut bloated: Vec<TableBloat> = self
+            .estimate_table_bloat()
+            .await?
+            .into_iter()
+            .filter(|t| t.bloat_percent > threshold_percent as f64)
+            .collect();
+        bloated.sort_by(|a, b| b.bloat_percent.total_cmp(&a.bloat_percent));
         Ok(bloated)
     }
+
+    /// Current on-disk size of a table
+    pub async fn table_size(&self, table: &TableBloat) -> Result<i64> {
+        let row = sqlx::query("SELECT pg_catalog.pg_table_size($1::regclass) AS size_bytes")
+            .bind(table.quoted_name())
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.try_get("size_bytes")?)
+    }
 }
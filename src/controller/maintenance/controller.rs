@@ -1,88 +1,445 @@
 //! Maintenance Window Controller logic
 //!
 //! Manages the lifecycle of maintenance windows and triggers DB tasks.
+//! A run can take most of the window, so the reconciler starts it as a
+//! background task tracked by [`MaintenanceRuns`] and only watches the
+//! `MaintenanceInProgress` condition. Runs stop between tables once the
+//! operator loses leadership.
 
-use super::bloat::BloatDetector;
+use super::bloat::{BloatDetector, TableBloat};
 use super::coordinator::MaintenanceCoordinator;
-use crate::crd::StellarNode;
-use crate::error::Result;
-use chrono::{Local, NaiveTime};
+use crate::controller::conditions::{
+    is_condition_true, set_condition, CONDITION_STATUS_FALSE, CONDITION_STATUS_TRUE,
+    CONDITION_TYPE_MAINTENANCE_IN_PROGRESS,
+};
+use crate::crd::{
+    Condition, DbMaintenanceConfig, DbMaintenanceStatus, StellarNode, TableMaintenanceResult,
+};
+use crate::error::{Error, Result};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use k8s_openapi::api::core::v1::Secret;
+use kube::api::{Api, Patch, PatchParams};
+use kube::{Client, ResourceExt};
+use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
-use tracing::{debug, info};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
+
+/// One occurrence of a node's daily maintenance window
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaintenanceWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl MaintenanceWindow {
+    /// The occurrence of the window that contains `now`, if any
+    pub fn current(config: &DbMaintenanceConfig, now: DateTime<Utc>) -> Option<Self> {
+        let start_time = config.window_start_time()?;
+        let length = config.window_length()?;
+
+        // A window opened yesterday may still be open after midnight
+        [0, 1].into_iter().find_map(|days_back| {
+            let start = (now.date_naive() - Duration::days(days_back))
+                .and_time(start_time)
+                .and_utc();
+            let end = start + length;
+            (start <= now && now < end).then_some(Self { start, end })
+        })
+    }
+}
+
+fn rfc3339(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Read the connection URL of the node's database from its Secret
+pub async fn database_url(client: &Client, node: &StellarNode) -> Result<String> {
+    let (secret_name, key) = if let Some(db) = &node.spec.database {
+        (
+            db.secret_key_ref.name.clone(),
+            db.secret_key_ref.key.clone(),
+        )
+    } else if node.spec.managed_database.is_some() {
+        // Application credentials written by CloudNativePG
+        (format!("{}-app", node.name_any()), "uri".to_string())
+    } else {
+        return Err(Error::ConfigError(format!(
+            "StellarNode {} has no database configured",
+            node.name_any()
+        )));
+    };
+
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let secret = Api::<Secret>::namespaced(client.clone(), &namespace)
+        .get(&secret_name)
+        .await?;
+
+    secret
+        .data
+        .as_ref()
+        .and_then(|data| data.get(&key))
+        .and_then(|value| String::from_utf8(value.0.clone()).ok())
+        .filter(|url| !url.is_empty())
+        .ok_or_else(|| {
+            Error::ConfigError(format!(
+                "Secret {secret_name} has no database URL in key {key}"
+            ))
+        })
+}
+
+/// Maintenance runs in progress in this operator, at most one per node
+#[derive(Debug, Default)]
+pub struct MaintenanceRuns {
+    running: Mutex<HashSet<(String, String)>>,
+}
+
+/// A node's claim on running maintenance, released when dropped
+#[derive(Debug)]
+pub struct MaintenanceClaim {
+    runs: Arc<MaintenanceRuns>,
+    key: (String, String),
+}
+
+impl Drop for MaintenanceClaim {
+    fn drop(&mut self) {
+        self.runs
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.key);
+    }
+}
+
+impl MaintenanceRuns {
+    fn key(node: &StellarNode) -> (String, String) {
+        (
+            node.namespace().unwrap_or_else(|| "default".to_string()),
+            node.name_any(),
+        )
+    }
+
+    /// Whether a run for `node` is in progress
+    pub fn is_running(&self, node: &StellarNode) -> bool {
+        self.running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(&Self::key(node))
+    }
+
+    /// Claim `node` for a run, or `None` when one is already in progress
+    pub fn claim(self: &Arc<Self>, node: &StellarNode) -> Option<MaintenanceClaim> {
+        let key = Self::key(node);
+        let inserted = self
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.clone());
+        inserted.then(|| MaintenanceClaim {
+            runs: Arc::clone(self),
+            key,
+        })
+    }
+}
 
 pub struct MaintenanceController {
+    client: Client,
     coordinator: MaintenanceCoordinator,
+    is_leader: Option<Arc<AtomicBool>>,
 }
 
 impl MaintenanceController {
-    pub fn new(coordinator: MaintenanceCoordinator) -> Self {
-        Self { coordinator }
+    pub fn new(client: Client) -> Self {
+        Self {
+            coordinator: MaintenanceCoordinator::new(client.clone()),
+            client,
+            is_leader: None,
+        }
+    }
+
+    /// Only run while this operator holds leadership
+    pub fn with_leadership(mut self, is_leader: Arc<AtomicBool>) -> Self {
+        self.is_leader = Some(is_leader);
+        self
+    }
+
+    fn is_leader(&self) -> bool {
+        self.is_leader
+            .as_ref()
+            .is_none_or(|l| l.load(Ordering::Relaxed))
     }
 
-    /// Check if we are currently in a maintenance window
-    pub fn is_in_window(&self, node: &StellarNode) -> bool {
+    /// Whether `node` has work for [`Self::reconcile`]: a due window, or a
+    /// run that was cut short
+    pub fn needs_run(node: &StellarNode, now: DateTime<Utc>) -> bool {
+        node.status.as_ref().is_some_and(|s| {
+            is_condition_true(&s.conditions, CONDITION_TYPE_MAINTENANCE_IN_PROGRESS)
+        }) || Self::due_window(node, now).is_some()
+    }
+
+    /// The window `node` should run maintenance in at `now`, if any.
+    ///
+    /// Each occurrence of the window is used at most once.
+    pub fn due_window(node: &StellarNode, now: DateTime<Utc>) -> Option<MaintenanceWindow> {
         let config = match &node.spec.db_maintenance_config {
             Some(c) if c.enabled => c,
-            _ => return false,
+            _ => return None,
         };
+        let window = MaintenanceWindow::current(config, now)?;
 
-        let now = Local::now().time();
-        let start = NaiveTime::parse_from_str(&config.window_start, "%H:%M")
-            .unwrap_or_else(|_| NaiveTime::from_hms_opt(2, 0, 0).unwrap());
-
-        // Simplistic window check
-        now >= start && now <= (start + chrono::Duration::hours(2)) // Default 2h window if duration not parsed
+        let last_window = node
+            .status
+            .as_ref()
+            .and_then(|s| s.db_maintenance.as_ref())
+            .and_then(|m| m.last_window_start.as_deref());
+        (last_window != Some(rfc3339(window.start).as_str())).then_some(window)
     }
 
-    /// Run maintenance tasks for a node if needed
-    pub async fn run_maintenance(&self, node: &StellarNode, pool: PgPool) -> Result<()> {
-        if !self.is_in_window(node) {
-            return Ok(());
+    /// Run maintenance for a node if its window is open.
+    ///
+    /// Must not run concurrently for the same node (see [`MaintenanceRuns`]).
+    /// Returns the recorded status when maintenance was attempted.
+    pub async fn reconcile(
+        &self,
+        node: &StellarNode,
+        now: DateTime<Utc>,
+    ) -> Result<Option<DbMaintenanceStatus>> {
+        if !Self::needs_run(node, now) || !self.is_leader() {
+            return Ok(None);
         }
 
-        let config = node.spec.db_maintenance_config.as_ref().unwrap();
-        let detector = BloatDetector::new(pool.clone());
-
-        let bloated_tables = detector
-            .get_bloated_tables(config.bloat_threshold_percent)
-            .await?;
+        // Decide on fresh status: the cached node may predate the end of
+        // the previous run
+        let name = node.name_any();
+        let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+        let api: Api<StellarNode> = Api::namespaced(self.client.clone(), &namespace);
+        let node = &api.get_status(&name).await?;
+        let interrupted = node.status.as_ref().is_some_and(|s| {
+            is_condition_true(&s.conditions, CONDITION_TYPE_MAINTENANCE_IN_PROGRESS)
+        });
+        let window = Self::due_window(node, now);
+        if !interrupted && window.is_none() {
+            return Ok(None);
+        }
+        let mut conditions = node
+            .status
+            .as_ref()
+            .map(|s| s.conditions.clone())
+            .unwrap_or_default();
 
-        if bloated_tables.is_empty() {
-            debug!(
-                "No bloated tables found for node {}",
-                node.metadata.name.as_ref().unwrap()
+        if interrupted {
+            // Runs are claimed per node, so an open condition means a
+            // previous run was cut short, e.g. by an operator restart
+            warn!("Maintenance of {namespace}/{name} was interrupted, restoring read traffic");
+            self.coordinator.finalize_maintenance(node).await?;
+            set_condition(
+                &mut conditions,
+                CONDITION_TYPE_MAINTENANCE_IN_PROGRESS,
+                CONDITION_STATUS_FALSE,
+                "Interrupted",
+                "Maintenance was interrupted; read replicas are back in rotation",
             );
-            return Ok(());
+            self.patch_status(&api, &name, Some(&conditions), None)
+                .await?;
+            return Ok(None);
+        }
+
+        let (Some(window), Some(config)) = (window, node.spec.db_maintenance_config.as_ref())
+        else {
+            return Ok(None);
+        };
+        let mut status = DbMaintenanceStatus {
+            last_window_start: Some(rfc3339(window.start)),
+            last_run_time: Some(rfc3339(now)),
+            ..Default::default()
+        };
+
+        let (pool, tables) = match self.find_bloated_tables(node, config).await {
+            Ok(found) => found,
+            Err(e) => {
+                status.last_error = Some(e.to_string());
+                self.patch_status(&api, &name, None, Some(&status)).await?;
+                return Ok(Some(status));
+            }
+        };
+
+        if tables.is_empty() {
+            debug!("No bloated tables found for node {namespace}/{name}");
+            status.last_completion_time = Some(rfc3339(Utc::now()));
+            self.patch_status(&api, &name, None, Some(&status)).await?;
+            pool.close().await;
+            return Ok(Some(status));
         }
 
         info!(
-            "Starting maintenance for node {}: found {} bloated tables",
-            node.metadata.name.as_ref().unwrap(),
-            bloated_tables.len()
+            "Starting maintenance for node {namespace}/{name}: found {} bloated tables",
+            tables.len()
+        );
+        set_condition(
+            &mut conditions,
+            CONDITION_TYPE_MAINTENANCE_IN_PROGRESS,
+            CONDITION_STATUS_TRUE,
+            "WindowOpen",
+            &format!("Running VACUUM FULL on {} tables", tables.len()),
         );
+        self.patch_status(&api, &name, Some(&conditions), Some(&status))
+            .await?;
 
-        if config.read_pool_coordination {
-            self.coordinator.prepare_node(node).await?;
-        }
+        let drained = if config.read_pool_coordination {
+            self.coordinator.prepare_node(node).await.map(|_| ())
+        } else {
+            Ok(())
+        };
 
-        for table in bloated_tables {
-            info!("Running VACUUM FULL on table {}", table);
-            sqlx::query(&format!("VACUUM FULL {}", table))
-                .execute(&pool)
-                .await?;
+        match drained {
+            Err(e) => status.last_error = Some(format!("Failed to drain read replicas: {e}")),
+            Ok(()) => {
+                let detector = BloatDetector::new(pool.clone());
+                for table in &tables {
+                    let skipped = if Utc::now() >= window.end {
+                        Some("maintenance window closed")
+                    } else if !self.is_leader() {
+                        Some("operator lost leadership")
+                    } else {
+                        None
+                    };
+                    let result = if let Some(reason) = skipped {
+                        TableMaintenanceResult {
+                            error: Some(reason.to_string()),
+                            ..table_result(table)
+                        }
+                    } else {
+                        maintain_table(&pool, &detector, table, config.auto_reindex).await
+                    };
+                    status.tables.push(result);
 
-            if config.auto_reindex {
-                info!("Reindexing table {}", table);
-                sqlx::query(&format!("REINDEX TABLE {}", table))
-                    .execute(&pool)
-                    .await?;
+                    // Record progress, a run can take most of the window
+                    if let Err(e) = self.patch_status(&api, &name, None, Some(&status)).await {
+                        warn!("Failed to record maintenance progress for {namespace}/{name}: {e}");
+                    }
+                }
             }
         }
+        pool.close().await;
 
+        // If this fails the condition stays open and the next reconcile
+        // restores traffic through the interrupted path
         if config.read_pool_coordination {
             self.coordinator.finalize_maintenance(node).await?;
         }
 
+        let failed = status.tables.iter().filter(|t| t.error.is_some()).count();
+        if status.last_error.is_none() && failed > 0 {
+            status.last_error = Some(format!(
+                "{failed} of {} tables were not maintained",
+                tables.len()
+            ));
+        }
+        status.last_completion_time = Some(rfc3339(Utc::now()));
+
+        let (reason, message) = match &status.last_error {
+            None => ("Completed", format!("Maintained {} tables", tables.len())),
+            Some(e) => ("Failed", e.clone()),
+        };
+        set_condition(
+            &mut conditions,
+            CONDITION_TYPE_MAINTENANCE_IN_PROGRESS,
+            CONDITION_STATUS_FALSE,
+            reason,
+            &message,
+        );
+        self.patch_status(&api, &name, Some(&conditions), Some(&status))
+            .await?;
+
+        info!("Finished maintenance for node {namespace}/{name}: {message}");
+        Ok(Some(status))
+    }
+
+    async fn find_bloated_tables(
+        &self,
+        node: &StellarNode,
+        config: &DbMaintenanceConfig,
+    ) -> Result<(PgPool, Vec<TableBloat>)> {
+        let url = database_url(&self.client, node).await?;
+        // VACUUM FULL runs one table at a time, a single connection suffices
+        let pool = PgPoolOptions::new()
+            .max_connections(1)
+            .acquire_timeout(std::time::Duration::from_secs(10))
+            .connect(&url)
+            .await?;
+
+        let tables = BloatDetector::new(pool.clone())
+            .get_bloated_tables(config.bloat_threshold_percent)
+            .await?;
+        Ok((pool, tables))
+    }
+
+    async fn patch_status(
+        &self,
+        api: &Api<StellarNode>,
+        name: &str,
+        conditions: Option<&[Condition]>,
+        maintenance: Option<&DbMaintenanceStatus>,
+    ) -> Result<()> {
+        let mut status = serde_json::Map::new();
+        if let Some(conditions) = conditions {
+            status.insert("conditions".to_string(), serde_json::json!(conditions));
+        }
+        if let Some(maintenance) = maintenance {
+            status.insert("dbMaintenance".to_string(), serde_json::json!(maintenance));
+        }
+        let patch = serde_json::json!({ "status": status });
+        api.patch_status(name, &PatchParams::default(), &Patch::Merge(&patch))
+            .await?;
         Ok(())
     }
 }
+
+/// Result entry for a table before any work was done on it
+pub fn table_result(table: &TableBloat) -> TableMaintenanceResult {
+    TableMaintenanceResult {
+        table: table.display_name(),
+        bloat_percent: (table.bloat_percent * 10.0).round() / 10.0,
+        size_bytes_before: table.size_bytes,
+        ..Default::default()
+    }
+}
+
+/// VACUUM FULL and optionally REINDEX a single table
+async fn maintain_table(
+    pool: &PgPool,
+    detector: &BloatDetector,
+    table: &TableBloat,
+    reindex: bool,
+) -> TableMaintenanceResult {
+    let started = std::time::Instant::now();
+    let mut result = table_result(table);
+    let name = table.quoted_name();
+
+    // Utility statements go through the simple query protocol
+    info!("Running VACUUM FULL on table {}", result.table);
+    match sqlx::raw_sql(&format!("VACUUM FULL {name}"))
+        .execute(pool)
+        .await
+    {
+        Ok(_) => result.vacuumed = true,
+        Err(e) => result.error = Some(format!("VACUUM FULL failed: {e}")),
+    }
+
+    if result.vacuumed && reindex {
+        info!("Reindexing table {}", result.table);
+        match sqlx::raw_sql(&format!("REINDEX TABLE {name}"))
+            .execute(pool)
+            .await
+        {
+            Ok(_) => result.reindexed = true,
+            Err(e) => result.error = Some(format!("REINDEX failed: {e}")),
+        }
+    }
+
+    result.size_bytes_after = detector.table_size(table).await.ok();
+    result.duration_seconds = started.elapsed().as_secs();
+    result
+}
@@ -1,44 +1,89 @@
 //! Maintenance Coordinator for zero-downtime DB operations
 //!
 //! Coordinates with the read-pool to ensure traffic is routed away from nodes
-//! undergoing maintenance.
+//! undergoing maintenance. Read replicas are drained by removing the label the
+//! read traffic Service selects on and marking them so that traffic routing
+//! leaves them out until maintenance is finalized.
 
+use crate::controller::traffic::{self, read_replica_selector, MAINTENANCE_LABEL, TRAFFIC_LABEL};
 use crate::crd::StellarNode;
 use crate::error::Result;
-use kube::Client;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, ListParams, Patch, PatchParams};
+use kube::{Client, ResourceExt};
 use tracing::info;
 
 pub struct MaintenanceCoordinator {
-    _client: Client,
+    client: Client,
 }
 
 impl MaintenanceCoordinator {
     pub fn new(client: Client) -> Self {
-        Self { _client: client }
+        Self { client }
     }
 
-    /// Prepare a node for maintenance by diverting traffic
-    pub async fn prepare_node(&self, node: &StellarNode) -> Result<()> {
-        info!(
-            "Preparing node {} for maintenance",
-            node.metadata.name.as_ref().unwrap()
-        );
+    fn pod_api(&self, node: &StellarNode) -> Api<Pod> {
+        let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+        Api::namespaced(self.client.clone(), &namespace)
+    }
+
+    /// Prepare a node for maintenance by diverting traffic.
+    ///
+    /// Returns the number of read replicas taken out of rotation.
+    pub async fn prepare_node(&self, node: &StellarNode) -> Result<usize> {
+        info!("Preparing node {} for maintenance", node.name_any());
+
+        let api = self.pod_api(node);
+        let pods = api
+            .list(&ListParams::default().labels(&read_replica_selector(node)))
+            .await?;
 
-        // Logic to update Service or Endpoint slices to remove this node from rotation
-        // If it's part of a read-pool, we might set a label that the service selector excludes
+        let patch = serde_json::json!({
+            "metadata": {
+                "labels": {
+                    TRAFFIC_LABEL: null,
+                    MAINTENANCE_LABEL: "in-progress",
+                }
+            }
+        });
+        for pod in &pods.items {
+            api.patch(
+                &pod.name_any(),
+                &PatchParams::apply("stellar-operator"),
+                &Patch::Merge(&patch),
+            )
+            .await?;
+        }
 
-        Ok(())
+        info!(
+            "Drained {} read replicas of {} from the read traffic Service",
+            pods.items.len(),
+            node.name_any()
+        );
+        Ok(pods.items.len())
     }
 
     /// Restore a node to service after maintenance
     pub async fn finalize_maintenance(&self, node: &StellarNode) -> Result<()> {
-        info!(
-            "Finalizing maintenance for node {}",
-            node.metadata.name.as_ref().unwrap()
-        );
+        info!("Finalizing maintenance for node {}", node.name_any());
+
+        let api = self.pod_api(node);
+        let selector = format!("{},{MAINTENANCE_LABEL}", read_replica_selector(node));
+        let pods = api.list(&ListParams::default().labels(&selector)).await?;
 
-        // Logic to restore node to rotation
+        let patch = serde_json::json!({
+            "metadata": { "labels": { MAINTENANCE_LABEL: null } }
+        });
+        for pod in &pods.items {
+            api.patch(
+                &pod.name_any(),
+                &PatchParams::apply("stellar-operator"),
+                &Patch::Merge(&patch),
+            )
+            .await?;
+        }
 
-        Ok(())
+        // Ready and fresh replicas rejoin the read traffic Service right away
+        traffic::reconcile_traffic_routing(&self.client, node).await
     }
 }
@@ -0,0 +1,163 @@
+//! Tests for maintenance windows and bloat estimation helpers.
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::conditions::CONDITION_TYPE_MAINTENANCE_IN_PROGRESS;
+    use crate::controller::maintenance::bloat::{bloat_percent, quote_identifier, TableBloat};
+    use crate::controller::maintenance::controller::table_result;
+    use crate::controller::maintenance::{
+        MaintenanceController, MaintenanceRuns, MaintenanceWindow,
+    };
+    use crate::crd::{Condition, DbMaintenanceStatus, StellarNode, StellarNodeStatus};
+    use chrono::{Duration, TimeZone, Utc};
+    use std::sync::Arc;
+
+    fn node(window_start: &str, window_duration: &str) -> StellarNode {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "stellar.org/v1alpha1",
+            "kind": "StellarNode",
+            "metadata": { "name": "horizon-1", "namespace": "stellar" },
+            "spec": {
+                "nodeType": "Horizon",
+                "network": "Testnet",
+                "version": "v2.30.0",
+                "database": { "secretKeyRef": { "name": "horizon-db", "key": "url" } },
+                "dbMaintenanceConfig": {
+                    "windowStart": window_start,
+                    "windowDuration": window_duration,
+                },
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_window_duration_parsing() {
+        let config = |duration: &str| {
+            node("02:00", duration)
+                .spec
+                .db_maintenance_config
+                .unwrap()
+                .window_length()
+        };
+
+        assert_eq!(config("2h"), Some(Duration::hours(2)));
+        assert_eq!(config("90m"), Some(Duration::minutes(90)));
+        assert_eq!(config("1h30m"), Some(Duration::minutes(90)));
+        assert_eq!(config(" 45s "), Some(Duration::seconds(45)));
+        for invalid in ["", "2", "h", "2d", "1h30", "-1h", "99999999h"] {
+            assert_eq!(config(invalid), None, "{invalid:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn test_current_window_spans_midnight() {
+        let config = node("23:00", "2h").spec.db_maintenance_config.unwrap();
+        let at = |h, m| Utc.with_ymd_and_hms(2026, 3, 2, h, m, 0).unwrap();
+
+        let window = MaintenanceWindow::current(&config, at(0, 30)).unwrap();
+        assert_eq!(
+            window.start,
+            Utc.with_ymd_and_hms(2026, 3, 1, 23, 0, 0).unwrap()
+        );
+        assert_eq!(window.end, at(1, 0));
+
+        let window = MaintenanceWindow::current(&config, at(23, 0)).unwrap();
+        assert_eq!(window.start, at(23, 0));
+
+        assert_eq!(MaintenanceWindow::current(&config, at(1, 0)), None);
+        assert_eq!(MaintenanceWindow::current(&config, at(12, 0)), None);
+    }
+
+    #[test]
+    fn test_each_window_runs_once() {
+        let mut node = node("02:00", "2h");
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 3, 0, 0).unwrap();
+
+        let window = MaintenanceController::due_window(&node, now).unwrap();
+        assert_eq!(
+            window.start,
+            Utc.with_ymd_and_hms(2026, 3, 1, 2, 0, 0).unwrap()
+        );
+
+        node.status = Some(StellarNodeStatus {
+            db_maintenance: Some(DbMaintenanceStatus {
+                last_window_start: Some("2026-03-01T02:00:00Z".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        assert_eq!(MaintenanceController::due_window(&node, now), None);
+
+        // The next day's window is due again
+        assert!(MaintenanceController::due_window(&node, now + Duration::days(1)).is_some());
+
+        node.spec.db_maintenance_config.as_mut().unwrap().enabled = false;
+        assert_eq!(
+            MaintenanceController::due_window(&node, now + Duration::days(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_interrupted_run_needs_a_run_outside_the_window() {
+        let mut node = node("02:00", "2h");
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        assert!(!MaintenanceController::needs_run(&node, now));
+
+        node.status = Some(StellarNodeStatus {
+            conditions: vec![Condition {
+                type_: CONDITION_TYPE_MAINTENANCE_IN_PROGRESS.to_string(),
+                status: "True".to_string(),
+                last_transition_time: "2026-03-01T02:00:00Z".to_string(),
+                reason: "WindowOpen".to_string(),
+                message: String::new(),
+                observed_generation: None,
+            }],
+            ..Default::default()
+        });
+        assert!(MaintenanceController::needs_run(&node, now));
+    }
+
+    #[test]
+    fn test_one_run_per_node() {
+        let runs = Arc::new(MaintenanceRuns::default());
+        let first = node("02:00", "2h");
+        let mut other = node("02:00", "2h");
+        other.metadata.name = Some("horizon-2".to_string());
+
+        let claim = runs.claim(&first).unwrap();
+        assert!(runs.is_running(&first));
+        assert!(runs.claim(&first).is_none());
+        assert!(!runs.is_running(&other));
+        let _other = runs.claim(&other).unwrap();
+
+        drop(claim);
+        assert!(!runs.is_running(&first));
+        assert!(runs.claim(&first).is_some());
+    }
+
+    #[test]
+    fn test_table_names_and_bloat_estimate() {
+        let table = TableBloat {
+            schema: "public".to_string(),
+            table: "history_\"ops\"; DROP TABLE x".to_string(),
+            bloat_percent: 42.345,
+            size_bytes: 8192,
+        };
+        assert_eq!(
+            table.quoted_name(),
+            "\"public\".\"history_\"\"ops\"\"; DROP TABLE x\""
+        );
+        assert_eq!(quote_identifier("ledgers"), "\"ledgers\"");
+
+        let result = table_result(&table);
+        assert_eq!(result.table, "public.history_\"ops\"; DROP TABLE x");
+        assert_eq!(result.bloat_percent, 42.3);
+        assert!(!result.vacuumed && !result.reindexed);
+
+        assert_eq!(bloat_percent(1000.0, 600.0), 40.0);
+        assert_eq!(bloat_percent(1000.0, 1200.0), 0.0);
+        assert_eq!(bloat_percent(0.0, 10.0), 0.0);
+    }
+}
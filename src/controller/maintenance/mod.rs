@@ -6,6 +6,9 @@ pub mod bloat;
 pub mod controller;
 pub mod coordinator;
 
+#[cfg(test)]
+mod maintenance_test;
+
 pub use bloat::BloatDetector;
-pub use controller::MaintenanceController;
+pub use controller::{MaintenanceClaim, MaintenanceController, MaintenanceRuns, MaintenanceWindow};
 pub use coordinator::MaintenanceCoordinator;
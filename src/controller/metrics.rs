@@ -8,6 +8,11 @@
 //! - `stellar_node_ingestion_lag` (gauge): ingestion lag labeled by namespace/name/node_type/network.
 //! - `stellar_horizon_tps` (gauge): Horizon TPS labeled by namespace/name/node_type/network.
 //! - `stellar_node_active_connections` (gauge): active peer connections labeled by namespace/name/node_type/network.
+//! - `stellar_webhook_plugin_execution_duration_seconds`, `stellar_webhook_plugin_fuel_consumed` and
+//!   `stellar_webhook_plugin_memory_peak_bytes` (histograms): per-execution cost of admission webhook Wasm plugins.
+//! - `stellar_webhook_plugin_limit_exceeded_total` (counter): plugin executions stopped by a fuel, timeout or memory limit.
+//! - `stellar_webhook_plugin_rejected_total` (counter): plugin executions rejected by the concurrency limit or circuit breaker.
+//! - `stellar_webhook_plugin_circuit_state` (gauge): 0 closed, 1 half-open, 2 open.
 
 use std::sync::atomic::{AtomicI64, AtomicU64};
 
@@ -110,6 +115,30 @@ pub struct TransactionResultLabels {
     pub result: String, // "success" or "failed"
 }
 
+/// Labels for decentralized backup metrics
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct BackupLabels {
+    pub namespace: String,
+    pub name: String,
+    /// Storage provider, e.g. "arweave" or "s3"
+    pub provider: String,
+}
+
+/// Labels for admission webhook plugin metrics
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct WasmPluginLabels {
+    pub plugin: String,
+}
+
+/// Labels for admission webhook plugin limit violations and rejections
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct WasmPluginReasonLabels {
+    pub plugin: String,
+    /// "fuel", "timeout" or "memory" for violations;
+    /// "concurrency" or "circuit_open" for rejections
+    pub reason: String,
+}
+
 /// Histogram tracking reconcile duration (seconds)
 pub static RECONCILE_DURATION_SECONDS: Lazy<Family<ReconcileLabels, Histogram>> = Lazy::new(|| {
     fn reconcile_histogram() -> Histogram {
@@ -171,6 +200,69 @@ pub static TRANSACTION_RESULT_TOTAL: Lazy<
 pub static HOST_FUNCTION_CALLS_TOTAL: Lazy<Family<SorobanLabels, Counter<u64, AtomicU64>>> =
     Lazy::new(Family::default);
 
+/// Histogram tracking webhook plugin execution time (seconds)
+pub static WASM_PLUGIN_EXECUTION_DURATION_SECONDS: Lazy<Family<WasmPluginLabels, Histogram>> =
+    Lazy::new(|| {
+        fn plugin_duration_histogram() -> Histogram {
+            // 100µs .. ~3.3s across 16 buckets
+            Histogram::new(exponential_buckets(0.0001, 2.0, 16))
+        }
+        Family::new_with_constructor(plugin_duration_histogram)
+    });
+
+/// Histogram tracking fuel consumed per webhook plugin execution
+pub static WASM_PLUGIN_FUEL_CONSUMED: Lazy<Family<WasmPluginLabels, Histogram>> = Lazy::new(|| {
+    fn fuel_histogram() -> Histogram {
+        // 1k .. ~500M instructions across 20 buckets
+        Histogram::new(exponential_buckets(1000.0, 2.0, 20))
+    }
+    Family::new_with_constructor(fuel_histogram)
+});
+
+/// Histogram tracking the linear memory high-water mark per webhook plugin execution
+pub static WASM_PLUGIN_MEMORY_PEAK_BYTES: Lazy<Family<WasmPluginLabels, Histogram>> =
+    Lazy::new(|| {
+        fn memory_histogram() -> Histogram {
+            // One 64KiB Wasm page .. 2GiB across 16 buckets
+            Histogram::new(exponential_buckets(65536.0, 2.0, 16))
+        }
+        Family::new_with_constructor(memory_histogram)
+    });
+
+/// Counter tracking webhook plugin executions stopped by a resource limit
+pub static WASM_PLUGIN_LIMIT_EXCEEDED_TOTAL: Lazy<
+    Family<WasmPluginReasonLabels, Counter<u64, AtomicU64>>,
+> = Lazy::new(Family::default);
+
+/// Counter tracking webhook plugin executions rejected before running
+pub static WASM_PLUGIN_REJECTED_TOTAL: Lazy<
+    Family<WasmPluginReasonLabels, Counter<u64, AtomicU64>>,
+> = Lazy::new(Family::default);
+
+/// Gauge tracking webhook plugin circuit breaker state
+pub static WASM_PLUGIN_CIRCUIT_STATE: Lazy<Family<WasmPluginLabels, Gauge<i64, AtomicI64>>> =
+    Lazy::new(Family::default);
+
+/// Gauge tracking the start of the last decentralized backup run (unix seconds)
+pub static BACKUP_LAST_RUN_TIMESTAMP: Lazy<Family<BackupLabels, Gauge<i64, AtomicI64>>> =
+    Lazy::new(Family::default);
+
+/// Gauge tracking the completion of the last successful backup run (unix seconds)
+pub static BACKUP_LAST_SUCCESS_TIMESTAMP: Lazy<Family<BackupLabels, Gauge<i64, AtomicI64>>> =
+    Lazy::new(Family::default);
+
+/// Gauge tracking whether the last backup run failed (0 or 1)
+pub static BACKUP_LAST_RUN_FAILED: Lazy<Family<BackupLabels, Gauge<i64, AtomicI64>>> =
+    Lazy::new(Family::default);
+
+/// Gauge tracking bytes uploaded by decentralized backups
+pub static BACKUP_UPLOADED_BYTES: Lazy<Family<BackupLabels, Gauge<i64, AtomicI64>>> =
+    Lazy::new(Family::default);
+
+/// Gauge tracking history archive files recorded in the backup manifest
+pub static BACKUP_FILES: Lazy<Family<BackupLabels, Gauge<i64, AtomicI64>>> =
+    Lazy::new(Family::default);
+
 /// Global metrics registry
 pub static REGISTRY: Lazy<Registry> = Lazy::new(|| {
     let mut registry = Registry::default();
@@ -267,6 +359,63 @@ pub static REGISTRY: Lazy<Registry> = Lazy::new(|| {
         HOST_FUNCTION_CALLS_TOTAL.clone(),
     );
 
+    // Admission webhook plugin metrics
+    registry.register(
+        "stellar_webhook_plugin_execution_duration_seconds",
+        "Wasm plugin execution time in seconds",
+        WASM_PLUGIN_EXECUTION_DURATION_SECONDS.clone(),
+    );
+    registry.register(
+        "stellar_webhook_plugin_fuel_consumed",
+        "Fuel (Wasm instructions) consumed per plugin execution",
+        WASM_PLUGIN_FUEL_CONSUMED.clone(),
+    );
+    registry.register(
+        "stellar_webhook_plugin_memory_peak_bytes",
+        "Linear memory high-water mark per plugin execution in bytes",
+        WASM_PLUGIN_MEMORY_PEAK_BYTES.clone(),
+    );
+    registry.register(
+        "stellar_webhook_plugin_limit_exceeded",
+        "Plugin executions stopped by a fuel, timeout or memory limit",
+        WASM_PLUGIN_LIMIT_EXCEEDED_TOTAL.clone(),
+    );
+    registry.register(
+        "stellar_webhook_plugin_rejected",
+        "Plugin executions rejected by the concurrency limit or circuit breaker",
+        WASM_PLUGIN_REJECTED_TOTAL.clone(),
+    );
+    registry.register(
+        "stellar_webhook_plugin_circuit_state",
+        "Plugin circuit breaker state (0 closed, 1 half-open, 2 open)",
+        WASM_PLUGIN_CIRCUIT_STATE.clone(),
+    );
+    registry.register(
+        "stellar_backup_last_run_timestamp_seconds",
+        "Start of the last decentralized backup run",
+        BACKUP_LAST_RUN_TIMESTAMP.clone(),
+    );
+    registry.register(
+        "stellar_backup_last_success_timestamp_seconds",
+        "Completion of the last decentralized backup run that uploaded every segment",
+        BACKUP_LAST_SUCCESS_TIMESTAMP.clone(),
+    );
+    registry.register(
+        "stellar_backup_last_run_failed",
+        "Whether the last decentralized backup run failed (1) or not (0)",
+        BACKUP_LAST_RUN_FAILED.clone(),
+    );
+    registry.register(
+        "stellar_backup_uploaded_bytes",
+        "Bytes uploaded by decentralized backups since they were enabled",
+        BACKUP_UPLOADED_BYTES.clone(),
+    );
+    registry.register(
+        "stellar_backup_files",
+        "History archive files recorded in the decentralized backup manifest",
+        BACKUP_FILES.clone(),
+    );
+
     registry
 });
 
@@ -564,6 +713,90 @@ pub fn inc_host_function_call(namespace: &str, name: &str, network: &str, contra
     HOST_FUNCTION_CALLS_TOTAL.get_or_create(&labels).inc();
 }
 
+/// Record the cost of a webhook plugin execution
+pub fn observe_wasm_plugin_execution(
+    plugin: &str,
+    seconds: f64,
+    fuel: u64,
+    memory_peak_bytes: u64,
+) {
+    let labels = WasmPluginLabels {
+        plugin: plugin.to_string(),
+    };
+    WASM_PLUGIN_EXECUTION_DURATION_SECONDS
+        .get_or_create(&labels)
+        .observe(seconds);
+    WASM_PLUGIN_FUEL_CONSUMED
+        .get_or_create(&labels)
+        .observe(fuel as f64);
+    WASM_PLUGIN_MEMORY_PEAK_BYTES
+        .get_or_create(&labels)
+        .observe(memory_peak_bytes as f64);
+}
+
+/// Increment the webhook plugin limit violation counter
+pub fn inc_wasm_plugin_limit_exceeded(plugin: &str, limit: &str) {
+    let labels = WasmPluginReasonLabels {
+        plugin: plugin.to_string(),
+        reason: limit.to_string(),
+    };
+    WASM_PLUGIN_LIMIT_EXCEEDED_TOTAL
+        .get_or_create(&labels)
+        .inc();
+}
+
+/// Increment the webhook plugin rejection counter
+pub fn inc_wasm_plugin_rejected(plugin: &str, reason: &str) {
+    let labels = WasmPluginReasonLabels {
+        plugin: plugin.to_string(),
+        reason: reason.to_string(),
+    };
+    WASM_PLUGIN_REJECTED_TOTAL.get_or_create(&labels).inc();
+}
+
+/// Set the webhook plugin circuit breaker state
+pub fn set_wasm_plugin_circuit_state(plugin: &str, state: i64) {
+    let labels = WasmPluginLabels {
+        plugin: plugin.to_string(),
+    };
+    WASM_PLUGIN_CIRCUIT_STATE.get_or_create(&labels).set(state);
+}
+
+/// Export a node's decentralized backup status
+pub fn set_backup_status(
+    namespace: &str,
+    name: &str,
+    provider: &str,
+    status: &crate::crd::DecentralizedBackupStatus,
+) {
+    let labels = BackupLabels {
+        namespace: namespace.to_string(),
+        name: name.to_string(),
+        provider: provider.to_string(),
+    };
+    let timestamp = |time: &Option<String>| {
+        time.as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.timestamp())
+    };
+
+    if let Some(ts) = timestamp(&status.last_run_time) {
+        BACKUP_LAST_RUN_TIMESTAMP.get_or_create(&labels).set(ts);
+    }
+    if let Some(ts) = timestamp(&status.last_success_time) {
+        BACKUP_LAST_SUCCESS_TIMESTAMP.get_or_create(&labels).set(ts);
+    }
+    BACKUP_LAST_RUN_FAILED
+        .get_or_create(&labels)
+        .set(status.last_error.is_some() as i64);
+    BACKUP_UPLOADED_BYTES
+        .get_or_create(&labels)
+        .set(status.total_bytes_uploaded as i64);
+    BACKUP_FILES
+        .get_or_create(&labels)
+        .set(status.files_backed_up as i64);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
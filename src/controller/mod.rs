@@ -7,13 +7,22 @@ pub mod resource_meta;
 
 mod archive_health;
 pub mod captive_core;
+pub mod cert_manager;
+#[cfg(test)]
+mod cert_manager_test;
 pub mod conditions;
 pub mod cross_cluster;
 pub mod cve;
 mod cve_reconciler;
 #[cfg(test)]
 mod cve_test;
+pub mod decentralized_backup;
+#[cfg(test)]
+mod decentralized_backup_test;
 pub mod dr;
+pub mod dr_probe;
+#[cfg(test)]
+mod dr_probe_test;
 #[cfg(test)]
 mod dr_test;
 mod finalizers;
@@ -21,6 +30,9 @@ mod health;
 #[cfg(test)]
 mod health_test;
 pub mod kms_secret;
+pub mod ledger_consistency;
+#[cfg(test)]
+mod ledger_consistency_test;
 #[cfg(feature = "metrics")]
 pub mod metrics;
 pub mod migration;
@@ -31,6 +43,9 @@ pub mod oci_snapshot;
 pub mod peer_discovery;
 #[cfg(test)]
 mod peer_discovery_test;
+pub mod quorum_analysis;
+#[cfg(test)]
+mod quorum_analysis_test;
 pub mod read_pool;
 mod reconciler;
 #[cfg(test)]
@@ -43,11 +58,21 @@ mod resources;
 mod resources_test;
 pub mod service_mesh;
 mod snapshot;
+pub mod strkey;
+#[cfg(test)]
+mod strkey_test;
 pub mod traffic;
 #[cfg(test)]
 mod traffic_test;
+pub mod validator_identity;
 pub mod vpa;
 mod vsl;
+mod vsl_refresh;
+#[cfg(test)]
+mod vsl_refresh_test;
+pub mod vsl_trust;
+#[cfg(test)]
+mod vsl_trust_test;
 
 pub use archive_health::{
     calculate_backoff, check_archive_integrity, check_history_archive_health, ArchiveHealthResult,
@@ -56,7 +81,7 @@ pub use archive_health::{
 pub use cross_cluster::{check_peer_latency, ensure_cross_cluster_services, PeerLatencyStatus};
 pub use cve_reconciler::reconcile_cve_patches;
 pub use finalizers::STELLAR_NODE_FINALIZER;
-pub use health::{check_node_health, HealthCheckResult};
+pub use health::{check_node_health, HealthCheckResult, ValidatorHealthDetails};
 pub use migration::{migrate_config, reconcile_migration};
 pub use peer_discovery::{
     get_peers_from_config_map, trigger_peer_config_reload, PeerDiscoveryConfig,
@@ -68,3 +93,4 @@ pub use service_mesh::{
     delete_service_mesh_resources, ensure_destination_rule, ensure_peer_authentication,
     ensure_request_authentication, ensure_virtual_service,
 };
+pub use vsl_refresh::{VslRefreshConfig, VslRefresher};
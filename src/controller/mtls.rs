@@ -1,11 +1,24 @@
 //! mTLS Certificate Management for internal communication
 //!
 //! Handles CA creation and certificate issuance for the Operator REST API
-//! and Stellar nodes. Supports automated rotation of server certificates
-//! before expiration.
+//! and Stellar nodes. Server and node certificates are rotated before
+//! expiration and are always signed by the CA certificate stored in the
+//! `stellar-operator-ca` Secret.
+//!
+//! The CA itself is rotated with an overlap period so no node ever sees a
+//! certificate it cannot verify:
+//!
+//! 1. When the CA nears expiry a new CA is generated and stored as pending
+//!    (`next.crt` / `next.key`). The trust bundle (`ca.crt`) now contains
+//!    both CAs and is distributed to every server and node certificate,
+//!    which are still signed by the old CA.
+//! 2. After the overlap period the pending CA is promoted. Certificates are
+//!    re-issued by the new CA as they are reconciled; the old CA stays in
+//!    the bundle until it expires.
 
 use crate::crd::StellarNode;
 use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
 use k8s_openapi::api::core::v1::Secret;
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
 use kube::{
@@ -16,66 +29,248 @@ use rcgen::{
     CertificateParams, DistinguishedName, ExtendedKeyUsagePurpose, Ia5String, IsCa, KeyPair,
     KeyUsagePurpose, SanType,
 };
+use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
 use std::time::Duration;
 use tracing::{debug, info};
 use x509_parser::certificate::X509Certificate;
 use x509_parser::pem::parse_x509_pem;
 use x509_parser::prelude::FromDer;
+use x509_parser::time::ASN1Time;
 
 pub const CA_SECRET_NAME: &str = "stellar-operator-ca";
 pub const SERVER_CERT_SECRET_NAME: &str = "stellar-operator-server-cert";
 
+/// Annotation on issued certificate Secrets recording the fingerprint of the signing CA.
+pub const CA_FINGERPRINT_ANNOTATION: &str = "stellar.org/ca-fingerprint";
+/// Annotation on the CA Secret recording when the pending CA was generated.
+pub const CA_ROTATION_STARTED_ANNOTATION: &str = "stellar.org/ca-rotation-started-at";
+
 /// Default number of days before certificate expiration at which to trigger rotation.
 pub const DEFAULT_CERT_ROTATION_THRESHOLD_DAYS: u32 = 30;
+/// Default number of days before CA expiration at which to start a CA rotation.
+pub const DEFAULT_CA_ROTATION_THRESHOLD_DAYS: u32 = 90;
+/// Default number of days both CAs are trusted before the new CA starts signing.
+pub const DEFAULT_CA_OVERLAP_DAYS: u32 = 7;
+
+/// Validity of a newly generated CA certificate.
+const CA_VALIDITY_DAYS: i64 = 3650;
+/// Validity of a newly issued server or node certificate.
+const CERT_VALIDITY_DAYS: i64 = 365;
+
+const SECONDS_PER_DAY: i64 = 24 * 3600;
+
+/// The operator CA, loaded from the `stellar-operator-ca` Secret
+pub struct CertificateAuthority {
+    cert_pem: String,
+    key_pair: KeyPair,
+    /// CA certificates to trust: the signing CA plus any CA it overlaps with
+    bundle_pem: String,
+}
 
-/// Ensure the CA exists in the cluster
-pub async fn ensure_ca(client: &Client, namespace: &str) -> Result<()> {
-    let secrets: Api<Secret> = Api::namespaced(client.clone(), namespace);
+impl CertificateAuthority {
+    /// Generate a new self-signed CA
+    pub fn generate(now: DateTime<Utc>) -> Result<Self> {
+        let mut params = CertificateParams::default();
+        params.is_ca = IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        params.distinguished_name = DistinguishedName::new();
+        params
+            .distinguished_name
+            .push(rcgen::DnType::CommonName, "stellar-operator-ca");
+        params.key_usages.push(KeyUsagePurpose::DigitalSignature);
+        params.key_usages.push(KeyUsagePurpose::KeyCertSign);
+        params.key_usages.push(KeyUsagePurpose::CrlSign);
+        set_validity(&mut params, now, CA_VALIDITY_DAYS)?;
+
+        let key_pair = KeyPair::generate()?;
+        let cert_pem = params.self_signed(&key_pair)?.pem();
+        Ok(Self {
+            bundle_pem: cert_pem.clone(),
+            cert_pem,
+            key_pair,
+        })
+    }
 
-    if secrets.get(CA_SECRET_NAME).await.is_ok() {
-        return Ok(());
+    /// Load the signing CA from its Secret.
+    ///
+    /// Secrets created before trust bundles existed have no `ca.crt`; the
+    /// CA certificate alone is trusted then.
+    pub fn from_secret(secret: &Secret) -> Result<Self> {
+        let cert_pem = secret_string(secret, "tls.crt")?;
+        let key_pem = secret_string(secret, "tls.key")?;
+        let bundle_pem = match secret_string(secret, "ca.crt") {
+            Ok(bundle) => bundle,
+            Err(_) => cert_pem.clone(),
+        };
+        Ok(Self {
+            key_pair: KeyPair::from_pem(&key_pem)?,
+            cert_pem,
+            bundle_pem,
+        })
     }
 
-    // Generate new CA
-    let mut params = CertificateParams::default();
-    params.is_ca = IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
-    params.distinguished_name = DistinguishedName::new();
-    params
-        .distinguished_name
-        .push(rcgen::DnType::CommonName, "stellar-operator-ca");
-    params.key_usages.push(KeyUsagePurpose::DigitalSignature);
-    params.key_usages.push(KeyUsagePurpose::KeyCertSign);
-    params.key_usages.push(KeyUsagePurpose::CrlSign);
+    /// The CA certificate in PEM format
+    pub fn cert_pem(&self) -> &str {
+        &self.cert_pem
+    }
 
-    let key_pair = KeyPair::generate().map_err(|e| Error::ConfigError(e.to_string()))?;
-    let cert = params
-        .self_signed(&key_pair)
-        .map_err(|e| Error::ConfigError(e.to_string()))?;
+    /// The trust bundle distributed as `ca.crt`
+    pub fn bundle_pem(&self) -> &str {
+        &self.bundle_pem
+    }
 
-    let mut data = BTreeMap::new();
-    data.insert("tls.crt".to_string(), cert.pem().into_bytes());
-    data.insert("tls.key".to_string(), key_pair.serialize_pem().into_bytes());
+    /// Hex-encoded SHA-256 of the CA certificate
+    pub fn fingerprint(&self) -> Result<String> {
+        fingerprint(self.cert_pem.as_bytes())
+    }
 
-    let secret = Secret {
-        metadata: ObjectMeta {
-            name: Some(CA_SECRET_NAME.to_string()),
-            namespace: Some(namespace.to_string()),
-            ..Default::default()
+    /// Issue a certificate signed by this CA.
+    ///
+    /// Returns the certificate and private key in PEM format.
+    pub fn issue(
+        &self,
+        common_name: &str,
+        dns_names: Vec<String>,
+        now: DateTime<Utc>,
+    ) -> Result<(String, String)> {
+        // Rebuild the issuer from the stored certificate so the issued chain
+        // matches the CA that is actually distributed
+        let issuer =
+            CertificateParams::from_ca_cert_pem(&self.cert_pem)?.self_signed(&self.key_pair)?;
+
+        let mut params = CertificateParams::default();
+        params.distinguished_name = DistinguishedName::new();
+        params
+            .distinguished_name
+            .push(rcgen::DnType::CommonName, common_name);
+        for dns in dns_names {
+            params.subject_alt_names.push(SanType::DnsName(
+                Ia5String::try_from(dns).map_err(|e| Error::CertificateError(e.to_string()))?,
+            ));
+        }
+        params.key_usages.push(KeyUsagePurpose::DigitalSignature);
+        params
+            .extended_key_usages
+            .push(ExtendedKeyUsagePurpose::ServerAuth);
+        params
+            .extended_key_usages
+            .push(ExtendedKeyUsagePurpose::ClientAuth);
+        params.use_authority_key_identifier_extension = true;
+        set_validity(&mut params, now, CERT_VALIDITY_DAYS)?;
+
+        let key_pair = KeyPair::generate()?;
+        let cert = params.signed_by(&key_pair, &issuer, &self.key_pair)?;
+        Ok((cert.pem(), key_pair.serialize_pem()))
+    }
+
+    /// Secret data for a certificate issued by this CA
+    fn issued_secret_data(&self, cert_pem: String, key_pem: String) -> BTreeMap<String, Vec<u8>> {
+        let mut data = BTreeMap::new();
+        data.insert("tls.crt".to_string(), cert_pem.into_bytes());
+        data.insert("tls.key".to_string(), key_pem.into_bytes());
+        data.insert("ca.crt".to_string(), self.bundle_pem.clone().into_bytes());
+        data
+    }
+}
+
+fn secret_string(secret: &Secret, key: &str) -> Result<String> {
+    let name = secret.metadata.name.as_deref().unwrap_or_default();
+    let bytes = secret
+        .data
+        .as_ref()
+        .and_then(|d| d.get(key))
+        .ok_or_else(|| Error::CertificateError(format!("Secret {name} has no {key}")))?;
+    String::from_utf8(bytes.0.clone())
+        .map_err(|e| Error::CertificateError(format!("Secret {name} has non-UTF-8 {key}: {e}")))
+}
+
+fn set_validity(params: &mut CertificateParams, now: DateTime<Utc>, days: i64) -> Result<()> {
+    let to_time = |secs: i64| {
+        ASN1Time::from_timestamp(secs)
+            .map(|t| t.to_datetime())
+            .map_err(|e| Error::CertificateError(format!("Invalid certificate validity: {e}")))
+    };
+    // Tolerate some clock skew between the operator and the nodes
+    params.not_before = to_time(now.timestamp() - 3600)?;
+    params.not_after = to_time(now.timestamp() + days * SECONDS_PER_DAY)?;
+    Ok(())
+}
+
+fn to_secret_data(data: BTreeMap<String, Vec<u8>>) -> BTreeMap<String, k8s_openapi::ByteString> {
+    data.into_iter()
+        .map(|(k, v)| (k, k8s_openapi::ByteString(v)))
+        .collect()
+}
+
+/// Hex-encoded SHA-256 of the (first) certificate in a PEM
+pub fn fingerprint(cert_pem: &[u8]) -> Result<String> {
+    let (_, pem) = parse_x509_pem(cert_pem)
+        .map_err(|e| Error::CertificateError(format!("Failed to parse PEM: {e}")))?;
+    Ok(format!("{:x}", Sha256::digest(&pem.contents)))
+}
+
+/// Expiry of the (first) certificate in a PEM
+pub fn cert_not_after(cert_pem: &[u8]) -> Result<DateTime<Utc>> {
+    let (_, pem) = parse_x509_pem(cert_pem)
+        .map_err(|e| Error::CertificateError(format!("Failed to parse PEM: {e}")))?;
+    let (_, cert) = X509Certificate::from_der(&pem.contents)
+        .map_err(|e| Error::CertificateError(format!("Failed to parse X.509 certificate: {e}")))?;
+    DateTime::from_timestamp(cert.validity().not_after.timestamp(), 0)
+        .ok_or_else(|| Error::CertificateError("Certificate expiry out of range".to_string()))
+}
+
+/// Split a PEM bundle into its certificates
+pub fn split_pem_bundle(bundle: &str) -> Vec<String> {
+    const END: &str = "-----END CERTIFICATE-----";
+    bundle
+        .split_inclusive(END)
+        .map(str::trim)
+        .filter(|block| block.ends_with(END))
+        .map(|block| format!("{block}\n"))
+        .collect()
+}
+
+/// Build a trust bundle from `certs`, dropping duplicates and expired certificates
+fn build_bundle<'a>(certs: impl IntoIterator<Item = &'a str>, now: DateTime<Utc>) -> String {
+    let mut seen = Vec::new();
+    let mut bundle = String::new();
+    for cert in certs.into_iter().flat_map(split_pem_bundle) {
+        let expired = cert_not_after(cert.as_bytes()).map_or(true, |na| na <= now);
+        if expired || seen.contains(&cert) {
+            continue;
+        }
+        bundle.push_str(&cert);
+        seen.push(cert);
+    }
+    bundle
+}
+
+/// Ensure the CA exists in the cluster and advance any CA rotation that is due
+pub async fn ensure_ca(client: &Client, namespace: &str) -> Result<()> {
+    let secrets: Api<Secret> = Api::namespaced(client.clone(), namespace);
+    let now = Utc::now();
+
+    let desired = match secrets.get_opt(CA_SECRET_NAME).await? {
+        None => {
+            info!("Creating CA in namespace {}", namespace);
+            ca_secret(namespace, &CertificateAuthority::generate(now)?, None, None)?
+        }
+        Some(existing) => match plan_ca_rotation(
+            &existing,
+            now,
+            DEFAULT_CA_ROTATION_THRESHOLD_DAYS,
+            DEFAULT_CA_OVERLAP_DAYS,
+        )? {
+            Some(updated) => updated,
+            None => return Ok(()),
         },
-        data: Some(
-            data.into_iter()
-                .map(|(k, v)| (k, k8s_openapi::ByteString(v)))
-                .collect(),
-        ),
-        ..Default::default()
     };
 
     secrets
         .patch(
             CA_SECRET_NAME,
             &PatchParams::apply("stellar-operator").force(),
-            &Patch::Apply(&secret),
+            &Patch::Apply(&desired),
         )
         .await
         .map_err(Error::KubeError)?;
@@ -83,6 +278,135 @@ pub async fn ensure_ca(client: &Client, namespace: &str) -> Result<()> {
     Ok(())
 }
 
+/// Build the CA Secret for a signing CA and optional pending CA
+fn ca_secret(
+    namespace: &str,
+    ca: &CertificateAuthority,
+    pending: Option<&CertificateAuthority>,
+    rotation_started: Option<DateTime<Utc>>,
+) -> Result<Secret> {
+    let mut data = BTreeMap::new();
+    data.insert("tls.crt".to_string(), ca.cert_pem.clone().into_bytes());
+    data.insert(
+        "tls.key".to_string(),
+        ca.key_pair.serialize_pem().into_bytes(),
+    );
+    data.insert("ca.crt".to_string(), ca.bundle_pem.clone().into_bytes());
+    if let Some(pending) = pending {
+        data.insert(
+            "next.crt".to_string(),
+            pending.cert_pem.clone().into_bytes(),
+        );
+        data.insert(
+            "next.key".to_string(),
+            pending.key_pair.serialize_pem().into_bytes(),
+        );
+    }
+
+    Ok(Secret {
+        metadata: ObjectMeta {
+            name: Some(CA_SECRET_NAME.to_string()),
+            namespace: Some(namespace.to_string()),
+            annotations: rotation_started.map(|started| {
+                BTreeMap::from([(
+                    CA_ROTATION_STARTED_ANNOTATION.to_string(),
+                    started.to_rfc3339(),
+                )])
+            }),
+            ..Default::default()
+        },
+        data: Some(to_secret_data(data)),
+        ..Default::default()
+    })
+}
+
+/// Decide the next state of the CA Secret, or `None` if nothing changes.
+///
+/// - No rotation in progress and the CA expires within `threshold_days`:
+///   generate a pending CA and trust both.
+/// - Pending CA older than `overlap_days`: promote it to signing CA, keeping
+///   the old CA in the bundle until it expires.
+/// - Otherwise expired CAs are dropped from the bundle.
+pub fn plan_ca_rotation(
+    secret: &Secret,
+    now: DateTime<Utc>,
+    threshold_days: u32,
+    overlap_days: u32,
+) -> Result<Option<Secret>> {
+    let namespace = secret.namespace().unwrap_or_default();
+    let current = CertificateAuthority::from_secret(secret)?;
+
+    let pending = match (
+        secret_string(secret, "next.crt"),
+        secret_string(secret, "next.key"),
+    ) {
+        (Ok(cert_pem), Ok(key_pem)) => Some(CertificateAuthority {
+            key_pair: KeyPair::from_pem(&key_pem)?,
+            bundle_pem: cert_pem.clone(),
+            cert_pem,
+        }),
+        _ => None,
+    };
+
+    if let Some(mut pending) = pending {
+        let started = secret
+            .annotations()
+            .get(CA_ROTATION_STARTED_ANNOTATION)
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|t| t.with_timezone(&Utc));
+        let Some(started) = started else {
+            // Unknown start: restart the overlap period from now
+            return Ok(Some(ca_secret(
+                &namespace,
+                &current,
+                Some(&pending),
+                Some(now),
+            )?));
+        };
+        if now < started + chrono::Duration::days(overlap_days.into()) {
+            return Ok(None);
+        }
+        info!("Promoting pending CA in namespace {}", namespace);
+        pending.bundle_pem = build_bundle([pending.cert_pem(), current.bundle_pem()], now);
+        return Ok(Some(ca_secret(&namespace, &pending, None, None)?));
+    }
+
+    let threshold = chrono::Duration::days(threshold_days.into());
+    if cert_not_after(current.cert_pem.as_bytes())? - now <= threshold {
+        info!(
+            "CA in namespace {} expires within {} days, starting rotation",
+            namespace, threshold_days
+        );
+        let mut current = current;
+        let next = CertificateAuthority::generate(now)?;
+        current.bundle_pem = build_bundle([current.bundle_pem(), next.cert_pem()], now);
+        return Ok(Some(ca_secret(
+            &namespace,
+            &current,
+            Some(&next),
+            Some(now),
+        )?));
+    }
+
+    let pruned = build_bundle([current.cert_pem(), current.bundle_pem()], now);
+    if pruned != current.bundle_pem {
+        debug!("Dropping expired CAs from trust bundle in {}", namespace);
+        let mut current = current;
+        current.bundle_pem = pruned;
+        return Ok(Some(ca_secret(&namespace, &current, None, None)?));
+    }
+    Ok(None)
+}
+
+/// Load the signing CA of a namespace
+async fn load_ca(secrets: &Api<Secret>) -> Result<CertificateAuthority> {
+    let secret = secrets
+        .get(CA_SECRET_NAME)
+        .await
+        .map_err(Error::KubeError)?;
+    CertificateAuthority::from_secret(&secret)
+}
+
 /// Ensure server certificate exists for the operator (creates only if missing).
 pub async fn ensure_server_cert(
     client: &Client,
@@ -102,9 +426,9 @@ pub async fn ensure_server_cert(
 /// Uses the first certificate in the PEM if multiple are present.
 pub fn cert_time_to_expiration(cert_pem: &[u8]) -> Result<Option<Duration>> {
     let (_, pem) = parse_x509_pem(cert_pem)
-        .map_err(|e| Error::ConfigError(format!("Failed to parse PEM: {e}")))?;
+        .map_err(|e| Error::CertificateError(format!("Failed to parse PEM: {e}")))?;
     let (_, cert) = X509Certificate::from_der(&pem.contents)
-        .map_err(|e| Error::ConfigError(format!("Failed to parse X.509 certificate: {e}")))?;
+        .map_err(|e| Error::CertificateError(format!("Failed to parse X.509 certificate: {e}")))?;
     let validity = cert.validity();
     let duration = validity.time_to_expiration();
     // x509-parser uses time::Duration; convert to std::time::Duration
@@ -114,8 +438,37 @@ pub fn cert_time_to_expiration(cert_pem: &[u8]) -> Result<Option<Duration>> {
     }))
 }
 
+/// Whether an issued certificate Secret has to be re-issued by `ca`.
+///
+/// True when the certificate is missing or unparseable, expires within
+/// `rotation_threshold_days`, was signed by another CA, or carries an
+/// outdated trust bundle.
+pub fn issued_cert_needs_rotation(
+    secret: &Secret,
+    ca: &CertificateAuthority,
+    rotation_threshold_days: u32,
+) -> Result<bool> {
+    let Ok(cert_pem) = secret_string(secret, "tls.crt") else {
+        return Ok(true);
+    };
+    let ca_fingerprint = ca.fingerprint()?;
+    if secret.annotations().get(CA_FINGERPRINT_ANNOTATION) != Some(&ca_fingerprint) {
+        return Ok(true);
+    }
+    if secret_string(secret, "ca.crt").ok().as_deref() != Some(ca.bundle_pem()) {
+        return Ok(true);
+    }
+    let threshold = Duration::from_secs(rotation_threshold_days as u64 * 24 * 3600);
+    match cert_time_to_expiration(cert_pem.as_bytes()) {
+        Ok(Some(d)) => Ok(d <= threshold),
+        // Expired or invalid, rotate
+        Ok(None) | Err(_) => Ok(true),
+    }
+}
+
 /// Check whether the current server certificate in the cluster is within the rotation threshold
-/// (i.e. expires within `rotation_threshold_days` days). Returns true if rotation should be performed.
+/// (i.e. expires within `rotation_threshold_days` days) or no longer matches the CA.
+/// Returns true if rotation should be performed.
 pub async fn server_cert_needs_rotation(
     client: &Client,
     namespace: &str,
@@ -126,20 +479,8 @@ pub async fn server_cert_needs_rotation(
         Ok(s) => s,
         Err(_) => return Ok(true), // No cert yet, needs creation (handled by ensure_server_cert)
     };
-    let data = secret
-        .data
-        .as_ref()
-        .ok_or_else(|| Error::ConfigError("Server cert secret has no data".to_string()))?;
-    let cert_pem = data
-        .get("tls.crt")
-        .ok_or_else(|| Error::ConfigError("Server cert secret missing tls.crt".to_string()))?;
-    let time_to_exp = cert_time_to_expiration(&cert_pem.0)?;
-    let threshold = Duration::from_secs(rotation_threshold_days as u64 * 24 * 3600);
-    match time_to_exp {
-        None => Ok(true), // Expired or invalid, rotate
-        Some(d) if d <= threshold => Ok(true),
-        Some(_) => Ok(false),
-    }
+    let ca = load_ca(&secrets).await?;
+    issued_cert_needs_rotation(&secret, &ca, rotation_threshold_days)
 }
 
 /// Generate a new server certificate and update the Secret (overwrites existing).
@@ -158,80 +499,20 @@ async fn generate_and_patch_server_cert_inner(
     namespace: &str,
     dns_names: Vec<String>,
 ) -> Result<()> {
-    let ca_secret = secrets
-        .get(CA_SECRET_NAME)
-        .await
-        .map_err(Error::KubeError)?;
-    let ca_cert_pem = String::from_utf8(
-        ca_secret
-            .data
-            .as_ref()
-            .unwrap()
-            .get("tls.crt")
-            .unwrap()
-            .0
-            .clone(),
-    )
-    .unwrap();
-    let ca_key_pem = String::from_utf8(
-        ca_secret
-            .data
-            .as_ref()
-            .unwrap()
-            .get("tls.key")
-            .unwrap()
-            .0
-            .clone(),
-    )
-    .unwrap();
-
-    let ca_key_pair =
-        KeyPair::from_pem(&ca_key_pem).map_err(|e| Error::ConfigError(e.to_string()))?;
-    let mut ca_params = CertificateParams::new(vec!["stellar-operator-ca".to_string()])?;
-    ca_params.is_ca = IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
-    let ca_cert = ca_params
-        .self_signed(&ca_key_pair)
-        .map_err(|e| Error::ConfigError(e.to_string()))?;
-
-    let mut params = CertificateParams::default();
-    params.distinguished_name = DistinguishedName::new();
-    params
-        .distinguished_name
-        .push(rcgen::DnType::CommonName, "stellar-operator");
-    for dns in dns_names {
-        params.subject_alt_names.push(SanType::DnsName(
-            Ia5String::try_from(dns).map_err(|e| Error::ConfigError(e.to_string()))?,
-        ));
-    }
-    params.key_usages.push(KeyUsagePurpose::DigitalSignature);
-    params
-        .extended_key_usages
-        .push(ExtendedKeyUsagePurpose::ServerAuth);
-    params
-        .extended_key_usages
-        .push(ExtendedKeyUsagePurpose::ClientAuth);
-
-    let key_pair = KeyPair::generate().map_err(|e| Error::ConfigError(e.to_string()))?;
-    let cert = params
-        .signed_by(&key_pair, &ca_cert, &ca_key_pair)
-        .map_err(|e| Error::ConfigError(e.to_string()))?;
-
-    let mut data = BTreeMap::new();
-    data.insert("tls.crt".to_string(), cert.pem().into_bytes());
-    data.insert("tls.key".to_string(), key_pair.serialize_pem().into_bytes());
-    data.insert("ca.crt".to_string(), ca_cert_pem.into_bytes());
+    let ca = load_ca(secrets).await?;
+    let (cert_pem, key_pem) = ca.issue("stellar-operator", dns_names, Utc::now())?;
 
     let secret = Secret {
         metadata: ObjectMeta {
             name: Some(SERVER_CERT_SECRET_NAME.to_string()),
             namespace: Some(namespace.to_string()),
+            annotations: Some(BTreeMap::from([(
+                CA_FINGERPRINT_ANNOTATION.to_string(),
+                ca.fingerprint()?,
+            )])),
             ..Default::default()
         },
-        data: Some(
-            data.into_iter()
-                .map(|(k, v)| (k, k8s_openapi::ByteString(v)))
-                .collect(),
-        ),
+        data: Some(to_secret_data(ca.issued_secret_data(cert_pem, key_pem))),
         ..Default::default()
     };
 
@@ -260,87 +541,39 @@ pub async fn maybe_rotate_server_cert(
         return Ok(false);
     }
     info!(
-        "Server certificate within {} days of expiration or missing, rotating",
+        "Server certificate within {} days of expiration, missing or signed by another CA, rotating",
         rotation_threshold_days
     );
     rotate_server_cert(client, namespace, dns_names).await?;
     Ok(true)
 }
 
-/// Ensure client certificate exists for a specific node
+/// Ensure a client certificate signed by the current CA exists for a specific
+/// node, re-issuing it when it nears expiry or the CA changed.
 pub async fn ensure_node_cert(client: &Client, node: &StellarNode) -> Result<()> {
     let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
     let node_name = node.name_any();
     let secret_name = format!("{node_name}-client-cert");
     let secrets: Api<Secret> = Api::namespaced(client.clone(), &namespace);
 
-    if secrets.get(&secret_name).await.is_ok() {
-        return Ok(());
+    let ca = load_ca(&secrets).await?;
+    if let Some(existing) = secrets.get_opt(&secret_name).await? {
+        if !issued_cert_needs_rotation(&existing, &ca, DEFAULT_CERT_ROTATION_THRESHOLD_DAYS)? {
+            return Ok(());
+        }
+        info!("Rotating client certificate {}/{}", namespace, secret_name);
     }
 
-    let ca_secret = secrets
-        .get(CA_SECRET_NAME)
-        .await
-        .map_err(Error::KubeError)?;
-    let ca_cert_pem = String::from_utf8(
-        ca_secret
-            .data
-            .as_ref()
-            .unwrap()
-            .get("tls.crt")
-            .unwrap()
-            .0
-            .clone(),
-    )
-    .unwrap();
-    let ca_key_pem = String::from_utf8(
-        ca_secret
-            .data
-            .as_ref()
-            .unwrap()
-            .get("tls.key")
-            .unwrap()
-            .0
-            .clone(),
-    )
-    .unwrap();
-
-    let ca_key_pair =
-        KeyPair::from_pem(&ca_key_pem).map_err(|e| Error::ConfigError(e.to_string()))?;
-    let mut ca_params = CertificateParams::new(vec!["stellar-operator-ca".to_string()])?;
-    ca_params.is_ca = IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
-    let ca_cert = ca_params
-        .self_signed(&ca_key_pair)
-        .map_err(|e| Error::ConfigError(e.to_string()))?;
-
-    let mut params = CertificateParams::default();
-    params.distinguished_name = DistinguishedName::new();
-    params.distinguished_name.push(
-        rcgen::DnType::CommonName,
-        format!("stellar-node-{node_name}"),
-    );
-    params.key_usages.push(KeyUsagePurpose::DigitalSignature);
-    params
-        .extended_key_usages
-        .push(ExtendedKeyUsagePurpose::ClientAuth);
-    params
-        .extended_key_usages
-        .push(ExtendedKeyUsagePurpose::ServerAuth);
-
-    let key_pair = KeyPair::generate().map_err(|e| Error::ConfigError(e.to_string()))?;
-    let cert = params
-        .signed_by(&key_pair, &ca_cert, &ca_key_pair)
-        .map_err(|e| Error::ConfigError(e.to_string()))?;
-
-    let mut data = BTreeMap::new();
-    data.insert("tls.crt".to_string(), cert.pem().into_bytes());
-    data.insert("tls.key".to_string(), key_pair.serialize_pem().into_bytes());
-    data.insert("ca.crt".to_string(), ca_cert_pem.into_bytes());
+    let (cert_pem, key_pem) = ca.issue(&format!("stellar-node-{node_name}"), vec![], Utc::now())?;
 
     let secret = Secret {
         metadata: ObjectMeta {
             name: Some(secret_name.clone()),
             namespace: Some(namespace.to_string()),
+            annotations: Some(BTreeMap::from([(
+                CA_FINGERPRINT_ANNOTATION.to_string(),
+                ca.fingerprint()?,
+            )])),
             owner_references: Some(vec![
                 k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference {
                     api_version: StellarNode::api_version(&()).to_string(),
@@ -353,11 +586,7 @@ pub async fn ensure_node_cert(client: &Client, node: &StellarNode) -> Result<()>
             ]),
             ..Default::default()
         },
-        data: Some(
-            data.into_iter()
-                .map(|(k, v)| (k, k8s_openapi::ByteString(v)))
-                .collect(),
-        ),
+        data: Some(to_secret_data(ca.issued_secret_data(cert_pem, key_pem))),
         ..Default::default()
     };
 
@@ -432,4 +661,176 @@ mod tests {
     fn rotation_threshold_constant() {
         assert_eq!(DEFAULT_CERT_ROTATION_THRESHOLD_DAYS, 30);
     }
+
+    // -----------------------------------------------------------------------
+    // CA-signed certificates and CA rotation
+    // -----------------------------------------------------------------------
+
+    fn at(year: i32, month: u32) -> DateTime<Utc> {
+        use chrono::TimeZone;
+        Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).unwrap()
+    }
+
+    fn secret(name: &str, data: &[(&str, &str)], annotations: &[(&str, String)]) -> Secret {
+        Secret {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some("stellar".to_string()),
+                annotations: Some(
+                    annotations
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.clone()))
+                        .collect(),
+                ),
+                ..Default::default()
+            },
+            data: Some(
+                data.iter()
+                    .map(|(k, v)| {
+                        (
+                            k.to_string(),
+                            k8s_openapi::ByteString(v.as_bytes().to_vec()),
+                        )
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    fn ca_secret_for(ca: &CertificateAuthority) -> Secret {
+        let key = ca.key_pair.serialize_pem();
+        secret(
+            CA_SECRET_NAME,
+            &[("tls.crt", ca.cert_pem()), ("tls.key", &key)],
+            &[],
+        )
+    }
+
+    fn issued_secret(ca: &CertificateAuthority, now: DateTime<Utc>) -> Secret {
+        let (cert, key) = ca.issue("stellar-node-test", vec![], now).unwrap();
+        secret(
+            "test-client-cert",
+            &[
+                ("tls.crt", &cert),
+                ("tls.key", &key),
+                ("ca.crt", ca.bundle_pem()),
+            ],
+            &[(CA_FINGERPRINT_ANNOTATION, ca.fingerprint().unwrap())],
+        )
+    }
+
+    fn subject_and_issuer(pem: &str) -> (String, String) {
+        let (_, pem) = parse_x509_pem(pem.as_bytes()).unwrap();
+        let (_, cert) = X509Certificate::from_der(&pem.contents).unwrap();
+        (cert.subject().to_string(), cert.issuer().to_string())
+    }
+
+    #[test]
+    fn issued_cert_is_signed_by_stored_ca() {
+        let ca = CertificateAuthority::generate(Utc::now()).unwrap();
+        let loaded = CertificateAuthority::from_secret(&ca_secret_for(&ca)).unwrap();
+        assert_eq!(loaded.cert_pem(), ca.cert_pem());
+        // Old secrets without a bundle trust the CA certificate alone
+        assert_eq!(loaded.bundle_pem(), ca.cert_pem());
+
+        let (cert, _) = loaded
+            .issue("stellar-node-a", vec!["a.stellar".to_string()], Utc::now())
+            .unwrap();
+        let (ca_subject, _) = subject_and_issuer(ca.cert_pem());
+        let (subject, issuer) = subject_and_issuer(&cert);
+        assert_eq!(subject, "CN=stellar-node-a");
+        assert_eq!(issuer, ca_subject);
+
+        let days = cert_time_to_expiration(cert.as_bytes())
+            .unwrap()
+            .unwrap()
+            .as_secs()
+            / 86400;
+        assert!((363..=365).contains(&days), "validity was {days} days");
+    }
+
+    #[test]
+    fn missing_ca_data_is_a_certificate_error() {
+        let err = CertificateAuthority::from_secret(&secret(CA_SECRET_NAME, &[], &[]))
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::CertificateError(_)));
+        assert!(err.to_string().contains("has no tls.crt"));
+
+        let err = CertificateAuthority::from_secret(&secret(
+            CA_SECRET_NAME,
+            &[("tls.crt", "x"), ("tls.key", "not a key")],
+            &[],
+        ))
+        .err()
+        .unwrap();
+        assert!(matches!(err, Error::CertificateError(_)));
+    }
+
+    #[test]
+    fn issued_cert_rotation_triggers() {
+        let now = Utc::now();
+        let ca = CertificateAuthority::generate(now).unwrap();
+        let healthy = issued_secret(&ca, now);
+        assert!(!issued_cert_needs_rotation(&healthy, &ca, 30).unwrap());
+        // Within the threshold of a 365-day certificate
+        assert!(issued_cert_needs_rotation(&healthy, &ca, 400).unwrap());
+
+        // Signed by another CA
+        let other = CertificateAuthority::generate(now).unwrap();
+        assert!(issued_cert_needs_rotation(&healthy, &other, 30).unwrap());
+
+        // Outdated trust bundle
+        let mut rebundled = CertificateAuthority::from_secret(&ca_secret_for(&ca)).unwrap();
+        rebundled.bundle_pem = format!("{}{}", ca.cert_pem(), other.cert_pem());
+        assert!(issued_cert_needs_rotation(&healthy, &rebundled, 30).unwrap());
+
+        let empty = secret("test-client-cert", &[], &[]);
+        assert!(issued_cert_needs_rotation(&empty, &ca, 30).unwrap());
+    }
+
+    #[test]
+    fn ca_rotation_overlaps_both_cas() {
+        // CA issued in 2024, so it expires at the end of 2033
+        let old = CertificateAuthority::generate(at(2024, 1)).unwrap();
+        let initial = ca_secret_for(&old);
+        assert!(plan_ca_rotation(&initial, at(2025, 1), 90, 7)
+            .unwrap()
+            .is_none());
+
+        // Within 90 days of expiry: pending CA, old CA still signs
+        let started = plan_ca_rotation(&initial, at(2033, 12), 90, 7)
+            .unwrap()
+            .unwrap();
+        let signing = CertificateAuthority::from_secret(&started).unwrap();
+        assert_eq!(signing.cert_pem(), old.cert_pem());
+        let bundle = split_pem_bundle(signing.bundle_pem());
+        assert_eq!(bundle.len(), 2);
+        let next_pem = secret_string(&started, "next.crt").unwrap();
+        assert_eq!(bundle[1], next_pem);
+
+        // Overlap not over yet
+        assert!(
+            plan_ca_rotation(&started, at(2033, 12) + chrono::Duration::days(3), 90, 7)
+                .unwrap()
+                .is_none()
+        );
+
+        // Promotion: the new CA signs, both stay trusted
+        let promoted = plan_ca_rotation(&started, at(2033, 12) + chrono::Duration::days(10), 90, 7)
+            .unwrap()
+            .unwrap();
+        let signing = CertificateAuthority::from_secret(&promoted).unwrap();
+        assert_eq!(signing.cert_pem(), next_pem);
+        assert!(secret_string(&promoted, "next.crt").is_err());
+        assert_eq!(split_pem_bundle(signing.bundle_pem()).len(), 2);
+
+        // Once the old CA has expired it is dropped from the bundle
+        let pruned = plan_ca_rotation(&promoted, at(2034, 6), 90, 7)
+            .unwrap()
+            .unwrap();
+        let signing = CertificateAuthority::from_secret(&pruned).unwrap();
+        assert_eq!(signing.bundle_pem(), next_pem);
+    }
 }
@@ -510,6 +510,7 @@ mod tests {
                 read_replica_config: None,
                 db_maintenance_config: None,
                 oci_snapshot: None,
+                decentralized_backup: None,
                 service_mesh: None,
                 read_pool_endpoint: None,
                 resource_meta: None,
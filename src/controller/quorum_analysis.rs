@@ -0,0 +1,751 @@
+//! Safety analysis for stellar-core quorum sets
+//!
+//! Checks a `[QUORUM_SET]` before it is rolled out, following the rules
+//! stellar-core enforces at startup plus the liveness and safety properties
+//! operators usually only discover when the network halts:
+//!
+//! - **Sanity**: every set has members, thresholds are within 1–100%, no
+//!   validator appears twice and nesting stays within stellar-core's limit.
+//!   Thresholds of 50% or less are rejected unless `UNSAFE_QUORUM` is set.
+//! - **Blocking sets**: the smallest set of validators whose failure leaves
+//!   the node unable to reach quorum. A single validator is a warning.
+//! - **Organisation failure**: inner sets are treated as organisations; if
+//!   losing any one of them halts the node, that is a warning.
+//! - **Intersection**: two quorum sets are checked for disjoint slices. Slice
+//!   intersection is a conservative (sufficient) condition for quorum
+//!   intersection; a violation means two halves of the network could
+//!   externalize different values.
+//!
+//! Validators are identified by public key. `$alias` entries are resolved
+//! through the names the configuration itself defines (`"KEY alias"` entries,
+//! `[[VALIDATORS]]` tables and `NODE_NAMES`); aliases it does not define are
+//! compared by name.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::OnceLock;
+
+use kube::{
+    api::{Api, Patch, PatchParams},
+    runtime::reflector::Store,
+    Client, ResourceExt,
+};
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::crd::{Condition, NodeType, StellarNode};
+use crate::error::{Error, Result};
+
+use super::conditions;
+
+/// Condition reporting the outcome of the quorum analysis
+pub const CONDITION_TYPE_QUORUM_HEALTHY: &str = "QuorumHealthy";
+
+/// stellar-core's `MAXIMUM_QUORUM_NESTING_LEVEL`
+const MAX_NESTING_LEVEL: usize = 4;
+/// stellar-core's default when `THRESHOLD_PERCENT` is omitted
+const DEFAULT_THRESHOLD_PERCENT: i64 = 67;
+/// Upper bound on enumerated slices for the intersection check
+const MAX_SLICES: usize = 10_000;
+
+/// A (possibly nested) quorum set
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuorumSetDef {
+    /// TOML table path, e.g. `QUORUM_SET.sdf`
+    pub name: String,
+    pub threshold_percent: i64,
+    pub validators: Vec<String>,
+    pub inner_sets: Vec<QuorumSetDef>,
+}
+
+/// The quorum-related part of a stellar-core configuration
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuorumConfig {
+    pub quorum_set: QuorumSetDef,
+    /// `UNSAFE_QUORUM = true` allows thresholds of 50% or less
+    pub unsafe_quorum: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// One problem found by the analysis
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Result of analysing one or more quorum sets
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QuorumReport {
+    pub findings: Vec<Finding>,
+    /// A smallest set of validators whose failure halts the node
+    pub min_blocking_set: Vec<String>,
+}
+
+impl QuorumReport {
+    pub fn errors(&self) -> impl Iterator<Item = &str> {
+        self.messages(Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &str> {
+        self.messages(Severity::Warning)
+    }
+
+    /// No errors (warnings are allowed)
+    pub fn is_safe(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    fn messages(&self, severity: Severity) -> impl Iterator<Item = &str> {
+        self.findings
+            .iter()
+            .filter(move |f| f.severity == severity)
+            .map(|f| f.message.as_str())
+    }
+
+    fn push(&mut self, severity: Severity, message: String) {
+        self.findings.push(Finding { severity, message });
+    }
+}
+
+impl QuorumSetDef {
+    /// Number of members (validators and inner sets) that must agree, using
+    /// stellar-core's rounding
+    pub fn threshold(&self) -> usize {
+        let members = self.members() as i64;
+        if members == 0 || self.threshold_percent <= 0 {
+            return 0;
+        }
+        (1 + (members * self.threshold_percent - 1) / 100) as usize
+    }
+
+    fn members(&self) -> usize {
+        self.validators.len() + self.inner_sets.len()
+    }
+
+    /// Every validator in this set and its inner sets
+    pub fn all_validators(&self) -> Vec<&str> {
+        let mut out: Vec<&str> = self.validators.iter().map(String::as_str).collect();
+        for inner in &self.inner_sets {
+            out.extend(inner.all_validators());
+        }
+        out
+    }
+
+    /// Whether a slice can be formed without any validator in `failed`
+    pub fn is_satisfiable(&self, failed: &BTreeSet<String>) -> bool {
+        let available = self
+            .validators
+            .iter()
+            .filter(|v| !failed.contains(*v))
+            .count()
+            + self
+                .inner_sets
+                .iter()
+                .filter(|i| i.is_satisfiable(failed))
+                .count();
+        let threshold = self.threshold();
+        threshold > 0 && available >= threshold
+    }
+
+    /// A smallest set of validators that blocks every slice.
+    ///
+    /// Exact for sane sets, where no validator appears twice: members are then
+    /// disjoint and it suffices to block the cheapest `n - t + 1` of them.
+    pub fn min_blocking_set(&self) -> Vec<String> {
+        let threshold = self.threshold();
+        let members = self.members();
+        if threshold == 0 || threshold > members {
+            // Already unsatisfiable
+            return Vec::new();
+        }
+        let mut costs: Vec<Vec<String>> = self
+            .validators
+            .iter()
+            .map(|v| vec![v.clone()])
+            .chain(self.inner_sets.iter().map(|i| i.min_blocking_set()))
+            .collect();
+        costs.sort_by_key(Vec::len);
+        costs
+            .into_iter()
+            .take(members - threshold + 1)
+            .flatten()
+            .collect()
+    }
+
+    /// Minimal slices, or `None` when there are more than `limit`
+    fn slices(&self, limit: usize) -> Option<Vec<BTreeSet<String>>> {
+        let threshold = self.threshold();
+        if threshold == 0 || threshold > self.members() {
+            return Some(Vec::new());
+        }
+        let mut member_slices: Vec<Vec<BTreeSet<String>>> = self
+            .validators
+            .iter()
+            .map(|v| vec![BTreeSet::from([v.clone()])])
+            .collect();
+        for inner in &self.inner_sets {
+            member_slices.push(inner.slices(limit)?);
+        }
+
+        // Every combination yields at least one slice unless an inner set is
+        // unsatisfiable, so bail out before enumerating
+        if more_combinations_than(member_slices.len(), threshold, limit) {
+            return None;
+        }
+        let mut out = Vec::new();
+        for combination in Combinations::new(member_slices.len(), threshold) {
+            let mut partial = vec![BTreeSet::new()];
+            for index in combination {
+                let mut next = Vec::new();
+                for base in &partial {
+                    for slice in &member_slices[index] {
+                        next.push(base.union(slice).cloned().collect());
+                        if next.len() > limit {
+                            return None;
+                        }
+                    }
+                }
+                partial = next;
+            }
+            out.extend(partial);
+            if out.len() > limit {
+                return None;
+            }
+        }
+        Some(out)
+    }
+}
+
+/// Whether `n` choose `k` exceeds `limit`, without computing it in full
+fn more_combinations_than(n: usize, k: usize, limit: usize) -> bool {
+    let k = k.min(n - k);
+    let mut count: usize = 1;
+    for i in 0..k {
+        // `count` is `n` choose `i` here, and grows with `i` up to `k <= n / 2`
+        count = count * (n - i) / (i + 1);
+        if count > limit {
+            return true;
+        }
+    }
+    false
+}
+
+/// `k`-element index combinations of `0..n`, in lexicographic order
+struct Combinations {
+    n: usize,
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl Combinations {
+    fn new(n: usize, k: usize) -> Self {
+        Self {
+            n,
+            indices: (0..k).collect(),
+            done: k > n,
+        }
+    }
+}
+
+impl Iterator for Combinations {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let current = self.indices.clone();
+
+        // Advance the rightmost index that still has room
+        let k = self.indices.len();
+        match (0..k).rev().find(|&i| self.indices[i] < self.n - k + i) {
+            Some(i) => {
+                self.indices[i] += 1;
+                for j in i + 1..k {
+                    self.indices[j] = self.indices[j - 1] + 1;
+                }
+            }
+            None => self.done = true,
+        }
+        Some(current)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parsing
+// ---------------------------------------------------------------------------
+
+/// Parse the `[QUORUM_SET]` of a stellar-core configuration.
+///
+/// Returns `Ok(None)` when there is no explicit quorum set (e.g. automatic
+/// quorum generation from `[[VALIDATORS]]`).
+pub fn parse_quorum_config(toml_str: &str) -> Result<Option<QuorumConfig>> {
+    let value: toml::Value = toml_str
+        .parse()
+        .map_err(|e| Error::ConfigError(format!("Invalid quorum set TOML: {e}")))?;
+    let Some(table) = value.get("QUORUM_SET").and_then(|v| v.as_table()) else {
+        return Ok(None);
+    };
+    let aliases = collect_aliases(&value);
+    Ok(Some(QuorumConfig {
+        quorum_set: parse_set("QUORUM_SET", table, &aliases)?,
+        unsafe_quorum: value
+            .get("UNSAFE_QUORUM")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+    }))
+}
+
+fn parse_set(
+    name: &str,
+    table: &toml::value::Table,
+    aliases: &HashMap<String, String>,
+) -> Result<QuorumSetDef> {
+    let threshold_percent = match table.get("THRESHOLD_PERCENT") {
+        None => DEFAULT_THRESHOLD_PERCENT,
+        Some(v) => v.as_integer().ok_or_else(|| {
+            Error::ConfigError(format!("{name}.THRESHOLD_PERCENT must be an integer"))
+        })?,
+    };
+    let validators = match table.get("VALIDATORS") {
+        None => Vec::new(),
+        Some(v) => v
+            .as_array()
+            .ok_or_else(|| Error::ConfigError(format!("{name}.VALIDATORS must be an array")))?
+            .iter()
+            .map(|entry| {
+                entry
+                    .as_str()
+                    .and_then(|entry| validator_id(entry, aliases))
+                    .ok_or_else(|| {
+                        Error::ConfigError(format!("Invalid entry in {name}.VALIDATORS"))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?,
+    };
+    let inner_sets = table
+        .iter()
+        .filter_map(|(key, v)| v.as_table().map(|t| (key, t)))
+        .map(|(key, t)| parse_set(&format!("{name}.{key}"), t, aliases))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(QuorumSetDef {
+        name: name.to_string(),
+        threshold_percent,
+        validators,
+        inner_sets,
+    })
+}
+
+/// Aliases defined anywhere in the configuration, mapped to public keys
+fn collect_aliases(value: &toml::Value) -> HashMap<String, String> {
+    fn from_entries(entries: &[toml::Value], aliases: &mut HashMap<String, String>) {
+        for entry in entries.iter().filter_map(|e| e.as_str()) {
+            let mut tokens = entry.split_whitespace();
+            if let (Some(key), Some(alias)) = (tokens.next(), tokens.next()) {
+                if !key.starts_with(['$', '@']) {
+                    aliases.insert(alias.to_string(), key.to_string());
+                }
+            }
+        }
+    }
+    fn from_set(table: &toml::value::Table, aliases: &mut HashMap<String, String>) {
+        if let Some(entries) = table.get("VALIDATORS").and_then(|v| v.as_array()) {
+            from_entries(entries, aliases);
+        }
+        for inner in table.values().filter_map(|v| v.as_table()) {
+            from_set(inner, aliases);
+        }
+    }
+
+    let mut aliases = HashMap::new();
+    if let Some(entries) = value.get("NODE_NAMES").and_then(|v| v.as_array()) {
+        from_entries(entries, &mut aliases);
+    }
+    if let Some(table) = value.get("QUORUM_SET").and_then(|v| v.as_table()) {
+        from_set(table, &mut aliases);
+    }
+    // `[[VALIDATORS]]` tables, as used with automatic quorum generation
+    for validator in value
+        .get("VALIDATORS")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_table())
+    {
+        let name = validator.get("NAME").and_then(|v| v.as_str());
+        let key = validator.get("PUBLIC_KEY").and_then(|v| v.as_str());
+        if let (Some(name), Some(key)) = (name, key) {
+            aliases.insert(name.to_string(), key.to_string());
+        }
+    }
+    aliases
+}
+
+/// `"GABC… alias"` → `GABC…`, `"$alias"` → the aliased key, or `alias` when
+/// the configuration does not define it
+fn validator_id(entry: &str, aliases: &HashMap<String, String>) -> Option<String> {
+    let token = entry.split_whitespace().next()?;
+    let id = token.trim_start_matches(['$', '@']);
+    if id.is_empty() {
+        return None;
+    }
+    if id.len() < token.len() {
+        if let Some(key) = aliases.get(id) {
+            return Some(key.clone());
+        }
+    }
+    Some(id.to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Analysis
+// ---------------------------------------------------------------------------
+
+/// Analyse a single node's quorum configuration
+pub fn analyze(config: &QuorumConfig) -> QuorumReport {
+    let mut report = QuorumReport::default();
+    let qset = &config.quorum_set;
+
+    check_structure(qset, 1, config.unsafe_quorum, &mut report);
+
+    let all = qset.all_validators();
+    let distinct: BTreeSet<&str> = all.iter().copied().collect();
+    if distinct.len() != all.len() {
+        let duplicates: BTreeSet<&str> = all
+            .iter()
+            .copied()
+            .filter(|v| all.iter().filter(|o| *o == v).count() > 1)
+            .collect();
+        report.push(
+            Severity::Error,
+            format!(
+                "Validators appear more than once: {}",
+                duplicates.into_iter().collect::<Vec<_>>().join(", ")
+            ),
+        );
+    }
+    if !report.is_safe() {
+        // Liveness figures are meaningless for an insane set
+        return report;
+    }
+
+    report.min_blocking_set = qset.min_blocking_set();
+    if report.min_blocking_set.len() == 1 && distinct.len() > 1 {
+        report.push(
+            Severity::Warning,
+            format!(
+                "Validator {} alone can halt this node (single point of failure)",
+                report.min_blocking_set[0]
+            ),
+        );
+    }
+
+    for org in &qset.inner_sets {
+        let failed: BTreeSet<String> = org.all_validators().into_iter().map(String::from).collect();
+        if !qset.is_satisfiable(&failed) {
+            report.push(
+                Severity::Warning,
+                format!("Losing organisation {} halts this node", org.name),
+            );
+        }
+    }
+
+    match check_intersection(qset, qset) {
+        Intersection::Holds => {}
+        Intersection::Disjoint(slice) => report.push(
+            Severity::Error,
+            format!(
+                "Quorum set admits disjoint slices (one avoids {}); the network could fork",
+                join(&slice)
+            ),
+        ),
+        Intersection::TooLarge => report.push(
+            Severity::Warning,
+            format!("Quorum intersection not checked: more than {MAX_SLICES} slices"),
+        ),
+    }
+    report
+}
+
+fn check_structure(
+    qset: &QuorumSetDef,
+    depth: usize,
+    unsafe_quorum: bool,
+    report: &mut QuorumReport,
+) {
+    if depth > MAX_NESTING_LEVEL {
+        report.push(
+            Severity::Error,
+            format!(
+                "{} is nested deeper than stellar-core allows ({MAX_NESTING_LEVEL} levels)",
+                qset.name
+            ),
+        );
+        return;
+    }
+    if qset.members() == 0 {
+        report.push(Severity::Error, format!("{} has no validators", qset.name));
+    }
+    let percent = qset.threshold_percent;
+    if !(1..=100).contains(&percent) {
+        report.push(
+            Severity::Error,
+            format!(
+                "{}.THRESHOLD_PERCENT must be between 1 and 100, got {percent}",
+                qset.name
+            ),
+        );
+    } else if percent <= 50 {
+        let message = format!(
+            "{}.THRESHOLD_PERCENT is {percent}%; thresholds of 50% or less allow disjoint quorums",
+            qset.name
+        );
+        if unsafe_quorum {
+            report.push(Severity::Warning, message);
+        } else {
+            report.push(
+                Severity::Error,
+                format!("{message} (set UNSAFE_QUORUM=true to override)"),
+            );
+        }
+    }
+    for inner in &qset.inner_sets {
+        check_structure(inner, depth + 1, unsafe_quorum, report);
+    }
+}
+
+/// Outcome of an intersection check between two quorum sets
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Intersection {
+    /// Every slice of one set intersects every slice of the other
+    Holds,
+    /// A slice of the first set the second set can avoid entirely
+    Disjoint(BTreeSet<String>),
+    /// Too many slices to check exhaustively
+    TooLarge,
+}
+
+/// Check whether `b` can form a slice disjoint from some slice of `a`
+pub fn check_intersection(a: &QuorumSetDef, b: &QuorumSetDef) -> Intersection {
+    let Some(slices) = a.slices(MAX_SLICES) else {
+        return Intersection::TooLarge;
+    };
+    match slices.into_iter().find(|slice| b.is_satisfiable(slice)) {
+        Some(slice) => Intersection::Disjoint(slice),
+        None => Intersection::Holds,
+    }
+}
+
+/// Analyse `local` and its intersection with the quorum sets of other
+/// validators in the cluster
+pub fn analyze_with_peers(local: &QuorumConfig, peers: &[(String, QuorumConfig)]) -> QuorumReport {
+    let mut report = analyze(local);
+    if !report.is_safe() {
+        return report;
+    }
+    for (name, peer) in peers {
+        match check_intersection(&local.quorum_set, &peer.quorum_set) {
+            Intersection::Holds => {}
+            Intersection::Disjoint(slice) => report.push(
+                Severity::Error,
+                format!(
+                    "Quorum sets of this node and {name} do not intersect: {name} can reach \
+                     quorum without {}",
+                    join(&slice)
+                ),
+            ),
+            Intersection::TooLarge => report.push(
+                Severity::Warning,
+                format!(
+                    "Quorum intersection with {name} not checked: more than {MAX_SLICES} slices"
+                ),
+            ),
+        }
+    }
+    report
+}
+
+fn join(validators: &BTreeSet<String>) -> String {
+    validators.iter().cloned().collect::<Vec<_>>().join(", ")
+}
+
+/// Set the `QuorumHealthy` condition from an analysis
+///
+/// `None` means the node has no explicit quorum set to analyse.
+pub fn apply_quorum_condition(conditions_list: &mut Vec<Condition>, report: Option<&QuorumReport>) {
+    let Some(report) = report else {
+        conditions::set_condition(
+            conditions_list,
+            CONDITION_TYPE_QUORUM_HEALTHY,
+            conditions::CONDITION_STATUS_UNKNOWN,
+            "NoExplicitQuorumSet",
+            "No [QUORUM_SET] to analyse",
+        );
+        return;
+    };
+    let errors: Vec<&str> = report.errors().collect();
+    let warnings: Vec<&str> = report.warnings().collect();
+    let (status, reason, message) = if !errors.is_empty() {
+        (
+            conditions::CONDITION_STATUS_FALSE,
+            "QuorumUnsafe",
+            errors.join("; "),
+        )
+    } else if !warnings.is_empty() {
+        (
+            conditions::CONDITION_STATUS_TRUE,
+            "QuorumAtRisk",
+            warnings.join("; "),
+        )
+    } else {
+        (
+            conditions::CONDITION_STATUS_TRUE,
+            "QuorumSafe",
+            format!(
+                "Quorum intersection holds; halting requires {} validator failures",
+                report.min_blocking_set.len()
+            ),
+        )
+    };
+    conditions::set_condition(
+        conditions_list,
+        CONDITION_TYPE_QUORUM_HEALTHY,
+        status,
+        reason,
+        &message,
+    );
+}
+
+/// Quorum sets of the cluster's validators, for the intersection check
+///
+/// Peers are read from the controller's StellarNode cache rather than listed
+/// on every reconcile. Validators using `vlSource` contribute the quorum set
+/// last rendered from their VSL, which the reconciler records here.
+#[derive(Default)]
+pub struct QuorumPeers {
+    nodes: OnceLock<Store<StellarNode>>,
+    /// `[QUORUM_SET]` TOML by `vlSource`
+    vsl_quorums: RwLock<HashMap<String, String>>,
+}
+
+impl QuorumPeers {
+    /// Use the controller's StellarNode cache; only the first call counts
+    pub fn set_store(&self, store: Store<StellarNode>) {
+        let _ = self.nodes.set(store);
+    }
+
+    /// Record the quorum set rendered from a `vlSource`
+    pub async fn record_vsl(&self, source: &str, quorum_toml: String) {
+        self.vsl_quorums
+            .write()
+            .await
+            .insert(source.to_string(), quorum_toml);
+    }
+
+    /// Quorum sets of the validators other than `node` in the cache
+    pub async fn peers_of(&self, node: &StellarNode) -> Vec<(String, QuorumConfig)> {
+        let Some(store) = self.nodes.get() else {
+            return Vec::new();
+        };
+        let nodes = store.state();
+        self.peers_among(node, nodes.iter().map(|n| n.as_ref()))
+            .await
+    }
+
+    /// Quorum sets of the validators in `nodes` other than `node`.
+    ///
+    /// Validators without an explicit quorum set, or whose VSL has not been
+    /// fetched yet, are left out.
+    pub async fn peers_among<'a>(
+        &self,
+        node: &StellarNode,
+        nodes: impl IntoIterator<Item = &'a StellarNode>,
+    ) -> Vec<(String, QuorumConfig)> {
+        let vsl_quorums = self.vsl_quorums.read().await;
+        nodes
+            .into_iter()
+            .filter(|peer| {
+                peer.spec.node_type == NodeType::Validator
+                    && !(peer.namespace() == node.namespace() && peer.name_any() == node.name_any())
+            })
+            .filter_map(|peer| {
+                let config = peer.spec.validator_config.as_ref()?;
+                let toml = match &config.vl_source {
+                    Some(source) => vsl_quorums.get(source)?,
+                    None => config.quorum_set.as_ref()?,
+                };
+                let config = parse_quorum_config(toml).ok()??;
+                Some((
+                    format!(
+                        "{}/{}",
+                        peer.namespace().unwrap_or_default(),
+                        peer.name_any()
+                    ),
+                    config,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Analyse a validator's effective quorum set against the other validators
+/// in the cluster and record the `QuorumHealthy` condition.
+///
+/// `effective_quorum` is the quorum configuration rendered into the node's
+/// stellar-core.cfg (VSL-derived or `spec.validatorConfig.quorumSet`), and
+/// `peers` the other validators' quorum sets (see [`QuorumPeers`]).
+pub async fn reconcile_quorum_condition(
+    client: &Client,
+    node: &StellarNode,
+    effective_quorum: Option<&str>,
+    peers: &[(String, QuorumConfig)],
+) -> Result<Option<QuorumReport>> {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let name = node.name_any();
+
+    let report = match effective_quorum.map(parse_quorum_config).transpose() {
+        Ok(Some(Some(local))) => Some(analyze_with_peers(&local, peers)),
+        Ok(_) => None,
+        Err(e) => {
+            let mut report = QuorumReport::default();
+            report.push(Severity::Error, e.to_string());
+            Some(report)
+        }
+    };
+
+    if let Some(report) = &report {
+        if !report.is_safe() {
+            warn!(
+                "Quorum set of {}/{} is unsafe: {}",
+                namespace,
+                name,
+                report.errors().collect::<Vec<_>>().join("; ")
+            );
+        }
+    }
+
+    // Re-read the node: earlier status updates in this reconcile rewrote the
+    // conditions list
+    let api: Api<StellarNode> = Api::namespaced(client.clone(), &namespace);
+    let mut conditions_list = api
+        .get_status(&name)
+        .await?
+        .status
+        .map(|s| s.conditions)
+        .unwrap_or_default();
+    apply_quorum_condition(&mut conditions_list, report.as_ref());
+    api.patch_status(
+        &name,
+        &PatchParams::apply("stellar-operator"),
+        &Patch::Merge(&serde_json::json!({ "status": { "conditions": conditions_list } })),
+    )
+    .await
+    .map_err(Error::KubeError)?;
+    Ok(report)
+}
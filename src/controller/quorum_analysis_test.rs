@@ -0,0 +1,351 @@
+//! Tests for the quorum set safety analysis.
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::conditions::{
+        find_condition, CONDITION_STATUS_FALSE, CONDITION_STATUS_TRUE, CONDITION_STATUS_UNKNOWN,
+    };
+    use crate::controller::quorum_analysis::*;
+    use crate::crd::StellarNode;
+
+    fn parse(toml: &str) -> QuorumConfig {
+        parse_quorum_config(toml).unwrap().unwrap()
+    }
+
+    /// Three organisations with three validators each, two of three needed
+    /// at every level
+    const THREE_ORGS: &str = r#"
+[QUORUM_SET]
+THRESHOLD_PERCENT = 66
+
+[QUORUM_SET.sdf]
+THRESHOLD_PERCENT = 66
+VALIDATORS = ["$sdf1", "$sdf2", "$sdf3"]
+
+[QUORUM_SET.lobstr]
+THRESHOLD_PERCENT = 66
+VALIDATORS = ["$lobstr1", "$lobstr2", "$lobstr3"]
+
+[QUORUM_SET.satoshipay]
+THRESHOLD_PERCENT = 66
+VALIDATORS = ["$satoshipay1", "$satoshipay2", "$satoshipay3"]
+"#;
+
+    // -----------------------------------------------------------------------
+    // Parsing
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_parse_nested_quorum_set() {
+        let config = parse(THREE_ORGS);
+        assert!(!config.unsafe_quorum);
+        assert_eq!(config.quorum_set.name, "QUORUM_SET");
+        assert_eq!(config.quorum_set.inner_sets.len(), 3);
+        let sdf = config
+            .quorum_set
+            .inner_sets
+            .iter()
+            .find(|s| s.name == "QUORUM_SET.sdf")
+            .unwrap();
+        assert_eq!(sdf.validators, vec!["sdf1", "sdf2", "sdf3"]);
+        assert_eq!(config.quorum_set.all_validators().len(), 9);
+    }
+
+    #[test]
+    fn test_parse_keys_with_names_and_unsafe_flag() {
+        let config = parse(
+            r#"
+UNSAFE_QUORUM = true
+[QUORUM_SET]
+VALIDATORS = ["GA core-1", "GB core-2"]
+"#,
+        );
+        assert!(config.unsafe_quorum);
+        assert_eq!(config.quorum_set.validators, vec!["GA", "GB"]);
+        assert_eq!(config.quorum_set.threshold_percent, 67);
+    }
+
+    #[test]
+    fn test_parse_without_quorum_set() {
+        assert!(parse_quorum_config("[[VALIDATORS]]\nNAME = \"a\"\n")
+            .unwrap()
+            .is_none());
+        let err = parse_quorum_config("[QUORUM_SET").unwrap_err();
+        assert!(err.to_string().contains("Invalid quorum set TOML"));
+    }
+
+    #[test]
+    fn test_threshold_rounding() {
+        let mut qset = parse("[QUORUM_SET]\nVALIDATORS = [\"a\", \"b\", \"c\"]\n").quorum_set;
+        // 67% of 3 rounds up to 3, as in stellar-core
+        assert_eq!(qset.threshold(), 3);
+        qset.threshold_percent = 66;
+        assert_eq!(qset.threshold(), 2);
+        qset.threshold_percent = 100;
+        assert_eq!(qset.threshold(), 3);
+    }
+
+    // -----------------------------------------------------------------------
+    // Single quorum set
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_healthy_three_orgs() {
+        let report = analyze(&parse(THREE_ORGS));
+        assert!(report.is_safe(), "{:?}", report.findings);
+        assert_eq!(report.warnings().count(), 0, "{:?}", report.findings);
+    }
+
+    #[test]
+    fn test_low_threshold_rejected_unless_unsafe() {
+        let toml =
+            "[QUORUM_SET]\nTHRESHOLD_PERCENT = 50\nVALIDATORS = [\"a\", \"b\", \"c\", \"d\"]\n";
+        let report = analyze(&parse(toml));
+        assert!(!report.is_safe());
+        assert!(report
+            .errors()
+            .any(|e| e.contains("THRESHOLD_PERCENT is 50%") && e.contains("UNSAFE_QUORUM")));
+
+        let report = analyze(&parse(&format!("UNSAFE_QUORUM = true\n{toml}")));
+        assert!(report.warnings().any(|w| w.contains("50%")));
+        // 2 of 4 admits the disjoint slices {a, b} and {c, d}
+        assert!(report.errors().any(|e| e.contains("disjoint slices")));
+    }
+
+    #[test]
+    fn test_structural_errors() {
+        let report = analyze(&parse("[QUORUM_SET]\nVALIDATORS = []\n"));
+        assert!(report.errors().any(|e| e.contains("has no validators")));
+
+        let report = analyze(&parse(
+            "[QUORUM_SET]\nTHRESHOLD_PERCENT = 120\nVALIDATORS = [\"a\"]\n",
+        ));
+        assert!(report.errors().any(|e| e.contains("between 1 and 100")));
+
+        let report = analyze(&parse(
+            "[QUORUM_SET]\nVALIDATORS = [\"a\", \"b\"]\n[QUORUM_SET.org]\nVALIDATORS = [\"b\", \"c\"]\n",
+        ));
+        assert!(report
+            .errors()
+            .any(|e| e.contains("more than once") && e.contains('b')));
+
+        let report = analyze(&parse(
+            r#"
+[QUORUM_SET]
+VALIDATORS = ["a"]
+[QUORUM_SET.l2]
+VALIDATORS = ["b"]
+[QUORUM_SET.l2.l3]
+VALIDATORS = ["c"]
+[QUORUM_SET.l2.l3.l4]
+VALIDATORS = ["d"]
+[QUORUM_SET.l2.l3.l4.l5]
+VALIDATORS = ["e"]
+"#,
+        ));
+        assert!(report.errors().any(|e| e.contains("nested deeper")));
+    }
+
+    #[test]
+    fn test_min_blocking_set_nested() {
+        // Halting needs two orgs down, i.e. two validators in each
+        let report = analyze(&parse(THREE_ORGS));
+        assert_eq!(report.min_blocking_set.len(), 4);
+
+        let qset = parse(THREE_ORGS).quorum_set;
+        assert!(!qset.is_satisfiable(&report.min_blocking_set.into_iter().collect()));
+    }
+
+    #[test]
+    fn test_single_point_of_failure_warns() {
+        let report = analyze(&parse(
+            "[QUORUM_SET]\nTHRESHOLD_PERCENT = 100\nVALIDATORS = [\"a\", \"b\"]\n",
+        ));
+        assert!(report.is_safe());
+        assert!(report
+            .warnings()
+            .any(|w| w.contains("alone can halt") && w.contains("single point of failure")));
+    }
+
+    #[test]
+    fn test_organisation_failure_warns() {
+        // Every organisation is required
+        let report = analyze(&parse(&THREE_ORGS.replacen("66", "100", 1)));
+        assert!(report.is_safe());
+        assert!(report
+            .warnings()
+            .any(|w| w.contains("Losing organisation QUORUM_SET.sdf")));
+    }
+
+    // -----------------------------------------------------------------------
+    // Intersection with peers
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_disjoint_peers_detected() {
+        let local = parse("[QUORUM_SET]\nVALIDATORS = [\"a\", \"b\", \"c\"]\n");
+        let overlapping = parse("[QUORUM_SET]\nVALIDATORS = [\"a\", \"b\", \"c\", \"d\"]\n");
+        let disjoint = parse("[QUORUM_SET]\nVALIDATORS = [\"x\", \"y\", \"z\"]\n");
+
+        let report = analyze_with_peers(
+            &local,
+            &[("stellar/validator-2".to_string(), overlapping.clone())],
+        );
+        assert!(report.is_safe(), "{:?}", report.findings);
+
+        let report = analyze_with_peers(
+            &local,
+            &[
+                ("stellar/validator-2".to_string(), overlapping),
+                ("other/validator-9".to_string(), disjoint),
+            ],
+        );
+        let errors: Vec<&str> = report.errors().collect();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("other/validator-9") && errors[0].contains("do not intersect"));
+    }
+
+    #[test]
+    fn test_large_flat_set_is_not_enumerated() {
+        let validators: Vec<String> = (0..40).map(|i| format!("\"v{i}\"")).collect();
+        let config = parse(&format!(
+            "[QUORUM_SET]\nVALIDATORS = [{}]\n",
+            validators.join(", ")
+        ));
+
+        let started = std::time::Instant::now();
+        assert_eq!(
+            check_intersection(&config.quorum_set, &config.quorum_set),
+            Intersection::TooLarge
+        );
+        let report = analyze_with_peers(
+            &config,
+            &[("stellar/validator-2".to_string(), config.clone())],
+        );
+        assert!(report.is_safe(), "{:?}", report.findings);
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+
+        // Small sets are still checked exhaustively
+        let small = parse("[QUORUM_SET]\nVALIDATORS = [\"a\", \"b\", \"c\", \"d\", \"e\"]\n");
+        assert_eq!(
+            check_intersection(&small.quorum_set, &small.quorum_set),
+            Intersection::Holds
+        );
+    }
+
+    #[test]
+    fn test_aliases_resolve_to_keys() {
+        let local = parse(
+            r#"
+NODE_NAMES = ["GC core-3"]
+
+[[VALIDATORS]]
+NAME = "core-2"
+PUBLIC_KEY = "GB"
+
+[QUORUM_SET]
+VALIDATORS = ["GA core-1", "$core-2", "@core-3"]
+"#,
+        );
+        assert_eq!(local.quorum_set.validators, vec!["GA", "GB", "GC"]);
+
+        // A peer naming the same validators by key intersects with it
+        let peer = parse("[QUORUM_SET]\nVALIDATORS = [\"GA\", \"GB\", \"GC\"]\n");
+        let report = analyze_with_peers(&local, &[("stellar/validator-2".to_string(), peer)]);
+        assert!(report.is_safe(), "{:?}", report.findings);
+
+        // Listing a validator by key and by alias is a duplicate
+        let config = parse("[QUORUM_SET]\nVALIDATORS = [\"GA core-1\", \"$core-1\", \"GB\"]\n");
+        assert!(!analyze(&config).is_safe());
+    }
+
+    fn validator(name: &str, config: serde_json::Value) -> StellarNode {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "stellar.org/v1alpha1",
+            "kind": "StellarNode",
+            "metadata": { "name": name, "namespace": "stellar" },
+            "spec": {
+                "nodeType": "Validator",
+                "network": "Testnet",
+                "version": "v21.0.0",
+                "validatorConfig": config,
+            },
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_peers_include_vsl_validators() {
+        let local = validator(
+            "validator-1",
+            serde_json::json!({ "seedSecretRef": "seed", "quorumSet": "[QUORUM_SET]\nVALIDATORS = [\"a\"]\n" }),
+        );
+        let explicit = validator(
+            "validator-2",
+            serde_json::json!({ "seedSecretRef": "seed", "quorumSet": "[QUORUM_SET]\nVALIDATORS = [\"b\"]\n" }),
+        );
+        let from_vsl = validator(
+            "validator-3",
+            serde_json::json!({ "seedSecretRef": "seed", "vlSource": "https://vsl.example.org" }),
+        );
+        let nodes = [local.clone(), explicit, from_vsl];
+
+        let peers = QuorumPeers::default();
+        let names = |peers: Vec<(String, QuorumConfig)>| {
+            peers.into_iter().map(|(name, _)| name).collect::<Vec<_>>()
+        };
+        // The VSL has not been fetched yet
+        assert_eq!(
+            names(peers.peers_among(&local, &nodes).await),
+            vec!["stellar/validator-2"]
+        );
+
+        peers
+            .record_vsl(
+                "https://vsl.example.org",
+                "[QUORUM_SET]\nVALIDATORS = [\"c\"]\n".to_string(),
+            )
+            .await;
+        let found = peers.peers_among(&local, &nodes).await;
+        assert_eq!(found[1].0, "stellar/validator-3");
+        assert_eq!(found[1].1.quorum_set.validators, vec!["c"]);
+
+        // Without the controller's cache there are no peers
+        assert!(peers.peers_of(&local).await.is_empty());
+    }
+
+    // -----------------------------------------------------------------------
+    // Condition
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_apply_quorum_condition() {
+        let mut conditions = Vec::new();
+
+        apply_quorum_condition(&mut conditions, None);
+        let c = find_condition(&conditions, CONDITION_TYPE_QUORUM_HEALTHY).unwrap();
+        assert_eq!(c.status, CONDITION_STATUS_UNKNOWN);
+
+        let safe = analyze(&parse(THREE_ORGS));
+        apply_quorum_condition(&mut conditions, Some(&safe));
+        let c = find_condition(&conditions, CONDITION_TYPE_QUORUM_HEALTHY).unwrap();
+        assert_eq!(c.status, CONDITION_STATUS_TRUE);
+        assert_eq!(c.reason, "QuorumSafe");
+        assert!(c.message.contains("4 validator failures"));
+
+        let at_risk = analyze(&parse(
+            "[QUORUM_SET]\nTHRESHOLD_PERCENT = 100\nVALIDATORS = [\"a\", \"b\"]\n",
+        ));
+        apply_quorum_condition(&mut conditions, Some(&at_risk));
+        let c = find_condition(&conditions, CONDITION_TYPE_QUORUM_HEALTHY).unwrap();
+        assert_eq!(c.status, CONDITION_STATUS_TRUE);
+        assert_eq!(c.reason, "QuorumAtRisk");
+
+        let unsafe_report = analyze(&parse("[QUORUM_SET]\nVALIDATORS = []\n"));
+        apply_quorum_condition(&mut conditions, Some(&unsafe_report));
+        let c = find_condition(&conditions, CONDITION_TYPE_QUORUM_HEALTHY).unwrap();
+        assert_eq!(c.status, CONDITION_STATUS_FALSE);
+        assert_eq!(c.reason, "QuorumUnsafe");
+        assert_eq!(conditions.len(), 1);
+    }
+}
@@ -39,6 +39,7 @@ use kube::{
 };
 use tracing::{debug, error, info, instrument, warn};
 
+use crate::carbon_aware::{BatchJobKind, CarbonWindowService, CarbonWindowVerdict};
 use crate::crd::{
     DisasterRecoveryStatus, NodeType, RolloutStrategy, SpecValidationError, StellarNode,
     StellarNodeStatus,
@@ -49,21 +50,26 @@ use super::archive_health::{
     calculate_backoff, check_archive_integrity, check_history_archive_health, ArchiveHealthResult,
     ARCHIVE_LAG_THRESHOLD,
 };
+use super::cert_manager;
 use super::conditions;
 use super::cve_reconciler;
+use super::decentralized_backup;
 use super::dr;
 use super::finalizers::STELLAR_NODE_FINALIZER;
 use super::health;
 use super::kms_secret;
+use super::maintenance::{MaintenanceController, MaintenanceRuns};
 #[cfg(feature = "metrics")]
 use super::metrics;
 use super::migration;
 use super::mtls;
 use super::oci_snapshot;
 use super::peer_discovery;
+use super::quorum_analysis;
 use super::remediation;
 use super::resources;
 use super::service_mesh;
+use super::validator_identity;
 use super::vpa as vpa_controller;
 use super::vsl;
 
@@ -80,9 +86,20 @@ pub struct ControllerState {
     pub client: Client,
     pub enable_mtls: bool,
     pub operator_namespace: String,
-    pub mtls_config: Option<crate::MtlsConfig>,
+    /// Client certificate for node health checks, kept current by the rotation task
+    pub mtls_config: crate::SharedMtlsConfig,
+    /// Delegate mTLS certificates to this cert-manager issuer instead of the built-in CA
+    pub cert_manager_issuer: Option<cert_manager::IssuerRef>,
     pub dry_run: bool,
     pub is_leader: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    /// Image running the decentralized backup worker (`stellar-operator backup`)
+    pub backup_image: String,
+    /// Defers snapshots, backups and database maintenance into low-carbon windows
+    pub carbon_window: Option<Arc<CarbonWindowService>>,
+    /// Other validators' quorum sets for the quorum intersection check
+    pub quorum_peers: quorum_analysis::QuorumPeers,
+    /// Database maintenance runs in progress
+    pub maintenance_runs: Arc<MaintenanceRuns>,
 }
 
 /// Main entry point to start the controller
@@ -116,10 +133,15 @@ pub struct ControllerState {
 ///     let state = Arc::new(ControllerState {
 ///         client,
 ///         enable_mtls: false,
-///         mtls_config: None,
+///         mtls_config: Default::default(),
+///         cert_manager_issuer: None,
 ///         operator_namespace: "stellar-operator".to_string(),
 ///         dry_run: false,
 ///         is_leader: Arc::new(AtomicBool::new(true)),
+///         backup_image: "ghcr.io/stellar/stellar-k8s:latest".to_string(),
+///         carbon_window: None,
+///         quorum_peers: Default::default(),
+///         maintenance_runs: Default::default(),
 ///     });
 ///     run_controller(state).await?;
 ///     Ok(())
@@ -145,7 +167,10 @@ pub async fn run_controller(state: Arc<ControllerState>) -> Result<()> {
         }
     }
 
-    Controller::new(stellar_nodes, Config::default())
+    let controller = Controller::new(stellar_nodes, Config::default());
+    state.quorum_peers.set_store(controller.store());
+
+    controller
         // Watch owned resources for changes
         .owns::<Deployment>(Api::all(client.clone()), Config::default())
         .owns::<StatefulSet>(Api::all(client.clone()), Config::default())
@@ -199,6 +224,85 @@ async fn emit_event(
     Ok(())
 }
 
+/// Consult the carbon window before starting deferrable batch work.
+///
+/// Returns `false` while the work is deferred. Emits `CarbonDeferred` when a
+/// deferral is made or moved, and `CarbonWindowReached` when deferred work
+/// starts, with the estimated CO2 saved.
+async fn carbon_window_allows(
+    ctx: &ControllerState,
+    node: &StellarNode,
+    kind: BatchJobKind,
+    due_at: chrono::DateTime<chrono::Utc>,
+    latest: Option<chrono::DateTime<chrono::Utc>>,
+) -> bool {
+    let Some(carbon_window) = &ctx.carbon_window else {
+        return true;
+    };
+
+    match carbon_window
+        .evaluate(node, kind, due_at, latest, chrono::Utc::now())
+        .await
+    {
+        CarbonWindowVerdict::Run => true,
+        CarbonWindowVerdict::RunAfterDeferral(decision) => {
+            emit_event(
+                &ctx.client,
+                node,
+                "Normal",
+                "CarbonWindowReached",
+                &format!(
+                    "Starting {kind} due at {}: {:.0} gCO2/kWh instead of {:.0}, an estimated {:.0} g CO2 saved",
+                    decision.due_at.to_rfc3339(),
+                    decision.window_intensity,
+                    decision.baseline_intensity,
+                    decision.estimated_co2_saved_grams
+                ),
+            )
+            .await
+            .ok();
+            true
+        }
+        CarbonWindowVerdict::Defer { decision, changed } => {
+            if changed {
+                emit_event(
+                    &ctx.client,
+                    node,
+                    "Normal",
+                    "CarbonDeferred",
+                    &format!(
+                        "Deferring {kind} to {} (deadline {}): forecast {:.0} gCO2/kWh vs {:.0} now, an estimated {:.0} g CO2 saved",
+                        decision.run_at.to_rfc3339(),
+                        decision.deadline.to_rfc3339(),
+                        decision.window_intensity,
+                        decision.baseline_intensity,
+                        decision.estimated_co2_saved_grams
+                    ),
+                )
+                .await
+                .ok();
+            }
+            false
+        }
+    }
+}
+
+/// Start the operator-triggered backup Job once its schedule has fired and
+/// the carbon window allows
+async fn trigger_due_backup(
+    ctx: &ControllerState,
+    node: &StellarNode,
+    backup: &crate::backup::DecentralizedBackupConfig,
+) -> Result<()> {
+    let Some(due_at) = decentralized_backup::backup_due_at(&ctx.client, node, backup).await? else {
+        return Ok(());
+    };
+    if carbon_window_allows(ctx, node, BatchJobKind::HistoryArchiveBackup, due_at, None).await {
+        decentralized_backup::trigger_backup_job(&ctx.client, node, due_at).await?;
+    }
+    Ok(())
+}
+
 /// Format structured spec validation errors into a user-friendly message
 fn format_spec_validation_errors(errors: &[SpecValidationError]) -> String {
     let mut msg = String::from("Spec validation failed with the following issues:\n");
@@ -322,6 +426,71 @@ async fn reconcile(obj: Arc<StellarNode>, ctx: Arc<ControllerState>) -> Result<A
     res
 }
 
+/// The `[QUORUM_SET]` fragment to render for a validator with a `vlSource`.
+///
+/// A VSL that fails the quorum safety analysis is not rendered, as in the
+/// refresher: the node keeps the quorum set its ConfigMap already has.
+/// `None` renders the spec's quorum set.
+async fn resolve_vsl_quorum(
+    client: &Client,
+    ctx: &ControllerState,
+    node: &StellarNode,
+) -> Result<Option<String>> {
+    let Some(vl_source) = node
+        .spec
+        .validator_config
+        .as_ref()
+        .and_then(|c| c.vl_source.as_ref())
+        .filter(|_| node.spec.node_type == NodeType::Validator)
+    else {
+        return Ok(None);
+    };
+
+    match vsl::fetch_trusted_vsl(client, &ctx.operator_namespace, vl_source).await {
+        Ok(Ok(quorum)) => {
+            let rendered = quorum.to_stellar_core_toml();
+            ctx.quorum_peers
+                .record_vsl(vl_source, rendered.clone())
+                .await;
+            Ok(Some(rendered))
+        }
+        Ok(Err(reason)) => {
+            warn!(
+                "Keeping the rendered quorum set of {}/{}: {}",
+                node.namespace().unwrap_or_default(),
+                node.name_any(),
+                reason
+            );
+            emit_event(
+                client,
+                node,
+                "Warning",
+                "VSLQuorumUnsafe",
+                &format!("Keeping the rendered quorum set: {reason}"),
+            )
+            .await?;
+            resources::rendered_quorum(client, node).await
+        }
+        Err(e) => {
+            warn!(
+                "Failed to fetch VSL for {}/{}: {}",
+                node.namespace().unwrap_or_default(),
+                node.name_any(),
+                e
+            );
+            emit_event(
+                client,
+                node,
+                "Warning",
+                "VSLFetchFailed",
+                &format!("Failed to fetch VSL from {vl_source}: {e}"),
+            )
+            .await?;
+            Ok(None)
+        }
+    }
+}
+
 /// Apply/create/update the StellarNode resources
 #[instrument(skip(client, node, ctx), fields(name = %node.name_any(), namespace = node.namespace()))]
 pub(crate) async fn apply_stellar_node(
@@ -349,10 +518,20 @@ pub(crate) async fn apply_stellar_node(
         return Ok(Action::requeue(Duration::from_secs(30)));
     }
 
+    // Quorum set of validators with a vlSource, rendered by every ConfigMap
+    // update below
+    let quorum_override = resolve_vsl_quorum(client, ctx, node).await?;
+
     // 1. Core infrastructure (PVC and ConfigMap) always managed by operator
     apply_or_emit(ctx, node, ActionType::Update, "PVC and ConfigMap", async {
         resources::ensure_pvc(client, node).await?;
-        resources::ensure_config_map(client, node, None, ctx.enable_mtls).await?;
+        resources::ensure_config_map_with_quorum(
+            client,
+            node,
+            quorum_override.clone(),
+            ctx.enable_mtls,
+        )
+        .await?;
         Ok(())
     })
     .await?;
@@ -374,7 +553,13 @@ pub(crate) async fn apply_stellar_node(
             "Suspended state resources",
             async {
                 resources::ensure_pvc(client, node).await?;
-                resources::ensure_config_map(client, node, None, ctx.enable_mtls).await?;
+                resources::ensure_config_map_with_quorum(
+                    client,
+                    node,
+                    quorum_override.clone(),
+                    ctx.enable_mtls,
+                )
+                .await?;
 
                 match node.spec.node_type {
                     NodeType::Validator => {
@@ -621,35 +806,23 @@ pub(crate) async fn apply_stellar_node(
     .await?;
     info!("PVC ensured for {}/{}", namespace, name);
 
-    // 2. Handle VSL Fetching for Validators
-    let mut quorum_override: Option<crate::controller::vsl::QuorumSet> = None;
-    if node.spec.node_type == NodeType::Validator {
-        if let Some(config) = &node.spec.validator_config {
-            if let Some(vl_source) = &config.vl_source {
-                match vsl::fetch_vsl(vl_source).await {
-                    Ok(quorum) => {
-                        quorum_override = Some(quorum);
-                    }
-                    Err(e) => {
-                        warn!("Failed to fetch VSL for {}/{}: {}", namespace, name, e);
-                        emit_event(
-                            client,
-                            node,
-                            "Warning",
-                            "VSLFetchFailed",
-                            &format!("Failed to fetch VSL from {vl_source}: {e}"),
-                        )
-                        .await?;
-                    }
-                }
-            }
-        }
-    }
+    // The quorum set rendered into stellar-core.cfg, for the safety analysis
+    let effective_quorum = quorum_override.clone().or_else(|| {
+        node.spec
+            .validator_config
+            .as_ref()
+            .and_then(|c| c.quorum_set.clone())
+    });
 
     // 3. Create/update the ConfigMap for node configuration
     apply_or_emit(ctx, node, ActionType::Update, "ConfigMap", async {
-        resources::ensure_config_map(client, node, quorum_override.clone(), ctx.enable_mtls)
-            .await?;
+        resources::ensure_config_map_with_quorum(
+            client,
+            node,
+            quorum_override.clone(),
+            ctx.enable_mtls,
+        )
+        .await?;
         Ok(())
     })
     .await?;
@@ -681,8 +854,13 @@ pub(crate) async fn apply_stellar_node(
 
     // 4. Ensure mTLS certificates
     apply_or_emit(ctx, node, ActionType::Update, "mTLS certificates", async {
-        mtls::ensure_ca(client, &namespace).await?;
-        mtls::ensure_node_cert(client, node).await?;
+        match &ctx.cert_manager_issuer {
+            Some(issuer) => cert_manager::ensure_node_certificate(client, node, issuer).await?,
+            None => {
+                mtls::ensure_ca(client, &namespace).await?;
+                mtls::ensure_node_cert(client, node).await?;
+            }
+        }
         Ok(())
     })
     .await?;
@@ -726,6 +904,15 @@ pub(crate) async fn apply_stellar_node(
                         seed_injection.as_ref(),
                     )
                     .await?;
+
+                    // Publish the validator's public key so quorum sets can be
+                    // mapped back to StellarNodes
+                    if let Err(e) = validator_identity::reconcile_public_key(client, node).await {
+                        warn!(
+                            "Failed to derive validator public key for {}/{}: {}",
+                            namespace, name, e
+                        );
+                    }
                 }
                 NodeType::Horizon | NodeType::SorobanRpc => {
                     // Handle Canary Deployment
@@ -885,9 +1072,9 @@ pub(crate) async fn apply_stellar_node(
         ActionType::Update,
         "MetalLB configuration",
         async {
-            // TODO: Load balancer and global discovery fields not yet implemented in StellarNodeSpec
-            // resources::ensure_metallb_config(client, node).await?;
-            // resources::ensure_load_balancer_service(client, node).await?;
+            resources::ensure_metallb_config(client, node).await?;
+            resources::ensure_load_balancer_service(client, node, ctx.enable_mtls).await?;
+            resources::update_load_balancer_status(client, node).await?;
             Ok(())
         },
     )
@@ -941,7 +1128,18 @@ pub(crate) async fn apply_stellar_node(
     // 6a. CSI VolumeSnapshot schedule (Validator only)
     if node.spec.node_type == NodeType::Validator {
         if let Some(ref snapshot_config) = node.spec.snapshot_schedule {
-            if let Err(e) = super::snapshot::reconcile_snapshot(client, node, snapshot_config).await
+            // Requested snapshots are taken right away; scheduled ones may wait
+            // for a low-carbon window
+            let deferred = match super::snapshot::scheduled_snapshot_due(snapshot_config, node) {
+                Some(due_at) if !super::snapshot::snapshot_requested(node) => {
+                    !carbon_window_allows(ctx, node, BatchJobKind::CsiSnapshot, due_at, None).await
+                }
+                _ => false,
+            };
+            if deferred {
+                debug!("Snapshot of {}/{} deferred for carbon", namespace, name);
+            } else if let Err(e) =
+                super::snapshot::reconcile_snapshot(client, node, snapshot_config).await
             {
                 warn!(
                     "Snapshot reconciliation failed for {}/{}: {}",
@@ -951,6 +1149,149 @@ pub(crate) async fn apply_stellar_node(
         }
     }
 
+    // 6b. Decentralized history archive backups (Validator only)
+    match &node.spec.decentralized_backup {
+        Some(backup) => {
+            let operator_triggered = ctx
+                .carbon_window
+                .as_ref()
+                .is_some_and(|w| w.defers(BatchJobKind::HistoryArchiveBackup));
+            let result = apply_or_emit(
+                ctx,
+                node,
+                ActionType::Update,
+                "Decentralized backup CronJob",
+                decentralized_backup::ensure_backup(
+                    client,
+                    node,
+                    backup,
+                    &ctx.backup_image,
+                    operator_triggered,
+                ),
+            )
+            .await;
+            if result.is_ok()
+                && operator_triggered
+                && backup.enabled
+                && !node.spec.suspended
+                && !ctx.dry_run
+            {
+                if let Err(e) = trigger_due_backup(ctx, node, backup).await {
+                    warn!(
+                        "Failed to start backup Job for {}/{}: {}",
+                        namespace, name, e
+                    );
+                }
+            }
+            if let Err(e) = result {
+                warn!(
+                    "Decentralized backup reconciliation failed for {}/{}: {}",
+                    namespace, name, e
+                );
+                emit_event(
+                    client,
+                    node,
+                    "Warning",
+                    "DecentralizedBackupFailed",
+                    &format!("Could not apply backup CronJob: {e}"),
+                )
+                .await
+                .ok();
+            }
+        }
+        None if !ctx.dry_run => {
+            if let Err(e) = decentralized_backup::delete_backup(client, node).await {
+                warn!(
+                    "Failed to delete decentralized backup for {}/{}: {}",
+                    namespace, name, e
+                );
+            }
+        }
+        None => {}
+    }
+
+    // 6c. Database maintenance window (Horizon only). A due window may be
+    // deferred for a cleaner grid, at most to its midpoint. The run itself
+    // can take hours, so it goes to a background task and this reconcile
+    // only watches the MaintenanceInProgress condition.
+    let now = chrono::Utc::now();
+    let maintenance_allowed = !ctx.maintenance_runs.is_running(node)
+        && MaintenanceController::needs_run(node, now)
+        && match MaintenanceController::due_window(node, now) {
+            Some(window)
+                if !node.status.as_ref().is_some_and(|s| {
+                    conditions::is_condition_true(
+                        &s.conditions,
+                        conditions::CONDITION_TYPE_MAINTENANCE_IN_PROGRESS,
+                    )
+                }) =>
+            {
+                let midpoint = window.start + (window.end - window.start) / 2;
+                carbon_window_allows(
+                    ctx,
+                    node,
+                    BatchJobKind::DbMaintenance,
+                    window.start,
+                    Some(midpoint),
+                )
+                .await
+            }
+            _ => true,
+        };
+    if !ctx.dry_run && maintenance_allowed {
+        if let Some(claim) = ctx.maintenance_runs.claim(node) {
+            let client = client.clone();
+            let node = node.clone();
+            let controller = MaintenanceController::new(client.clone())
+                .with_leadership(Arc::clone(&ctx.is_leader));
+            tokio::spawn(async move {
+                let _claim = claim;
+                match controller.reconcile(&node, chrono::Utc::now()).await {
+                    Ok(Some(status)) if status.last_error.is_some() => {
+                        emit_event(
+                            &client,
+                            &node,
+                            "Warning",
+                            "DatabaseMaintenanceFailed",
+                            status.last_error.as_deref().unwrap_or_default(),
+                        )
+                        .await
+                        .ok();
+                    }
+                    Ok(Some(status)) if !status.tables.is_empty() => {
+                        emit_event(
+                            &client,
+                            &node,
+                            "Normal",
+                            "DatabaseMaintenanceCompleted",
+                            &format!("Maintained {} tables", status.tables.len()),
+                        )
+                        .await
+                        .ok();
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!(
+                            "Database maintenance failed for {}/{}: {}",
+                            node.namespace().unwrap_or_default(),
+                            node.name_any(),
+                            e
+                        );
+                        emit_event(
+                            &client,
+                            &node,
+                            "Warning",
+                            "DatabaseMaintenanceFailed",
+                            &e.to_string(),
+                        )
+                        .await
+                        .ok();
+                    }
+                }
+            });
+        }
+    }
+
     // 7. Perform health check to determine if node is ready
     //
     // Measure reduction in API polling overhead: Reactive Status check
@@ -977,7 +1318,7 @@ pub(crate) async fn apply_stellar_node(
     let health_result = if skipped_poll {
         recent_health.unwrap()
     } else {
-        health::check_node_health(client, node, ctx.mtls_config.as_ref()).await?
+        health::check_node_health(client, node, ctx.mtls_config.load().as_deref()).await?
     };
 
     debug!(
@@ -1030,7 +1371,14 @@ pub(crate) async fn apply_stellar_node(
     }
 
     // 8. Disaster Recovery reconciliation
-    if let Some(dr_status) = dr::reconcile_dr(client, node).await? {
+    let latest_dr_status = dr::reconcile_dr(
+        client,
+        node,
+        &health_result,
+        ctx.mtls_config.load().as_deref(),
+    )
+    .await?;
+    if let Some(dr_status) = latest_dr_status.clone() {
         apply_or_emit(ctx, node, ActionType::Update, "Status (DR)", async {
             update_dr_status(client, node, dr_status).await?;
             Ok(())
@@ -1099,7 +1447,15 @@ pub(crate) async fn apply_stellar_node(
     };
 
     apply_or_emit(ctx, node, ActionType::Update, "Status (Final)", async {
-        update_status_with_health(client, node, phase, Some(&message), &health_result).await?;
+        update_status_with_health(
+            client,
+            node,
+            phase,
+            Some(&message),
+            &health_result,
+            latest_dr_status.as_ref(),
+        )
+        .await?;
 
         let ready_replicas = get_ready_replicas(client, node).await.unwrap_or(0);
         update_status(client, node, phase, Some(&message), ready_replicas, true).await?;
@@ -1107,6 +1463,21 @@ pub(crate) async fn apply_stellar_node(
     })
     .await?;
 
+    if node.spec.node_type == NodeType::Validator {
+        let peers = ctx.quorum_peers.peers_of(node).await;
+        apply_or_emit(ctx, node, ActionType::Update, "Status (Quorum)", async {
+            quorum_analysis::reconcile_quorum_condition(
+                client,
+                node,
+                effective_quorum.as_deref(),
+                &peers,
+            )
+            .await?;
+            Ok(())
+        })
+        .await?;
+    }
+
     // 9. Update status with ready replica count
     let phase = if node.spec.suspended {
         "Suspended"
@@ -1149,6 +1520,15 @@ pub(crate) async fn apply_stellar_node(
         }
     }
 
+    // 10a. Export decentralized backup status written by the backup worker
+    #[cfg(feature = "metrics")]
+    if let (Some(backup), Some(status)) = (
+        &node.spec.decentralized_backup,
+        node.status.as_ref().and_then(|s| s.backup.as_ref()),
+    ) {
+        metrics::set_backup_status(&namespace, &name, backup.provider.kind(), status);
+    }
+
     // 11. OCI snapshot push/pull Jobs
     if let Some(oci_cfg) = &node.spec.oci_snapshot {
         if oci_cfg.enabled {
@@ -1159,7 +1539,19 @@ pub(crate) async fn apply_stellar_node(
                 .unwrap_or(0);
 
             // Push: trigger when node is healthy, synced, and we have a ledger number.
-            if oci_cfg.push && health_result.healthy && health_result.synced && ledger_seq > 0 {
+            if oci_cfg.push
+                && health_result.healthy
+                && health_result.synced
+                && ledger_seq > 0
+                && carbon_window_allows(
+                    ctx,
+                    node,
+                    BatchJobKind::OciSnapshotPush,
+                    chrono::Utc::now(),
+                    None,
+                )
+                .await
+            {
                 if let Err(e) =
                     oci_snapshot::ensure_snapshot_push_job(client, node, oci_cfg, ledger_seq).await
                 {
@@ -1222,11 +1614,12 @@ pub(crate) async fn apply_stellar_node(
     }
 
     // 13. Update status to Running with ready replica count
-    Ok(Action::requeue(Duration::from_secs(if phase == "Ready" {
-        60
-    } else {
-        15
-    })))
+    let mut requeue_secs = if phase == "Ready" { 60 } else { 15 };
+    if let Some(dr_config) = node.spec.dr_config.as_ref().filter(|c| c.enabled) {
+        // DR peer probe rounds are due every health_check_interval
+        requeue_secs = requeue_secs.min(u64::from(dr_config.health_check_interval.max(1)));
+    }
+    Ok(Action::requeue(Duration::from_secs(requeue_secs)))
 }
 
 /// Clean up resources when the StellarNode is deleted
@@ -1270,6 +1663,21 @@ pub(crate) async fn cleanup_stellar_node(
     })
     .await?;
 
+    // 0c. Delete decentralized backup CronJob and RBAC
+    apply_or_emit(
+        ctx,
+        node,
+        ActionType::Delete,
+        "Decentralized backup",
+        async {
+            if let Err(e) = decentralized_backup::delete_backup(client, node).await {
+                warn!("Failed to delete decentralized backup: {:?}", e);
+            }
+            Ok(())
+        },
+    )
+    .await?;
+
     // 1. Delete HPA (if autoscaling was configured)
     apply_or_emit(ctx, node, ActionType::Delete, "HPA", async {
         if let Err(e) = resources::delete_hpa(client, node).await {
@@ -1974,6 +2382,7 @@ async fn update_status_with_health(
     _phase: &str,
     message: Option<&str>,
     health: &health::HealthCheckResult,
+    dr_status: Option<&DisasterRecoveryStatus>,
 ) -> Result<()> {
     let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
     let api: Api<StellarNode> = Api::namespaced(client.clone(), &namespace);
@@ -1985,6 +2394,10 @@ async fn update_status_with_health(
         .map(|s| s.conditions.clone())
         .unwrap_or_default();
 
+    if let Some(dr_status) = dr_status {
+        dr::apply_divergence_condition(&mut conditions, dr_status);
+    }
+
     // Ready condition based on health status
     if health.synced {
         conditions::set_condition(
@@ -2156,9 +2569,17 @@ async fn update_dr_status(
     let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
     let api: Api<StellarNode> = Api::namespaced(client.clone(), &namespace);
 
+    let mut conditions = node
+        .status
+        .as_ref()
+        .map(|s| s.conditions.clone())
+        .unwrap_or_default();
+    dr::apply_divergence_condition(&mut conditions, &dr_status);
+
     let patch = serde_json::json!({
         "status": {
-            "drStatus": dr_status
+            "drStatus": dr_status,
+            "conditions": conditions
         }
     });
 
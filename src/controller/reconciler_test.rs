@@ -99,6 +99,7 @@ VALIDATORS=["VALIDATOR1", "VALIDATOR2"]"#
                 read_replica_config: None,
                 db_maintenance_config: None,
                 oci_snapshot: None,
+                decentralized_backup: None,
                 service_mesh: None,
                 read_pool_endpoint: None,
                 resource_meta: None,
@@ -184,6 +185,7 @@ VALIDATORS=["VALIDATOR1", "VALIDATOR2"]"#
                 read_replica_config: None,
                 db_maintenance_config: None,
                 oci_snapshot: None,
+                decentralized_backup: None,
                 service_mesh: None,
                 read_pool_endpoint: None,
                 resource_meta: None,
@@ -268,6 +270,7 @@ VALIDATORS=["VALIDATOR1", "VALIDATOR2"]"#
                 read_replica_config: None,
                 db_maintenance_config: None,
                 oci_snapshot: None,
+                decentralized_backup: None,
                 service_mesh: None,
                 read_pool_endpoint: None,
                 resource_meta: None,
@@ -297,9 +300,14 @@ VALIDATORS=["VALIDATOR1", "VALIDATOR2"]"#
             client,
             enable_mtls: false,
             operator_namespace: "stellar-operator".to_string(),
-            mtls_config: None,
+            mtls_config: Default::default(),
+            cert_manager_issuer: None,
             dry_run: true,
             is_leader: Arc::new(AtomicBool::new(true)),
+            backup_image: "stellar-operator:test".to_string(),
+            carbon_window: None,
+            quorum_peers: Default::default(),
+            maintenance_runs: Default::default(),
         });
 
         // Test with a retriable error (network-related)
@@ -325,9 +333,14 @@ VALIDATORS=["VALIDATOR1", "VALIDATOR2"]"#
             client,
             enable_mtls: false,
             operator_namespace: "stellar-operator".to_string(),
-            mtls_config: None,
+            mtls_config: Default::default(),
+            cert_manager_issuer: None,
             dry_run: true,
             is_leader: Arc::new(AtomicBool::new(true)),
+            backup_image: "stellar-operator:test".to_string(),
+            carbon_window: None,
+            quorum_peers: Default::default(),
+            maintenance_runs: Default::default(),
         });
 
         // Test with validation error (non-retriable)
@@ -352,9 +365,14 @@ VALIDATORS=["VALIDATOR1", "VALIDATOR2"]"#
             client,
             enable_mtls: false,
             operator_namespace: "stellar-operator".to_string(),
-            mtls_config: None,
+            mtls_config: Default::default(),
+            cert_manager_issuer: None,
             dry_run: true,
             is_leader: Arc::new(AtomicBool::new(true)),
+            backup_image: "stellar-operator:test".to_string(),
+            carbon_window: None,
+            quorum_peers: Default::default(),
+            maintenance_runs: Default::default(),
         });
 
         let errors = vec![
@@ -571,9 +589,14 @@ VALIDATORS=["VALIDATOR1", "VALIDATOR2"]"#
             client: client.clone(),
             enable_mtls: true,
             operator_namespace: "test-namespace".to_string(),
-            mtls_config: None,
+            mtls_config: Default::default(),
+            cert_manager_issuer: None,
             dry_run: false,
             is_leader: Arc::new(AtomicBool::new(true)),
+            backup_image: "stellar-operator:test".to_string(),
+            carbon_window: None,
+            quorum_peers: Default::default(),
+            maintenance_runs: Default::default(),
         };
 
         assert_eq!(state.operator_namespace, "test-namespace");
@@ -594,9 +617,14 @@ VALIDATORS=["VALIDATOR1", "VALIDATOR2"]"#
             client,
             enable_mtls: false,
             operator_namespace: "default".to_string(),
-            mtls_config: None,
+            mtls_config: Default::default(),
+            cert_manager_issuer: None,
             dry_run: true,
             is_leader: Arc::new(AtomicBool::new(true)),
+            backup_image: "stellar-operator:test".to_string(),
+            carbon_window: None,
+            quorum_peers: Default::default(),
+            maintenance_runs: Default::default(),
         };
 
         assert!(
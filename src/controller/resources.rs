@@ -31,15 +31,19 @@ use k8s_openapi::api::policy::v1::{PodDisruptionBudget, PodDisruptionBudgetSpec}
 use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, ObjectMeta, OwnerReference};
 use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
-use kube::api::{Api, DeleteParams, Patch, PatchParams, PostParams};
+use kube::api::{
+    Api, ApiResource, DeleteParams, DynamicObject, GroupVersionKind, ListParams, Patch,
+    PatchParams, PostParams,
+};
 use kube::{Client, Resource, ResourceExt};
 use tracing::{info, instrument, warn};
 
 use crate::crd::{
-    BackupConfiguration, BarmanObjectStore, BootstrapConfiguration, Cluster, ClusterSpec,
-    HistoryMode, HsmProvider, IngressConfig, InitDbConfiguration, KeySource, ManagedDatabaseConfig,
-    MonitoringConfiguration, NetworkPolicyConfig, NodeType, PgBouncerSpec, Pooler, PoolerCluster,
-    PoolerSpec, PostgresConfiguration, RolloutStrategy, S3Credentials,
+    BGPConfig, BGPStatus, BackupConfiguration, BarmanObjectStore, BootstrapConfiguration, Cluster,
+    ClusterSpec, HistoryMode, HsmProvider, IngressConfig, InitDbConfiguration, KeySource,
+    LoadBalancerConfig, LoadBalancerMode, ManagedDatabaseConfig, MonitoringConfiguration,
+    NetworkPolicyConfig, NodeType, PgBouncerSpec, Pooler, PoolerCluster, PoolerSpec,
+    PostgresConfiguration, RolloutStrategy, S3Credentials,
     SecretKeySelector as CnpgSecretKeySelector, StellarNode, StorageConfiguration,
     WalBackupConfiguration,
 };
@@ -199,12 +203,30 @@ pub async fn ensure_config_map(
     node: &StellarNode,
     quorum_override: Option<crate::controller::vsl::QuorumSet>,
     enable_mtls: bool,
+) -> Result<()> {
+    ensure_config_map_with_quorum(
+        client,
+        node,
+        quorum_override.map(|qs| qs.to_stellar_core_toml()),
+        enable_mtls,
+    )
+    .await
+}
+
+/// Ensure the node's ConfigMap, rendering `quorum_toml` (a `[QUORUM_SET]`
+/// fragment) into stellar-core.cfg in place of the spec's quorum set
+#[instrument(skip(client, node, quorum_toml), fields(name = %node.name_any(), namespace = node.namespace()))]
+pub async fn ensure_config_map_with_quorum(
+    client: &Client,
+    node: &StellarNode,
+    quorum_toml: Option<String>,
+    enable_mtls: bool,
 ) -> Result<()> {
     let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
     let api: Api<ConfigMap> = Api::namespaced(client.clone(), &namespace);
     let name = resource_name(node, "config");
 
-    let cm = build_config_map(node, quorum_override, enable_mtls);
+    let cm = build_config_map(node, quorum_toml, enable_mtls);
 
     let patch = Patch::Apply(&cm);
     api.patch(
@@ -217,9 +239,39 @@ pub async fn ensure_config_map(
     Ok(())
 }
 
+/// The quorum set fragment currently rendered into the node's stellar-core.cfg
+pub async fn rendered_quorum(client: &Client, node: &StellarNode) -> Result<Option<String>> {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let api: Api<ConfigMap> = Api::namespaced(client.clone(), &namespace);
+    let cm = api.get_opt(&resource_name(node, "config")).await?;
+    Ok(cm.and_then(|cm| cm.data).and_then(|data| {
+        data.get("stellar-core.cfg")
+            .and_then(|cfg| quorum_fragment(cfg))
+    }))
+}
+
+/// The quorum part of a rendered stellar-core.cfg: everything before the
+/// sections [`build_config_map`] appends
+pub(crate) fn quorum_fragment(core_cfg: &str) -> Option<String> {
+    let end = CORE_CFG_SECTIONS
+        .iter()
+        .filter_map(|section| core_cfg.find(section))
+        .min()
+        .unwrap_or(core_cfg.len());
+    let fragment = &core_cfg[..end];
+    (!fragment.trim().is_empty()).then(|| fragment.to_string())
+}
+
+/// Headers of the stellar-core.cfg sections that follow the quorum set
+const CORE_CFG_SECTIONS: [&str; 3] = [
+    "\n# mTLS Configuration\n",
+    "\n# Full History Mode\n",
+    "\n# Recent History Mode\n",
+];
+
 fn build_config_map(
     node: &StellarNode,
-    quorum_override: Option<crate::controller::vsl::QuorumSet>,
+    quorum_toml: Option<String>,
     enable_mtls: bool,
 ) -> ConfigMap {
     let labels = standard_labels(node);
@@ -240,8 +292,8 @@ fn build_config_map(
         NodeType::Validator => {
             let mut core_cfg = String::new();
             if let Some(config) = &node.spec.validator_config {
-                if let Some(qs) = quorum_override {
-                    core_cfg.push_str(&qs.to_stellar_core_toml());
+                if let Some(qs) = quorum_toml {
+                    core_cfg.push_str(&qs);
                 } else if let Some(q) = &config.quorum_set {
                     core_cfg.push_str(q);
                 }
@@ -700,28 +752,510 @@ fn build_service(node: &StellarNode, enable_mtls: bool) -> Service {
 }
 
 // ============================================================================
-// LoadBalancer Service (MetalLB Integration) — stubs unchanged
+// LoadBalancer Service (MetalLB Integration)
 // ============================================================================
+//
+// MetalLB custom resources live in the MetalLB namespace, so they cannot be
+// owned by the StellarNode. They are named `<namespace>-<node>-<suffix>` and
+// labelled with the owning node for cleanup.
+
+/// Namespace MetalLB watches for its configuration resources
+pub(crate) const METALLB_NAMESPACE: &str = "metallb-system";
+const METALLB_GROUP: &str = "metallb.io";
+const METALLB_NODE_LABEL: &str = "stellar.org/node-name";
+const METALLB_NODE_NAMESPACE_LABEL: &str = "stellar.org/node-namespace";
+
+/// MetalLB kinds managed per node, with their API versions
+const METALLB_KINDS: [(&str, &str); 4] = [
+    ("IPAddressPool", "v1beta1"),
+    ("L2Advertisement", "v1beta1"),
+    ("BGPAdvertisement", "v1beta1"),
+    ("BGPPeer", "v1beta2"),
+];
+
+fn metallb_api_resource(kind: &str, version: &str) -> ApiResource {
+    ApiResource::from_gvk(&GroupVersionKind {
+        group: METALLB_GROUP.to_string(),
+        version: version.to_string(),
+        kind: kind.to_string(),
+    })
+}
 
-#[allow(dead_code)]
-#[instrument(skip(_client, _node), fields(name = %_node.name_any(), namespace = _node.namespace()))]
-pub async fn ensure_load_balancer_service(_client: &Client, _node: &StellarNode) -> Result<()> {
+/// Name of a MetalLB resource belonging to `node`
+pub(crate) fn metallb_resource_name(node: &StellarNode, suffix: &str) -> String {
+    format!(
+        "{}-{}-{}",
+        node.namespace().unwrap_or_else(|| "default".to_string()),
+        node.name_any(),
+        suffix
+    )
+}
+
+fn metallb_labels(node: &StellarNode) -> BTreeMap<String, String> {
+    BTreeMap::from([
+        (
+            "app.kubernetes.io/managed-by".to_string(),
+            "stellar-operator".to_string(),
+        ),
+        (METALLB_NODE_LABEL.to_string(), node.name_any()),
+        (
+            METALLB_NODE_NAMESPACE_LABEL.to_string(),
+            node.namespace().unwrap_or_else(|| "default".to_string()),
+        ),
+    ])
+}
+
+fn metallb_label_selector(node: &StellarNode) -> String {
+    format!(
+        "{}={},{}={}",
+        METALLB_NODE_LABEL,
+        node.name_any(),
+        METALLB_NODE_NAMESPACE_LABEL,
+        node.namespace().unwrap_or_else(|| "default".to_string())
+    )
+}
+
+fn metallb_object(
+    node: &StellarNode,
+    kind: &str,
+    version: &str,
+    name: String,
+    spec: serde_json::Value,
+) -> DynamicObject {
+    let mut obj = DynamicObject::new(&name, &metallb_api_resource(kind, version))
+        .within(METALLB_NAMESPACE)
+        .data(serde_json::json!({ "spec": spec }));
+    obj.metadata.labels = Some(metallb_labels(node));
+    obj
+}
+
+/// The MetalLB address pool the node's Service draws from.
+///
+/// A user-named `addressPool` is referenced as-is and never modified. With
+/// only a `loadBalancerIP`, the operator manages a single-address pool.
+pub(crate) fn metallb_pool_name(node: &StellarNode, lb: &LoadBalancerConfig) -> Option<String> {
+    match (&lb.address_pool, &lb.load_balancer_ip) {
+        (Some(pool), _) => Some(pool.clone()),
+        (None, Some(_)) => Some(metallb_resource_name(node, "pool")),
+        (None, None) => None,
+    }
+}
+
+fn node_selector_terms(selectors: Option<&BTreeMap<String, String>>) -> serde_json::Value {
+    match selectors {
+        Some(labels) if !labels.is_empty() => serde_json::json!([{ "matchLabels": labels }]),
+        _ => serde_json::json!([]),
+    }
+}
+
+/// Build the MetalLB resources for a node's load balancer configuration
+pub(crate) fn build_metallb_objects(
+    node: &StellarNode,
+    lb: &LoadBalancerConfig,
+) -> Vec<DynamicObject> {
+    let mut objects = Vec::new();
+    let pool = metallb_pool_name(node, lb);
+
+    if let (None, Some(ip)) = (&lb.address_pool, &lb.load_balancer_ip) {
+        let prefix = if ip.contains(':') { 128 } else { 32 };
+        objects.push(metallb_object(
+            node,
+            "IPAddressPool",
+            "v1beta1",
+            metallb_resource_name(node, "pool"),
+            serde_json::json!({
+                "addresses": [format!("{ip}/{prefix}")],
+                "autoAssign": false,
+            }),
+        ));
+    }
+
+    let Some(pool) = pool else {
+        // Without a pool the Service draws from auto-assigned pools, which
+        // are advertised by the cluster's own MetalLB configuration
+        return objects;
+    };
+
+    match (&lb.mode, &lb.bgp) {
+        (LoadBalancerMode::BGP, Some(bgp)) => {
+            let peer_names: Vec<String> = (0..bgp.peers.len())
+                .map(|i| metallb_resource_name(node, &format!("peer-{i}")))
+                .collect();
+            for (peer, name) in bgp.peers.iter().zip(&peer_names) {
+                let mut spec = serde_json::json!({
+                    "myASN": bgp.local_asn,
+                    "peerASN": peer.asn,
+                    "peerAddress": peer.address,
+                    "peerPort": peer.port,
+                    "holdTime": format!("{}s", peer.hold_time),
+                    "keepaliveTime": format!("{}s", peer.keepalive_time),
+                    "ebgpMultiHop": peer.ebgp_multi_hop,
+                    "enableGracefulRestart": peer.graceful_restart,
+                    "nodeSelectors": node_selector_terms(bgp.node_selectors.as_ref()),
+                });
+                if let Some(router_id) = &peer.router_id {
+                    spec["routerID"] = serde_json::json!(router_id);
+                }
+                if let Some(source) = &peer.source_address {
+                    spec["sourceAddress"] = serde_json::json!(source);
+                }
+                if let Some(secret) = &peer.password_secret_ref {
+                    // MetalLB reads the `password` key of a basic-auth Secret
+                    // in its own namespace
+                    spec["passwordSecret"] = serde_json::json!({
+                        "name": secret.name,
+                        "namespace": METALLB_NAMESPACE,
+                    });
+                }
+                if bgp.bfd_enabled {
+                    if let Some(profile) = &bgp.bfd_profile {
+                        spec["bfdProfile"] = serde_json::json!(profile);
+                    }
+                }
+                objects.push(metallb_object(
+                    node,
+                    "BGPPeer",
+                    "v1beta2",
+                    name.clone(),
+                    spec,
+                ));
+            }
+
+            let advertisement = bgp.advertisement.as_ref();
+            let communities: Vec<String> = bgp
+                .communities
+                .iter()
+                .cloned()
+                .chain(bgp.large_communities.iter().map(|c| {
+                    if c.starts_with("large:") {
+                        c.clone()
+                    } else {
+                        format!("large:{c}")
+                    }
+                }))
+                .collect();
+            let mut spec = serde_json::json!({
+                "ipAddressPools": [pool],
+                "peers": peer_names,
+                "aggregationLength": advertisement.map_or(32, |a| a.aggregation_length),
+                "aggregationLengthV6": advertisement.map_or(128, |a| a.aggregation_length_v6),
+                "communities": communities,
+                "nodeSelectors": node_selector_terms(
+                    advertisement.and_then(|a| a.node_selectors.as_ref()),
+                ),
+            });
+            if let Some(local_pref) = advertisement.and_then(|a| a.local_pref) {
+                spec["localPref"] = serde_json::json!(local_pref);
+            }
+            objects.push(metallb_object(
+                node,
+                "BGPAdvertisement",
+                "v1beta1",
+                metallb_resource_name(node, "bgp"),
+                spec,
+            ));
+        }
+        _ => objects.push(metallb_object(
+            node,
+            "L2Advertisement",
+            "v1beta1",
+            metallb_resource_name(node, "l2"),
+            serde_json::json!({ "ipAddressPools": [pool] }),
+        )),
+    }
+    objects
+}
+
+/// Build the LoadBalancer Service exposing a node through MetalLB
+pub(crate) fn build_load_balancer_service(
+    node: &StellarNode,
+    lb: &LoadBalancerConfig,
+    enable_mtls: bool,
+) -> Service {
+    let mut service = build_service(node, enable_mtls);
+    service.metadata.name = Some(resource_name(node, "lb"));
+
+    let mut annotations = lb.annotations.clone().unwrap_or_default();
+    if let Some(pool) = metallb_pool_name(node, lb) {
+        annotations.insert("metallb.universe.tf/address-pool".to_string(), pool);
+    }
+    if let Some(ip) = &lb.load_balancer_ip {
+        annotations.insert(
+            "metallb.universe.tf/loadBalancerIPs".to_string(),
+            ip.clone(),
+        );
+    }
+    service.metadata.annotations = Some(annotations);
+
+    if let Some(spec) = &mut service.spec {
+        spec.type_ = Some("LoadBalancer".to_string());
+        spec.external_traffic_policy = Some(lb.external_traffic_policy.to_string());
+
+        let ports = spec.ports.get_or_insert_with(Vec::new);
+        if lb.health_check_enabled && !ports.iter().any(|p| p.port == lb.health_check_port) {
+            // Probe port for external load balancers, backed by the node's HTTP endpoint
+            let http_port = match node.spec.node_type {
+                NodeType::Validator => 11626,
+                NodeType::Horizon | NodeType::SorobanRpc => 8000,
+            };
+            ports.push(ServicePort {
+                name: Some("health".to_string()),
+                port: lb.health_check_port,
+                target_port: Some(IntOrString::Int(http_port)),
+                ..Default::default()
+            });
+        }
+    }
+    service
+}
+
+fn enabled_load_balancer(node: &StellarNode) -> Option<&LoadBalancerConfig> {
+    node.spec.load_balancer.as_ref().filter(|lb| lb.enabled)
+}
+
+/// Ensure the LoadBalancer Service exists when `spec.loadBalancer` is enabled,
+/// and remove it (with its MetalLB resources) once disabled
+#[instrument(skip(client, node), fields(name = %node.name_any(), namespace = node.namespace()))]
+pub async fn ensure_load_balancer_service(
+    client: &Client,
+    node: &StellarNode,
+    enable_mtls: bool,
+) -> Result<()> {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let api: Api<Service> = Api::namespaced(client.clone(), &namespace);
+    let name = resource_name(node, "lb");
+
+    let Some(lb) = enabled_load_balancer(node) else {
+        if api.get_opt(&name).await?.is_some() {
+            info!("Load balancer disabled, removing Service {}", name);
+            delete_load_balancer_service(client, node).await?;
+            delete_metallb_config(client, node).await?;
+        }
+        return Ok(());
+    };
+
+    let service = build_load_balancer_service(node, lb, enable_mtls);
+    api.patch(
+        &name,
+        &PatchParams::apply("stellar-operator").force(),
+        &Patch::Apply(&service),
+    )
+    .await?;
     Ok(())
 }
 
-#[instrument(skip(_client, _node), fields(name = %_node.name_any(), namespace = _node.namespace()))]
-pub async fn delete_load_balancer_service(_client: &Client, _node: &StellarNode) -> Result<()> {
+#[instrument(skip(client, node), fields(name = %node.name_any(), namespace = node.namespace()))]
+pub async fn delete_load_balancer_service(client: &Client, node: &StellarNode) -> Result<()> {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let api: Api<Service> = Api::namespaced(client.clone(), &namespace);
+    let name = resource_name(node, "lb");
+    match api.delete(&name, &DeleteParams::default()).await {
+        Ok(_) => info!("Deleted LoadBalancer Service {}", name),
+        Err(kube::Error::Api(e)) if e.code == 404 => {}
+        Err(e) => return Err(Error::KubeError(e)),
+    }
     Ok(())
 }
 
-#[allow(dead_code)]
-#[instrument(skip(_client, _node), fields(name = %_node.name_any(), namespace = _node.namespace()))]
-pub async fn ensure_metallb_config(_client: &Client, _node: &StellarNode) -> Result<()> {
+/// Apply the node's MetalLB resources and prune ones no longer wanted
+/// (e.g. after switching from L2 to BGP)
+#[instrument(skip(client, node), fields(name = %node.name_any(), namespace = node.namespace()))]
+pub async fn ensure_metallb_config(client: &Client, node: &StellarNode) -> Result<()> {
+    let Some(lb) = enabled_load_balancer(node) else {
+        return Ok(());
+    };
+    let desired = build_metallb_objects(node, lb);
+
+    for obj in &desired {
+        let types = obj.types.as_ref().ok_or_else(|| {
+            Error::ConfigError("MetalLB object is missing its type metadata".to_string())
+        })?;
+        let (_, version) = types.api_version.split_once('/').unwrap_or_default();
+        let api: Api<DynamicObject> = Api::namespaced_with(
+            client.clone(),
+            METALLB_NAMESPACE,
+            &metallb_api_resource(&types.kind, version),
+        );
+        api.patch(
+            &obj.name_any(),
+            &PatchParams::apply("stellar-operator").force(),
+            &Patch::Apply(obj),
+        )
+        .await?;
+    }
+
+    let keep: Vec<String> = desired.iter().map(|o| o.name_any()).collect();
+    prune_metallb_objects(client, node, &keep).await
+}
+
+#[instrument(skip(client, node), fields(name = %node.name_any(), namespace = node.namespace()))]
+pub async fn delete_metallb_config(client: &Client, node: &StellarNode) -> Result<()> {
+    prune_metallb_objects(client, node, &[]).await
+}
+
+/// Delete the node's MetalLB resources whose names are not in `keep`
+async fn prune_metallb_objects(client: &Client, node: &StellarNode, keep: &[String]) -> Result<()> {
+    let lp = ListParams::default().labels(&metallb_label_selector(node));
+    for (kind, version) in METALLB_KINDS {
+        let api: Api<DynamicObject> = Api::namespaced_with(
+            client.clone(),
+            METALLB_NAMESPACE,
+            &metallb_api_resource(kind, version),
+        );
+        let existing = match api.list(&lp).await {
+            Ok(list) => list.items,
+            // MetalLB (or this kind) is not installed
+            Err(kube::Error::Api(e)) if e.code == 404 => continue,
+            Err(e) => return Err(Error::KubeError(e)),
+        };
+        for obj in existing {
+            let name = obj.name_any();
+            if keep.contains(&name) {
+                continue;
+            }
+            match api.delete(&name, &DeleteParams::default()).await {
+                Ok(_) => info!("Deleted MetalLB {} {}", kind, name),
+                Err(kube::Error::Api(e)) if e.code == 404 => {}
+                Err(e) => return Err(Error::KubeError(e)),
+            }
+        }
+    }
     Ok(())
 }
 
-#[instrument(skip(_client, _node), fields(name = %_node.name_any(), namespace = _node.namespace()))]
-pub async fn delete_metallb_config(_client: &Client, _node: &StellarNode) -> Result<()> {
+/// Derive the BGP status from MetalLB's session states.
+///
+/// `sessions` are `(peer address, state)` pairs from `BGPSessionState`
+/// resources. Sessions count as established only when every configured peer
+/// has one; `lastUpdate` only moves when the status changes.
+pub(crate) fn compute_bgp_status(
+    bgp: &BGPConfig,
+    external_ip: Option<&str>,
+    sessions: &[(String, String)],
+    previous: Option<&BGPStatus>,
+    now: &str,
+) -> BGPStatus {
+    let active_peers = bgp
+        .peers
+        .iter()
+        .filter(|peer| {
+            sessions
+                .iter()
+                .any(|(address, state)| *address == peer.address && state == "Established")
+        })
+        .count() as i32;
+
+    let advertised_prefixes = match external_ip {
+        Some(ip) if active_peers > 0 => {
+            let advertisement = bgp.advertisement.as_ref();
+            let length = if ip.contains(':') {
+                advertisement.map_or(128, |a| a.aggregation_length_v6)
+            } else {
+                advertisement.map_or(32, |a| a.aggregation_length)
+            };
+            vec![format!("{ip}/{length}")]
+        }
+        _ => Vec::new(),
+    };
+
+    let mut status = BGPStatus {
+        sessions_established: active_peers > 0 && active_peers as usize == bgp.peers.len(),
+        active_peers,
+        advertised_prefixes,
+        last_update: None,
+    };
+    status.last_update = match previous {
+        Some(prev)
+            if prev.sessions_established == status.sessions_established
+                && prev.active_peers == status.active_peers
+                && prev.advertised_prefixes == status.advertised_prefixes =>
+        {
+            prev.last_update.clone()
+        }
+        _ => Some(now.to_string()),
+    };
+    status
+}
+
+/// Record the external IP MetalLB assigned and, in BGP mode, the session
+/// state MetalLB reports in `status.externalIp` / `status.bgpStatus`
+#[instrument(skip(client, node), fields(name = %node.name_any(), namespace = node.namespace()))]
+pub async fn update_load_balancer_status(client: &Client, node: &StellarNode) -> Result<()> {
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let lb = enabled_load_balancer(node);
+
+    let external_ip = match lb {
+        Some(_) => {
+            let services: Api<Service> = Api::namespaced(client.clone(), &namespace);
+            services
+                .get_opt(&resource_name(node, "lb"))
+                .await?
+                .and_then(|svc| svc.status?.load_balancer?.ingress)
+                .and_then(|ingress| ingress.into_iter().next())
+                .and_then(|ingress| ingress.ip.or(ingress.hostname))
+        }
+        None => None,
+    };
+
+    let previous = node.status.as_ref();
+    let bgp_status = match lb.and_then(|lb| {
+        (lb.mode == LoadBalancerMode::BGP)
+            .then_some(lb.bgp.as_ref())
+            .flatten()
+    }) {
+        Some(bgp) => {
+            let api: Api<DynamicObject> = Api::namespaced_with(
+                client.clone(),
+                METALLB_NAMESPACE,
+                &metallb_api_resource("BGPSessionState", "v1beta1"),
+            );
+            match api.list(&ListParams::default()).await {
+                Ok(list) => {
+                    let sessions: Vec<(String, String)> = list
+                        .items
+                        .iter()
+                        .filter_map(|s| {
+                            let status = s.data.get("status")?;
+                            Some((
+                                status.get("peer")?.as_str()?.to_string(),
+                                status.get("bgpStatus")?.as_str()?.to_string(),
+                            ))
+                        })
+                        .collect();
+                    Some(compute_bgp_status(
+                        bgp,
+                        external_ip.as_deref(),
+                        &sessions,
+                        previous.and_then(|s| s.bgp_status.as_ref()),
+                        &chrono::Utc::now().to_rfc3339(),
+                    ))
+                }
+                Err(e) => {
+                    // Older MetalLB releases do not report session state
+                    warn!("Cannot read MetalLB BGP session state: {}", e);
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+
+    let unchanged = previous.and_then(|s| s.external_ip.as_deref()) == external_ip.as_deref()
+        && serde_json::to_value(previous.and_then(|s| s.bgp_status.as_ref()))?
+            == serde_json::to_value(bgp_status.as_ref())?;
+    if unchanged {
+        return Ok(());
+    }
+
+    let api: Api<StellarNode> = Api::namespaced(client.clone(), &namespace);
+    api.patch_status(
+        &node.name_any(),
+        &PatchParams::apply("stellar-operator"),
+        &Patch::Merge(&serde_json::json!({
+            "status": { "externalIp": external_ip, "bgpStatus": bgp_status }
+        })),
+    )
+    .await?;
     Ok(())
 }
 
@@ -9,7 +9,10 @@ mod tests {
     use k8s_openapi::api::core::v1::TopologySpreadConstraint;
     use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
 
-    use crate::controller::resources::build_topology_spread_constraints;
+    use crate::controller::resources::{
+        build_load_balancer_service, build_metallb_objects, build_topology_spread_constraints,
+        compute_bgp_status, quorum_fragment, METALLB_NAMESPACE,
+    };
     use crate::crd::{
         types::{ResourceRequirements, ResourceSpec, StorageConfig},
         NodeType, StellarNetwork, StellarNodeSpec,
@@ -64,6 +67,7 @@ mod tests {
             read_pool_endpoint: None,
             db_maintenance_config: None,
             oci_snapshot: None,
+            decentralized_backup: None,
             service_mesh: None,
             resource_meta: None,
         }
@@ -281,4 +285,225 @@ mod tests {
             );
         }
     }
+
+    // -----------------------------------------------------------------------
+    // MetalLB load balancer
+    // -----------------------------------------------------------------------
+
+    fn lb_node(load_balancer: serde_json::Value) -> crate::crd::StellarNode {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "stellar.org/v1alpha1",
+            "kind": "StellarNode",
+            "metadata": { "name": "validator-1", "namespace": "stellar", "uid": "abc-123" },
+            "spec": {
+                "nodeType": "Validator",
+                "network": "Testnet",
+                "version": "v21.0.0",
+                "validatorConfig": { "seedSecretRef": "validator-seed" },
+                "loadBalancer": load_balancer,
+            },
+        }))
+        .unwrap()
+    }
+
+    fn bgp_node() -> crate::crd::StellarNode {
+        lb_node(serde_json::json!({
+            "enabled": true,
+            "mode": "BGP",
+            "loadBalancerIp": "192.0.2.10",
+            "bgp": {
+                "localAsn": 64512,
+                "peers": [
+                    { "address": "10.0.0.1", "asn": 64513,
+                      "passwordSecretRef": { "name": "bgp-auth", "key": "password" } },
+                    { "address": "10.0.0.2", "asn": 64513 },
+                ],
+                "communities": ["64512:100"],
+                "largeCommunities": ["64512:1:1"],
+                "advertisement": { "localPref": 200 },
+            },
+        }))
+    }
+
+    fn kinds(objects: &[kube::api::DynamicObject]) -> Vec<(String, String)> {
+        objects
+            .iter()
+            .map(|o| {
+                (
+                    o.types.as_ref().unwrap().kind.clone(),
+                    o.metadata.name.clone().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_metallb_l2_with_user_pool() {
+        let node = lb_node(serde_json::json!({ "enabled": true, "addressPool": "public" }));
+        let lb = node.spec.load_balancer.as_ref().unwrap();
+        let objects = build_metallb_objects(&node, lb);
+        // The user's pool is referenced, never created
+        assert_eq!(
+            kinds(&objects),
+            vec![(
+                "L2Advertisement".to_string(),
+                "stellar-validator-1-l2".to_string()
+            )]
+        );
+        assert_eq!(
+            objects[0].metadata.namespace.as_deref(),
+            Some(METALLB_NAMESPACE)
+        );
+        assert_eq!(objects[0].data["spec"]["ipAddressPools"][0], "public");
+        let labels = objects[0].metadata.labels.as_ref().unwrap();
+        assert_eq!(labels["stellar.org/node-name"], "validator-1");
+        assert_eq!(labels["stellar.org/node-namespace"], "stellar");
+
+        // Nothing to advertise without a pool or a fixed IP
+        let node = lb_node(serde_json::json!({ "enabled": true }));
+        assert!(build_metallb_objects(&node, node.spec.load_balancer.as_ref().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_metallb_bgp_objects() {
+        let node = bgp_node();
+        let objects = build_metallb_objects(&node, node.spec.load_balancer.as_ref().unwrap());
+        let names: Vec<String> = kinds(&objects).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(
+            names,
+            vec!["IPAddressPool", "BGPPeer", "BGPPeer", "BGPAdvertisement"]
+        );
+
+        let pool = &objects[0].data["spec"];
+        assert_eq!(pool["addresses"][0], "192.0.2.10/32");
+        assert_eq!(pool["autoAssign"], false);
+
+        let peer = &objects[1].data["spec"];
+        assert_eq!(
+            objects[1].types.as_ref().unwrap().api_version,
+            "metallb.io/v1beta2"
+        );
+        assert_eq!(peer["myASN"], 64512);
+        assert_eq!(peer["peerASN"], 64513);
+        assert_eq!(peer["peerAddress"], "10.0.0.1");
+        assert_eq!(peer["holdTime"], "90s");
+        assert_eq!(peer["passwordSecret"]["name"], "bgp-auth");
+        assert_eq!(peer["passwordSecret"]["namespace"], METALLB_NAMESPACE);
+        assert!(objects[2].data["spec"].get("passwordSecret").is_none());
+
+        let adv = &objects[3].data["spec"];
+        assert_eq!(adv["ipAddressPools"][0], "stellar-validator-1-pool");
+        assert_eq!(adv["peers"][1], "stellar-validator-1-peer-1");
+        assert_eq!(adv["localPref"], 200);
+        assert_eq!(adv["communities"][0], "64512:100");
+        assert_eq!(adv["communities"][1], "large:64512:1:1");
+    }
+
+    #[test]
+    fn test_load_balancer_service() {
+        let node = bgp_node();
+        let service =
+            build_load_balancer_service(&node, node.spec.load_balancer.as_ref().unwrap(), false);
+        assert_eq!(service.metadata.name.as_deref(), Some("validator-1-lb"));
+        let annotations = service.metadata.annotations.as_ref().unwrap();
+        assert_eq!(
+            annotations["metallb.universe.tf/address-pool"],
+            "stellar-validator-1-pool"
+        );
+        assert_eq!(
+            annotations["metallb.universe.tf/loadBalancerIPs"],
+            "192.0.2.10"
+        );
+
+        let spec = service.spec.unwrap();
+        assert_eq!(spec.type_.as_deref(), Some("LoadBalancer"));
+        assert_eq!(spec.external_traffic_policy.as_deref(), Some("Cluster"));
+        let health = spec
+            .ports
+            .unwrap()
+            .into_iter()
+            .find(|p| p.name.as_deref() == Some("health"))
+            .unwrap();
+        assert_eq!(health.port, 9100);
+    }
+
+    #[test]
+    fn test_compute_bgp_status() {
+        let node = bgp_node();
+        let bgp = node
+            .spec
+            .load_balancer
+            .as_ref()
+            .unwrap()
+            .bgp
+            .clone()
+            .unwrap();
+        let session = |peer: &str, state: &str| (peer.to_string(), state.to_string());
+
+        let partial = compute_bgp_status(
+            &bgp,
+            Some("192.0.2.10"),
+            &[
+                session("10.0.0.1", "Established"),
+                session("10.0.0.2", "Active"),
+            ],
+            None,
+            "t1",
+        );
+        assert_eq!(partial.active_peers, 1);
+        assert!(!partial.sessions_established);
+        assert_eq!(partial.advertised_prefixes, vec!["192.0.2.10/32"]);
+        assert_eq!(partial.last_update.as_deref(), Some("t1"));
+
+        // Unchanged state keeps the previous timestamp
+        let again = compute_bgp_status(
+            &bgp,
+            Some("192.0.2.10"),
+            &[session("10.0.0.1", "Established")],
+            Some(&partial),
+            "t2",
+        );
+        assert_eq!(again.last_update.as_deref(), Some("t1"));
+
+        let all = compute_bgp_status(
+            &bgp,
+            Some("192.0.2.10"),
+            &[
+                session("10.0.0.1", "Established"),
+                session("10.0.0.2", "Established"),
+                session("10.9.9.9", "Established"),
+            ],
+            Some(&partial),
+            "t3",
+        );
+        assert_eq!(all.active_peers, 2);
+        assert!(all.sessions_established);
+        assert_eq!(all.last_update.as_deref(), Some("t3"));
+
+        let down = compute_bgp_status(&bgp, Some("192.0.2.10"), &[], None, "t4");
+        assert_eq!(down.active_peers, 0);
+        assert!(down.advertised_prefixes.is_empty());
+    }
+
+    // -----------------------------------------------------------------------
+    // Validator config
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_quorum_fragment_of_rendered_config() {
+        let quorum = "[QUORUM_SET]\nTHRESHOLD_PERCENT=67\nVALIDATORS=[\"GA\", \"GB\", \"GC\"]\n";
+        let cfg = format!(
+            "{quorum}\n# mTLS Configuration\nHTTP_PORT_SECURE=true\n\n# Full History Mode\nCATCHUP_COMPLETE=true\n"
+        );
+        assert_eq!(quorum_fragment(&cfg).as_deref(), Some(quorum));
+
+        let cfg = format!("{quorum}\n# Recent History Mode\nCATCHUP_COMPLETE=false\n");
+        assert_eq!(quorum_fragment(&cfg).as_deref(), Some(quorum));
+
+        // Nothing rendered before the appended sections
+        assert_eq!(
+            quorum_fragment("\n# Full History Mode\nCATCHUP_COMPLETE=true\n"),
+            None
+        );
+    }
 }
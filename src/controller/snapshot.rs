@@ -5,14 +5,14 @@
 //! flushes the Stellar database before the snapshot for consistency, then resumes normal operations.
 
 use std::collections::BTreeMap;
-use std::str::FromStr;
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use kube::api::{Api, DeleteParams, DynamicObject, ListParams, Patch, PatchParams, PostParams};
 use kube::discovery::ApiResource;
 use kube::{Client, ResourceExt};
 use tracing::{info, instrument, warn};
 
+use crate::carbon_aware::window::next_cron_fire;
 use crate::controller::resource_meta::merge_resource_meta;
 use crate::controller::resources::{
     owner_reference, resource_name, standard_labels as node_standard_labels,
@@ -48,16 +48,10 @@ pub async fn reconcile_snapshot(
     let pvc_name = resource_name(node, "data");
 
     // Check if snapshot was requested via annotation (one-shot)
-    let request_snapshot = node
-        .metadata
-        .annotations
-        .as_ref()
-        .and_then(|a| a.get(REQUEST_SNAPSHOT_ANNOTATION))
-        .map(|v| v == "true" || v == "1")
-        .unwrap_or(false);
+    let request_snapshot = snapshot_requested(node);
 
     // If schedule is set, check if cron has fired since last snapshot; otherwise react to annotation only.
-    let should_snapshot = request_snapshot || schedule_matches_now(config, node);
+    let should_snapshot = request_snapshot || scheduled_snapshot_due(config, node).is_some();
     if !should_snapshot {
         return Ok(());
     }
@@ -89,16 +83,23 @@ pub async fn reconcile_snapshot(
     Ok(())
 }
 
-/// Returns true if the cron schedule has fired (next run time is in the past or within 1 minute of now).
-fn schedule_matches_now(config: &SnapshotScheduleConfig, node: &StellarNode) -> bool {
-    let schedule = match &config.schedule {
-        Some(s) if !s.is_empty() => s,
-        _ => return false,
-    };
-    let s = match cron::Schedule::from_str(schedule) {
-        Ok(s) => s,
-        Err(_) => return false,
-    };
+/// Whether a one-shot snapshot was requested via the `stellar.org/request-snapshot` annotation
+pub fn snapshot_requested(node: &StellarNode) -> bool {
+    node.metadata
+        .annotations
+        .as_ref()
+        .and_then(|a| a.get(REQUEST_SNAPSHOT_ANNOTATION))
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false)
+}
+
+/// Returns when the cron schedule fired if it has fired since the last snapshot
+/// (or fires within 1 minute of now).
+pub fn scheduled_snapshot_due(
+    config: &SnapshotScheduleConfig,
+    node: &StellarNode,
+) -> Option<DateTime<Utc>> {
+    let schedule = config.schedule.as_deref().filter(|s| !s.is_empty())?;
     let now = Utc::now();
     let from = node
         .metadata
@@ -108,11 +109,8 @@ fn schedule_matches_now(config: &SnapshotScheduleConfig, node: &StellarNode) ->
         .and_then(|v| chrono::DateTime::parse_from_rfc3339(v).ok())
         .map(|t| t.with_timezone(&Utc))
         .unwrap_or_else(|| now - chrono::Duration::days(1));
-    let next = s.after(&from).next();
-    match next {
-        Some(t) => t <= now || t.signed_duration_since(now).num_seconds() < 60,
-        None => false,
-    }
+    next_cron_fire(schedule, from)
+        .filter(|t| *t <= now || t.signed_duration_since(now).num_seconds() < 60)
 }
 
 /// Request a graceful flush of the Stellar database (if supported).
@@ -0,0 +1,126 @@
+//! Stellar strkey encoding for Ed25519 keys
+//!
+//! A strkey is `base32(version_byte || payload || crc16_xmodem_le)` without
+//! padding. Public keys (`G…`) use version byte `6 << 3`, secret seeds (`S…`)
+//! use `18 << 3`.
+
+use ed25519_dalek::SigningKey;
+
+use crate::error::{Error, Result};
+
+const VERSION_PUBLIC_KEY: u8 = 6 << 3;
+const VERSION_SEED: u8 = 18 << 3;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Encode a raw Ed25519 public key as a `G…` strkey
+pub fn encode_public_key(key: &[u8; 32]) -> String {
+    encode(VERSION_PUBLIC_KEY, key)
+}
+
+/// Decode a `G…` strkey into the raw Ed25519 public key
+pub fn decode_public_key(strkey: &str) -> Result<[u8; 32]> {
+    decode(VERSION_PUBLIC_KEY, strkey)
+}
+
+/// Whether `s` is a well-formed `G…` strkey (including checksum)
+pub fn is_public_key(s: &str) -> bool {
+    s.starts_with('G') && decode_public_key(s).is_ok()
+}
+
+/// Derive the `G…` public key for an `S…` secret seed.
+///
+/// The seed only lives in memory for the duration of the call; errors never
+/// include it.
+pub fn public_key_from_seed(seed: &str) -> Result<String> {
+    let seed_bytes = decode(VERSION_SEED, seed.trim())?;
+    let signing_key = SigningKey::from_bytes(&seed_bytes);
+    Ok(encode_public_key(signing_key.verifying_key().as_bytes()))
+}
+
+fn encode(version: u8, payload: &[u8; 32]) -> String {
+    let mut data = Vec::with_capacity(35);
+    data.push(version);
+    data.extend_from_slice(payload);
+    let crc = crc16_xmodem(&data);
+    data.extend_from_slice(&crc.to_le_bytes());
+    base32_encode(&data)
+}
+
+fn decode(version: u8, strkey: &str) -> Result<[u8; 32]> {
+    let kind = if version == VERSION_SEED {
+        "secret seed"
+    } else {
+        "public key"
+    };
+    let invalid = |reason: &str| Error::ConfigError(format!("Invalid Stellar {kind}: {reason}"));
+
+    let data = base32_decode(strkey).ok_or_else(|| invalid("not base32"))?;
+    if data.len() != 35 {
+        return Err(invalid("wrong length"));
+    }
+    if data[0] != version {
+        return Err(invalid("wrong version byte"));
+    }
+    let (body, checksum) = data.split_at(33);
+    if crc16_xmodem(body).to_le_bytes() != checksum {
+        return Err(invalid("checksum mismatch"));
+    }
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&body[1..]);
+    Ok(key)
+}
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= u16::from(*byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in data {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+fn base32_decode(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(s.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in s.bytes() {
+        let value = BASE32_ALPHABET.iter().position(|a| *a == c)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    // Leftover bits must be zero padding
+    if buffer & ((1 << bits) - 1) != 0 {
+        return None;
+    }
+    Some(out)
+}
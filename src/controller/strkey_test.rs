@@ -0,0 +1,51 @@
+//! Tests for Stellar strkey encoding.
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::strkey::*;
+
+    const ZERO_KEY: &str = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+
+    #[test]
+    fn test_encode_zero_public_key() {
+        assert_eq!(encode_public_key(&[0u8; 32]), ZERO_KEY);
+        assert_eq!(decode_public_key(ZERO_KEY).unwrap(), [0u8; 32]);
+    }
+
+    #[test]
+    fn test_public_key_roundtrip() {
+        let key: [u8; 32] = std::array::from_fn(|i| (i * 7 + 3) as u8);
+        let encoded = encode_public_key(&key);
+        assert!(encoded.starts_with('G'));
+        assert_eq!(encoded.len(), 56);
+        assert_eq!(decode_public_key(&encoded).unwrap(), key);
+        assert!(is_public_key(&encoded));
+    }
+
+    #[test]
+    fn test_rejects_corrupted_keys() {
+        // Flip one character: checksum no longer matches
+        let mut tampered = ZERO_KEY.to_string();
+        tampered.replace_range(10..11, "B");
+        assert!(decode_public_key(&tampered).is_err());
+
+        assert!(decode_public_key("GAAAA").is_err());
+        assert!(decode_public_key("not-a-key").is_err());
+        assert!(!is_public_key("validator-1"));
+    }
+
+    #[test]
+    fn test_public_key_from_seed() {
+        let seed = "SCZANGBA5YHTNYVVV4C3U252E2B6P6F5T3U6MM63WBSBZATAQI3EBTQ4";
+        assert_eq!(
+            public_key_from_seed(seed).unwrap(),
+            "GC2BKLYOOYPDEFJKLKY6FNNRQMGFLVHJKQRGNSSRRGSMPGF32LHCQVGF"
+        );
+        // Surrounding whitespace from Secret values is tolerated
+        assert!(public_key_from_seed(&format!("{seed}\n")).is_ok());
+
+        // A seed is not a public key, and vice versa
+        assert!(!is_public_key(seed));
+        assert!(public_key_from_seed(ZERO_KEY).is_err());
+    }
+}
@@ -12,6 +12,19 @@ use tracing::{debug, info, instrument};
 use crate::crd::{ReadReplicaStrategy, StellarNode};
 use crate::error::{Error, Result};
 
+/// Pod label selected by the read traffic Service
+pub(crate) const TRAFFIC_LABEL: &str = "stellar.org/traffic";
+/// Pod label marking a read replica drained for database maintenance
+pub(crate) const MAINTENANCE_LABEL: &str = "stellar.org/maintenance";
+
+/// Label selector for the read replicas of a node
+pub(crate) fn read_replica_selector(node: &StellarNode) -> String {
+    format!(
+        "app.kubernetes.io/instance={},stellar.org/role=read-replica",
+        node.name_any()
+    )
+}
+
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
 struct StellarCoreInfo {
@@ -64,7 +77,7 @@ async fn ensure_traffic_service(client: &Client, node: &StellarNode) -> Result<(
 
     let mut selector = super::resources::standard_labels(node);
     selector.insert("stellar.org/role".to_string(), "read-replica".to_string());
-    selector.insert("stellar.org/traffic".to_string(), "enabled".to_string());
+    selector.insert(TRAFFIC_LABEL.to_string(), "enabled".to_string());
 
     let ports = vec![ServicePort {
         name: Some("http".to_string()),
@@ -108,10 +121,7 @@ async fn update_pod_labels_based_on_lag(client: &Client, node: &StellarNode) ->
     let pod_api: Api<Pod> = Api::namespaced(client.clone(), &namespace);
 
     // Select read replicas
-    let label_selector = format!(
-        "app.kubernetes.io/instance={},stellar.org/role=read-replica",
-        node.name_any()
-    );
+    let label_selector = read_replica_selector(node);
     let lp = ListParams::default().labels(&label_selector);
     let pods = pod_api.list(&lp).await?;
 
@@ -170,10 +180,7 @@ async fn ensure_all_ready_pods_enabled(client: &Client, node: &StellarNode) -> R
     let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
     let pod_api: Api<Pod> = Api::namespaced(client.clone(), &namespace);
 
-    let label_selector = format!(
-        "app.kubernetes.io/instance={},stellar.org/role=read-replica",
-        node.name_any()
-    );
+    let label_selector = read_replica_selector(node);
     let pods = pod_api
         .list(&ListParams::default().labels(&label_selector))
         .await?;
@@ -198,14 +205,18 @@ async fn ensure_all_ready_pods_enabled(client: &Client, node: &StellarNode) -> R
 
 #[allow(dead_code)]
 async fn ensure_traffic_label(api: &Api<Pod>, pod: &Pod, enabled: bool) -> Result<()> {
-    let current_val = pod
-        .metadata
-        .labels
-        .as_ref()
-        .and_then(|l| l.get("stellar.org/traffic"))
+    let labels = pod.metadata.labels.as_ref();
+    let current_val = labels
+        .and_then(|l| l.get(TRAFFIC_LABEL))
         .map(|s| s.as_str());
 
-    let desired_val = if enabled { Some("enabled") } else { None };
+    // Pods drained for database maintenance stay out of rotation
+    let in_maintenance = labels.is_some_and(|l| l.contains_key(MAINTENANCE_LABEL));
+    let desired_val = if enabled && !in_maintenance {
+        Some("enabled")
+    } else {
+        None
+    };
 
     if current_val != desired_val {
         let name = pod.name_any();
@@ -217,7 +228,7 @@ async fn ensure_traffic_label(api: &Api<Pod>, pod: &Pod, enabled: bool) -> Resul
             serde_json::json!({
                 "metadata": {
                     "labels": {
-                        "stellar.org/traffic": val
+                        TRAFFIC_LABEL: val
                     }
                 }
             })
@@ -225,7 +236,7 @@ async fn ensure_traffic_label(api: &Api<Pod>, pod: &Pod, enabled: bool) -> Resul
             serde_json::json!({
                 "metadata": {
                     "labels": {
-                        "stellar.org/traffic": null
+                        TRAFFIC_LABEL: null
                     }
                 }
             })
@@ -48,6 +48,7 @@ mod tests {
             read_replica_config: None,
             db_maintenance_config: None,
             oci_snapshot: None,
+            decentralized_backup: None,
             service_mesh: None,
             resource_meta: None,
             vpa_config: None,
@@ -109,6 +110,7 @@ mod tests {
                 }),
                 db_maintenance_config: None,
                 oci_snapshot: None,
+                decentralized_backup: None,
                 service_mesh: None,
                 resource_meta: None,
                 vpa_config: None,
@@ -163,6 +165,7 @@ mod tests {
                 restore_from_snapshot: None,
                 read_replica_config: None,
                 oci_snapshot: None,
+                decentralized_backup: None,
                 service_mesh: None,
                 resource_meta: None,
                 vpa_config: None,
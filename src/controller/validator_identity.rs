@@ -0,0 +1,112 @@
+//! Publishes each validator's public key in `status.validatorPublicKey`
+//!
+//! Quorum sets and VSLs reference validators by `G…` public key, while the
+//! scheduler and quorum tooling work with StellarNodes. The operator derives
+//! the public key from the seed Secret the pod already consumes (a local
+//! Secret or the one ESO materialises) and records it in status, so the
+//! mapping is available cluster-wide without anyone reading the seed again.
+//!
+//! The seed is decoded in memory only; it is never logged or stored. CSI-backed
+//! seeds never reach the API server, so no key is published for them.
+
+use k8s_openapi::api::core::v1::Secret;
+use kube::{
+    api::{Api, Patch, PatchParams},
+    Client, ResourceExt,
+};
+use tracing::{debug, info};
+
+use super::kms_secret::eso_target_secret_name;
+use super::strkey;
+use crate::crd::{seed_secret::DEFAULT_SEED_KEY, StellarNode};
+use crate::error::{Error, Result};
+
+/// The Secret name and key holding a validator's seed, when the seed is
+/// available as a Kubernetes Secret
+pub fn seed_secret_key(node: &StellarNode) -> Option<(String, String)> {
+    let source = node.spec.validator_config.as_ref()?.resolve_seed_source()?;
+    if let Some(local) = &source.local_ref {
+        return Some((local.name.clone(), local.effective_key().to_string()));
+    }
+    if source.external_ref.is_some() {
+        return Some((
+            eso_target_secret_name(&node.name_any()),
+            DEFAULT_SEED_KEY.to_string(),
+        ));
+    }
+    None
+}
+
+/// Derive the validator's public key from its seed Secret.
+///
+/// Returns `Ok(None)` when the seed is not available as a Secret (CSI) or the
+/// Secret has not been created yet (e.g. ESO still syncing).
+pub async fn derive_public_key(client: &Client, node: &StellarNode) -> Result<Option<String>> {
+    let Some((secret_name, key)) = seed_secret_key(node) else {
+        return Ok(None);
+    };
+
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let secrets: Api<Secret> = Api::namespaced(client.clone(), &namespace);
+    let Some(secret) = secrets.get_opt(&secret_name).await? else {
+        debug!(
+            "Seed secret {}/{} not found yet; validator public key unknown",
+            namespace, secret_name
+        );
+        return Ok(None);
+    };
+
+    let seed = secret
+        .data
+        .as_ref()
+        .and_then(|d| d.get(&key))
+        .map(|v| String::from_utf8_lossy(&v.0).into_owned())
+        .or_else(|| {
+            secret
+                .string_data
+                .as_ref()
+                .and_then(|d| d.get(&key))
+                .cloned()
+        })
+        .ok_or_else(|| {
+            Error::ConfigError(format!(
+                "Seed secret {namespace}/{secret_name} has no key {key}"
+            ))
+        })?;
+
+    strkey::public_key_from_seed(&seed).map(Some)
+}
+
+/// Derive the validator public key and record it in status when it changed
+pub async fn reconcile_public_key(client: &Client, node: &StellarNode) -> Result<Option<String>> {
+    let Some(public_key) = derive_public_key(client, node).await? else {
+        return Ok(None);
+    };
+
+    let published = node
+        .status
+        .as_ref()
+        .and_then(|s| s.validator_public_key.as_deref());
+    if published != Some(public_key.as_str()) {
+        let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+        let api: Api<StellarNode> = Api::namespaced(client.clone(), &namespace);
+        let patch = serde_json::json!({
+            "status": { "validatorPublicKey": public_key }
+        });
+        api.patch_status(
+            &node.name_any(),
+            &PatchParams::apply("stellar-operator"),
+            &Patch::Merge(&patch),
+        )
+        .await
+        .map_err(Error::KubeError)?;
+        info!(
+            "Published validator public key {} for {}/{}",
+            public_key,
+            namespace,
+            node.name_any()
+        );
+    }
+
+    Ok(Some(public_key))
+}
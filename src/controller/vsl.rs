@@ -4,8 +4,11 @@
 //! This module:
 //!   1. Downloads the raw VSL document from a URL.
 //!   2. Parses it into a structured [`QuorumSet`] type.
-//!   3. Verifies the Ed25519 signature to prevent quorum-set poisoning.
-//!   4. Returns the verified [`QuorumSet`] ready for stellar-core.cfg generation.
+//!   3. Verifies the Ed25519 signature against the configured trust store
+//!      (see [`super::vsl_trust`]) to prevent quorum-set poisoning.
+//!   4. Rejects VSLs older than the highest sequence already accepted from
+//!      the same source.
+//!   5. Returns the verified [`QuorumSet`] ready for stellar-core.cfg generation.
 //!
 //! # VSL document format
 //!
@@ -42,26 +45,16 @@
 //! ```
 
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chrono::{DateTime, Utc};
 use ed25519_dalek::{Signature, VerifyingKey};
+use kube::Client;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
+use super::vsl_refresh;
+use super::vsl_trust::{self, TrustStore};
 use crate::error::{Error, Result};
 
-// ---------------------------------------------------------------------------
-// Public key constants for trusted VSL signers
-// ---------------------------------------------------------------------------
-
-/// Base64-encoded Ed25519 public keys of trusted VSL signers.
-///
-/// Add additional trusted keys here as Stellar entities publish their keys.
-/// These are the keys whose signatures we accept when verifying a VSL.
-pub const TRUSTED_VSL_SIGNERS: &[&str] = &[
-    // Stellar Development Foundation primary signing key (placeholder — replace
-    // with the real SDF key when integrating against production VSLs).
-    "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
-];
-
 // ---------------------------------------------------------------------------
 // Structured types
 // ---------------------------------------------------------------------------
@@ -152,6 +145,37 @@ impl QuorumSet {
     }
 }
 
+/// Validators added to or removed from a quorum set between two VSLs
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QuorumSetDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Threshold or inner set structure changed
+    pub structure_changed: bool,
+}
+
+impl QuorumSetDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && !self.structure_changed
+    }
+}
+
+/// Compare the validators and structure of two quorum sets.
+///
+/// Only what ends up in stellar-core.cfg counts; changes to names, hosts or
+/// history URLs do not require a config reload.
+pub fn diff_quorum_sets(old: &QuorumSet, new: &QuorumSet) -> QuorumSetDiff {
+    let keys = |qs: &QuorumSet| -> std::collections::BTreeSet<String> {
+        qs.validators.iter().map(|v| v.public_key.clone()).collect()
+    };
+    let (old_keys, new_keys) = (keys(old), keys(new));
+    QuorumSetDiff {
+        added: new_keys.difference(&old_keys).cloned().collect(),
+        removed: old_keys.difference(&new_keys).cloned().collect(),
+        structure_changed: old.threshold != new.threshold || old.inner_sets != new.inner_sets,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Raw TOML document shape (used for deserialization before verification)
 // ---------------------------------------------------------------------------
@@ -164,11 +188,11 @@ struct RawVslDocument {
     version: u32,
     /// Monotonically increasing sequence number
     #[serde(default)]
-    sequence: u32,
+    sequence: u64,
     /// Base64-encoded Ed25519 signature over the canonical document bytes
     #[serde(default)]
     signature: String,
-    /// Base64-encoded Ed25519 public key of the signer
+    /// Ed25519 public key of the signer (G… strkey or base64)
     #[serde(default)]
     signing_key: String,
     /// List of validators
@@ -201,25 +225,17 @@ fn canonical_bytes(raw_toml: &str) -> Vec<u8> {
 }
 
 /// Verifies that `signature_b64` is a valid Ed25519 signature over
-/// `canonical_payload` produced by the key `pubkey_b64`.
+/// `canonical_payload` produced by `pubkey` (a `G…` strkey or base64).
 ///
 /// Returns `Ok(())` on success or an `Error::ConfigError` on any failure.
 pub fn verify_ed25519_signature(
-    pubkey_b64: &str,
+    pubkey: &str,
     signature_b64: &str,
     canonical_payload: &[u8],
 ) -> Result<()> {
     // Decode public key
-    let pubkey_bytes = BASE64
-        .decode(pubkey_b64)
-        .map_err(|e| Error::ConfigError(format!("Invalid base64 in signing_key: {e}")))?;
-
-    let pubkey_arr: [u8; 32] = pubkey_bytes.as_slice().try_into().map_err(|_| {
-        Error::ConfigError(format!(
-            "signing_key must be 32 bytes, got {}",
-            pubkey_bytes.len()
-        ))
-    })?;
+    let pubkey_arr = vsl_trust::decode_ed25519_key(pubkey)
+        .map_err(|e| Error::ConfigError(format!("Invalid signing_key: {e}")))?;
 
     let verifying_key = VerifyingKey::from_bytes(&pubkey_arr)
         .map_err(|e| Error::ConfigError(format!("Invalid Ed25519 public key: {e}")))?;
@@ -247,28 +263,40 @@ pub fn verify_ed25519_signature(
     Ok(())
 }
 
-/// Returns `true` if `pubkey_b64` is in the list of trusted VSL signers.
-fn is_trusted_signer(pubkey_b64: &str) -> bool {
-    TRUSTED_VSL_SIGNERS.contains(&pubkey_b64)
-}
-
 // ---------------------------------------------------------------------------
 // Parsing
 // ---------------------------------------------------------------------------
 
+/// A VSL that passed parsing and signature verification
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedVsl {
+    pub quorum_set: QuorumSet,
+    /// The document's `sequence`
+    pub sequence: u64,
+    /// SHA-256 of the raw document
+    pub sha256: String,
+    /// Whether the document was signed by a trusted signer
+    pub signed: bool,
+}
+
 /// Parse a raw TOML string into a [`QuorumSet`], verifying the signature.
 ///
 /// # Verification flow
 ///
 /// 1. Parse the TOML to extract `signing_key` and `signature`.
-/// 2. Check that `signing_key` is in [`TRUSTED_VSL_SIGNERS`].
+/// 2. Check that `signing_key` belongs to a signer in `trust` that is valid
+///    at `now`.
 /// 3. Strip signature/signing_key lines to produce the canonical payload.
 /// 4. Verify the Ed25519 signature over the canonical payload.
 /// 5. If all checks pass, return the structured [`QuorumSet`].
 ///
-/// If the document has no `signature` field (e.g. in development/testing),
-/// verification is skipped with a warning.
-pub fn parse_and_verify_vsl(raw_toml: &str) -> Result<QuorumSet> {
+/// A document without a `signature` field (e.g. in development/testing) is
+/// only accepted, with a warning, when the trust store allows unsigned VSLs.
+pub fn parse_and_verify_vsl(
+    raw_toml: &str,
+    trust: &TrustStore,
+    now: DateTime<Utc>,
+) -> Result<VerifiedVsl> {
     // Step 1: parse the raw document
     let doc: RawVslDocument = toml::from_str(raw_toml)
         .map_err(|e| Error::ConfigError(format!("Failed to parse VSL TOML: {e}")))?;
@@ -285,27 +313,34 @@ pub fn parse_and_verify_vsl(raw_toml: &str) -> Result<QuorumSet> {
     );
 
     // Step 2 & 3 & 4: signature verification
-    if doc.signature.is_empty() {
+    let signed = !doc.signature.is_empty();
+    if !signed {
+        if !trust.allow_unsigned() {
+            return Err(Error::ConfigError(format!(
+                "VSL has no signature and the trust configuration does not allow unsigned VSLs. \
+                 Set allowUnsigned: true in the {} ConfigMap to accept them.",
+                vsl_trust::TRUST_CONFIG_MAP_NAME
+            )));
+        }
         warn!(
             "VSL has no signature field — skipping verification. \
              Do NOT use unsigned VSLs in production."
         );
     } else {
         // Check the signer is trusted
-        if !is_trusted_signer(&doc.signing_key) {
-            return Err(Error::ConfigError(format!(
-                "VSL signing key '{}' is not in the trusted signers list. \
-                 Add it to TRUSTED_VSL_SIGNERS if this is intentional.",
-                doc.signing_key
-            )));
-        }
+        let key = vsl_trust::decode_ed25519_key(&doc.signing_key)
+            .map_err(|e| Error::ConfigError(format!("Invalid signing_key: {e}")))?;
+        let signer = trust.check(&key, now)?;
 
         // Compute canonical payload and verify
         let payload = canonical_bytes(raw_toml);
         verify_ed25519_signature(&doc.signing_key, &doc.signature, &payload)?;
         info!(
             "VSL signature verified successfully (signer={})",
-            &doc.signing_key[..8.min(doc.signing_key.len())]
+            signer
+                .name
+                .as_deref()
+                .unwrap_or(&doc.signing_key[..8.min(doc.signing_key.len())])
         );
     }
 
@@ -314,10 +349,15 @@ pub fn parse_and_verify_vsl(raw_toml: &str) -> Result<QuorumSet> {
     // Default threshold: simple majority
     let threshold = (total / 2) + 1;
 
-    Ok(QuorumSet {
-        threshold,
-        validators: doc.validators,
-        inner_sets: doc.quorum_sets,
+    Ok(VerifiedVsl {
+        quorum_set: QuorumSet {
+            threshold,
+            validators: doc.validators,
+            inner_sets: doc.quorum_sets,
+        },
+        sequence: doc.sequence,
+        sha256: vsl_trust::document_hash(raw_toml),
+        signed,
     })
 }
 
@@ -325,13 +365,26 @@ pub fn parse_and_verify_vsl(raw_toml: &str) -> Result<QuorumSet> {
 // Public API
 // ---------------------------------------------------------------------------
 
-/// Fetch a VSL from `url`, parse it, verify its signature, and return the
-/// structured [`QuorumSet`].
-///
-/// This replaces the old `fetch_vsl` that returned a raw `String`.
-/// The reconciler passes the returned [`QuorumSet`] to the
-/// stellar-core.cfg generation logic.
-pub async fn fetch_vsl(url: &str) -> Result<QuorumSet> {
+/// Outcome of a conditional VSL download
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VslResponse {
+    /// The server answered 304: the previously fetched document is current
+    NotModified,
+    /// A new document, with the validators to send on the next request
+    Modified {
+        body: String,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+/// Download a VSL, sending `If-None-Match`/`If-Modified-Since` from a
+/// previous response so unchanged lists are not transferred again.
+pub async fn fetch_vsl_conditional(
+    url: &str,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+) -> Result<VslResponse> {
     debug!("Fetching VSL from {}", url);
 
     let client = reqwest::Client::builder()
@@ -339,8 +392,19 @@ pub async fn fetch_vsl(url: &str) -> Result<QuorumSet> {
         .build()
         .map_err(|e| Error::ConfigError(format!("Failed to build HTTP client: {e}")))?;
 
-    let response = client.get(url).send().await.map_err(Error::HttpError)?;
+    let mut request = client.get(url);
+    if let Some(etag) = etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = last_modified {
+        request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+    }
+    let response = request.send().await.map_err(Error::HttpError)?;
 
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        debug!("VSL at {} not modified", url);
+        return Ok(VslResponse::NotModified);
+    }
     if !response.status().is_success() {
         return Err(Error::ConfigError(format!(
             "Failed to fetch VSL from {}: HTTP {}",
@@ -349,10 +413,57 @@ pub async fn fetch_vsl(url: &str) -> Result<QuorumSet> {
         )));
     }
 
-    let raw_toml = response.text().await.map_err(Error::HttpError)?;
-    info!("Fetched VSL from {} ({} bytes)", url, raw_toml.len());
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let etag = header(reqwest::header::ETAG);
+    let last_modified = header(reqwest::header::LAST_MODIFIED);
+
+    let body = response.text().await.map_err(Error::HttpError)?;
+    info!("Fetched VSL from {} ({} bytes)", url, body.len());
+
+    Ok(VslResponse::Modified {
+        body,
+        etag,
+        last_modified,
+    })
+}
 
-    parse_and_verify_vsl(&raw_toml)
+/// Fetch a VSL from `url`, parse it and verify its signature against `trust`.
+pub async fn fetch_vsl(url: &str, trust: &TrustStore) -> Result<VerifiedVsl> {
+    match fetch_vsl_conditional(url, None, None).await? {
+        VslResponse::Modified { body, .. } => parse_and_verify_vsl(&body, trust, Utc::now()),
+        VslResponse::NotModified => Err(Error::ConfigError(format!(
+            "Unexpected 304 Not Modified for unconditional VSL request to {url}"
+        ))),
+    }
+}
+
+/// Fetch and verify a VSL using the cluster trust store in
+/// `operator_namespace`, rejecting documents older than the highest sequence
+/// already accepted from `url`.
+///
+/// The reconciler passes the returned [`QuorumSet`] to the
+/// stellar-core.cfg generation logic. A VSL whose quorum set fails the
+/// safety analysis yields the refusal reason instead, like the refresher.
+pub async fn fetch_trusted_vsl(
+    client: &Client,
+    operator_namespace: &str,
+    url: &str,
+) -> Result<std::result::Result<QuorumSet, String>> {
+    let trust = TrustStore::load(client, operator_namespace).await?;
+    let vsl = fetch_vsl(url, &trust).await?;
+    match vsl_refresh::check_quorum_safety(url, &vsl) {
+        Ok(()) => {}
+        Err(Error::ConfigError(reason)) => return Ok(Err(reason)),
+        Err(e) => return Err(e),
+    }
+    vsl_trust::enforce_sequence(client, operator_namespace, url, vsl.sequence, &vsl.sha256).await?;
+    Ok(Ok(vsl.quorum_set))
 }
 
 /// Trigger a configuration reload in Stellar Core if it's already running.
@@ -431,6 +542,19 @@ host = "v3.example.com"
         format!("signing_key = \"{pubkey_b64}\"\nsignature = \"{sig_b64}\"\n{body}")
     }
 
+    /// Trust store that trusts exactly `pubkey`.
+    fn trusting(pubkey: &str) -> TrustStore {
+        TrustStore::from_yaml(&format!(
+            "signers:\n  - name: test\n    key: \"{pubkey}\"\n"
+        ))
+        .unwrap()
+    }
+
+    fn parse_dev(raw: &str) -> Result<QuorumSet> {
+        let trust = TrustStore::from_yaml("allowUnsigned: true\n").unwrap();
+        parse_and_verify_vsl(raw, &trust, Utc::now()).map(|v| v.quorum_set)
+    }
+
     // -----------------------------------------------------------------------
     // canonical_bytes
     // -----------------------------------------------------------------------
@@ -523,7 +647,7 @@ host = "v3.example.com"
     #[test]
     fn test_parse_unsigned_vsl_succeeds_with_warning() {
         let raw = minimal_unsigned_vsl();
-        let result = parse_and_verify_vsl(&raw);
+        let result = parse_dev(&raw);
         assert!(result.is_ok(), "unsigned VSL should parse: {:?}", result);
         let qs = result.unwrap();
         assert_eq!(qs.validators.len(), 3);
@@ -532,7 +656,7 @@ host = "v3.example.com"
     #[test]
     fn test_parse_vsl_threshold_is_majority() {
         let raw = minimal_unsigned_vsl(); // 3 validators
-        let qs = parse_and_verify_vsl(&raw).unwrap();
+        let qs = parse_dev(&raw).unwrap();
         // majority of 3 = 2
         assert_eq!(qs.threshold, 2);
     }
@@ -540,7 +664,7 @@ host = "v3.example.com"
     #[test]
     fn test_parse_vsl_validator_fields() {
         let raw = minimal_unsigned_vsl();
-        let qs = parse_and_verify_vsl(&raw).unwrap();
+        let qs = parse_dev(&raw).unwrap();
         let v = &qs.validators[0];
         assert_eq!(v.name, "Test Validator 1");
         assert_eq!(
@@ -553,14 +677,14 @@ host = "v3.example.com"
     #[test]
     fn test_parse_empty_validators_fails() {
         let raw = "version = 1\nsequence = 1\n";
-        let result = parse_and_verify_vsl(raw);
+        let result = parse_dev(raw);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("no validators"));
     }
 
     #[test]
     fn test_parse_invalid_toml_fails() {
-        let result = parse_and_verify_vsl("this is not toml [[[");
+        let result = parse_dev("this is not toml [[[");
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
@@ -578,10 +702,41 @@ host = "v3.example.com"
         let canonical = canonical_bytes(&body);
         let (_, pubkey_b64, sig_b64) = sign_payload(&canonical);
 
-        // Temporarily override trusted signers by calling verify directly
-        // (we can't mutate the const in tests, so we test the verify path directly)
-        let result = verify_ed25519_signature(&pubkey_b64, &sig_b64, &canonical);
+        let doc = signed_vsl(&pubkey_b64, &sig_b64, &body);
+        let result = parse_and_verify_vsl(&doc, &trusting(&pubkey_b64), Utc::now());
         assert!(result.is_ok(), "signature should verify: {:?}", result);
+        let vsl = result.unwrap();
+        assert!(vsl.signed);
+        assert_eq!(vsl.sequence, 1);
+        assert_eq!(vsl.quorum_set.validators.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_signed_vsl_with_strkey_signing_key() {
+        let body = minimal_unsigned_vsl();
+        let canonical = canonical_bytes(&body);
+        let (signing_key, _, sig_b64) = sign_payload(&canonical);
+        let strkey =
+            crate::controller::strkey::encode_public_key(signing_key.verifying_key().as_bytes());
+
+        let doc = signed_vsl(&strkey, &sig_b64, &body);
+        assert!(parse_and_verify_vsl(&doc, &trusting(&strkey), Utc::now()).is_ok());
+    }
+
+    #[test]
+    fn test_parse_unsigned_vsl_rejected_by_configured_store() {
+        let (_, pubkey_b64, _) = sign_payload(b"unused");
+        let result =
+            parse_and_verify_vsl(&minimal_unsigned_vsl(), &trusting(&pubkey_b64), Utc::now());
+        assert!(result.unwrap_err().to_string().contains("no signature"));
+
+        // Without a trust configuration unsigned VSLs are rejected as well
+        let result = parse_and_verify_vsl(
+            &minimal_unsigned_vsl(),
+            &TrustStore::unconfigured(),
+            Utc::now(),
+        );
+        assert!(result.unwrap_err().to_string().contains("allowUnsigned"));
     }
 
     #[test]
@@ -592,7 +747,8 @@ host = "v3.example.com"
 
         // Build a document with a real signature but an untrusted signer
         let doc = signed_vsl(&pubkey_b64, &sig_b64, &body);
-        let result = parse_and_verify_vsl(&doc);
+        let (_, other_b64, _) = sign_payload(b"other");
+        let result = parse_and_verify_vsl(&doc, &trusting(&other_b64), Utc::now());
         assert!(result.is_err());
         let msg = result.unwrap_err().to_string();
         assert!(msg.contains("not in the trusted signers list"));
@@ -608,11 +764,11 @@ host = "v3.example.com"
         let tampered = body.replace("Test Validator 1", "EVIL VALIDATOR");
         let doc = signed_vsl(&pubkey_b64, &sig_b64, &tampered);
 
-        // Even though the key is untrusted, we get past that check only if the
-        // key is trusted — here the untrusted-key error fires first, which is
-        // correct behaviour (defence in depth).
-        let result = parse_and_verify_vsl(&doc);
-        assert!(result.is_err());
+        let result = parse_and_verify_vsl(&doc, &trusting(&pubkey_b64), Utc::now());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("signature verification failed"));
     }
 
     // -----------------------------------------------------------------------
@@ -693,21 +849,4 @@ host = "v3.example.com"
         assert!(toml_out.contains("[QUORUM_SET.0]"));
         assert!(toml_out.contains("THRESHOLD_PERCENT=2"));
     }
-
-    // -----------------------------------------------------------------------
-    // is_trusted_signer
-    // -----------------------------------------------------------------------
-
-    #[test]
-    fn test_is_trusted_signer_known_key() {
-        // The placeholder key in TRUSTED_VSL_SIGNERS
-        assert!(is_trusted_signer(
-            "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA="
-        ));
-    }
-
-    #[test]
-    fn test_is_trusted_signer_unknown_key() {
-        assert!(!is_trusted_signer("completely-unknown-key"));
-    }
 }
@@ -0,0 +1,360 @@
+//! Periodic VSL refresh for validators using `vlSource`
+//!
+//! The reconciler only fetches a validator's VSL when the StellarNode is
+//! reconciled, so a new list would otherwise sit unused until someone touched
+//! the CR. The refresher polls every distinct `vlSource` on an interval:
+//!
+//! - Requests are conditional (`If-None-Match` / `If-Modified-Since`), so an
+//!   unchanged list costs a 304.
+//! - New documents go through the same trust store and sequence checks as
+//!   the reconciler (see [`super::vsl_trust`]).
+//! - Validators whose applied VSL differs get their stellar-core ConfigMap
+//!   re-rendered and their ready pods reloaded one at a time, pausing between
+//!   pods so the network never loses several validators at once. If only VSL
+//!   metadata changed, the reload is skipped.
+//! - The applied sequence and hash are recorded in `status.appliedVsl`.
+//!   Validators whose status already names the fetched document are left
+//!   alone, so a new leader does not reload every validator.
+//! - A VSL whose quorum set fails [`super::quorum_analysis`] is not rolled
+//!   out; validators stay on the last safe list.
+//!
+//! Only the leader refreshes.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use k8s_openapi::api::core::v1::Pod;
+use kube::{
+    api::{Api, ListParams, Patch, PatchParams},
+    Client, ResourceExt,
+};
+use tracing::{debug, info, warn};
+
+use super::quorum_analysis;
+use super::resources;
+use super::vsl::{self, VerifiedVsl, VslResponse};
+use super::vsl_trust::{self, TrustStore};
+use crate::crd::{AppliedVslStatus, NodeType, StellarNode};
+use crate::error::{Error, Result};
+
+/// Configuration for the VSL refresher
+#[derive(Clone, Debug)]
+pub struct VslRefreshConfig {
+    /// How often each `vlSource` is polled
+    pub interval: Duration,
+    /// Pause between reloading consecutive validator pods
+    pub reload_pause: Duration,
+    /// Render mTLS settings into the regenerated config
+    pub enable_mtls: bool,
+}
+
+impl Default for VslRefreshConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(300),
+            reload_pause: Duration::from_secs(10),
+            enable_mtls: false,
+        }
+    }
+}
+
+/// What is known about one `vlSource`
+#[derive(Debug, Default)]
+struct SourceState {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    current: Option<VerifiedVsl>,
+    /// The VSL `current` replaced, to tell metadata-only updates apart
+    previous: Option<VerifiedVsl>,
+}
+
+/// Background task keeping validators on the latest VSL
+pub struct VslRefresher {
+    client: Client,
+    operator_namespace: String,
+    is_leader: Arc<AtomicBool>,
+    config: VslRefreshConfig,
+    sources: HashMap<String, SourceState>,
+}
+
+impl VslRefresher {
+    pub fn new(
+        client: Client,
+        operator_namespace: String,
+        is_leader: Arc<AtomicBool>,
+        config: VslRefreshConfig,
+    ) -> Self {
+        Self {
+            client,
+            operator_namespace,
+            is_leader,
+            config,
+            sources: HashMap::new(),
+        }
+    }
+
+    /// Poll all VSL sources until the process exits
+    pub async fn run(mut self) -> Result<()> {
+        info!(
+            "Starting VSL refresher (interval: {}s)",
+            self.config.interval.as_secs()
+        );
+        loop {
+            if self.is_leader.load(Ordering::Relaxed) {
+                if let Err(e) = self.refresh_all().await {
+                    warn!("VSL refresh failed: {}", e);
+                }
+            }
+            tokio::time::sleep(self.config.interval).await;
+        }
+    }
+
+    async fn refresh_all(&mut self) -> Result<()> {
+        let api: Api<StellarNode> = Api::all(self.client.clone());
+        let nodes = api.list(&ListParams::default()).await?.items;
+        let by_source = group_by_source(nodes);
+
+        // Forget sources no validator references anymore
+        self.sources
+            .retain(|source, _| by_source.contains_key(source));
+        if by_source.is_empty() {
+            return Ok(());
+        }
+
+        let trust = TrustStore::load(&self.client, &self.operator_namespace).await?;
+        for (source, nodes) in by_source {
+            if let Err(e) = self.refresh_source(&trust, &source, &nodes).await {
+                warn!("Failed to refresh VSL from {}: {}", source, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn refresh_source(
+        &mut self,
+        trust: &TrustStore,
+        source: &str,
+        nodes: &[StellarNode],
+    ) -> Result<()> {
+        let state = self.sources.entry(source.to_string()).or_default();
+        let response = vsl::fetch_vsl_conditional(
+            source,
+            state.etag.as_deref(),
+            state.last_modified.as_deref(),
+        )
+        .await?;
+
+        if let VslResponse::Modified {
+            body,
+            etag,
+            last_modified,
+        } = response
+        {
+            let verified = vsl::parse_and_verify_vsl(&body, trust, Utc::now())?;
+            check_quorum_safety(source, &verified)?;
+            vsl_trust::enforce_sequence(
+                &self.client,
+                &self.operator_namespace,
+                source,
+                verified.sequence,
+                &verified.sha256,
+            )
+            .await?;
+            if state.current.as_ref().map(|c| &c.sha256) != Some(&verified.sha256) {
+                if let Some(current) = &state.current {
+                    let diff = vsl::diff_quorum_sets(&current.quorum_set, &verified.quorum_set);
+                    info!(
+                        "VSL from {} changed (sequence {} -> {}): {} added, {} removed{}",
+                        source,
+                        current.sequence,
+                        verified.sequence,
+                        diff.added.len(),
+                        diff.removed.len(),
+                        if diff.structure_changed {
+                            ", thresholds changed"
+                        } else {
+                            ""
+                        }
+                    );
+                }
+                state.previous = state.current.replace(verified);
+            }
+            state.etag = etag;
+            state.last_modified = last_modified;
+        }
+
+        let Some(current) = state.current.clone() else {
+            return Ok(());
+        };
+        let previous = state.previous.clone();
+
+        for node in nodes {
+            let applied = node.status.as_ref().and_then(|s| s.applied_vsl.as_ref());
+            if applied.is_some_and(|a| a.source == source && a.sha256 == current.sha256) {
+                continue;
+            }
+            let reload = needs_reload(applied, source, previous.as_ref(), &current);
+            if let Err(e) = self.apply(node, source, &current, reload).await {
+                warn!(
+                    "Failed to apply VSL sequence {} to {}/{}: {}",
+                    current.sequence,
+                    node.namespace().unwrap_or_default(),
+                    node.name_any(),
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Render `vsl` into the node's config, reload its pods and record it
+    async fn apply(
+        &self,
+        node: &StellarNode,
+        source: &str,
+        vsl: &VerifiedVsl,
+        reload: bool,
+    ) -> Result<()> {
+        let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+        let name = node.name_any();
+
+        if reload {
+            resources::ensure_config_map(
+                &self.client,
+                node,
+                Some(vsl.quorum_set.clone()),
+                self.config.enable_mtls,
+            )
+            .await?;
+            self.rolling_reload(&namespace, &name).await?;
+        } else {
+            debug!(
+                "VSL for {}/{} changed without quorum changes; skipping reload",
+                namespace, name
+            );
+        }
+
+        let status = AppliedVslStatus {
+            source: source.to_string(),
+            sequence: vsl.sequence,
+            sha256: vsl.sha256.clone(),
+            applied_at: Utc::now().to_rfc3339(),
+        };
+        let api: Api<StellarNode> = Api::namespaced(self.client.clone(), &namespace);
+        api.patch_status(
+            &name,
+            &PatchParams::apply("stellar-operator"),
+            &Patch::Merge(&serde_json::json!({ "status": { "appliedVsl": status } })),
+        )
+        .await
+        .map_err(Error::KubeError)?;
+        info!(
+            "Applied VSL sequence {} to {}/{}",
+            vsl.sequence, namespace, name
+        );
+        Ok(())
+    }
+
+    /// Reload ready pods one at a time; stops at the first failure so the
+    /// VSL is retried (and not recorded) on the next refresh.
+    async fn rolling_reload(&self, namespace: &str, name: &str) -> Result<()> {
+        let pods: Api<Pod> = Api::namespaced(self.client.clone(), namespace);
+        let lp = ListParams::default().labels(&format!("app.kubernetes.io/instance={name}"));
+        let mut pods = pods.list(&lp).await?.items;
+        pods.sort_by_key(|p| p.name_any());
+
+        let mut reloaded = 0;
+        for pod in &pods {
+            let Some(ip) = pod_ip_if_ready(pod) else {
+                // Pods that are not ready pick up the new config when they start
+                debug!("Skipping config-reload for unready pod {}", pod.name_any());
+                continue;
+            };
+            if reloaded > 0 {
+                tokio::time::sleep(self.config.reload_pause).await;
+            }
+            vsl::trigger_config_reload(ip).await?;
+            reloaded += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Refuse to roll out a VSL whose quorum set fails the safety analysis
+pub(crate) fn check_quorum_safety(source: &str, vsl: &VerifiedVsl) -> Result<()> {
+    let Some(config) =
+        quorum_analysis::parse_quorum_config(&vsl.quorum_set.to_stellar_core_toml())?
+    else {
+        return Ok(());
+    };
+    let report = quorum_analysis::analyze(&config);
+    for warning in report.warnings() {
+        warn!("VSL sequence {} from {}: {}", vsl.sequence, source, warning);
+    }
+    if !report.is_safe() {
+        return Err(Error::ConfigError(format!(
+            "Refusing to roll out VSL sequence {} from {}: {}",
+            vsl.sequence,
+            source,
+            report.errors().collect::<Vec<_>>().join("; ")
+        )));
+    }
+    Ok(())
+}
+
+/// Validators with a `vlSource`, grouped by source
+pub(crate) fn group_by_source(nodes: Vec<StellarNode>) -> BTreeMap<String, Vec<StellarNode>> {
+    let mut groups: BTreeMap<String, Vec<StellarNode>> = BTreeMap::new();
+    for node in nodes {
+        if node.spec.node_type != NodeType::Validator || node.metadata.deletion_timestamp.is_some()
+        {
+            continue;
+        }
+        let Some(source) = node
+            .spec
+            .validator_config
+            .as_ref()
+            .and_then(|c| c.vl_source.clone())
+        else {
+            continue;
+        };
+        groups.entry(source).or_default().push(node);
+    }
+    groups
+}
+
+/// Whether moving a node from `applied` to `current` changes its quorum set.
+///
+/// Without knowing what the node runs (no status, another source, or an
+/// older VSL than the one `current` replaced) the config is always rendered.
+pub(crate) fn needs_reload(
+    applied: Option<&AppliedVslStatus>,
+    source: &str,
+    previous: Option<&VerifiedVsl>,
+    current: &VerifiedVsl,
+) -> bool {
+    match (applied, previous) {
+        (Some(applied), Some(previous))
+            if applied.source == source && applied.sha256 == previous.sha256 =>
+        {
+            !vsl::diff_quorum_sets(&previous.quorum_set, &current.quorum_set).is_empty()
+        }
+        _ => true,
+    }
+}
+
+fn pod_ip_if_ready(pod: &Pod) -> Option<&str> {
+    let status = pod.status.as_ref()?;
+    let ready = status
+        .conditions
+        .as_ref()?
+        .iter()
+        .any(|c| c.type_ == "Ready" && c.status == "True");
+    if !ready {
+        return None;
+    }
+    status.pod_ip.as_deref()
+}
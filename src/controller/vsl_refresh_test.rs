@@ -0,0 +1,207 @@
+//! Tests for the VSL refresher and conditional VSL fetching.
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::vsl::*;
+    use crate::controller::vsl_refresh::{group_by_source, needs_reload};
+    use crate::crd::{AppliedVslStatus, StellarNode};
+
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const SOURCE: &str = "https://vsl.example.com/vsl.toml";
+
+    fn quorum(keys: &[&str]) -> QuorumSet {
+        QuorumSet {
+            threshold: (keys.len() as u32 / 2) + 1,
+            validators: keys
+                .iter()
+                .map(|k| VslValidator {
+                    name: k.to_string(),
+                    public_key: k.to_string(),
+                    host: None,
+                    history: None,
+                })
+                .collect(),
+            inner_sets: vec![],
+        }
+    }
+
+    fn verified(sequence: u64, sha256: &str, quorum_set: QuorumSet) -> VerifiedVsl {
+        VerifiedVsl {
+            quorum_set,
+            sequence,
+            sha256: sha256.to_string(),
+            signed: true,
+        }
+    }
+
+    fn applied(sha256: &str) -> AppliedVslStatus {
+        AppliedVslStatus {
+            source: SOURCE.to_string(),
+            sequence: 1,
+            sha256: sha256.to_string(),
+            applied_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Conditional fetch
+    // -----------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn test_conditional_fetch_uses_etag() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/vsl.toml"))
+            .and(header("if-none-match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/vsl.toml"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("ETag", "\"v1\"")
+                    .insert_header("Last-Modified", "Wed, 01 Jan 2026 00:00:00 GMT")
+                    .set_body_string("sequence = 1"),
+            )
+            .mount(&server)
+            .await;
+        let url = format!("{}/vsl.toml", server.uri());
+
+        let first = fetch_vsl_conditional(&url, None, None).await.unwrap();
+        assert_eq!(
+            first,
+            VslResponse::Modified {
+                body: "sequence = 1".to_string(),
+                etag: Some("\"v1\"".to_string()),
+                last_modified: Some("Wed, 01 Jan 2026 00:00:00 GMT".to_string()),
+            }
+        );
+
+        let second = fetch_vsl_conditional(&url, Some("\"v1\""), None)
+            .await
+            .unwrap();
+        assert_eq!(second, VslResponse::NotModified);
+    }
+
+    #[tokio::test]
+    async fn test_conditional_fetch_surfaces_http_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+        let err = fetch_vsl_conditional(&server.uri(), None, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("503"));
+    }
+
+    // -----------------------------------------------------------------------
+    // Quorum set diff and reload decisions
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_diff_quorum_sets() {
+        let old = quorum(&["GA", "GB", "GC"]);
+        assert!(diff_quorum_sets(&old, &old).is_empty());
+
+        // Metadata-only change
+        let mut renamed = old.clone();
+        renamed.validators[0].name = "SDF 1".to_string();
+        assert!(diff_quorum_sets(&old, &renamed).is_empty());
+
+        let diff = diff_quorum_sets(&old, &quorum(&["GA", "GB", "GD"]));
+        assert_eq!(diff.added, vec!["GD".to_string()]);
+        assert_eq!(diff.removed, vec!["GC".to_string()]);
+
+        let mut stricter = old.clone();
+        stricter.threshold = 3;
+        assert!(diff_quorum_sets(&old, &stricter).structure_changed);
+    }
+
+    #[test]
+    fn test_needs_reload() {
+        let previous = verified(1, "aaa", quorum(&["GA", "GB", "GC"]));
+        let bumped = verified(2, "bbb", quorum(&["GA", "GB", "GC"]));
+        let changed = verified(2, "ccc", quorum(&["GA", "GB", "GD"]));
+
+        // Node runs the previous VSL and only the sequence moved
+        assert!(!needs_reload(
+            Some(&applied("aaa")),
+            SOURCE,
+            Some(&previous),
+            &bumped
+        ));
+        assert!(needs_reload(
+            Some(&applied("aaa")),
+            SOURCE,
+            Some(&previous),
+            &changed
+        ));
+
+        // Unknown starting point: always render
+        assert!(needs_reload(None, SOURCE, Some(&previous), &bumped));
+        assert!(needs_reload(Some(&applied("aaa")), SOURCE, None, &bumped));
+        assert!(needs_reload(
+            Some(&applied("old")),
+            SOURCE,
+            Some(&previous),
+            &bumped
+        ));
+        assert!(needs_reload(
+            Some(&applied("aaa")),
+            "https://other.example.com/vsl.toml",
+            Some(&previous),
+            &bumped
+        ));
+    }
+
+    // -----------------------------------------------------------------------
+    // Source grouping
+    // -----------------------------------------------------------------------
+
+    fn node(name: &str, node_type: &str, vl_source: Option<&str>) -> StellarNode {
+        let mut spec = serde_json::json!({
+            "nodeType": node_type,
+            "network": "Testnet",
+            "version": "v21.0.0",
+        });
+        if let Some(source) = vl_source {
+            spec["validatorConfig"] = serde_json::json!({
+                "seedSecretRef": "validator-seed",
+                "vlSource": source,
+            });
+        }
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "stellar.org/v1alpha1",
+            "kind": "StellarNode",
+            "metadata": { "name": name, "namespace": "stellar" },
+            "spec": spec,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_group_by_source() {
+        let groups = group_by_source(vec![
+            node("validator-1", "Validator", Some(SOURCE)),
+            node("validator-2", "Validator", Some(SOURCE)),
+            node(
+                "validator-3",
+                "Validator",
+                Some("https://other.example.com/vsl.toml"),
+            ),
+            node("validator-4", "Validator", None),
+            node("horizon", "Horizon", None),
+        ]);
+        assert_eq!(groups.len(), 2);
+        let names: Vec<String> = groups[SOURCE]
+            .iter()
+            .map(|n| n.metadata.name.clone().unwrap())
+            .collect();
+        assert_eq!(names, vec!["validator-1", "validator-2"]);
+    }
+}
@@ -0,0 +1,315 @@
+//! Trust configuration and rollback protection for signed VSLs
+//!
+//! Trusted VSL signers are configured cluster-wide in the `stellar-vsl-trust`
+//! ConfigMap in the operator namespace, under the `signers.yaml` key:
+//!
+//! ```yaml
+//! # Reject VSLs without a signature (default: false)
+//! allowUnsigned: false
+//! signers:
+//!   - name: sdf-2025
+//!     # Stellar public key (G…) or base64-encoded 32-byte Ed25519 key
+//!     key: GC2BKLYOOYPDEFJKLKY6FNNRQMGFLVHJKQRGNSSRRGSMPGF32LHCQVGF
+//!     notAfter: "2026-01-31T00:00:00Z"
+//!   - name: sdf-2026
+//!     key: 4ASuW3m7tQ1bK4f1A4r1J3l4b0mK5q8yYdG2o9mQ0bE=
+//!     notBefore: "2026-01-01T00:00:00Z"
+//! ```
+//!
+//! Several keys may be valid at once, so a signer can rotate by publishing
+//! the new key with an overlapping validity window. Without the ConfigMap no
+//! signer is trusted and unsigned VSLs are rejected, so every VSL is. Setups
+//! that use unsigned VSLs, such as development clusters, opt in with
+//! `allowUnsigned: true`.
+//!
+//! The highest accepted `sequence` of every VSL source is persisted in the
+//! `stellar-vsl-sequences` ConfigMap, so an older VSL cannot be replayed to
+//! roll back the quorum set, even across restarts.
+
+use std::collections::BTreeMap;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::{
+    api::{Api, ObjectMeta, PostParams},
+    Client,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use super::strkey;
+use crate::error::{Error, Result};
+
+/// ConfigMap (in the operator namespace) holding the trusted signers
+pub const TRUST_CONFIG_MAP_NAME: &str = "stellar-vsl-trust";
+/// Key of the trust configuration inside [`TRUST_CONFIG_MAP_NAME`]
+pub const TRUST_CONFIG_KEY: &str = "signers.yaml";
+/// ConfigMap (in the operator namespace) recording accepted VSL sequences
+pub const SEQUENCE_CONFIG_MAP_NAME: &str = "stellar-vsl-sequences";
+
+// ---------------------------------------------------------------------------
+// Trust configuration
+// ---------------------------------------------------------------------------
+
+/// A signing key trusted for VSLs, optionally limited to a validity window
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedSigner {
+    /// Human-readable label used in logs and errors
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Stellar public key (G…) or base64-encoded 32-byte Ed25519 key
+    pub key: String,
+    /// The key is not trusted before this instant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<DateTime<Utc>>,
+    /// The key is not trusted after this instant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_after: Option<DateTime<Utc>>,
+}
+
+impl TrustedSigner {
+    fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.key)
+    }
+
+    fn is_valid_at(&self, at: DateTime<Utc>) -> bool {
+        self.not_before.is_none_or(|nb| at >= nb) && self.not_after.is_none_or(|na| at <= na)
+    }
+}
+
+/// Contents of the `signers.yaml` key
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustConfig {
+    #[serde(default)]
+    pub signers: Vec<TrustedSigner>,
+    /// Accept VSLs that carry no signature
+    #[serde(default)]
+    pub allow_unsigned: bool,
+}
+
+/// Trusted VSL signers with their keys decoded
+#[derive(Clone, Debug, Default)]
+pub struct TrustStore {
+    signers: Vec<([u8; 32], TrustedSigner)>,
+    allow_unsigned: bool,
+}
+
+impl TrustStore {
+    /// The store used when no trust configuration exists: no signer is
+    /// trusted and unsigned VSLs are rejected.
+    pub fn unconfigured() -> Self {
+        Self::default()
+    }
+
+    /// Build a store from a trust configuration, rejecting malformed keys
+    pub fn from_config(config: TrustConfig) -> Result<Self> {
+        let signers = config
+            .signers
+            .into_iter()
+            .map(|signer| {
+                let key = decode_ed25519_key(&signer.key).map_err(|e| {
+                    Error::ConfigError(format!("Trusted VSL signer '{}': {e}", signer.label()))
+                })?;
+                Ok((key, signer))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            signers,
+            allow_unsigned: config.allow_unsigned,
+        })
+    }
+
+    /// Parse the `signers.yaml` document
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let config: TrustConfig = serde_yaml::from_str(yaml)
+            .map_err(|e| Error::ConfigError(format!("Invalid VSL trust configuration: {e}")))?;
+        Self::from_config(config)
+    }
+
+    /// Load the trust store from the operator namespace
+    pub async fn load(client: &Client, namespace: &str) -> Result<Self> {
+        let api: Api<ConfigMap> = Api::namespaced(client.clone(), namespace);
+        let Some(cm) = api.get_opt(TRUST_CONFIG_MAP_NAME).await? else {
+            warn!(
+                "No {}/{} ConfigMap; no VSL signers are trusted and unsigned VSLs are rejected",
+                namespace, TRUST_CONFIG_MAP_NAME
+            );
+            return Ok(Self::unconfigured());
+        };
+        let yaml = cm
+            .data
+            .as_ref()
+            .and_then(|d| d.get(TRUST_CONFIG_KEY))
+            .ok_or_else(|| {
+                Error::ConfigError(format!(
+                    "ConfigMap {namespace}/{TRUST_CONFIG_MAP_NAME} has no {TRUST_CONFIG_KEY} key"
+                ))
+            })?;
+        Self::from_yaml(yaml)
+    }
+
+    pub fn allow_unsigned(&self) -> bool {
+        self.allow_unsigned
+    }
+
+    /// Check that `key` belongs to a signer trusted at `at`.
+    ///
+    /// Returns the matching signer so callers can log which key was used.
+    pub fn check(&self, key: &[u8; 32], at: DateTime<Utc>) -> Result<&TrustedSigner> {
+        let mut matching = self
+            .signers
+            .iter()
+            .filter(|(k, _)| k == key)
+            .map(|(_, s)| s)
+            .peekable();
+        if matching.peek().is_none() {
+            return Err(Error::ConfigError(format!(
+                "VSL signing key '{}' is not in the trusted signers list. \
+                 Add it to the {TRUST_CONFIG_MAP_NAME} ConfigMap if this is intentional.",
+                strkey::encode_public_key(key)
+            )));
+        }
+        let mut expired = Vec::new();
+        for signer in matching {
+            if signer.is_valid_at(at) {
+                return Ok(signer);
+            }
+            expired.push(signer.label());
+        }
+        Err(Error::ConfigError(format!(
+            "VSL signing key '{}' is outside its validity window at {} (signer: {})",
+            strkey::encode_public_key(key),
+            at.to_rfc3339(),
+            expired.join(", ")
+        )))
+    }
+}
+
+/// Decode an Ed25519 public key given as a Stellar `G…` strkey or as base64
+pub fn decode_ed25519_key(key: &str) -> Result<[u8; 32]> {
+    let key = key.trim();
+    if key.len() == 56 && key.starts_with('G') {
+        return strkey::decode_public_key(key);
+    }
+    let bytes = BASE64
+        .decode(key)
+        .map_err(|e| Error::ConfigError(format!("Invalid base64 in public key: {e}")))?;
+    let len = bytes.len();
+    bytes
+        .try_into()
+        .map_err(|_| Error::ConfigError(format!("Ed25519 public key must be 32 bytes, got {len}")))
+}
+
+// ---------------------------------------------------------------------------
+// Rollback protection
+// ---------------------------------------------------------------------------
+
+/// The highest accepted VSL for a source
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SequenceRecord {
+    pub source: String,
+    pub sequence: u64,
+    /// SHA-256 of the accepted document
+    pub sha256: String,
+}
+
+/// Hex-encoded SHA-256 of a VSL document
+pub fn document_hash(raw: &str) -> String {
+    format!("{:x}", Sha256::digest(raw.as_bytes()))
+}
+
+/// ConfigMap key under which a source's record is stored
+pub fn source_key(source: &str) -> String {
+    format!("{:x}", Sha256::digest(source.as_bytes()))[..32].to_string()
+}
+
+/// Decide whether a VSL with `sequence`/`sha256` may replace `previous`.
+///
+/// Returns `true` when the record has to be advanced. A lower sequence is a
+/// rollback; the same sequence is only accepted for the identical document.
+pub fn check_sequence(
+    previous: Option<&SequenceRecord>,
+    sequence: u64,
+    sha256: &str,
+) -> Result<bool> {
+    let Some(previous) = previous else {
+        return Ok(true);
+    };
+    if sequence < previous.sequence {
+        return Err(Error::ConfigError(format!(
+            "VSL from {} has sequence {sequence}, older than the accepted sequence {}; \
+             refusing to roll back the quorum set",
+            previous.source, previous.sequence
+        )));
+    }
+    if sequence == previous.sequence && sha256 != previous.sha256 {
+        return Err(Error::ConfigError(format!(
+            "VSL from {} reuses sequence {sequence} with different content",
+            previous.source
+        )));
+    }
+    Ok(sequence > previous.sequence)
+}
+
+/// Enforce monotonic sequences for `source` and persist the new highest one.
+///
+/// The record is written with optimistic concurrency; a concurrent writer
+/// surfaces as a conflict and the VSL is retried on the next reconcile.
+pub async fn enforce_sequence(
+    client: &Client,
+    namespace: &str,
+    source: &str,
+    sequence: u64,
+    sha256: &str,
+) -> Result<()> {
+    let api: Api<ConfigMap> = Api::namespaced(client.clone(), namespace);
+    let key = source_key(source);
+    let existing = api.get_opt(SEQUENCE_CONFIG_MAP_NAME).await?;
+
+    let previous = existing
+        .as_ref()
+        .and_then(|cm| cm.data.as_ref())
+        .and_then(|d| d.get(&key))
+        .map(|v| serde_json::from_str::<SequenceRecord>(v))
+        .transpose()?;
+    if !check_sequence(previous.as_ref(), sequence, sha256)? {
+        return Ok(());
+    }
+
+    let record = SequenceRecord {
+        source: source.to_string(),
+        sequence,
+        sha256: sha256.to_string(),
+    };
+    let value = serde_json::to_string(&record)?;
+    match existing {
+        Some(mut cm) => {
+            cm.data.get_or_insert_with(BTreeMap::new).insert(key, value);
+            api.replace(SEQUENCE_CONFIG_MAP_NAME, &PostParams::default(), &cm)
+                .await?;
+        }
+        None => {
+            let cm = ConfigMap {
+                metadata: ObjectMeta {
+                    name: Some(SEQUENCE_CONFIG_MAP_NAME.to_string()),
+                    namespace: Some(namespace.to_string()),
+                    labels: Some(BTreeMap::from([
+                        ("app".to_string(), "stellar-operator".to_string()),
+                        ("component".to_string(), "vsl".to_string()),
+                    ])),
+                    ..Default::default()
+                },
+                data: Some(BTreeMap::from([(key, value)])),
+                ..Default::default()
+            };
+            api.create(&PostParams::default(), &cm).await?;
+        }
+    }
+    info!("Accepted VSL sequence {} from {}", sequence, source);
+    Ok(())
+}
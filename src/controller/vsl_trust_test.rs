@@ -0,0 +1,132 @@
+//! Tests for the VSL trust store and sequence rollback protection.
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::strkey::encode_public_key;
+    use crate::controller::vsl_trust::*;
+
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+    use chrono::{DateTime, TimeZone, Utc};
+
+    fn at(year: i32, month: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).unwrap()
+    }
+
+    // -----------------------------------------------------------------------
+    // Key decoding
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_decode_key_accepts_strkey_and_base64() {
+        let raw = [7u8; 32];
+        assert_eq!(decode_ed25519_key(&encode_public_key(&raw)).unwrap(), raw);
+        assert_eq!(decode_ed25519_key(&BASE64.encode(raw)).unwrap(), raw);
+
+        let short = decode_ed25519_key(&BASE64.encode([0u8; 16])).unwrap_err();
+        assert!(short.to_string().contains("32 bytes"));
+        assert!(decode_ed25519_key("!!!not-base64!!!").is_err());
+    }
+
+    // -----------------------------------------------------------------------
+    // Trust store
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_key_rotation_windows() {
+        let old_key = [1u8; 32];
+        let new_key = [2u8; 32];
+        let yaml = format!(
+            r#"
+signers:
+  - name: sdf-2025
+    key: {}
+    notAfter: "2026-01-31T00:00:00Z"
+  - name: sdf-2026
+    key: "{}"
+    notBefore: "2026-01-01T00:00:00Z"
+"#,
+            encode_public_key(&old_key),
+            BASE64.encode(new_key)
+        );
+        let store = TrustStore::from_yaml(&yaml).unwrap();
+        assert!(!store.allow_unsigned());
+
+        // Before the overlap only the old key is valid
+        assert_eq!(
+            store.check(&old_key, at(2025, 6)).unwrap().name.as_deref(),
+            Some("sdf-2025")
+        );
+        assert!(store.check(&new_key, at(2025, 6)).is_err());
+
+        // During the overlap both keys are valid
+        assert!(store.check(&old_key, at(2026, 1)).is_ok());
+        assert!(store.check(&new_key, at(2026, 1)).is_ok());
+
+        // Afterwards the old key has expired
+        let expired = store.check(&old_key, at(2026, 3)).unwrap_err();
+        assert!(expired.to_string().contains("validity window"));
+        assert!(store.check(&new_key, at(2026, 3)).is_ok());
+    }
+
+    #[test]
+    fn test_untrusted_key_rejected() {
+        let store = TrustStore::from_yaml(&format!(
+            "signers:\n  - key: {}\n",
+            encode_public_key(&[1u8; 32])
+        ))
+        .unwrap();
+        let err = store.check(&[9u8; 32], Utc::now()).unwrap_err();
+        assert!(err.to_string().contains("not in the trusted signers list"));
+
+        // No configuration: nothing is trusted and unsigned VSLs are rejected
+        let unconfigured = TrustStore::unconfigured();
+        assert!(!unconfigured.allow_unsigned());
+        assert!(unconfigured.check(&[1u8; 32], Utc::now()).is_err());
+        assert!(TrustStore::from_yaml("allowUnsigned: true\n")
+            .unwrap()
+            .allow_unsigned());
+    }
+
+    #[test]
+    fn test_malformed_signer_key_rejected() {
+        let err = TrustStore::from_yaml("signers:\n  - name: broken\n    key: GABC\n").unwrap_err();
+        assert!(err.to_string().contains("broken"));
+        assert!(TrustStore::from_yaml("signers: [").is_err());
+    }
+
+    // -----------------------------------------------------------------------
+    // Sequence rollback protection
+    // -----------------------------------------------------------------------
+
+    fn record(sequence: u64, raw: &str) -> SequenceRecord {
+        SequenceRecord {
+            source: "https://vsl.example.com/vsl.toml".to_string(),
+            sequence,
+            sha256: document_hash(raw),
+        }
+    }
+
+    #[test]
+    fn test_check_sequence() {
+        // First VSL from a source is always accepted
+        assert!(check_sequence(None, 0, &document_hash("a")).unwrap());
+
+        let previous = record(42, "a");
+        assert!(check_sequence(Some(&previous), 43, &document_hash("b")).unwrap());
+        // Re-fetching the same document is fine but does not advance the record
+        assert!(!check_sequence(Some(&previous), 42, &document_hash("a")).unwrap());
+
+        let rollback = check_sequence(Some(&previous), 41, &document_hash("old")).unwrap_err();
+        assert!(rollback.to_string().contains("roll back"));
+        let reuse = check_sequence(Some(&previous), 42, &document_hash("b")).unwrap_err();
+        assert!(reuse.to_string().contains("different content"));
+    }
+
+    #[test]
+    fn test_source_key_is_valid_config_map_key() {
+        let key = source_key("https://vsl.example.com/vsl.toml?network=public");
+        assert_eq!(key.len(), 32);
+        assert!(key.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(key, source_key("https://vsl.example.com/other.toml"));
+    }
+}
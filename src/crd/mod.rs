@@ -19,6 +19,7 @@ pub use service_mesh::{
     ServiceMeshConfig,
 };
 pub use stellar_node::{
-    BGPStatus, SpecValidationError, StellarNode, StellarNodeSpec, StellarNodeStatus,
+    BGPStatus, DbMaintenanceStatus, DecentralizedBackupStatus, SpecValidationError, StellarNode,
+    StellarNodeSpec, StellarNodeStatus, TableMaintenanceResult,
 };
 pub use types::*;
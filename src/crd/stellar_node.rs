@@ -10,7 +10,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::types::{
-    AutoscalingConfig, Condition, CrossClusterConfig, DisasterRecoveryConfig,
+    AppliedVslStatus, AutoscalingConfig, Condition, CrossClusterConfig, DisasterRecoveryConfig,
     DisasterRecoveryStatus, ExternalDatabaseConfig, GlobalDiscoveryConfig, HistoryMode,
     HorizonConfig, IngressConfig, LoadBalancerConfig, ManagedDatabaseConfig, NetworkPolicyConfig,
     NodeType, OciSnapshotConfig, ResourceRequirements, RestoreFromSnapshotConfig, RetentionPolicy,
@@ -172,6 +172,10 @@ pub struct StellarNodeSpec {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub oci_snapshot: Option<OciSnapshotConfig>,
 
+    /// Decentralized history archive backups run as a managed CronJob (Validator only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decentralized_backup: Option<crate::backup::DecentralizedBackupConfig>,
+
     /// Service mesh configuration (Istio/Linkerd) for mTLS and advanced traffic control
     #[serde(skip_serializing_if = "Option::is_none")]
     pub service_mesh: Option<super::service_mesh::ServiceMeshConfig>,
@@ -219,6 +223,7 @@ impl Default for StellarNodeSpec {
             read_replica_config: None,
             db_maintenance_config: None,
             oci_snapshot: None,
+            decentralized_backup: None,
             service_mesh: None,
             resource_meta: None,
         }
@@ -277,6 +282,7 @@ impl StellarNodeSpec {
     /// # read_replica_config: None,
     /// # db_maintenance_config: None,
     /// # oci_snapshot: None,
+    /// # decentralized_backup: None,
     /// # service_mesh: None,
     /// # vpa_config: None,
     /// # resource_meta: None,
@@ -473,6 +479,32 @@ impl StellarNodeSpec {
         if let Some(ref mesh) = self.service_mesh {
             validate_service_mesh(mesh, &mut errors);
         }
+        if let Some(ref backup) = self.decentralized_backup {
+            if self.node_type != NodeType::Validator {
+                errors.push(SpecValidationError::new(
+                    "spec.decentralizedBackup",
+                    "decentralized backups are only supported for Validator nodes",
+                    "Remove spec.decentralizedBackup; only Validators publish a history archive.",
+                ));
+            }
+            validate_decentralized_backup(backup, &mut errors);
+        }
+        if let Some(ref maintenance) = self.db_maintenance_config {
+            if self.node_type != NodeType::Horizon {
+                errors.push(SpecValidationError::new(
+                    "spec.dbMaintenanceConfig",
+                    "database maintenance is only supported for Horizon nodes",
+                    "Remove spec.dbMaintenanceConfig; only Horizon databases are maintained.",
+                ));
+            } else if self.database.is_none() && self.managed_database.is_none() {
+                errors.push(SpecValidationError::new(
+                    "spec.dbMaintenanceConfig",
+                    "database maintenance requires spec.database or spec.managedDatabase",
+                    "Configure the Horizon database under spec.database or spec.managedDatabase.",
+                ));
+            }
+            validate_db_maintenance(maintenance, &mut errors);
+        }
 
         if errors.is_empty() {
             Ok(())
@@ -493,6 +525,135 @@ impl StellarNodeSpec {
         self.storage.retention_policy == RetentionPolicy::Delete
     }
 }
+fn validate_decentralized_backup(
+    backup: &crate::backup::DecentralizedBackupConfig,
+    errors: &mut Vec<SpecValidationError>,
+) {
+    use crate::backup::providers::s3::MIN_PART_SIZE;
+    use crate::backup::{PinningServiceType, StorageProvider};
+    use crate::controller::decentralized_backup::DATA_MOUNT_PATH;
+
+    if backup.schedule.trim().is_empty() {
+        errors.push(SpecValidationError::new(
+            "spec.decentralizedBackup.schedule",
+            "decentralizedBackup.schedule must not be empty",
+            "Set spec.decentralizedBackup.schedule to a cron expression, e.g. \"0 */6 * * *\".",
+        ));
+    }
+    if backup.max_concurrent_uploads == 0 {
+        errors.push(SpecValidationError::new(
+            "spec.decentralizedBackup.maxConcurrentUploads",
+            "decentralizedBackup.maxConcurrentUploads must be at least 1",
+            "Set spec.decentralizedBackup.maxConcurrentUploads to 1 or greater.",
+        ));
+    }
+    let archive_path = std::path::Path::new(&backup.archive_path);
+    if archive_path.is_absolute()
+        || archive_path
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        errors.push(SpecValidationError::new(
+            "spec.decentralizedBackup.archivePath",
+            "decentralizedBackup.archivePath must be relative to the data volume",
+            "Set spec.decentralizedBackup.archivePath to a relative path without '..', e.g. \"history\".",
+        ));
+    }
+
+    match &backup.provider {
+        StorageProvider::Filesystem { path, claim_name } => {
+            if claim_name.as_deref().is_none_or(str::is_empty) {
+                errors.push(SpecValidationError::new(
+                    "spec.decentralizedBackup.provider.claim_name",
+                    "the filesystem provider needs a PersistentVolumeClaim for managed backups",
+                    "Set claim_name to a PVC in the node's namespace; the backup Job mounts it read-write at path.",
+                ));
+            }
+            if !path.starts_with('/') || path == "/" || path == DATA_MOUNT_PATH {
+                errors.push(SpecValidationError::new(
+                    "spec.decentralizedBackup.provider.path",
+                    "path must be an absolute directory to mount the backup PVC at",
+                    format!(
+                        "Set path to an absolute directory other than / and {DATA_MOUNT_PATH}, e.g. \"/backups\"."
+                    ),
+                ));
+            }
+        }
+        StorageProvider::Arweave { wallet_secret, .. } if wallet_secret.is_empty() => {
+            errors.push(SpecValidationError::new(
+                "spec.decentralizedBackup.provider.wallet_secret",
+                "wallet_secret must name a Secret",
+                "Set wallet_secret to a Secret holding the Arweave wallet JWK under wallet.json.",
+            ));
+        }
+        StorageProvider::IPFS {
+            pinning_service: Some(pinning),
+            ..
+        } if pinning.service_url().is_none() => {
+            let service = match pinning.service_type {
+                PinningServiceType::Pinata => "pinata",
+                PinningServiceType::Web3Storage => "web3storage",
+                PinningServiceType::Infura => "infura",
+            };
+            errors.push(SpecValidationError::new(
+                "spec.decentralizedBackup.provider.pinning_service.endpoint",
+                format!("pinning service {service} has no default endpoint"),
+                "Set pinning_service.endpoint to the service's Pinning Service API URL.",
+            ));
+        }
+        StorageProvider::S3 {
+            credentials_secret,
+            part_size,
+            ..
+        } => {
+            if credentials_secret.is_empty() {
+                errors.push(SpecValidationError::new(
+                    "spec.decentralizedBackup.provider.credentials_secret",
+                    "credentials_secret must name a Secret",
+                    "Set credentials_secret to a Secret holding accessKeyId and secretAccessKey.",
+                ));
+            }
+            if *part_size < MIN_PART_SIZE as u64 {
+                errors.push(SpecValidationError::new(
+                    "spec.decentralizedBackup.provider.part_size",
+                    "part_size must be at least 5 MiB",
+                    format!("Set part_size to {MIN_PART_SIZE} or greater, or leave it unset."),
+                ));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn validate_db_maintenance(
+    maintenance: &super::types::DbMaintenanceConfig,
+    errors: &mut Vec<SpecValidationError>,
+) {
+    if maintenance.window_start_time().is_none() {
+        errors.push(SpecValidationError::new(
+            "spec.dbMaintenanceConfig.windowStart",
+            "dbMaintenanceConfig.windowStart must be a time in HH:MM format",
+            "Set spec.dbMaintenanceConfig.windowStart to a UTC time such as \"02:00\".",
+        ));
+    }
+    match maintenance.window_length() {
+        Some(length)
+            if length > chrono::Duration::zero() && length <= chrono::Duration::days(1) => {}
+        _ => errors.push(SpecValidationError::new(
+            "spec.dbMaintenanceConfig.windowDuration",
+            "dbMaintenanceConfig.windowDuration must be a duration of at most 24h",
+            "Set spec.dbMaintenanceConfig.windowDuration to a value such as \"2h\" or \"1h30m\".",
+        )),
+    }
+    if maintenance.bloat_threshold_percent == 0 || maintenance.bloat_threshold_percent >= 100 {
+        errors.push(SpecValidationError::new(
+            "spec.dbMaintenanceConfig.bloatThresholdPercent",
+            "dbMaintenanceConfig.bloatThresholdPercent must be between 1 and 99",
+            "Set spec.dbMaintenanceConfig.bloatThresholdPercent to a percentage such as 30.",
+        ));
+    }
+}
+
 #[allow(dead_code)]
 fn validate_ingress(ingress: &IngressConfig, errors: &mut Vec<SpecValidationError>) {
     if ingress.hosts.is_empty() {
@@ -941,6 +1102,111 @@ pub struct StellarNodeStatus {
     /// Migration status for node type transitions (e.g., Horizon to Soroban RPC)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub migration_status: Option<MigrationStatus>,
+
+    /// For validators: Stellar public key (G...) derived from the seed Secret
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validator_public_key: Option<String>,
+
+    /// For validators with `vlSource`: the VSL currently applied to the config
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applied_vsl: Option<AppliedVslStatus>,
+
+    /// Outcome of the decentralized history archive backups
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<DecentralizedBackupStatus>,
+
+    /// Outcome of the automated database maintenance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub db_maintenance: Option<DbMaintenanceStatus>,
+}
+
+/// Decentralized backup status, written by the backup worker after each run
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecentralizedBackupStatus {
+    /// Start time of the last backup run (RFC3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run_time: Option<String>,
+
+    /// Completion time of the last run that uploaded every segment (RFC3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success_time: Option<String>,
+
+    /// Error of the last run, cleared by a successful run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+
+    /// Bytes uploaded by the last run
+    #[serde(default)]
+    pub last_bytes_uploaded: u64,
+
+    /// Bytes uploaded since backups were enabled
+    #[serde(default)]
+    pub total_bytes_uploaded: u64,
+
+    /// Archive files recorded in the backup manifest
+    #[serde(default)]
+    pub files_backed_up: u64,
+
+    /// Latest history checkpoint ledger fully backed up
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_checkpoint: Option<u64>,
+}
+
+/// Database maintenance status, written by the operator after each window
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DbMaintenanceStatus {
+    /// Opening time of the maintenance window the last run belongs to (RFC3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_window_start: Option<String>,
+
+    /// Start time of the last run (RFC3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run_time: Option<String>,
+
+    /// Completion time of the last run (RFC3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_completion_time: Option<String>,
+
+    /// Error of the last run, cleared by the next run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+
+    /// Tables above the bloat threshold and what the last run did to them
+    #[serde(default)]
+    pub tables: Vec<TableMaintenanceResult>,
+}
+
+/// Maintenance result for a single table
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableMaintenanceResult {
+    /// Schema-qualified table name
+    pub table: String,
+
+    /// Estimated bloat before maintenance
+    pub bloat_percent: f64,
+
+    /// Table size before maintenance
+    pub size_bytes_before: i64,
+
+    /// Table size after maintenance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes_after: Option<i64>,
+
+    /// Whether VACUUM FULL completed
+    pub vacuumed: bool,
+
+    /// Whether REINDEX completed
+    pub reindexed: bool,
+
+    /// Time spent on the table, in seconds
+    pub duration_seconds: u64,
+
+    /// Why the table was not fully maintained
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 /// BGP advertisement status information
@@ -1156,6 +1422,7 @@ mod tests {
             read_replica_config: None,
             db_maintenance_config: None,
             oci_snapshot: None,
+            decentralized_backup: None,
             service_mesh: None,
             resource_meta: None,
             vpa_config: None,
@@ -1210,6 +1477,7 @@ mod tests {
             read_replica_config: None,
             db_maintenance_config: None,
             oci_snapshot: None,
+            decentralized_backup: None,
             service_mesh: None,
             resource_meta: None,
             vpa_config: None,
@@ -6,9 +6,10 @@
 #[cfg(test)]
 mod stellar_node_spec_validation {
     use crate::crd::{
-        AutoscalingConfig, HorizonConfig, IngressConfig, IngressHost, IngressPath, NodeType,
-        ResourceRequirements, ResourceSpec, SorobanConfig, SpecValidationError, StellarNetwork,
-        StellarNodeSpec, StorageConfig, ValidatorConfig,
+        AutoscalingConfig, DbMaintenanceConfig, ExternalDatabaseConfig, HorizonConfig,
+        IngressConfig, IngressHost, IngressPath, NodeType, ResourceRequirements, ResourceSpec,
+        SecretKeyRef, SorobanConfig, SpecValidationError, StellarNetwork, StellarNodeSpec,
+        StorageConfig, ValidatorConfig,
     };
 
     /// Helper to create a minimal valid StellarNodeSpec for a Validator
@@ -57,6 +58,7 @@ mod stellar_node_spec_validation {
             read_replica_config: None,
             db_maintenance_config: None,
             oci_snapshot: None,
+            decentralized_backup: None,
             service_mesh: None,
             resource_meta: None,
             vpa_config: None,
@@ -106,6 +108,7 @@ mod stellar_node_spec_validation {
             read_replica_config: None,
             db_maintenance_config: None,
             oci_snapshot: None,
+            decentralized_backup: None,
             service_mesh: None,
             resource_meta: None,
             vpa_config: None,
@@ -155,6 +158,7 @@ mod stellar_node_spec_validation {
             read_replica_config: None,
             db_maintenance_config: None,
             oci_snapshot: None,
+            decentralized_backup: None,
             service_mesh: None,
             resource_meta: None,
             vpa_config: None,
@@ -967,4 +971,144 @@ mod stellar_node_spec_validation {
 
         assert!(deserialized_yaml.captive_core_structured_config.is_some());
     }
+
+    // =========================================================================
+    // Decentralized Backup Validation Tests
+    // =========================================================================
+
+    fn s3_backup() -> crate::backup::DecentralizedBackupConfig {
+        serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "provider": {
+                "type": "s3",
+                "bucket": "stellar-history",
+                "credentials_secret": "backup-s3"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validator_decentralized_backup_passes() {
+        let mut spec = valid_validator_spec();
+        spec.decentralized_backup = Some(s3_backup());
+        assert!(spec.validate().is_ok());
+    }
+
+    #[test]
+    fn test_horizon_decentralized_backup_fails() {
+        let mut spec = valid_horizon_spec();
+        spec.decentralized_backup = Some(s3_backup());
+
+        let errors = spec.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "spec.decentralizedBackup"));
+    }
+
+    #[test]
+    fn test_decentralized_backup_rejects_escaping_archive_path() {
+        let mut backup = s3_backup();
+        backup.archive_path = "../other".to_string();
+        let mut spec = valid_validator_spec();
+        spec.decentralized_backup = Some(backup);
+
+        let errors = spec.validate().unwrap_err();
+        assert!(errors
+            .iter()
+            .any(|e| e.field == "spec.decentralizedBackup.archivePath"));
+    }
+
+    #[test]
+    fn test_decentralized_backup_filesystem_needs_pvc() {
+        let mut backup = s3_backup();
+        backup.provider = crate::backup::StorageProvider::Filesystem {
+            path: "/archive".to_string(),
+            claim_name: None,
+        };
+        let mut spec = valid_validator_spec();
+        spec.decentralized_backup = Some(backup.clone());
+
+        let errors = spec.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert!(fields.contains(&"spec.decentralizedBackup.provider.claim_name"));
+        assert!(fields.contains(&"spec.decentralizedBackup.provider.path"));
+
+        backup.provider = crate::backup::StorageProvider::Filesystem {
+            path: "/backups".to_string(),
+            claim_name: Some("backup-pvc".to_string()),
+        };
+        spec.decentralized_backup = Some(backup);
+        assert!(spec.validate().is_ok());
+    }
+
+    #[test]
+    fn test_decentralized_backup_s3_part_size_minimum() {
+        let mut backup = s3_backup();
+        if let crate::backup::StorageProvider::S3 { part_size, .. } = &mut backup.provider {
+            *part_size = 1024 * 1024;
+        }
+        let mut spec = valid_validator_spec();
+        spec.decentralized_backup = Some(backup);
+
+        let errors = spec.validate().unwrap_err();
+        assert!(errors
+            .iter()
+            .any(|e| e.field == "spec.decentralizedBackup.provider.part_size"));
+    }
+
+    // =========================================================================
+    // Database Maintenance Validation Tests
+    // =========================================================================
+
+    fn db_maintenance(window_start: &str, window_duration: &str) -> DbMaintenanceConfig {
+        serde_json::from_value(serde_json::json!({
+            "windowStart": window_start,
+            "windowDuration": window_duration,
+        }))
+        .unwrap()
+    }
+
+    fn horizon_with_database() -> StellarNodeSpec {
+        let mut spec = valid_horizon_spec();
+        spec.database = Some(ExternalDatabaseConfig {
+            secret_key_ref: SecretKeyRef {
+                name: "horizon-db".to_string(),
+                key: "url".to_string(),
+            },
+        });
+        spec
+    }
+
+    #[test]
+    fn test_horizon_db_maintenance_passes() {
+        let mut spec = horizon_with_database();
+        spec.db_maintenance_config = Some(db_maintenance("23:30", "1h30m"));
+        assert!(spec.validate().is_ok());
+    }
+
+    #[test]
+    fn test_db_maintenance_requires_horizon_database() {
+        let mut spec = valid_horizon_spec();
+        spec.db_maintenance_config = Some(db_maintenance("02:00", "2h"));
+        let errors = spec.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "spec.dbMaintenanceConfig"));
+
+        let mut spec = valid_validator_spec();
+        spec.db_maintenance_config = Some(db_maintenance("02:00", "2h"));
+        let errors = spec.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "spec.dbMaintenanceConfig"));
+    }
+
+    #[test]
+    fn test_db_maintenance_rejects_invalid_window() {
+        let mut spec = horizon_with_database();
+        spec.db_maintenance_config = Some(db_maintenance("2am", "25h"));
+
+        let errors = spec.validate().unwrap_err();
+        assert!(errors
+            .iter()
+            .any(|e| e.field == "spec.dbMaintenanceConfig.windowStart"));
+        assert!(errors
+            .iter()
+            .any(|e| e.field == "spec.dbMaintenanceConfig.windowDuration"));
+    }
 }
@@ -880,14 +880,73 @@ pub struct DisasterRecoveryConfig {
     pub sync_strategy: DRSyncStrategy,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub failover_dns: Option<ExternalDNSConfig>,
+    /// Seconds between peer probe rounds
     #[serde(default = "default_dr_check_interval")]
     pub health_check_interval: u32,
+    /// Probes used to determine peer cluster health and ledger position
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub peer_probes: Vec<DRPeerProbe>,
+    /// Consecutive failed probe rounds before the peer is declared unreachable
+    #[serde(default = "default_dr_failure_threshold")]
+    pub failure_threshold: u32,
+    /// Consecutive healthy probe rounds before an unreachable peer is declared healthy again
+    #[serde(default = "default_dr_recovery_threshold")]
+    pub recovery_threshold: u32,
+    /// Number of probes that must fail for a round to count as failed (defaults to a majority)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub probe_quorum: Option<u32>,
+    /// Identifier of this cluster, recorded as the DNS record holder after failover
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_cluster_id: Option<String>,
+    /// Number of recent ledger hashes retained and compared for consistency checks
+    #[serde(default = "default_dr_consistency_window")]
+    pub consistency_window: u32,
 }
 
 fn default_dr_check_interval() -> u32 {
     30
 }
 
+fn default_dr_failure_threshold() -> u32 {
+    3
+}
+
+fn default_dr_recovery_threshold() -> u32 {
+    2
+}
+
+fn default_dr_consistency_window() -> u32 {
+    10
+}
+
+/// A single peer health probe for disaster recovery
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DRPeerProbe {
+    /// What the probe talks to
+    pub kind: DRProbeKind,
+    /// Base URL of the endpoint (e.g. `https://operator.us-west-2.example.com:9090`)
+    pub endpoint: String,
+    /// Namespace of the peer StellarNode (OperatorApi probes; defaults to the local namespace)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_namespace: Option<String>,
+    /// Name of the peer StellarNode (OperatorApi probes; defaults to the local name)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_name: Option<String>,
+}
+
+/// Endpoint type queried by a DR peer probe
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DRProbeKind {
+    /// Peer cluster operator REST API (`/api/v1/nodes/:namespace/:name`)
+    OperatorApi,
+    /// Peer node's Horizon root endpoint
+    Horizon,
+    /// Peer node's Stellar Core HTTP admin endpoint (`/info`)
+    Core,
+}
+
 /// Role of a node in a DR configuration
 #[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -915,6 +974,44 @@ pub struct DisasterRecoveryStatus {
     pub last_peer_contact: Option<String>,
     pub sync_lag: Option<u64>,
     pub failover_active: bool,
+    /// Latest ledger sequence reported by the peer probes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_ledger_sequence: Option<u64>,
+    /// Consecutive probe rounds in which the peer was considered down
+    #[serde(default)]
+    pub consecutive_failures: u32,
+    /// Consecutive probe rounds in which the peer was considered up
+    #[serde(default)]
+    pub consecutive_successes: u32,
+    /// Cluster currently holding the failover DNS record
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_record_holder: Option<String>,
+    /// When the current holder took over the DNS record (RFC3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_record_since: Option<String>,
+    /// Recently observed local ledger hashes, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recent_ledger_hashes: Vec<LedgerHashSample>,
+    /// Whether the local ledger history disagrees with the peer or history archive
+    #[serde(default)]
+    pub diverged: bool,
+    /// Details of the last detected divergence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub divergence_message: Option<String>,
+    /// Time of the last ledger hash consistency check (RFC3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_consistency_check: Option<String>,
+    /// Time of the last peer probe round (RFC3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_probe_time: Option<String>,
+}
+
+/// A ledger sequence and its header hash (hex)
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerHashSample {
+    pub sequence: u64,
+    pub hash: String,
 }
 
 /// Configuration for cross-cluster communication
@@ -1203,6 +1300,20 @@ pub struct MigrationStatus {
     pub message: String,
 }
 
+/// The Validator Selection List currently rendered into a validator's config
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedVslStatus {
+    /// URL the VSL was fetched from
+    pub source: String,
+    /// `sequence` of the applied VSL document
+    pub sequence: u64,
+    /// SHA-256 of the applied VSL document
+    pub sha256: String,
+    /// Timestamp when the VSL was applied (RFC3339)
+    pub applied_at: String,
+}
+
 /// Phase of the migration process
 #[derive(Clone, Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
@@ -1290,11 +1401,11 @@ pub struct DbMaintenanceConfig {
     #[serde(default = "default_true")]
     pub enabled: bool,
 
-    /// Maintenance window start time (24h format, e.g., "02:00")
+    /// Maintenance window start time (24h format in UTC, e.g., "02:00")
     /// Maintenance will only trigger during this window
     pub window_start: String,
 
-    /// Maintenance window duration (e.g., "2h")
+    /// Maintenance window duration (e.g., "2h", "90m" or "1h30m")
     pub window_duration: String,
 
     /// Bloat threshold percentage to trigger VACUUM FULL (default: 30)
@@ -1310,6 +1421,45 @@ pub struct DbMaintenanceConfig {
     pub read_pool_coordination: bool,
 }
 
+impl DbMaintenanceConfig {
+    /// Daily start of the maintenance window, in UTC
+    pub fn window_start_time(&self) -> Option<chrono::NaiveTime> {
+        chrono::NaiveTime::parse_from_str(self.window_start.trim(), "%H:%M").ok()
+    }
+
+    /// Length of the maintenance window
+    pub fn window_length(&self) -> Option<chrono::Duration> {
+        parse_window_duration(&self.window_duration)
+    }
+}
+
+/// Parse a duration made of hour, minute and second parts, e.g. "1h30m"
+fn parse_window_duration(value: &str) -> Option<chrono::Duration> {
+    let mut total = chrono::Duration::zero();
+    let mut digits = String::new();
+    let mut parts = 0;
+    for c in value.trim().chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        // No part of a window of at most a day exceeds 86400
+        let amount: i64 = digits.parse().ok().filter(|n| *n <= 86_400)?;
+        digits.clear();
+        total += match c {
+            'h' => chrono::Duration::hours(amount),
+            'm' => chrono::Duration::minutes(amount),
+            's' => chrono::Duration::seconds(amount),
+            _ => return None,
+        };
+        parts += 1;
+    }
+    if parts == 0 || !digits.is_empty() {
+        return None;
+    }
+    Some(total)
+}
+
 fn default_bloat_threshold() -> u32 {
     30
 }
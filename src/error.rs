@@ -68,9 +68,9 @@ pub enum Error {
     #[error("Network error: {0}")]
     NetworkError(String),
 
-    /// Certificate generation error
+    /// Certificate generation, parsing or storage error
     #[error("Certificate error: {0}")]
-    CertificateError(#[from] rcgen::Error),
+    CertificateError(String),
 
     /// I/O error
     #[error("I/O error: {0}")]
@@ -80,6 +80,10 @@ pub enum Error {
     #[error("Database maintenance error: {0}")]
     MaintenanceError(String),
 
+    /// Decentralized backup run failed
+    #[error("Backup error: {0}")]
+    BackupError(String),
+
     /// SQLx error
     #[error("SQL error: {0}")]
     SqlxError(#[from] sqlx::Error),
@@ -117,6 +121,12 @@ impl Error {
     }
 }
 
+impl From<rcgen::Error> for Error {
+    fn from(e: rcgen::Error) -> Self {
+        Error::CertificateError(e.to_string())
+    }
+}
+
 // Implement From for kube::runtime::finalizer::Error to enable ? operator
 impl From<kube::runtime::finalizer::Error<Error>> for Error {
     fn from(e: kube::runtime::finalizer::Error<Error>) -> Self {
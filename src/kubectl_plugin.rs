@@ -677,6 +677,7 @@ mod tests {
                 read_replica_config: None,
                 db_maintenance_config: None,
                 oci_snapshot: None,
+                decentralized_backup: None,
                 service_mesh: None,
                 resource_meta: None,
                 read_pool_endpoint: None,
@@ -703,6 +704,10 @@ mod tests {
 =======
                 ledger_updated_at: None,
 >>>>>>> main
+                validator_public_key: None,
+                applied_vsl: None,
+                backup: None,
+                db_maintenance: None,
             }),
         }
     }
@@ -20,9 +20,34 @@ pub mod webhook;
 pub use crate::error::{Error, Result};
 
 /// Configuration for mTLS
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MtlsConfig {
     pub cert_pem: Vec<u8>,
     pub key_pem: Vec<u8>,
     pub ca_pem: Vec<u8>,
 }
+
+/// mTLS material that the certificate rotation task replaces at runtime.
+///
+/// Readers take a snapshot per use, so a rotated certificate or CA bundle is
+/// picked up by the next health check.
+#[derive(Clone, Debug, Default)]
+pub struct SharedMtlsConfig(std::sync::Arc<std::sync::RwLock<Option<std::sync::Arc<MtlsConfig>>>>);
+
+impl SharedMtlsConfig {
+    pub fn new(config: Option<MtlsConfig>) -> Self {
+        Self(std::sync::Arc::new(std::sync::RwLock::new(
+            config.map(std::sync::Arc::new),
+        )))
+    }
+
+    /// The current material, if mTLS is enabled
+    pub fn load(&self) -> Option<std::sync::Arc<MtlsConfig>> {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace the material after a rotation
+    pub fn store(&self, config: MtlsConfig) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = Some(std::sync::Arc::new(config));
+    }
+}
@@ -6,6 +6,10 @@ use clap::{Parser, Subcommand};
 use k8s_openapi::api::coordination::v1::Lease;
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::MicroTime;
 use kube::api::{Api, ObjectMeta, Patch, PatchParams, PostParams};
+use stellar_k8s::carbon_aware::{
+    CarbonAwareConfig, CarbonAwareScheduler, CarbonDataStore, CarbonIntensityAPI,
+    CarbonWindowService,
+};
 use stellar_k8s::{controller, crd::StellarNode, Error};
 use tracing::{info, warn, Level};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
@@ -23,6 +27,10 @@ enum Commands {
     Run(RunArgs),
     /// Run the admission webhook server
     Webhook(WebhookArgs),
+    /// Back up a validator's history archive once (run by the backup CronJob)
+    Backup(BackupArgs),
+    /// Rebuild a validator's history archive from its decentralized backup
+    Restore(RestoreArgs),
     /// Show version and build information
     Version,
     /// Show cluster information
@@ -35,6 +43,18 @@ struct RunArgs {
     #[arg(long, env = "ENABLE_MTLS")]
     enable_mtls: bool,
 
+    /// Issue mTLS certificates through this cert-manager issuer instead of the built-in CA
+    #[arg(long, env = "CERT_MANAGER_ISSUER")]
+    cert_manager_issuer: Option<String>,
+
+    /// Kind of the cert-manager issuer (Issuer or ClusterIssuer)
+    #[arg(
+        long,
+        env = "CERT_MANAGER_ISSUER_KIND",
+        default_value = "ClusterIssuer"
+    )]
+    cert_manager_issuer_kind: String,
+
     /// Operator namespace
     #[arg(long, env = "OPERATOR_NAMESPACE", default_value = "default")]
     namespace: String,
@@ -50,6 +70,15 @@ struct RunArgs {
     /// Custom scheduler name (used when --scheduler is set)
     #[arg(long, env = "SCHEDULER_NAME", default_value = "stellar-scheduler")]
     scheduler_name: String,
+
+    /// Image for decentralized backup CronJobs (defaults to this operator's release image)
+    #[arg(long, env = "BACKUP_WORKER_IMAGE")]
+    backup_image: Option<String>,
+
+    /// Carbon-aware scheduling configuration (YAML); carbon data is not
+    /// fetched when unset
+    #[arg(long, env = "CARBON_AWARE_CONFIG")]
+    carbon_config: Option<std::path::PathBuf>,
 }
 
 #[derive(Parser, Debug)]
@@ -76,6 +105,55 @@ struct WebhookArgs {
     /// Log level (trace, debug, info, warn, error)
     #[arg(long, env = "LOG_LEVEL", default_value = "info")]
     log_level: String,
+
+    /// Namespace of the webhook, used for plugin sources and pull secrets
+    /// that do not name one
+    #[arg(long, env = "POD_NAMESPACE", default_value = "stellar-webhook")]
+    namespace: String,
+
+    /// Ed25519 public keys (Stellar G… or base64) trusted to sign plugin
+    /// modules, comma-separated
+    #[arg(long, env = "PLUGIN_SIGNING_KEYS", value_delimiter = ',')]
+    plugin_signing_keys: Vec<String>,
+
+    /// Load plugin modules without a verifiable signature, checking only
+    /// their pinned sha256 (development only)
+    #[arg(long, env = "ALLOW_UNSIGNED_PLUGINS")]
+    allow_unsigned_plugins: bool,
+}
+
+#[derive(Parser, Debug)]
+struct BackupArgs {
+    /// StellarNode whose history archive is backed up
+    #[arg(long)]
+    node: String,
+
+    /// Namespace of the StellarNode
+    #[arg(long, env = "POD_NAMESPACE", default_value = "default")]
+    namespace: String,
+
+    /// Where the node's data volume is mounted
+    #[arg(long, default_value = controller::decentralized_backup::DATA_MOUNT_PATH)]
+    data_dir: std::path::PathBuf,
+}
+
+#[derive(Parser, Debug)]
+struct RestoreArgs {
+    /// StellarNode whose backup is restored
+    #[arg(long)]
+    node: String,
+
+    /// Namespace of the StellarNode
+    #[arg(long, env = "POD_NAMESPACE", default_value = "default")]
+    namespace: String,
+
+    /// Directory the history archive is written to
+    #[arg(long)]
+    dest: std::path::PathBuf,
+
+    /// Restore checkpoints up to this ledger only (default: all)
+    #[arg(long)]
+    up_to: Option<u64>,
 }
 
 #[tokio::main]
@@ -99,6 +177,12 @@ async fn main() -> Result<(), Error> {
         Commands::Webhook(webhook_args) => {
             return run_webhook(webhook_args).await;
         }
+        Commands::Backup(backup_args) => {
+            return run_backup(backup_args).await;
+        }
+        Commands::Restore(restore_args) => {
+            return run_restore(restore_args).await;
+        }
     }
 }
 
@@ -118,9 +202,64 @@ async fn run_info(args: InfoArgs) -> Result<(), Error> {
     Ok(())
 }
 
+async fn run_backup(args: BackupArgs) -> Result<(), Error> {
+    tracing_subscriber::registry()
+        .with(
+            EnvFilter::builder()
+                .with_default_directive(Level::INFO.into())
+                .from_env_lossy(),
+        )
+        .with(fmt::layer().with_target(true))
+        .init();
+
+    let client = kube::Client::try_default()
+        .await
+        .map_err(Error::KubeError)?;
+
+    stellar_k8s::backup::worker::run_once(client, &args.namespace, &args.node, &args.data_dir)
+        .await
+        .map_err(|e| Error::BackupError(format!("{e:#}")))
+}
+
+async fn run_restore(args: RestoreArgs) -> Result<(), Error> {
+    tracing_subscriber::registry()
+        .with(
+            EnvFilter::builder()
+                .with_default_directive(Level::INFO.into())
+                .from_env_lossy(),
+        )
+        .with(fmt::layer().with_target(true))
+        .init();
+
+    let client = kube::Client::try_default()
+        .await
+        .map_err(Error::KubeError)?;
+
+    let report = stellar_k8s::backup::worker::restore_once(
+        client,
+        &args.namespace,
+        &args.node,
+        &args.dest,
+        args.up_to,
+    )
+    .await
+    .map_err(|e| Error::BackupError(format!("{e:#}")))?;
+    println!(
+        "Restored {} files ({} already present), latest checkpoint {}",
+        report.restored,
+        report.skipped,
+        report
+            .latest_checkpoint
+            .map(|c| c.to_string())
+            .unwrap_or_default()
+    );
+    Ok(())
+}
+
 #[cfg(feature = "admission-webhook")]
 async fn run_webhook(args: WebhookArgs) -> Result<(), Error> {
-    use stellar_k8s::webhook::{runtime::WasmRuntime, server::WebhookServer};
+    use stellar_k8s::controller::vsl_trust::decode_ed25519_key;
+    use stellar_k8s::webhook::{loader::PluginLoader, runtime::WasmRuntime, server::WebhookServer};
 
     // Initialize tracing
     let env_filter = EnvFilter::builder()
@@ -162,10 +301,34 @@ async fn run_webhook(args: WebhookArgs) -> Result<(), Error> {
         warn!("Running webhook server without TLS (not recommended for production)");
     }
 
+    let server = std::sync::Arc::new(server);
+
+    // Load StellarValidationPlugin resources when running in a cluster
+    let signing_keys = args
+        .plugin_signing_keys
+        .iter()
+        .map(|key| decode_ed25519_key(key))
+        .collect::<Result<Vec<_>, _>>()?;
+    match kube::Client::try_default().await {
+        Ok(client) => {
+            let loader = PluginLoader::new(client, server.clone(), args.namespace, signing_keys)
+                .with_allow_unsigned(args.allow_unsigned_plugins);
+            tokio::spawn(async move {
+                if let Err(e) = loader.run().await {
+                    warn!("Plugin loader stopped: {}", e);
+                }
+            });
+        }
+        Err(e) => warn!(
+            "No Kubernetes client ({}); StellarValidationPlugin resources will not be loaded",
+            e
+        ),
+    }
+
     // Start the server
     info!("Webhook server listening on {}", addr);
     server
-        .start(addr)
+        .serve(addr)
         .await
         .map_err(|e| Error::ConfigError(format!("Webhook server error: {}", e)))?;
 
@@ -216,13 +379,57 @@ async fn run_operator(args: RunArgs) -> Result<(), Error> {
 
     info!("Connected to Kubernetes cluster");
 
+    // Leader election configuration
+    let leader_namespace =
+        std::env::var("POD_NAMESPACE").unwrap_or_else(|_| args.namespace.clone());
+    let holder_identity = std::env::var("HOSTNAME").unwrap_or_else(|_| {
+        hostname::get()
+            .ok()
+            .and_then(|h| h.into_string().ok())
+            .unwrap_or_else(|| "unknown-host".to_string())
+    });
+
+    info!("Leader election using holder ID: {}", holder_identity);
+
     // If --scheduler flag is set, run the latency-aware scheduler instead
     if args.scheduler {
         info!(
             "Running in scheduler mode with name: {}",
             args.scheduler_name
         );
-        let scheduler = stellar_k8s::scheduler::core::Scheduler::new(client, args.scheduler_name);
+
+        // Replicas running the scheduler elect a leader of their own, so only
+        // one of them binds pods
+        let is_leader = Arc::new(AtomicBool::new(false));
+        let scheduler_namespace = leader_namespace.clone();
+        {
+            let lease_client = client.clone();
+            let lease_name = format!("{}-leader", args.scheduler_name);
+            let identity = holder_identity.clone();
+            let is_leader_bg = Arc::clone(&is_leader);
+
+            tokio::spawn(async move {
+                run_leader_election(
+                    lease_client,
+                    &leader_namespace,
+                    &lease_name,
+                    &identity,
+                    is_leader_bg,
+                )
+                .await;
+            });
+        }
+
+        let carbon_store = CarbonDataStore::new(
+            client.clone(),
+            &scheduler_namespace,
+            format!("{}-carbon-data", args.scheduler_name),
+        );
+        let carbon =
+            start_carbon_aware(args.carbon_config.as_deref(), &is_leader, carbon_store).await?;
+        let scheduler = stellar_k8s::scheduler::core::Scheduler::new(client, args.scheduler_name)
+            .with_leader_election(is_leader)
+            .with_carbon_aware(carbon);
         return scheduler
             .run()
             .await
@@ -232,66 +439,62 @@ async fn run_operator(args: RunArgs) -> Result<(), Error> {
     let client_clone = client.clone();
     let namespace = args.namespace.clone();
 
+    let cert_manager_issuer = match &args.cert_manager_issuer {
+        Some(name) => Some(controller::cert_manager::IssuerRef {
+            name: name.clone(),
+            kind: args.cert_manager_issuer_kind.parse()?,
+        }),
+        None => None,
+    };
+    let server_dns_names = vec![
+        "stellar-operator".to_string(),
+        format!("stellar-operator.{}", namespace),
+    ];
+
     let mtls_config = if args.enable_mtls {
         info!("Initializing mTLS for Operator...");
 
-        controller::mtls::ensure_ca(&client_clone, &namespace).await?;
-        controller::mtls::ensure_server_cert(
-            &client_clone,
-            &namespace,
-            vec![
-                "stellar-operator".to_string(),
-                format!("stellar-operator.{}", namespace),
-            ],
-        )
-        .await?;
-
-        let secrets: kube::Api<k8s_openapi::api::core::v1::Secret> =
-            kube::Api::namespaced(client_clone.clone(), &namespace);
-        let secret = secrets
-            .get(controller::mtls::SERVER_CERT_SECRET_NAME)
-            .await
-            .map_err(Error::KubeError)?;
-        let data = secret
-            .data
-            .ok_or_else(|| Error::ConfigError("Secret has no data".to_string()))?;
-
-        let cert_pem = data
-            .get("tls.crt")
-            .ok_or_else(|| Error::ConfigError("Missing tls.crt".to_string()))?
-            .0
-            .clone();
-        let key_pem = data
-            .get("tls.key")
-            .ok_or_else(|| Error::ConfigError("Missing tls.key".to_string()))?
-            .0
-            .clone();
-        let ca_pem = data
-            .get("ca.crt")
-            .ok_or_else(|| Error::ConfigError("Missing ca.crt".to_string()))?
-            .0
-            .clone();
-
-        Some(stellar_k8s::MtlsConfig {
-            cert_pem,
-            key_pem,
-            ca_pem,
-        })
+        if let Some(issuer) = &cert_manager_issuer {
+            info!(
+                "Requesting mTLS certificates from cert-manager {} {}",
+                issuer.kind, issuer.name
+            );
+            controller::cert_manager::ensure_server_certificate(
+                &client_clone,
+                &namespace,
+                &server_dns_names,
+                issuer,
+            )
+            .await?;
+            Some(
+                controller::cert_manager::wait_for_certificate_secret(
+                    &client_clone,
+                    &namespace,
+                    controller::mtls::SERVER_CERT_SECRET_NAME,
+                    std::time::Duration::from_secs(120),
+                )
+                .await?,
+            )
+        } else {
+            controller::mtls::ensure_ca(&client_clone, &namespace).await?;
+            controller::mtls::ensure_server_cert(
+                &client_clone,
+                &namespace,
+                server_dns_names.clone(),
+            )
+            .await?;
+
+            let secrets: kube::Api<k8s_openapi::api::core::v1::Secret> =
+                kube::Api::namespaced(client_clone.clone(), &namespace);
+            let secret = secrets
+                .get(controller::mtls::SERVER_CERT_SECRET_NAME)
+                .await
+                .map_err(Error::KubeError)?;
+            Some(controller::cert_manager::mtls_config_from_secret(&secret)?)
+        }
     } else {
         None
     };
-    // Leader election configuration
-    let leader_namespace =
-        std::env::var("POD_NAMESPACE").unwrap_or_else(|_| args.namespace.clone());
-    let holder_identity = std::env::var("HOSTNAME").unwrap_or_else(|_| {
-        hostname::get()
-            .ok()
-            .and_then(|h| h.into_string().ok())
-            .unwrap_or_else(|| "unknown-host".to_string())
-    });
-
-    info!("Leader election using holder ID: {}", holder_identity);
-
     let is_leader = Arc::new(AtomicBool::new(false));
 
     {
@@ -301,18 +504,36 @@ async fn run_operator(args: RunArgs) -> Result<(), Error> {
         let is_leader_bg = Arc::clone(&is_leader);
 
         tokio::spawn(async move {
-            run_leader_election(lease_client, &lease_ns, &identity, is_leader_bg).await;
+            run_leader_election(lease_client, &lease_ns, LEASE_NAME, &identity, is_leader_bg).await;
         });
     }
 
+    // Carbon data serves the REST API and defers batch work into low-carbon windows
+    let carbon_store = CarbonDataStore::new(
+        client.clone(),
+        &leader_namespace,
+        "stellar-operator-carbon-data",
+    );
+    let carbon =
+        start_carbon_aware(args.carbon_config.as_deref(), &is_leader, carbon_store).await?;
+    let carbon_window = Arc::new(CarbonWindowService::new(carbon));
+
     // Create shared controller state
     let state = Arc::new(controller::ControllerState {
         client: client.clone(),
         enable_mtls: args.enable_mtls,
         operator_namespace: args.namespace.clone(),
-        mtls_config: mtls_config.clone(),
+        mtls_config: stellar_k8s::SharedMtlsConfig::new(mtls_config.clone()),
+        cert_manager_issuer: cert_manager_issuer.clone(),
         dry_run: args.dry_run,
         is_leader: Arc::clone(&is_leader),
+        backup_image: args
+            .backup_image
+            .clone()
+            .unwrap_or_else(controller::decentralized_backup::default_worker_image),
+        carbon_window: Some(Arc::clone(&carbon_window)),
+        quorum_peers: Default::default(),
+        maintenance_runs: Default::default(),
     });
 
     // Start the peer discovery manager
@@ -326,9 +547,25 @@ async fn run_operator(args: RunArgs) -> Result<(), Error> {
         }
     });
 
-    // Start the REST API server and optional mTLS certificate rotation
+    // Start the VSL refresher for validators using vlSource
+    let vsl_refresher = controller::VslRefresher::new(
+        client.clone(),
+        args.namespace.clone(),
+        Arc::clone(&is_leader),
+        controller::VslRefreshConfig {
+            enable_mtls: args.enable_mtls,
+            ..Default::default()
+        },
+    );
+    tokio::spawn(async move {
+        if let Err(e) = vsl_refresher.run().await {
+            tracing::error!("VSL refresher error: {:?}", e);
+        }
+    });
+
+    // Start the REST API server
     #[cfg(feature = "rest-api")]
-    {
+    let rustls_config = {
         let api_state = state.clone();
         let rustls_config = mtls_config
             .as_ref()
@@ -344,81 +581,106 @@ async fn run_operator(args: RunArgs) -> Result<(), Error> {
         let server_tls = rustls_config.clone();
 
         tokio::spawn(async move {
-            if let Err(e) = stellar_k8s::rest_api::run_server(api_state, server_tls).await {
+            if let Err(e) =
+                stellar_k8s::rest_api::run_server(api_state, server_tls, carbon_window).await
+            {
                 tracing::error!("REST API server error: {:?}", e);
             }
         });
+        rustls_config
+    };
 
-        // Certificate rotation: when mTLS is enabled, periodically check and rotate
-        // server cert if within threshold, then graceful reload of TLS config
-        if let (true, Some(rustls_config)) = (args.enable_mtls, rustls_config) {
-            let rotation_client = client.clone();
-            let rotation_namespace = args.namespace.clone();
-            let rotation_dns = vec![
-                "stellar-operator".to_string(),
-                format!("stellar-operator.{}", args.namespace),
-            ];
-            let rotation_threshold_days = std::env::var("CERT_ROTATION_THRESHOLD_DAYS")
-                .ok()
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(controller::mtls::DEFAULT_CERT_ROTATION_THRESHOLD_DAYS);
-            let is_leader_rot = Arc::clone(&is_leader);
+    // Certificate rotation: when mTLS is enabled, periodically check and rotate
+    // server cert if within threshold (or pick up cert-manager renewals), then
+    // reload the REST server's TLS config and the health check client material
+    if let (true, Some(mut loaded)) = (args.enable_mtls, mtls_config) {
+        let rotation_client = client.clone();
+        let rotation_namespace = args.namespace.clone();
+        let rotation_dns = server_dns_names.clone();
+        let rotation_threshold_days = std::env::var("CERT_ROTATION_THRESHOLD_DAYS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(controller::mtls::DEFAULT_CERT_ROTATION_THRESHOLD_DAYS);
+        let is_leader_rot = Arc::clone(&is_leader);
+        let uses_cert_manager = cert_manager_issuer.is_some();
+        let shared_mtls = state.mtls_config.clone();
 
-            tokio::spawn(async move {
-                let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600)); // check hourly
-                interval.tick().await; // first tick completes immediately
-                loop {
-                    interval.tick().await;
-                    if !is_leader_rot.load(Ordering::Relaxed) {
-                        continue;
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600)); // check hourly
+            interval.tick().await; // first tick completes immediately
+            loop {
+                interval.tick().await;
+                let rotated = if uses_cert_manager {
+                    // cert-manager renews the Secret itself; reload if it changed
+                    Ok(true)
+                } else if !is_leader_rot.load(Ordering::Relaxed) {
+                    // The leader rotates; standbys still pick up its new Secret
+                    Ok(true)
+                } else {
+                    // Advance CA rotation first so the server cert picks up the new bundle
+                    if let Err(e) =
+                        controller::mtls::ensure_ca(&rotation_client, &rotation_namespace).await
+                    {
+                        tracing::error!("CA rotation check failed: {:?}", e);
                     }
-                    match controller::mtls::maybe_rotate_server_cert(
+                    controller::mtls::maybe_rotate_server_cert(
                         &rotation_client,
                         &rotation_namespace,
                         rotation_dns.clone(),
                         rotation_threshold_days,
                     )
                     .await
-                    {
-                        Ok(true) => {
-                            // Rotation performed: fetch new secret and reload TLS
-                            let secrets: kube::Api<k8s_openapi::api::core::v1::Secret> =
-                                kube::Api::namespaced(rotation_client.clone(), &rotation_namespace);
-                            if let Ok(secret) =
-                                secrets.get(controller::mtls::SERVER_CERT_SECRET_NAME).await
-                            {
-                                if let (Some(cert), Some(key), Some(ca)) = (
-                                    secret.data.as_ref().and_then(|d| d.get("tls.crt")),
-                                    secret.data.as_ref().and_then(|d| d.get("tls.key")),
-                                    secret.data.as_ref().and_then(|d| d.get("ca.crt")),
-                                ) {
+                };
+                match rotated {
+                    Ok(true) => {
+                        // Rotation performed: fetch new secret and reload TLS
+                        let secrets: kube::Api<k8s_openapi::api::core::v1::Secret> =
+                            kube::Api::namespaced(rotation_client.clone(), &rotation_namespace);
+                        let current = secrets
+                            .get(controller::mtls::SERVER_CERT_SECRET_NAME)
+                            .await
+                            .map_err(Error::KubeError)
+                            .and_then(|secret| {
+                                controller::cert_manager::mtls_config_from_secret(&secret)
+                            });
+                        match current {
+                            Ok(cfg) if cfg != loaded => {
+                                #[cfg(feature = "rest-api")]
+                                if let Some(rustls_config) = &rustls_config {
                                     match stellar_k8s::rest_api::build_tls_server_config(
-                                        &cert.0, &key.0, &ca.0,
+                                        &cfg.cert_pem,
+                                        &cfg.key_pem,
+                                        &cfg.ca_pem,
                                     ) {
                                         Ok(new_config) => {
                                             rustls_config.reload_from_config(new_config);
-                                            info!(
-                                                "TLS server config reloaded with new certificate"
-                                            );
                                         }
                                         Err(e) => {
                                             tracing::error!(
                                                 "Failed to build TLS config after rotation: {:?}",
                                                 e
                                             );
+                                            continue;
                                         }
                                     }
                                 }
+                                shared_mtls.store(cfg.clone());
+                                loaded = cfg;
+                                info!("mTLS config reloaded with new certificate");
+                            }
+                            Ok(_) => {}
+                            Err(e) => {
+                                tracing::error!("Failed to load server certificate: {:?}", e);
                             }
                         }
-                        Ok(false) => {}
-                        Err(e) => {
-                            tracing::error!("Certificate rotation check failed: {:?}", e);
-                        }
+                    }
+                    Ok(false) => {}
+                    Err(e) => {
+                        tracing::error!("Certificate rotation check failed: {:?}", e);
                     }
                 }
-            });
-        }
+            }
+        });
     }
 
     // Run the main controller loop
@@ -430,6 +692,38 @@ async fn run_operator(args: RunArgs) -> Result<(), Error> {
     result
 }
 
+/// Load the carbon-aware configuration and start refreshing carbon data
+/// while this replica holds the lease. Standby replicas read the data the
+/// leader publishes to `store`.
+async fn start_carbon_aware(
+    config_path: Option<&std::path::Path>,
+    is_leader: &Arc<AtomicBool>,
+    store: CarbonDataStore,
+) -> Result<Arc<CarbonAwareScheduler>, Error> {
+    let config = match config_path {
+        Some(path) => {
+            let yaml = std::fs::read_to_string(path).map_err(|e| {
+                Error::ConfigError(format!(
+                    "Failed to read carbon-aware configuration {}: {e}",
+                    path.display()
+                ))
+            })?;
+            CarbonAwareConfig::from_yaml(&yaml)?
+        }
+        None => CarbonAwareConfig::disabled(),
+    };
+
+    let api = CarbonIntensityAPI::new(config.provider.clone())
+        .with_max_data_age(config.max_data_age_minutes);
+    let scheduler = Arc::new(
+        CarbonAwareScheduler::new(api, config)
+            .with_leader_election(Arc::clone(is_leader))
+            .with_shared_store(store),
+    );
+    scheduler.start_refresh_loop().await?;
+    Ok(scheduler)
+}
+
 const LEASE_NAME: &str = "stellar-operator-leader";
 const LEASE_DURATION_SECS: i32 = 15;
 const RENEW_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
@@ -438,23 +732,24 @@ const RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
 async fn run_leader_election(
     client: kube::Client,
     namespace: &str,
+    lease_name: &str,
     identity: &str,
     is_leader: Arc<AtomicBool>,
 ) {
     let leases: Api<Lease> = Api::namespaced(client, namespace);
 
     loop {
-        match try_acquire_or_renew(&leases, identity).await {
+        match try_acquire_or_renew(&leases, lease_name, identity).await {
             Ok(true) => {
                 if !is_leader.load(Ordering::Relaxed) {
-                    info!("Acquired leadership for lease {}", LEASE_NAME);
+                    info!("Acquired leadership for lease {}", lease_name);
                 }
                 is_leader.store(true, Ordering::Relaxed);
                 tokio::time::sleep(RENEW_INTERVAL).await;
             }
             Ok(false) => {
                 if is_leader.load(Ordering::Relaxed) {
-                    warn!("Lost leadership for lease {}", LEASE_NAME);
+                    warn!("Lost leadership for lease {}", lease_name);
                 }
                 is_leader.store(false, Ordering::Relaxed);
                 tokio::time::sleep(RETRY_INTERVAL).await;
@@ -468,10 +763,14 @@ async fn run_leader_election(
     }
 }
 
-async fn try_acquire_or_renew(leases: &Api<Lease>, identity: &str) -> Result<bool, kube::Error> {
+async fn try_acquire_or_renew(
+    leases: &Api<Lease>,
+    lease_name: &str,
+    identity: &str,
+) -> Result<bool, kube::Error> {
     let now = Utc::now();
 
-    match leases.get(LEASE_NAME).await {
+    match leases.get(lease_name).await {
         Ok(existing) => {
             let spec = existing.spec.as_ref();
             let current_holder = spec.and_then(|s| s.holder_identity.as_deref());
@@ -484,7 +783,7 @@ async fn try_acquire_or_renew(leases: &Api<Lease>, identity: &str) -> Result<boo
                     }
                 });
                 leases
-                    .patch(LEASE_NAME, &PatchParams::default(), &Patch::Merge(&patch))
+                    .patch(lease_name, &PatchParams::default(), &Patch::Merge(&patch))
                     .await?;
                 return Ok(true);
             }
@@ -514,7 +813,7 @@ async fn try_acquire_or_renew(leases: &Api<Lease>, identity: &str) -> Result<boo
                     }
                 });
                 leases
-                    .patch(LEASE_NAME, &PatchParams::default(), &Patch::Merge(&patch))
+                    .patch(lease_name, &PatchParams::default(), &Patch::Merge(&patch))
                     .await?;
                 Ok(true)
             } else {
@@ -524,7 +823,7 @@ async fn try_acquire_or_renew(leases: &Api<Lease>, identity: &str) -> Result<boo
         Err(kube::Error::Api(err)) if err.code == 404 => {
             let lease = Lease {
                 metadata: ObjectMeta {
-                    name: Some(LEASE_NAME.to_string()),
+                    name: Some(lease_name.to_string()),
                     namespace: Some(
                         leases
                             .resource_url()
@@ -544,7 +843,7 @@ async fn try_acquire_or_renew(leases: &Api<Lease>, identity: &str) -> Result<boo
                 }),
             };
             leases.create(&PostParams::default(), &lease).await?;
-            info!("Created lease {} with holder {}", LEASE_NAME, identity);
+            info!("Created lease {} with holder {}", lease_name, identity);
             Ok(true)
         }
         Err(e) => Err(e),
@@ -7,5 +7,7 @@ mod dto;
 mod handlers;
 mod server;
 mod sustainability;
+#[cfg(test)]
+mod sustainability_test;
 
 pub use server::{build_tls_server_config, run_server};
@@ -17,11 +17,13 @@ use rustls_pki_types::{CertificateDer, PrivateKeyDer};
 use tower_http::trace::TraceLayer;
 use tracing::info;
 
+use crate::carbon_aware::CarbonWindowService;
 use crate::controller::ControllerState;
 use crate::{Error, Result};
 
 use super::custom_metrics;
 use super::handlers;
+use super::sustainability::{sustainability_router, SustainabilityState};
 
 /// Build a rustls ServerConfig from PEM data (cert, key, CA for client verification).
 /// Used for initial server setup and after certificate rotation to reload without restart.
@@ -73,23 +75,34 @@ async fn metrics_handler() -> String {
 /// shared with a certificate rotation task: after rotating the Secret, build a new
 /// `ServerConfig` and call `reload_from_config` on the RustlsConfig to adopt the new
 /// certificate without dropping active connections.
+///
+/// The sustainability dashboard under `/api/v1/sustainability` serves the carbon
+/// data kept current by the carbon scheduler's refresh loop, and the batch work
+/// deferred by `carbon_window`.
 pub async fn run_server(
     state: Arc<ControllerState>,
     rustls_config: Option<RustlsConfig>,
+    carbon_window: Arc<CarbonWindowService>,
 ) -> Result<()> {
+    let sustainability = sustainability_router().with_state(SustainabilityState {
+        carbon_scheduler: Arc::clone(carbon_window.scheduler()),
+        carbon_window,
+    });
+
     let mut app = Router::new()
         .route("/health", get(handlers::health))
         .route("/leader", get(handlers::leader_status))
         .route("/api/v1/nodes", get(handlers::list_nodes))
-        .route("/api/v1/nodes/:namespace/:name", get(handlers::get_node))
+        .route("/api/v1/nodes/{namespace}/{name}", get(handlers::get_node))
         .route(
-            "/apis/custom.metrics.k8s.io/v1beta2/namespaces/:namespace/pods/:name/:metric",
+            "/apis/custom.metrics.k8s.io/v1beta2/namespaces/{namespace}/pods/{name}/{metric}",
             get(custom_metrics::get_pod_metric),
         )
         .route(
-            "/apis/custom.metrics.k8s.io/v1beta2/namespaces/:namespace/stellarnodes.stellar.org/:name/:metric",
+            "/apis/custom.metrics.k8s.io/v1beta2/namespaces/{namespace}/stellarnodes.stellar.org/{name}/{metric}",
             get(custom_metrics::get_stellar_node_metric),
         )
+        .nest("/api/v1/sustainability", sustainability)
         .layer(TraceLayer::new_for_http())
         .with_state(state);
 
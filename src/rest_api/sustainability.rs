@@ -3,8 +3,10 @@
 //! Provides REST API endpoints for monitoring CO2 footprint and carbon intensity
 //! of managed Stellar infrastructure.
 
-use crate::carbon_aware::{CarbonAwareScheduler, CarbonIntensityAPI};
-use crate::error::Result;
+use crate::carbon_aware::{
+    CarbonAwareScheduler, CarbonIntensityData, CarbonWindowDecision, CarbonWindowService,
+    RegionCarbonData,
+};
 use axum::{
     extract::State,
     http::StatusCode,
@@ -19,15 +21,35 @@ use tracing::{debug, info};
 
 /// Sustainability dashboard state
 #[derive(Clone)]
-#[allow(dead_code)]
 pub struct SustainabilityState {
+    /// Holds the carbon data shared with the scheduler and its refresh loop
     pub carbon_scheduler: Arc<CarbonAwareScheduler>,
-    pub carbon_api: Arc<CarbonIntensityAPI>,
+    /// Batch work deferred into low-carbon windows
+    pub carbon_window: Arc<CarbonWindowService>,
+}
+
+impl SustainabilityState {
+    /// Snapshot of the shared carbon intensity data
+    async fn carbon_data(&self) -> RegionCarbonData {
+        self.carbon_scheduler.carbon_data().read().await.clone()
+    }
+}
+
+impl From<&CarbonIntensityData> for RegionInfo {
+    fn from(data: &CarbonIntensityData) -> Self {
+        Self {
+            region: data.region.clone(),
+            carbon_intensity: data.carbon_intensity,
+            renewable_percentage: data.renewable_percentage,
+            source: data.source.clone(),
+            last_updated: data.timestamp,
+            ranking: None,
+        }
+    }
 }
 
 /// Sustainability metrics response
 #[derive(Serialize, Debug)]
-#[allow(dead_code)]
 pub struct SustainabilityMetrics {
     /// Current timestamp
     pub timestamp: DateTime<Utc>,
@@ -49,7 +71,6 @@ pub struct SustainabilityMetrics {
 
 /// Region carbon information
 #[derive(Clone, Serialize, Debug)]
-#[allow(dead_code)]
 pub struct RegionInfo {
     /// Region identifier
     pub region: String,
@@ -67,7 +88,6 @@ pub struct RegionInfo {
 
 /// Data status information
 #[derive(Serialize, Debug)]
-#[allow(dead_code)]
 pub struct DataStatus {
     /// Last successful update
     pub last_updated: DateTime<Utc>,
@@ -79,7 +99,6 @@ pub struct DataStatus {
 
 /// Node CO2 footprint information
 #[derive(Serialize, Debug)]
-#[allow(dead_code)]
 pub struct NodeFootprint {
     /// Node name
     pub node_name: String,
@@ -109,7 +128,6 @@ pub struct CarbonHistoryRequest {
 
 /// Carbon intensity forecast response
 #[derive(Serialize, Debug)]
-#[allow(dead_code)]
 pub struct CarbonForecastResponse {
     /// Region identifier
     pub region: String,
@@ -121,7 +139,6 @@ pub struct CarbonForecastResponse {
 
 /// Single forecast data point
 #[derive(Serialize, Debug)]
-#[allow(dead_code)]
 pub struct ForecastPoint {
     /// Timestamp
     pub timestamp: DateTime<Utc>,
@@ -131,20 +148,31 @@ pub struct ForecastPoint {
     pub confidence: f64,
 }
 
+/// Batch work deferred into low-carbon windows
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeferralsResponse {
+    /// Jobs waiting for their window, soonest first
+    pub pending: Vec<CarbonWindowDecision>,
+    /// Deferred jobs that have started since the operator started, newest first
+    pub completed: Vec<CarbonWindowDecision>,
+    /// Estimated CO2 saved by the completed deferrals (grams)
+    pub total_estimated_co2_saved_grams: f64,
+}
+
 /// Create sustainability dashboard router
-#[allow(dead_code)]
 pub fn sustainability_router() -> Router<SustainabilityState> {
     Router::new()
         .route("/metrics", get(get_sustainability_metrics))
         .route("/regions", get(get_region_data))
-        .route("/regions/:region", get(get_region_details))
-        .route("/forecast/:region", get(get_carbon_forecast))
+        .route("/regions/{region}", get(get_region_details))
+        .route("/forecast/{region}", get(get_carbon_forecast))
         .route("/nodes", get(get_node_footprints))
+        .route("/deferrals", get(get_deferrals))
         .route("/health", get(get_carbon_api_health))
 }
 
 /// Get overall sustainability metrics
-#[allow(dead_code)]
 pub async fn get_sustainability_metrics(
     State(state): State<SustainabilityState>,
 ) -> Result<Json<SustainabilityMetrics>, StatusCode> {
@@ -161,24 +189,9 @@ pub async fn get_sustainability_metrics(
         })?;
 
     // Get detailed region data
-    let carbon_data: crate::carbon_aware::types::RegionCarbonData =
-        state.carbon_api.fetch_all_regions().await.map_err(|e| {
-            debug!("Failed to fetch carbon data: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+    let carbon_data = state.carbon_data().await;
 
-    let mut regions: Vec<RegionInfo> = carbon_data
-        .regions
-        .values()
-        .map(|data| RegionInfo {
-            region: data.region.clone(),
-            carbon_intensity: data.carbon_intensity,
-            renewable_percentage: data.renewable_percentage,
-            source: data.source.clone(),
-            last_updated: data.timestamp,
-            ranking: None,
-        })
-        .collect();
+    let mut regions: Vec<RegionInfo> = carbon_data.regions.values().map(RegionInfo::from).collect();
 
     // Sort by carbon intensity and add rankings
     regions.sort_by(|a, b| a.carbon_intensity.partial_cmp(&b.carbon_intensity).unwrap());
@@ -196,7 +209,7 @@ pub async fn get_sustainability_metrics(
         .num_minutes();
     let data_status = DataStatus {
         last_updated: carbon_data.last_updated,
-        is_stale: age_minutes > 15, // Consider stale after 15 minutes
+        is_stale: carbon_stats.is_stale,
         age_minutes,
     };
 
@@ -218,79 +231,72 @@ pub async fn get_sustainability_metrics(
 }
 
 /// Get region-specific carbon data
-#[allow(dead_code)]
 pub async fn get_region_data(
     State(state): State<SustainabilityState>,
 ) -> Result<Json<Vec<RegionInfo>>, StatusCode> {
-    let carbon_data: crate::carbon_aware::types::RegionCarbonData = state
-        .carbon_api
-        .fetch_all_regions()
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    let regions: Vec<RegionInfo> = carbon_data
-        .regions
-        .values()
-        .map(|data| RegionInfo {
-            region: data.region.clone(),
-            carbon_intensity: data.carbon_intensity,
-            renewable_percentage: data.renewable_percentage,
-            source: data.source.clone(),
-            last_updated: data.timestamp,
-            ranking: None,
-        })
-        .collect();
+    let carbon_data = state.carbon_data().await;
+    let regions: Vec<RegionInfo> = carbon_data.regions.values().map(RegionInfo::from).collect();
 
     Ok(Json(regions))
 }
 
 /// Get detailed information for a specific region
-#[allow(dead_code)]
 pub async fn get_region_details(
     State(state): State<SustainabilityState>,
     axum::extract::Path(region): axum::extract::Path<String>,
 ) -> Result<Json<RegionInfo>, StatusCode> {
-    let carbon_data: Option<crate::carbon_aware::types::CarbonIntensityData> = state
-        .carbon_api
-        .fetch_region(&region)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    match carbon_data {
-        Some(data) => {
-            let region_info = RegionInfo {
-                region: data.region.clone(),
-                carbon_intensity: data.carbon_intensity,
-                renewable_percentage: data.renewable_percentage,
-                source: data.source.clone(),
-                last_updated: data.timestamp,
-                ranking: None,
-            };
-            Ok(Json(region_info))
-        }
-        None => Err(StatusCode::NOT_FOUND),
-    }
+    let carbon_data = state.carbon_data().await;
+    carbon_data
+        .get_region(&region)
+        .map(|data| Json(RegionInfo::from(data)))
+        .ok_or(StatusCode::NOT_FOUND)
 }
 
 /// Get carbon intensity forecast for a region
-#[allow(dead_code)]
 pub async fn get_carbon_forecast(
-    State(_state): State<SustainabilityState>,
+    State(state): State<SustainabilityState>,
     axum::extract::Path(region): axum::extract::Path<String>,
 ) -> Result<Json<CarbonForecastResponse>, StatusCode> {
-    // For now, return mock forecast data
-    // In real implementation, this would call the carbon API's forecast endpoint
-    let forecast = generate_mock_forecast(&region);
+    let carbon_data = state.carbon_data().await;
+    let zone = state.carbon_scheduler.config().zone_for_region(&region);
+    let data = carbon_data.get_region(zone).ok_or(StatusCode::NOT_FOUND)?;
+
+    // Providers without forecasts report an empty series
+    let forecast = data
+        .forecast
+        .iter()
+        .flatten()
+        .map(|point| ForecastPoint {
+            timestamp: point.timestamp,
+            carbon_intensity: point.carbon_intensity,
+            confidence: point.confidence,
+        })
+        .collect();
 
     Ok(Json(CarbonForecastResponse {
         region,
         forecast,
-        generated_at: Utc::now(),
+        generated_at: data.timestamp,
+    }))
+}
+
+/// List batch work deferred into low-carbon windows
+pub async fn get_deferrals(
+    State(state): State<SustainabilityState>,
+) -> Result<Json<DeferralsResponse>, StatusCode> {
+    let pending = state.carbon_window.pending().await;
+    let completed = state.carbon_window.history().await;
+    let total_estimated_co2_saved_grams =
+        completed.iter().map(|d| d.estimated_co2_saved_grams).sum();
+
+    Ok(Json(DeferralsResponse {
+        pending,
+        completed,
+        total_estimated_co2_saved_grams,
     }))
 }
 
 /// Get CO2 footprint information for managed nodes
-#[allow(dead_code)]
 pub async fn get_node_footprints(
     State(_state): State<SustainabilityState>,
 ) -> Result<Json<Vec<NodeFootprint>>, StatusCode> {
@@ -300,18 +306,19 @@ pub async fn get_node_footprints(
 }
 
 /// Check carbon API health
-#[allow(dead_code)]
 pub async fn get_carbon_api_health(
     State(state): State<SustainabilityState>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let is_healthy: bool = state
-        .carbon_api
+        .carbon_scheduler
+        .api()
         .health_check()
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     let health = serde_json::json!({
         "healthy": is_healthy,
+        "enabled": state.carbon_scheduler.config().enabled,
         "timestamp": Utc::now(),
         "api": "carbon_intensity"
     });
@@ -320,7 +327,6 @@ pub async fn get_carbon_api_health(
 }
 
 /// Generate mock node footprint data
-#[allow(dead_code)]
 async fn generate_mock_node_footprints(regions: &[RegionInfo]) -> Vec<NodeFootprint> {
     // Mock node data with realistic power consumption
     let mock_nodes = vec![
@@ -356,41 +362,3 @@ async fn generate_mock_node_footprints(regions: &[RegionInfo]) -> Vec<NodeFootpr
         })
         .collect()
 }
-
-/// Generate mock forecast data
-#[allow(dead_code)]
-fn generate_mock_forecast(region: &str) -> Vec<ForecastPoint> {
-    let mut forecast = Vec::new();
-    let now = Utc::now();
-
-    // Generate 24-hour forecast with some variation
-    let base_intensity = match region {
-        "us-west-2" => 150.0,
-        "us-east-1" => 400.0,
-        "eu-west-1" => 300.0,
-        "eu-central-1" => 450.0,
-        "ap-southeast-1" => 600.0,
-        _ => 350.0,
-    };
-
-    for hour in 0..24 {
-        let timestamp = now + chrono::Duration::hours(hour);
-        // Add some daily variation (lower during "daylight" hours)
-        let variation = if (hour % 24) >= 8 && (hour % 24) <= 18 {
-            -20.0 // Daytime - more solar
-        } else {
-            30.0 // Nighttime - less solar
-        };
-
-        let carbon_intensity = (base_intensity + variation + (hour as f64 * 2.0)).max(50.0);
-        let confidence = 0.8 - (hour as f64 * 0.02); // Decreasing confidence over time
-
-        forecast.push(ForecastPoint {
-            timestamp,
-            carbon_intensity,
-            confidence: confidence.max(0.5),
-        });
-    }
-
-    forecast
-}
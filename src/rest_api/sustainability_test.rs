@@ -0,0 +1,56 @@
+//! Tests for the sustainability dashboard router.
+
+#[cfg(test)]
+mod tests {
+    use crate::carbon_aware::{
+        CarbonAwareConfig, CarbonAwareScheduler, CarbonIntensityAPI, CarbonProvider,
+        CarbonWindowService,
+    };
+    use crate::rest_api::sustainability::{sustainability_router, SustainabilityState};
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    async fn state() -> SustainabilityState {
+        let scheduler = Arc::new(CarbonAwareScheduler::new(
+            CarbonIntensityAPI::new(CarbonProvider::Mock),
+            CarbonAwareConfig::default(),
+        ));
+        scheduler.refresh().await.unwrap();
+        SustainabilityState {
+            carbon_scheduler: Arc::clone(&scheduler),
+            carbon_window: Arc::new(CarbonWindowService::new(scheduler)),
+        }
+    }
+
+    async fn get(uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = sustainability_router()
+            .with_state(state().await)
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_router_serves_region_paths() {
+        let (status, body) = get("/regions/FR").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["region"], "FR");
+
+        let (status, body) = get("/forecast/FR").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["forecast"].as_array().unwrap().len(), 24);
+
+        assert_eq!(get("/regions/XX").await.0, StatusCode::NOT_FOUND);
+        assert_eq!(get("/deferrals").await.0, StatusCode::OK);
+    }
+}
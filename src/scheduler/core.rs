@@ -1,15 +1,57 @@
-use anyhow::Result;
-use k8s_openapi::api::core::v1::{Binding, Node, Pod};
-use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
-use kube::{api::PostParams, Api, Client, ResourceExt};
-use tokio::time::{sleep, Duration};
-use tracing::{error, info, warn};
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
+use k8s_openapi::api::core::v1::{
+    Binding, Event, EventSource, Node, PersistentVolume, PersistentVolumeClaim, Pod,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, Time};
+use kube::runtime::reflector::{self, ObjectRef, Store};
+use kube::runtime::{watcher, WatchStreamExt};
+use kube::{api::PostParams, Api, Client, Resource, ResourceExt};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tokio::time::Duration;
+use tracing::{debug, error, info, warn};
 
-use super::scoring;
+use super::queue::SchedulingQueue;
+use super::{filter, scoring};
+use crate::carbon_aware::CarbonAwareScheduler;
+
+/// How often a non-leader replica re-checks whether it acquired leadership
+const IDLE_POLL: Duration = Duration::from_secs(5);
+
+/// Cluster changes that matter to the scheduling queue
+#[derive(Debug)]
+enum ClusterEvent {
+    /// A pod for this scheduler is waiting for a node
+    PodPending(ObjectRef<Pod>),
+    /// A pod is bound (by us or anyone else)
+    PodBound(ObjectRef<Pod>),
+    /// A pod was deleted; `freed` is set when it held node resources
+    PodDeleted { pod: ObjectRef<Pod>, freed: bool },
+    /// A node appeared or its labels, taints or capacity changed
+    NodeChanged,
+}
+
+/// Outcome of one scheduling attempt
+enum Attempt {
+    /// Bound; carries the pod as it will look once the watch catches up
+    Bound(Box<Pod>),
+    /// The pod is gone or no longer pending
+    Skipped,
+    /// No feasible node, or binding failed; retry with backoff
+    Unschedulable,
+}
 
 pub struct Scheduler {
     client: Client,
     scheduler_name: String,
+    is_leader: Option<Arc<AtomicBool>>,
+    carbon: Option<Arc<CarbonAwareScheduler>>,
 }
 
 impl Scheduler {
@@ -17,104 +59,311 @@ impl Scheduler {
         Self {
             client,
             scheduler_name,
+            is_leader: None,
+            carbon: None,
         }
     }
 
+    /// Score opted-in pods with the carbon data kept by `carbon`
+    pub fn with_carbon_aware(mut self, carbon: Arc<CarbonAwareScheduler>) -> Self {
+        self.carbon = Some(carbon);
+        self
+    }
+
+    /// Only bind pods while `is_leader` is set. Caches keep syncing either
+    /// way so a standby replica can take over immediately.
+    pub fn with_leader_election(mut self, is_leader: Arc<AtomicBool>) -> Self {
+        self.is_leader = Some(is_leader);
+        self
+    }
+
+    fn is_leader(&self) -> bool {
+        self.is_leader
+            .as_ref()
+            .is_none_or(|l| l.load(Ordering::Relaxed))
+    }
+
     pub async fn run(&self) -> Result<()> {
         info!("Starting scheduler: {}", self.scheduler_name);
 
-        loop {
-            if let Err(e) = self.schedule_one_cycle().await {
-                error!("Error in scheduler cycle: {}", e);
-            }
-            sleep(Duration::from_secs(5)).await;
-        }
-    }
+        let (pods, pod_writer) = reflector::store::<Pod>();
+        let (nodes, node_writer) = reflector::store::<Node>();
+        let (tx, mut rx) = mpsc::unbounded_channel();
 
-    async fn schedule_one_cycle(&self) -> Result<()> {
-        let pods: Api<Pod> = Api::all(self.client.clone());
-        let nodes: Api<Node> = Api::all(self.client.clone());
+        tokio::spawn(watch_pods(
+            Api::all(self.client.clone()),
+            pod_writer,
+            self.scheduler_name.clone(),
+            tx.clone(),
+        ));
+        tokio::spawn(watch_nodes(Api::all(self.client.clone()), node_writer, tx));
 
-        // List all pods and filter for our scheduler and unscheduled
-        let all_pods = pods.list(&kube::api::ListParams::default()).await?;
+        pods.wait_until_ready().await?;
+        nodes.wait_until_ready().await?;
+        info!(
+            "Scheduler caches synced: {} pods, {} nodes",
+            pods.len(),
+            nodes.len()
+        );
 
-        let mut candidates = Vec::new();
-        for p in all_pods {
-            let spec = match &p.spec {
-                Some(s) => s,
-                None => continue,
+        let mut queue = SchedulingQueue::default();
+        // Pods we bound that the watch has not reported as bound yet
+        let mut assumed: HashMap<ObjectRef<Pod>, Arc<Pod>> = HashMap::new();
+
+        loop {
+            let wake = if self.is_leader() {
+                queue.next_retry()
+            } else {
+                None
             };
+            let wake = wake.map_or_else(
+                || tokio::time::Instant::now() + IDLE_POLL,
+                tokio::time::Instant::from_std,
+            );
 
-            if spec.scheduler_name.as_deref() == Some(&self.scheduler_name)
-                && spec.node_name.is_none()
-            {
-                candidates.push(p);
+            tokio::select! {
+                event = rx.recv() => {
+                    let event = event.ok_or_else(|| anyhow!("Scheduler watches stopped"))?;
+                    apply_event(event, &mut queue, &mut assumed);
+                    // Apply everything that arrived meanwhile before scheduling
+                    while let Ok(event) = rx.try_recv() {
+                        apply_event(event, &mut queue, &mut assumed);
+                    }
+                }
+                _ = tokio::time::sleep_until(wake) => {}
+            }
+
+            if !self.is_leader() {
+                continue;
+            }
+
+            while let Some(key) = queue.pop(Instant::now()) {
+                match self.schedule_pod(&key, &pods, &nodes, &assumed).await {
+                    Attempt::Bound(pod) => {
+                        queue.forget(&key);
+                        assumed.insert(key, Arc::new(*pod));
+                    }
+                    Attempt::Skipped => queue.forget(&key),
+                    Attempt::Unschedulable => {
+                        let delay = queue.backoff(key.clone(), Instant::now());
+                        debug!("Retrying pod {} in {:?}", key, delay);
+                    }
+                }
             }
         }
+    }
 
-        if candidates.is_empty() {
-            return Ok(());
+    /// Try to place a single pending pod
+    async fn schedule_pod(
+        &self,
+        key: &ObjectRef<Pod>,
+        pods: &Store<Pod>,
+        nodes: &Store<Node>,
+        assumed: &HashMap<ObjectRef<Pod>, Arc<Pod>>,
+    ) -> Attempt {
+        let Some(pod) = pods.get(key) else {
+            return Attempt::Skipped;
+        };
+        if !is_pending(&pod, &self.scheduler_name) || assumed.contains_key(key) {
+            return Attempt::Skipped;
         }
+        let pod_name = pod.name_any();
+        info!("Attempting to schedule pod: {}", pod_name);
 
-        info!("Found {} unscheduled pods", candidates.len());
+        // 1. Filter nodes (predicates)
+        let node_list = nodes.state();
+        let filtered_nodes = match self.filter_nodes(&pod, &node_list, pods, assumed).await {
+            Ok(Ok(n)) => n,
+            Ok(Err(summary)) => {
+                warn!("No suitable nodes found for pod {}: {}", pod_name, summary);
+                self.emit_event(&pod, "Warning", "FailedScheduling", &summary)
+                    .await;
+                return Attempt::Unschedulable;
+            }
+            Err(e) => {
+                warn!("Cannot evaluate nodes for pod {}: {}", pod_name, e);
+                self.emit_event(&pod, "Warning", "FailedScheduling", &e.to_string())
+                    .await;
+                return Attempt::Unschedulable;
+            }
+        };
 
-        let node_list = nodes.list(&kube::api::ListParams::default()).await?;
-        let nodes_vec = node_list.items;
+        // 2. Score nodes
+        let best_node =
+            match scoring::score_nodes(&pod, &filtered_nodes, &self.client, self.carbon.as_deref())
+                .await
+            {
+                Ok(Some(node)) => node,
+                Ok(None) => {
+                    warn!("No best node found for pod {}", pod_name);
+                    return Attempt::Unschedulable;
+                }
+                Err(e) => {
+                    error!("Scoring failed for pod {}: {}", pod_name, e);
+                    return Attempt::Unschedulable;
+                }
+            };
 
-        for pod in candidates {
-            self.schedule_pod(&pod, &nodes_vec).await?;
+        // 3. Bind
+        let node_name = best_node.name_any();
+        info!("Binding pod {} to node {}", pod_name, node_name);
+        if let Err(e) = self.bind_pod(&pod, best_node).await {
+            warn!("Failed to bind pod {} to {}: {}", pod_name, node_name, e);
+            self.emit_event(
+                &pod,
+                "Warning",
+                "FailedScheduling",
+                &format!("Binding rejected: {e}"),
+            )
+            .await;
+            return Attempt::Unschedulable;
         }
 
-        Ok(())
-    }
+        self.emit_event(
+            &pod,
+            "Normal",
+            "Scheduled",
+            &format!(
+                "Successfully assigned {}/{} to {}",
+                pod.namespace().unwrap_or_default(),
+                pod_name,
+                node_name
+            ),
+        )
+        .await;
 
-    async fn schedule_pod(&self, pod: &Pod, nodes: &[Node]) -> Result<()> {
-        let pod_name = pod.name_any();
-        info!("Attempting to schedule pod: {}", pod_name);
-
-        // 1. Filter nodes (basic checks)
-        let filtered_nodes = self.filter_nodes(pod, nodes).await;
-        if filtered_nodes.is_empty() {
-            warn!("No suitable nodes found for pod {}", pod_name);
-            return Ok(());
+        let mut bound = (*pod).clone();
+        if let Some(spec) = bound.spec.as_mut() {
+            spec.node_name = Some(node_name);
         }
+        Attempt::Bound(Box::new(bound))
+    }
 
-        // 2. Score nodes
-        let best_node = scoring::score_nodes(pod, &filtered_nodes, &self.client).await?;
+    /// Feasible nodes for the pod, or the FailedScheduling summary when
+    /// there are none
+    async fn filter_nodes<'a>(
+        &self,
+        pod: &Pod,
+        nodes: &'a [Arc<Node>],
+        pods: &Store<Pod>,
+        assumed: &HashMap<ObjectRef<Pod>, Arc<Pod>>,
+    ) -> Result<std::result::Result<Vec<&'a Node>, String>> {
+        let volumes = self.bound_volumes(pod).await?;
 
-        if let Some(node) = best_node {
-            info!("Binding pod {} to node {}", pod_name, node.name_any());
-            self.bind_pod(pod, node).await?;
-        } else {
-            warn!("No best node found for pod {}", pod_name);
+        // Assumed pods replace their (still unbound) cached copies
+        let cached = pods.state();
+        let bound_pods = cached
+            .iter()
+            .filter(|p| !assumed.contains_key(&ObjectRef::from_obj(p.as_ref())))
+            .chain(assumed.values())
+            .map(|p| p.as_ref());
+        let ctx = filter::FilterContext::new(bound_pods, &volumes);
+        let result = filter::filter_nodes(pod, nodes.iter().map(|n| n.as_ref()), &ctx);
+
+        for (node_name, reason) in &result.rejected {
+            debug!(
+                "Node {} rejected for pod {}: {}",
+                node_name,
+                pod.name_any(),
+                reason
+            );
+        }
+        if result.feasible.is_empty() {
+            return Ok(Err(result.summary()));
         }
 
-        Ok(())
+        // Quorum-aware placement stays in scoring: filtering is "hard", so
+        // avoiding nodes that already host a peer would leave validators
+        // unschedulable on small clusters.
+
+        Ok(Ok(result.feasible))
     }
 
-    async fn filter_nodes<'a>(&self, _pod: &Pod, nodes: &'a [Node]) -> Vec<&'a Node> {
-        let mut filtered = Vec::new();
+    /// Record a scheduling Event on the pod; failures are only logged.
+    ///
+    /// Repeated attempts reuse one Event per pod and reason, bumping its
+    /// count, so a pod stuck in the queue does not flood the namespace.
+    async fn emit_event(&self, pod: &Pod, event_type: &str, reason: &str, message: &str) {
+        let namespace = pod.namespace().unwrap_or_else(|| "default".to_string());
+        let events: Api<Event> = Api::namespaced(self.client.clone(), &namespace);
 
-        for n in nodes {
-            // 1. Check for unschedulable taint/flag
-            if let Some(spec) = &n.spec {
-                if spec.unschedulable == Some(true) {
-                    continue;
-                }
+        let mut hasher = DefaultHasher::new();
+        (pod.uid(), reason).hash(&mut hasher);
+        let name = format!("{}.{:016x}", pod.name_any(), hasher.finish());
+
+        let time = Time(chrono::Utc::now());
+        let result = match events.get_opt(&name).await {
+            Ok(Some(mut event)) => {
+                event.type_ = Some(event_type.to_string());
+                event.message = Some(message.to_string());
+                event.last_timestamp = Some(time);
+                event.count = Some(event.count.unwrap_or(1) + 1);
+                events
+                    .replace(&name, &PostParams::default(), &event)
+                    .await
+                    .map(|_| ())
+            }
+            Ok(None) => {
+                let event = Event {
+                    metadata: ObjectMeta {
+                        name: Some(name),
+                        ..Default::default()
+                    },
+                    type_: Some(event_type.to_string()),
+                    reason: Some(reason.to_string()),
+                    message: Some(message.to_string()),
+                    involved_object: pod.object_ref(&()),
+                    source: Some(EventSource {
+                        component: Some(self.scheduler_name.clone()),
+                        ..Default::default()
+                    }),
+                    reporting_component: Some(self.scheduler_name.clone()),
+                    first_timestamp: Some(time.clone()),
+                    last_timestamp: Some(time),
+                    count: Some(1),
+                    ..Default::default()
+                };
+                events
+                    .create(&PostParams::default(), &event)
+                    .await
+                    .map(|_| ())
             }
+            Err(e) => Err(e),
+        };
 
-            // 2. Resource check (Stub for CPU/Mem)
-            // In a production scheduler, we would check if node has enough capacity
+        if let Err(e) = result {
+            warn!(
+                "Failed to record {} event for pod {}: {}",
+                reason,
+                pod.name_any(),
+                e
+            );
+        }
+    }
 
-            filtered.push(n);
+    /// PersistentVolumes already bound to the pod's claims.
+    ///
+    /// Unbound claims (e.g. WaitForFirstConsumer) place no constraint here;
+    /// the volume is provisioned in whatever zone the pod lands in.
+    async fn bound_volumes(&self, pod: &Pod) -> Result<Vec<PersistentVolume>> {
+        let claim_names = filter::pod_claim_names(pod);
+        if claim_names.is_empty() {
+            return Ok(Vec::new());
         }
 
-        // 3. Quorum-aware filtering
-        // If this is a validator, we ideally want to avoid nodes that already host a peer.
-        // However, filtering is "hard" - if all nodes have peers, we'd fail to schedule.
-        // So we keep filtering light and let scoring do the heavy lifting for "best" node.
+        let namespace = pod.namespace().unwrap_or_else(|| "default".into());
+        let claims: Api<PersistentVolumeClaim> = Api::namespaced(self.client.clone(), &namespace);
+        let pvs: Api<PersistentVolume> = Api::all(self.client.clone());
 
-        filtered
+        let mut volumes = Vec::new();
+        for claim_name in claim_names {
+            let claim = claims.get(&claim_name).await?;
+            let volume_name = claim.spec.as_ref().and_then(|s| s.volume_name.as_deref());
+            if let Some(volume_name) = volume_name.filter(|n| !n.is_empty()) {
+                volumes.push(pvs.get(volume_name).await?);
+            }
+        }
+        Ok(volumes)
     }
 
     async fn bind_pod(&self, pod: &Pod, node: &Node) -> Result<()> {
@@ -150,3 +399,132 @@ impl Scheduler {
         Ok(())
     }
 }
+
+fn apply_event(
+    event: ClusterEvent,
+    queue: &mut SchedulingQueue<ObjectRef<Pod>>,
+    assumed: &mut HashMap<ObjectRef<Pod>, Arc<Pod>>,
+) {
+    let now = Instant::now();
+    match event {
+        ClusterEvent::PodPending(pod) => queue.add(pod),
+        ClusterEvent::PodBound(pod) => {
+            assumed.remove(&pod);
+            queue.forget(&pod);
+        }
+        ClusterEvent::PodDeleted { pod, freed } => {
+            let was_assumed = assumed.remove(&pod).is_some();
+            queue.forget(&pod);
+            if freed || was_assumed {
+                queue.retry_all(now);
+            }
+        }
+        ClusterEvent::NodeChanged => queue.retry_all(now),
+    }
+}
+
+/// Whether a pod is waiting for this scheduler to place it
+pub(crate) fn is_pending(pod: &Pod, scheduler_name: &str) -> bool {
+    let Some(spec) = &pod.spec else {
+        return false;
+    };
+    spec.scheduler_name.as_deref() == Some(scheduler_name)
+        && spec.node_name.is_none()
+        && pod.metadata.deletion_timestamp.is_none()
+        && !matches!(
+            pod.status.as_ref().and_then(|s| s.phase.as_deref()),
+            Some("Succeeded" | "Failed")
+        )
+}
+
+fn is_bound(pod: &Pod) -> bool {
+    pod.spec.as_ref().is_some_and(|s| s.node_name.is_some())
+}
+
+/// Hash of the node fields the predicates look at. Node status heartbeats
+/// arrive every few seconds; only changes to these should wake parked pods.
+pub(crate) fn node_fingerprint(node: &Node) -> u64 {
+    let spec = node.spec.as_ref();
+    let relevant = (
+        &node.metadata.labels,
+        spec.and_then(|s| s.taints.as_ref()),
+        spec.and_then(|s| s.unschedulable),
+        node.status.as_ref().and_then(|s| s.allocatable.as_ref()),
+    );
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(&relevant)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+async fn watch_pods(
+    api: Api<Pod>,
+    writer: reflector::store::Writer<Pod>,
+    scheduler_name: String,
+    tx: mpsc::UnboundedSender<ClusterEvent>,
+) {
+    let stream = watcher(api, watcher::Config::default())
+        .default_backoff()
+        .modify(|pod| pod.managed_fields_mut().clear())
+        .reflect(writer);
+    futures::pin_mut!(stream);
+
+    while let Some(event) = stream.next().await {
+        let event = match event {
+            Ok(watcher::Event::Apply(pod) | watcher::Event::InitApply(pod)) => {
+                let key = ObjectRef::from_obj(&pod);
+                if is_pending(&pod, &scheduler_name) {
+                    ClusterEvent::PodPending(key)
+                } else if is_bound(&pod) {
+                    ClusterEvent::PodBound(key)
+                } else {
+                    continue;
+                }
+            }
+            Ok(watcher::Event::Delete(pod)) => ClusterEvent::PodDeleted {
+                pod: ObjectRef::from_obj(&pod),
+                freed: is_bound(&pod),
+            },
+            Ok(_) => continue,
+            Err(e) => {
+                warn!("Scheduler pod watch error: {}", e);
+                continue;
+            }
+        };
+        if tx.send(event).is_err() {
+            return;
+        }
+    }
+}
+
+async fn watch_nodes(
+    api: Api<Node>,
+    writer: reflector::store::Writer<Node>,
+    tx: mpsc::UnboundedSender<ClusterEvent>,
+) {
+    let stream = watcher(api, watcher::Config::default())
+        .default_backoff()
+        .modify(|node| node.managed_fields_mut().clear())
+        .reflect(writer);
+    futures::pin_mut!(stream);
+
+    let mut fingerprints: HashMap<String, u64> = HashMap::new();
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(watcher::Event::Apply(node) | watcher::Event::InitApply(node)) => {
+                let fingerprint = node_fingerprint(&node);
+                if fingerprints.insert(node.name_any(), fingerprint) != Some(fingerprint)
+                    && tx.send(ClusterEvent::NodeChanged).is_err()
+                {
+                    return;
+                }
+            }
+            Ok(watcher::Event::Delete(node)) => {
+                fingerprints.remove(&node.name_any());
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Scheduler node watch error: {}", e),
+        }
+    }
+}
@@ -0,0 +1,539 @@
+//! Node filtering predicates for the Stellar scheduler
+//!
+//! These mirror the kube-scheduler filter plugins we rely on: node
+//! unschedulable, resource fit, taint toleration, node selector / required
+//! node affinity and volume topology. Everything here is pure so it can be
+//! exercised with synthetic `Node`/`Pod` objects.
+
+use k8s_openapi::api::core::v1::{
+    Node, NodeSelector, NodeSelectorRequirement, NodeSelectorTerm, PersistentVolume, Pod, Taint,
+    Toleration,
+};
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use kube::ResourceExt;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Resources checked by the fit predicate
+pub const RESOURCE_CPU: &str = "cpu";
+pub const RESOURCE_MEMORY: &str = "memory";
+pub const RESOURCE_EPHEMERAL_STORAGE: &str = "ephemeral-storage";
+pub const RESOURCE_PODS: &str = "pods";
+
+// Zone/region labels, current and legacy, as set on nodes and zonal PVs
+const ZONE_LABELS: [&str; 2] = [
+    "topology.kubernetes.io/zone",
+    "failure-domain.beta.kubernetes.io/zone",
+];
+const REGION_LABELS: [&str; 2] = [
+    "topology.kubernetes.io/region",
+    "failure-domain.beta.kubernetes.io/region",
+];
+
+/// Why a node was rejected for a pod
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterReason {
+    /// `spec.unschedulable` is set (cordoned)
+    Unschedulable,
+    /// Not enough allocatable capacity left for the named resource
+    InsufficientResource(&'static str),
+    /// A NoSchedule/NoExecute taint the pod does not tolerate
+    UntoleratedTaint { key: String, value: String },
+    /// `nodeSelector` or required node affinity does not match
+    NodeAffinityMismatch,
+    /// A bound PersistentVolume cannot be attached from this node's topology
+    VolumeNodeAffinityConflict,
+}
+
+impl fmt::Display for FilterReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Wording follows kube-scheduler so FailedScheduling messages look familiar
+        match self {
+            Self::Unschedulable => write!(f, "node(s) were unschedulable"),
+            Self::InsufficientResource(resource) => write!(f, "Insufficient {resource}"),
+            Self::UntoleratedTaint { key, value } if value.is_empty() => {
+                write!(f, "node(s) had untolerated taint {{{key}}}")
+            }
+            Self::UntoleratedTaint { key, value } => {
+                write!(f, "node(s) had untolerated taint {{{key}: {value}}}")
+            }
+            Self::NodeAffinityMismatch => {
+                write!(f, "node(s) didn't match Pod's node affinity/selector")
+            }
+            Self::VolumeNodeAffinityConflict => {
+                write!(f, "node(s) had volume node affinity conflict")
+            }
+        }
+    }
+}
+
+/// Requests already committed to a node
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NodeUsage {
+    pub requested: ResourceAmounts,
+    pub pods: i64,
+}
+
+/// Cluster state the predicates need besides the candidate nodes
+#[derive(Debug, Clone, Default)]
+pub struct FilterContext<'a> {
+    /// Requests of pods already bound (or assumed) to each node
+    pub usage: HashMap<String, NodeUsage>,
+    /// PersistentVolumes bound to the pod's claims
+    pub volumes: &'a [PersistentVolume],
+}
+
+impl<'a> FilterContext<'a> {
+    /// Sum up requests per node once, so filtering stays cheap on clusters
+    /// with thousands of pods
+    pub fn new<'p>(
+        pods: impl IntoIterator<Item = &'p Pod>,
+        volumes: &'a [PersistentVolume],
+    ) -> Self {
+        let mut usage: HashMap<String, NodeUsage> = HashMap::new();
+        for pod in pods {
+            let Some(node_name) = pod.spec.as_ref().and_then(|s| s.node_name.as_deref()) else {
+                continue;
+            };
+            if matches!(
+                pod.status.as_ref().and_then(|s| s.phase.as_deref()),
+                Some("Succeeded" | "Failed")
+            ) {
+                continue;
+            }
+            let entry = usage.entry(node_name.to_string()).or_default();
+            entry.requested.add(pod_requests(pod));
+            entry.pods += 1;
+        }
+        Self { usage, volumes }
+    }
+}
+
+/// Result of filtering a node list
+#[derive(Debug, Default)]
+pub struct FilterResult<'a> {
+    pub feasible: Vec<&'a Node>,
+    /// Rejected node names with the first failing predicate
+    pub rejected: Vec<(String, FilterReason)>,
+}
+
+impl FilterResult<'_> {
+    /// kube-scheduler style summary, e.g.
+    /// `0/3 nodes are available: 1 Insufficient cpu, 2 node(s) were unschedulable.`
+    pub fn summary(&self) -> String {
+        let total = self.feasible.len() + self.rejected.len();
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for (_, reason) in &self.rejected {
+            *counts.entry(reason.to_string()).or_default() += 1;
+        }
+        let reasons: Vec<String> = counts
+            .into_iter()
+            .map(|(reason, count)| format!("{count} {reason}"))
+            .collect();
+
+        if reasons.is_empty() {
+            format!("{}/{} nodes are available.", self.feasible.len(), total)
+        } else {
+            format!(
+                "{}/{} nodes are available: {}.",
+                self.feasible.len(),
+                total,
+                reasons.join(", ")
+            )
+        }
+    }
+}
+
+/// Run every predicate against every node
+pub fn filter_nodes<'a>(
+    pod: &Pod,
+    nodes: impl IntoIterator<Item = &'a Node>,
+    ctx: &FilterContext,
+) -> FilterResult<'a> {
+    let mut result = FilterResult::default();
+    for node in nodes {
+        match check_node(pod, node, ctx) {
+            Ok(()) => result.feasible.push(node),
+            Err(reason) => result.rejected.push((node.name_any(), reason)),
+        }
+    }
+    result
+}
+
+/// Check a single node, returning the first failing predicate
+pub fn check_node(pod: &Pod, node: &Node, ctx: &FilterContext) -> Result<(), FilterReason> {
+    if node.spec.as_ref().and_then(|s| s.unschedulable) == Some(true) {
+        return Err(FilterReason::Unschedulable);
+    }
+    check_taints(pod, node)?;
+    check_node_affinity(pod, node)?;
+    check_resources(pod, node, ctx)?;
+    check_volume_topology(node, ctx.volumes)
+}
+
+// ---------------------------------------------------------------------------
+// Resource fit
+// ---------------------------------------------------------------------------
+
+/// Resource amounts in scheduler units: millicores for CPU, bytes otherwise
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceAmounts {
+    pub cpu_millis: i64,
+    pub memory_bytes: i64,
+    pub ephemeral_storage_bytes: i64,
+}
+
+impl ResourceAmounts {
+    fn from_map(map: Option<&BTreeMap<String, Quantity>>) -> Self {
+        let get = |name: &str, millis: bool| {
+            map.and_then(|m| m.get(name))
+                .and_then(|q| {
+                    if millis {
+                        quantity_to_millis(q)
+                    } else {
+                        quantity_to_units(q)
+                    }
+                })
+                .unwrap_or(0)
+        };
+        Self {
+            cpu_millis: get(RESOURCE_CPU, true),
+            memory_bytes: get(RESOURCE_MEMORY, false),
+            ephemeral_storage_bytes: get(RESOURCE_EPHEMERAL_STORAGE, false),
+        }
+    }
+
+    fn add(&mut self, other: Self) {
+        self.cpu_millis += other.cpu_millis;
+        self.memory_bytes += other.memory_bytes;
+        self.ephemeral_storage_bytes += other.ephemeral_storage_bytes;
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            cpu_millis: self.cpu_millis.max(other.cpu_millis),
+            memory_bytes: self.memory_bytes.max(other.memory_bytes),
+            ephemeral_storage_bytes: self
+                .ephemeral_storage_bytes
+                .max(other.ephemeral_storage_bytes),
+        }
+    }
+}
+
+/// Effective requests of a pod, as kube-scheduler computes them: the larger
+/// of the summed app containers and the largest init container, plus overhead
+pub fn pod_requests(pod: &Pod) -> ResourceAmounts {
+    let Some(spec) = &pod.spec else {
+        return ResourceAmounts::default();
+    };
+
+    let mut containers = ResourceAmounts::default();
+    for c in &spec.containers {
+        containers.add(ResourceAmounts::from_map(
+            c.resources.as_ref().and_then(|r| r.requests.as_ref()),
+        ));
+    }
+
+    let init = spec
+        .init_containers
+        .iter()
+        .flatten()
+        .map(|c| ResourceAmounts::from_map(c.resources.as_ref().and_then(|r| r.requests.as_ref())))
+        .fold(ResourceAmounts::default(), ResourceAmounts::max);
+
+    let mut total = containers.max(init);
+    total.add(ResourceAmounts::from_map(spec.overhead.as_ref()));
+    total
+}
+
+/// Node allocatable, falling back to capacity when allocatable is not reported
+pub fn node_allocatable(node: &Node) -> ResourceAmounts {
+    let status = node.status.as_ref();
+    ResourceAmounts::from_map(
+        status
+            .and_then(|s| s.allocatable.as_ref())
+            .or_else(|| status.and_then(|s| s.capacity.as_ref())),
+    )
+}
+
+fn max_pods(node: &Node) -> Option<i64> {
+    let status = node.status.as_ref()?;
+    status
+        .allocatable
+        .as_ref()
+        .or(status.capacity.as_ref())?
+        .get(RESOURCE_PODS)
+        .and_then(quantity_to_units)
+}
+
+fn check_resources(pod: &Pod, node: &Node, ctx: &FilterContext) -> Result<(), FilterReason> {
+    let usage = ctx.usage.get(&node.name_any()).copied().unwrap_or_default();
+    let used = usage.requested;
+
+    if max_pods(node).is_some_and(|max| usage.pods + 1 > max) {
+        return Err(FilterReason::InsufficientResource(RESOURCE_PODS));
+    }
+
+    let requested = pod_requests(pod);
+    let allocatable = node_allocatable(node);
+    let checks = [
+        (
+            RESOURCE_CPU,
+            requested.cpu_millis,
+            used.cpu_millis,
+            allocatable.cpu_millis,
+        ),
+        (
+            RESOURCE_MEMORY,
+            requested.memory_bytes,
+            used.memory_bytes,
+            allocatable.memory_bytes,
+        ),
+        (
+            RESOURCE_EPHEMERAL_STORAGE,
+            requested.ephemeral_storage_bytes,
+            used.ephemeral_storage_bytes,
+            allocatable.ephemeral_storage_bytes,
+        ),
+    ];
+    for (resource, requested, used, allocatable) in checks {
+        // Requests of zero always fit, even on nodes that do not report the resource
+        if requested > 0 && requested + used > allocatable {
+            return Err(FilterReason::InsufficientResource(resource));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse a quantity into millis (CPU), rounding up like the API server does
+pub fn quantity_to_millis(q: &Quantity) -> Option<i64> {
+    parse_quantity(&q.0).map(|v| (v * 1000.0).ceil() as i64)
+}
+
+/// Parse a quantity into whole units (bytes, pods), rounding up
+pub fn quantity_to_units(q: &Quantity) -> Option<i64> {
+    parse_quantity(&q.0).map(|v| v.ceil() as i64)
+}
+
+fn parse_quantity(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '+' | '-')))
+        .unwrap_or(s.len());
+    let (number, suffix) = s.split_at(split);
+    let value: f64 = number.parse().ok()?;
+
+    let multiplier = match suffix {
+        "" => 1.0,
+        "n" => 1e-9,
+        "u" => 1e-6,
+        "m" => 1e-3,
+        "k" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        "P" => 1e15,
+        "E" => 1e18,
+        "Ki" => 1024.0,
+        "Mi" => 1024f64.powi(2),
+        "Gi" => 1024f64.powi(3),
+        "Ti" => 1024f64.powi(4),
+        "Pi" => 1024f64.powi(5),
+        "Ei" => 1024f64.powi(6),
+        exp if exp.starts_with(['e', 'E']) => 10f64.powi(exp[1..].parse().ok()?),
+        _ => return None,
+    };
+    Some(value * multiplier)
+}
+
+// ---------------------------------------------------------------------------
+// Taints and tolerations
+// ---------------------------------------------------------------------------
+
+fn tolerates(toleration: &Toleration, taint: &Taint) -> bool {
+    if toleration
+        .effect
+        .as_deref()
+        .is_some_and(|e| !e.is_empty() && e != taint.effect)
+    {
+        return false;
+    }
+
+    match toleration.operator.as_deref() {
+        Some("Exists") => toleration
+            .key
+            .as_deref()
+            .is_none_or(|k| k.is_empty() || k == taint.key),
+        _ => {
+            toleration.key.as_deref() == Some(taint.key.as_str())
+                && toleration.value.as_deref().unwrap_or("") == taint.value.as_deref().unwrap_or("")
+        }
+    }
+}
+
+fn check_taints(pod: &Pod, node: &Node) -> Result<(), FilterReason> {
+    let tolerations = pod
+        .spec
+        .as_ref()
+        .and_then(|s| s.tolerations.as_deref())
+        .unwrap_or_default();
+    let taints = node
+        .spec
+        .as_ref()
+        .and_then(|s| s.taints.as_deref())
+        .unwrap_or_default();
+
+    // PreferNoSchedule is a scoring hint, not a filter
+    for taint in taints
+        .iter()
+        .filter(|t| t.effect == "NoSchedule" || t.effect == "NoExecute")
+    {
+        if !tolerations.iter().any(|t| tolerates(t, taint)) {
+            return Err(FilterReason::UntoleratedTaint {
+                key: taint.key.clone(),
+                value: taint.value.clone().unwrap_or_default(),
+            });
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Node selector and required node affinity
+// ---------------------------------------------------------------------------
+
+fn check_node_affinity(pod: &Pod, node: &Node) -> Result<(), FilterReason> {
+    let Some(spec) = &pod.spec else {
+        return Ok(());
+    };
+    let labels = node.labels();
+
+    if let Some(selector) = &spec.node_selector {
+        if selector.iter().any(|(k, v)| labels.get(k) != Some(v)) {
+            return Err(FilterReason::NodeAffinityMismatch);
+        }
+    }
+
+    let required = spec
+        .affinity
+        .as_ref()
+        .and_then(|a| a.node_affinity.as_ref())
+        .and_then(|na| {
+            na.required_during_scheduling_ignored_during_execution
+                .as_ref()
+        });
+    if let Some(selector) = required {
+        if !node_selector_matches(selector, labels, &node.name_any()) {
+            return Err(FilterReason::NodeAffinityMismatch);
+        }
+    }
+
+    Ok(())
+}
+
+/// Terms are ORed; requirements within a term are ANDed. An empty term
+/// matches nothing, as in Kubernetes.
+pub fn node_selector_matches(
+    selector: &NodeSelector,
+    labels: &BTreeMap<String, String>,
+    node_name: &str,
+) -> bool {
+    selector
+        .node_selector_terms
+        .iter()
+        .any(|term| term_matches(term, labels, node_name))
+}
+
+fn term_matches(
+    term: &NodeSelectorTerm,
+    labels: &BTreeMap<String, String>,
+    node_name: &str,
+) -> bool {
+    let expressions = term.match_expressions.as_deref().unwrap_or_default();
+    let fields = term.match_fields.as_deref().unwrap_or_default();
+    if expressions.is_empty() && fields.is_empty() {
+        return false;
+    }
+
+    // The only supported field selector is metadata.name
+    let node_fields = BTreeMap::from([("metadata.name".to_string(), node_name.to_string())]);
+
+    expressions.iter().all(|r| requirement_matches(r, labels))
+        && fields.iter().all(|r| requirement_matches(r, &node_fields))
+}
+
+fn requirement_matches(req: &NodeSelectorRequirement, labels: &BTreeMap<String, String>) -> bool {
+    let value = labels.get(&req.key);
+    let values = req.values.as_deref().unwrap_or_default();
+
+    let compare = |ordering: std::cmp::Ordering| {
+        let (Some(actual), Some(bound)) = (
+            value.and_then(|v| v.parse::<i64>().ok()),
+            values.first().and_then(|v| v.parse::<i64>().ok()),
+        ) else {
+            return false;
+        };
+        actual.cmp(&bound) == ordering
+    };
+
+    match req.operator.as_str() {
+        "In" => value.is_some_and(|v| values.contains(v)),
+        "NotIn" => value.is_none_or(|v| !values.contains(v)),
+        "Exists" => value.is_some(),
+        "DoesNotExist" => value.is_none(),
+        "Gt" => compare(std::cmp::Ordering::Greater),
+        "Lt" => compare(std::cmp::Ordering::Less),
+        _ => false,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Volume topology
+// ---------------------------------------------------------------------------
+
+/// A bound volume can only be used from nodes matching its required node
+/// affinity, or, for volumes provisioned before node affinity existed, from
+/// the zone/region in its topology labels (multi-zone values use `__`).
+fn check_volume_topology(node: &Node, volumes: &[PersistentVolume]) -> Result<(), FilterReason> {
+    let labels = node.labels();
+    let node_name = node.name_any();
+
+    for pv in volumes {
+        let required = pv
+            .spec
+            .as_ref()
+            .and_then(|s| s.node_affinity.as_ref())
+            .and_then(|a| a.required.as_ref());
+        if let Some(selector) = required {
+            if !node_selector_matches(selector, labels, &node_name) {
+                return Err(FilterReason::VolumeNodeAffinityConflict);
+            }
+            continue;
+        }
+
+        for keys in [ZONE_LABELS, REGION_LABELS] {
+            let Some(pv_value) = keys.iter().find_map(|k| pv.labels().get(*k)) else {
+                continue;
+            };
+            let node_value = keys.iter().find_map(|k| labels.get(*k));
+            if !node_value.is_some_and(|v| pv_value.split("__").any(|z| z == v)) {
+                return Err(FilterReason::VolumeNodeAffinityConflict);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Names of the PersistentVolumeClaims a pod mounts
+pub fn pod_claim_names(pod: &Pod) -> Vec<String> {
+    pod.spec
+        .as_ref()
+        .and_then(|s| s.volumes.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.persistent_volume_claim.as_ref())
+        .map(|c| c.claim_name.clone())
+        .collect()
+}
@@ -0,0 +1,375 @@
+//! Tests for scheduler node filtering against synthetic nodes and pods.
+
+#[cfg(test)]
+mod tests {
+    use crate::scheduler::filter::*;
+
+    use k8s_openapi::api::core::v1::{
+        Affinity, Container, Node, NodeAffinity, NodeSelector, NodeSelectorRequirement,
+        NodeSelectorTerm, NodeSpec, NodeStatus, PersistentVolume, PersistentVolumeSpec, Pod,
+        PodSpec, PodStatus, ResourceRequirements, Taint, Toleration, VolumeNodeAffinity,
+    };
+    use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use std::collections::BTreeMap;
+
+    const ZONE: &str = "topology.kubernetes.io/zone";
+
+    fn quantities(pairs: &[(&str, &str)]) -> BTreeMap<String, Quantity> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), Quantity(v.to_string())))
+            .collect()
+    }
+
+    fn node(name: &str, cpu: &str, memory: &str, zone: &str) -> Node {
+        Node {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                labels: Some(BTreeMap::from([(ZONE.to_string(), zone.to_string())])),
+                ..Default::default()
+            },
+            spec: Some(NodeSpec::default()),
+            status: Some(NodeStatus {
+                allocatable: Some(quantities(&[
+                    ("cpu", cpu),
+                    ("memory", memory),
+                    ("ephemeral-storage", "100Gi"),
+                    ("pods", "110"),
+                ])),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn pod(name: &str, cpu: &str, memory: &str) -> Pod {
+        Pod {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some("stellar".to_string()),
+                ..Default::default()
+            },
+            spec: Some(PodSpec {
+                containers: vec![Container {
+                    name: "stellar-core".to_string(),
+                    resources: Some(ResourceRequirements {
+                        requests: Some(quantities(&[("cpu", cpu), ("memory", memory)])),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            status: None,
+        }
+    }
+
+    fn bound(mut pod: Pod, node_name: &str) -> Pod {
+        pod.spec.as_mut().unwrap().node_name = Some(node_name.to_string());
+        pod
+    }
+
+    fn names(result: &FilterResult) -> Vec<String> {
+        result
+            .feasible
+            .iter()
+            .map(|n| n.metadata.name.clone().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_quantity_parsing() {
+        let q = |s: &str| Quantity(s.to_string());
+        assert_eq!(quantity_to_millis(&q("500m")), Some(500));
+        assert_eq!(quantity_to_millis(&q("2")), Some(2000));
+        assert_eq!(quantity_to_millis(&q("0.25")), Some(250));
+        assert_eq!(quantity_to_units(&q("1Gi")), Some(1 << 30));
+        assert_eq!(quantity_to_units(&q("1G")), Some(1_000_000_000));
+        assert_eq!(quantity_to_units(&q("128974848")), Some(128_974_848));
+        assert_eq!(quantity_to_units(&q("12e6")), Some(12_000_000));
+        assert_eq!(quantity_to_units(&q("1Xi")), None);
+    }
+
+    #[test]
+    fn test_pod_requests_include_init_containers_and_overhead() {
+        let mut p = pod("core", "1", "1Gi");
+        let spec = p.spec.as_mut().unwrap();
+        spec.init_containers = Some(vec![Container {
+            name: "restore".to_string(),
+            resources: Some(ResourceRequirements {
+                requests: Some(quantities(&[("cpu", "2"), ("memory", "512Mi")])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }]);
+        spec.overhead = Some(quantities(&[("cpu", "100m")]));
+
+        let requests = pod_requests(&p);
+        assert_eq!(requests.cpu_millis, 2100);
+        assert_eq!(requests.memory_bytes, 1 << 30);
+    }
+
+    #[test]
+    fn test_resources_account_for_bound_pods() {
+        let nodes = vec![
+            node("small", "4", "8Gi", "a"),
+            node("big", "16", "64Gi", "a"),
+        ];
+        let bound_pods = vec![
+            bound(pod("existing", "3", "2Gi"), "small"),
+            // Completed pods no longer hold resources
+            Pod {
+                status: Some(PodStatus {
+                    phase: Some("Succeeded".to_string()),
+                    ..Default::default()
+                }),
+                ..bound(pod("done", "4", "8Gi"), "big")
+            },
+        ];
+        let ctx = FilterContext::new(&bound_pods, &[]);
+
+        let result = filter_nodes(&pod("validator", "2", "4Gi"), &nodes, &ctx);
+        assert_eq!(names(&result), vec!["big"]);
+        assert_eq!(
+            result.rejected,
+            vec![(
+                "small".to_string(),
+                FilterReason::InsufficientResource("cpu")
+            )]
+        );
+
+        let result = filter_nodes(&pod("validator", "1", "7Gi"), &nodes, &ctx);
+        assert_eq!(
+            result.rejected[0].1,
+            FilterReason::InsufficientResource("memory")
+        );
+    }
+
+    #[test]
+    fn test_ephemeral_storage_and_pod_count() {
+        let mut full = node("full", "8", "16Gi", "a");
+        full.status.as_mut().unwrap().allocatable = Some(quantities(&[
+            ("cpu", "8"),
+            ("memory", "16Gi"),
+            ("ephemeral-storage", "10Gi"),
+            ("pods", "1"),
+        ]));
+        let roomy = node("roomy", "8", "16Gi", "a");
+        let nodes = vec![full, roomy];
+
+        let mut p = pod("history", "1", "1Gi");
+        p.spec.as_mut().unwrap().containers[0]
+            .resources
+            .as_mut()
+            .unwrap()
+            .requests
+            .as_mut()
+            .unwrap()
+            .insert(
+                "ephemeral-storage".to_string(),
+                Quantity("20Gi".to_string()),
+            );
+
+        let result = filter_nodes(&p, &nodes, &FilterContext::default());
+        assert_eq!(names(&result), vec!["roomy"]);
+        assert_eq!(
+            result.rejected[0].1,
+            FilterReason::InsufficientResource("ephemeral-storage")
+        );
+
+        let bound_pods = vec![bound(pod("other", "0", "0"), "full")];
+        let ctx = FilterContext::new(&bound_pods, &[]);
+        let result = filter_nodes(&pod("small", "1", "1Gi"), &nodes, &ctx);
+        assert_eq!(
+            result.rejected[0].1,
+            FilterReason::InsufficientResource("pods")
+        );
+    }
+
+    #[test]
+    fn test_unschedulable_and_taints() {
+        let mut cordoned = node("cordoned", "8", "16Gi", "a");
+        cordoned.spec.as_mut().unwrap().unschedulable = Some(true);
+
+        let mut dedicated = node("dedicated", "8", "16Gi", "a");
+        dedicated.spec.as_mut().unwrap().taints = Some(vec![Taint {
+            key: "stellar.org/dedicated".to_string(),
+            value: Some("validator".to_string()),
+            effect: "NoSchedule".to_string(),
+            ..Default::default()
+        }]);
+
+        let mut preferred = node("preferred", "8", "16Gi", "a");
+        preferred.spec.as_mut().unwrap().taints = Some(vec![Taint {
+            key: "spot".to_string(),
+            effect: "PreferNoSchedule".to_string(),
+            ..Default::default()
+        }]);
+
+        let nodes = vec![cordoned, dedicated, preferred];
+        let ctx = FilterContext::default();
+
+        let result = filter_nodes(&pod("validator", "1", "1Gi"), &nodes, &ctx);
+        assert_eq!(names(&result), vec!["preferred"]);
+        assert_eq!(
+            result.summary(),
+            "1/3 nodes are available: 1 node(s) had untolerated taint \
+             {stellar.org/dedicated: validator}, 1 node(s) were unschedulable."
+        );
+
+        let mut tolerant = pod("validator", "1", "1Gi");
+        tolerant.spec.as_mut().unwrap().tolerations = Some(vec![Toleration {
+            key: Some("stellar.org/dedicated".to_string()),
+            operator: Some("Equal".to_string()),
+            value: Some("validator".to_string()),
+            effect: Some("NoSchedule".to_string()),
+            ..Default::default()
+        }]);
+        assert_eq!(
+            names(&filter_nodes(&tolerant, &nodes, &ctx)),
+            vec!["dedicated", "preferred"]
+        );
+
+        // A wrong value does not tolerate; a blanket Exists toleration does
+        tolerant
+            .spec
+            .as_mut()
+            .unwrap()
+            .tolerations
+            .as_mut()
+            .unwrap()[0]
+            .value = Some("watcher".to_string());
+        assert_eq!(
+            names(&filter_nodes(&tolerant, &nodes, &ctx)),
+            vec!["preferred"]
+        );
+        tolerant.spec.as_mut().unwrap().tolerations = Some(vec![Toleration {
+            operator: Some("Exists".to_string()),
+            ..Default::default()
+        }]);
+        assert_eq!(
+            names(&filter_nodes(&tolerant, &nodes, &ctx)),
+            vec!["dedicated", "preferred"]
+        );
+    }
+
+    #[test]
+    fn test_node_selector_and_required_affinity() {
+        let mut ssd = node("ssd", "8", "16Gi", "us-east-1a");
+        ssd.metadata
+            .labels
+            .as_mut()
+            .unwrap()
+            .insert("disktype".to_string(), "ssd".to_string());
+        let nodes = vec![
+            ssd,
+            node("hdd", "8", "16Gi", "us-east-1b"),
+            node("west", "8", "16Gi", "us-west-2a"),
+        ];
+        let ctx = FilterContext::default();
+
+        let mut p = pod("validator", "1", "1Gi");
+        p.spec.as_mut().unwrap().node_selector = Some(BTreeMap::from([(
+            "disktype".to_string(),
+            "ssd".to_string(),
+        )]));
+        assert_eq!(names(&filter_nodes(&p, &nodes, &ctx)), vec!["ssd"]);
+
+        let mut p = pod("validator", "1", "1Gi");
+        p.spec.as_mut().unwrap().affinity = Some(Affinity {
+            node_affinity: Some(NodeAffinity {
+                required_during_scheduling_ignored_during_execution: Some(NodeSelector {
+                    node_selector_terms: vec![
+                        NodeSelectorTerm {
+                            match_expressions: Some(vec![
+                                NodeSelectorRequirement {
+                                    key: ZONE.to_string(),
+                                    operator: "In".to_string(),
+                                    values: Some(vec![
+                                        "us-east-1a".to_string(),
+                                        "us-east-1b".to_string(),
+                                    ]),
+                                },
+                                NodeSelectorRequirement {
+                                    key: "disktype".to_string(),
+                                    operator: "DoesNotExist".to_string(),
+                                    values: None,
+                                },
+                            ]),
+                            match_fields: None,
+                        },
+                        NodeSelectorTerm {
+                            match_expressions: None,
+                            match_fields: Some(vec![NodeSelectorRequirement {
+                                key: "metadata.name".to_string(),
+                                operator: "In".to_string(),
+                                values: Some(vec!["west".to_string()]),
+                            }]),
+                        },
+                    ],
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        let result = filter_nodes(&p, &nodes, &ctx);
+        assert_eq!(names(&result), vec!["hdd", "west"]);
+        assert_eq!(result.rejected[0].1, FilterReason::NodeAffinityMismatch);
+    }
+
+    #[test]
+    fn test_bound_volume_zone() {
+        let nodes = vec![
+            node("a", "8", "16Gi", "us-east-1a"),
+            node("b", "8", "16Gi", "us-east-1b"),
+            node("c", "8", "16Gi", "us-east-1c"),
+        ];
+
+        // Volume pinned through required node affinity
+        let pinned = PersistentVolume {
+            spec: Some(PersistentVolumeSpec {
+                node_affinity: Some(VolumeNodeAffinity {
+                    required: Some(NodeSelector {
+                        node_selector_terms: vec![NodeSelectorTerm {
+                            match_expressions: Some(vec![NodeSelectorRequirement {
+                                key: ZONE.to_string(),
+                                operator: "In".to_string(),
+                                values: Some(vec!["us-east-1b".to_string()]),
+                            }]),
+                            match_fields: None,
+                        }],
+                    }),
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let volumes = vec![pinned];
+        let ctx = FilterContext::new([], &volumes);
+        let result = filter_nodes(&pod("validator", "1", "1Gi"), &nodes, &ctx);
+        assert_eq!(names(&result), vec!["b"]);
+        assert_eq!(
+            result.rejected[0].1,
+            FilterReason::VolumeNodeAffinityConflict
+        );
+
+        // Legacy zone label with a multi-zone value
+        let labelled = PersistentVolume {
+            metadata: ObjectMeta {
+                labels: Some(BTreeMap::from([(
+                    "failure-domain.beta.kubernetes.io/zone".to_string(),
+                    "us-east-1a__us-east-1c".to_string(),
+                )])),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let volumes = vec![labelled];
+        let ctx = FilterContext::new([], &volumes);
+        assert_eq!(
+            names(&filter_nodes(&pod("validator", "1", "1Gi"), &nodes, &ctx)),
+            vec!["a", "c"]
+        );
+    }
+}
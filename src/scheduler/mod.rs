@@ -1,2 +1,11 @@
 pub mod core;
+pub mod filter;
+#[cfg(test)]
+mod filter_test;
+pub mod queue;
+#[cfg(test)]
+mod queue_test;
+pub mod quorum;
+#[cfg(test)]
+mod quorum_test;
 pub mod scoring;
@@ -0,0 +1,133 @@
+//! Scheduling queue for pending pods
+//!
+//! Pods waiting for a placement are popped in FIFO order. A pod that cannot be
+//! placed is parked with exponential backoff (1s doubling up to 60s) until its
+//! retry time, or until a cluster change makes it worth trying again early.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Backoff after the first failed attempt
+pub const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// Upper bound for the backoff between attempts
+pub const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy)]
+struct Backoff {
+    attempts: u32,
+    retry_at: Instant,
+}
+
+/// FIFO queue with per-key backoff
+#[derive(Debug)]
+pub struct SchedulingQueue<K> {
+    active: VecDeque<K>,
+    queued: HashSet<K>,
+    backoff: HashMap<K, Backoff>,
+}
+
+impl<K> Default for SchedulingQueue<K> {
+    fn default() -> Self {
+        Self {
+            active: VecDeque::new(),
+            queued: HashSet::new(),
+            backoff: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash> SchedulingQueue<K> {
+    /// Queue a pending pod. Pods already queued or backing off are left alone.
+    pub fn add(&mut self, key: K) {
+        if self.queued.contains(&key) || self.backoff.contains_key(&key) {
+            return;
+        }
+        self.queued.insert(key.clone());
+        self.active.push_back(key);
+    }
+
+    /// Next pod to schedule, promoting pods whose backoff has expired
+    pub fn pop(&mut self, now: Instant) -> Option<K> {
+        let expired: Vec<K> = self
+            .backoff
+            .iter()
+            .filter(|(_, b)| b.retry_at <= now)
+            .map(|(k, _)| k.clone())
+            .collect();
+        for key in expired {
+            if self.queued.insert(key.clone()) {
+                self.active.push_back(key);
+            }
+        }
+
+        let key = self.active.pop_front()?;
+        self.queued.remove(&key);
+        Some(key)
+    }
+
+    /// Park a pod that could not be placed; returns the delay before its next attempt
+    pub fn backoff(&mut self, key: K, now: Instant) -> Duration {
+        self.queued.remove(&key);
+        self.active.retain(|k| k != &key);
+
+        let attempts = self.backoff.get(&key).map_or(0, |b| b.attempts) + 1;
+        let delay = backoff_delay(attempts);
+        self.backoff.insert(
+            key,
+            Backoff {
+                attempts,
+                retry_at: now + delay,
+            },
+        );
+        delay
+    }
+
+    /// Drop all state for a pod that was bound or deleted
+    pub fn forget(&mut self, key: &K) {
+        self.queued.remove(key);
+        self.active.retain(|k| k != key);
+        self.backoff.remove(key);
+    }
+
+    /// Retry parked pods now, e.g. after a node was added or capacity freed.
+    /// Attempt counts are kept so repeated failures still back off.
+    pub fn retry_all(&mut self, now: Instant) {
+        for b in self.backoff.values_mut() {
+            b.retry_at = b.retry_at.min(now);
+        }
+    }
+
+    /// When the earliest parked pod becomes ready, if any
+    pub fn next_retry(&self) -> Option<Instant> {
+        self.backoff
+            .iter()
+            .filter(|(k, _)| !self.queued.contains(*k))
+            .map(|(_, b)| b.retry_at)
+            .min()
+    }
+
+    /// Number of attempts that have failed for a pod
+    pub fn attempts(&self, key: &K) -> u32 {
+        self.backoff.get(key).map_or(0, |b| b.attempts)
+    }
+
+    /// Pods queued or backing off
+    pub fn len(&self) -> usize {
+        self.queued.len()
+            + self
+                .backoff
+                .keys()
+                .filter(|k| !self.queued.contains(*k))
+                .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn backoff_delay(attempts: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+    INITIAL_BACKOFF.saturating_mul(factor).min(MAX_BACKOFF)
+}
@@ -0,0 +1,151 @@
+//! Tests for the scheduling queue and the scheduler's watch helpers.
+
+#[cfg(test)]
+mod tests {
+    use crate::scheduler::core::{is_pending, node_fingerprint};
+    use crate::scheduler::queue::*;
+
+    use k8s_openapi::api::core::v1::{Node, NodeSpec, NodeStatus, Pod, PodSpec, PodStatus};
+    use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, Time};
+    use std::collections::BTreeMap;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_pop_is_fifo_and_deduplicates() {
+        let mut queue = SchedulingQueue::default();
+        let now = Instant::now();
+        queue.add("a");
+        queue.add("b");
+        queue.add("a");
+        assert_eq!(queue.len(), 2);
+
+        assert_eq!(queue.pop(now), Some("a"));
+        assert_eq!(queue.pop(now), Some("b"));
+        assert_eq!(queue.pop(now), None);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let mut queue = SchedulingQueue::default();
+        let now = Instant::now();
+
+        let delays: Vec<Duration> = (0..9).map(|_| queue.backoff("validator", now)).collect();
+        assert_eq!(delays[0], INITIAL_BACKOFF);
+        assert_eq!(delays[1], Duration::from_secs(2));
+        assert_eq!(delays[4], Duration::from_secs(16));
+        assert_eq!(delays[8], MAX_BACKOFF);
+        assert_eq!(queue.attempts(&"validator"), 9);
+    }
+
+    #[test]
+    fn test_parked_pods_wait_for_retry_time() {
+        let mut queue = SchedulingQueue::default();
+        let now = Instant::now();
+        queue.add("validator");
+        let key = queue.pop(now).unwrap();
+        queue.backoff(key, now);
+
+        // Re-adding from a watch event does not skip the backoff
+        queue.add("validator");
+        assert_eq!(queue.pop(now), None);
+        assert_eq!(queue.next_retry(), Some(now + INITIAL_BACKOFF));
+
+        assert_eq!(queue.pop(now + INITIAL_BACKOFF), Some("validator"));
+        // Attempts survive the retry, so the next failure waits longer
+        assert_eq!(queue.backoff("validator", now), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_retry_all_and_forget() {
+        let mut queue = SchedulingQueue::default();
+        let now = Instant::now();
+        queue.backoff("a", now);
+        queue.backoff("b", now);
+        queue.backoff("b", now);
+
+        queue.retry_all(now);
+        let mut ready = vec![queue.pop(now).unwrap(), queue.pop(now).unwrap()];
+        ready.sort();
+        assert_eq!(ready, vec!["a", "b"]);
+        assert_eq!(queue.attempts(&"b"), 2);
+
+        queue.forget(&"a");
+        queue.forget(&"b");
+        assert!(queue.is_empty());
+        assert_eq!(queue.next_retry(), None);
+    }
+
+    fn pending_pod(scheduler: &str) -> Pod {
+        Pod {
+            metadata: ObjectMeta {
+                name: Some("validator-0".to_string()),
+                ..Default::default()
+            },
+            spec: Some(PodSpec {
+                scheduler_name: Some(scheduler.to_string()),
+                ..Default::default()
+            }),
+            status: None,
+        }
+    }
+
+    #[test]
+    fn test_is_pending() {
+        assert!(is_pending(
+            &pending_pod("stellar-scheduler"),
+            "stellar-scheduler"
+        ));
+        assert!(!is_pending(
+            &pending_pod("default-scheduler"),
+            "stellar-scheduler"
+        ));
+
+        let mut bound = pending_pod("stellar-scheduler");
+        bound.spec.as_mut().unwrap().node_name = Some("node-a".to_string());
+        assert!(!is_pending(&bound, "stellar-scheduler"));
+
+        let mut deleting = pending_pod("stellar-scheduler");
+        deleting.metadata.deletion_timestamp = Some(Time(chrono::Utc::now()));
+        assert!(!is_pending(&deleting, "stellar-scheduler"));
+
+        let mut failed = pending_pod("stellar-scheduler");
+        failed.status = Some(PodStatus {
+            phase: Some("Failed".to_string()),
+            ..Default::default()
+        });
+        assert!(!is_pending(&failed, "stellar-scheduler"));
+    }
+
+    #[test]
+    fn test_node_fingerprint_ignores_heartbeats() {
+        let node = Node {
+            metadata: ObjectMeta {
+                name: Some("node-a".to_string()),
+                labels: Some(BTreeMap::from([(
+                    "topology.kubernetes.io/zone".to_string(),
+                    "us-east-1a".to_string(),
+                )])),
+                ..Default::default()
+            },
+            spec: Some(NodeSpec::default()),
+            status: Some(NodeStatus {
+                allocatable: Some(BTreeMap::from([(
+                    "cpu".to_string(),
+                    Quantity("8".to_string()),
+                )])),
+                ..Default::default()
+            }),
+        };
+
+        let mut heartbeat = node.clone();
+        heartbeat.metadata.resource_version = Some("42".to_string());
+        heartbeat.status.as_mut().unwrap().conditions = Some(vec![]);
+        assert_eq!(node_fingerprint(&node), node_fingerprint(&heartbeat));
+
+        let mut cordoned = node.clone();
+        cordoned.spec.as_mut().unwrap().unschedulable = Some(true);
+        assert_ne!(node_fingerprint(&node), node_fingerprint(&cordoned));
+    }
+}
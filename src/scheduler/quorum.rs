@@ -0,0 +1,150 @@
+//! Quorum set peers for quorum-proximity scoring
+//!
+//! Quorum sets name validators by public key, optionally followed by an alias
+//! (`"GABC… sdf1"`), or by `$alias`. Keys are mapped to StellarNodes through
+//! `status.validatorPublicKey`; anything else is treated as a StellarNode name
+//! in the scheduled pod's namespace, as before.
+//!
+//! Each peer carries a weight derived from the thresholds on its path: in a
+//! set needing `t` of `n` members, every member weighs `t / n` of its parent,
+//! so validators in strict (high-threshold) sets pull harder on placement.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::controller::strkey;
+use crate::crd::StellarNode;
+
+/// stellar-core's default when `THRESHOLD_PERCENT` is omitted
+const DEFAULT_THRESHOLD_PERCENT: f64 = 67.0;
+
+/// A quorum set member and how much it matters to the local node
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuorumPeer {
+    /// Public key (`G…`) or StellarNode name
+    pub id: String,
+    pub weight: f64,
+}
+
+/// Parse the peers referenced by a stellar-core quorum configuration.
+///
+/// Understands `[QUORUM_SET]` with nested inner sets (`[QUORUM_SET.x]`), the
+/// `[[VALIDATORS]]` array used with `HOME_DOMAINS`, and the legacy
+/// `[VALIDATORS]` name table.
+pub fn parse_quorum_peers(toml_str: &str) -> Vec<QuorumPeer> {
+    let Ok(value) = toml_str.parse::<toml::Value>() else {
+        return Vec::new();
+    };
+
+    let mut weights: BTreeMap<String, f64> = BTreeMap::new();
+
+    if let Some(qs) = value.get("QUORUM_SET").and_then(|v| v.as_table()) {
+        walk_quorum_set(qs, 1.0, &mut weights);
+    }
+
+    match value.get("VALIDATORS") {
+        // [[VALIDATORS]] entries with PUBLIC_KEY (automatic quorum set generation)
+        Some(toml::Value::Array(entries)) => {
+            for entry in entries {
+                if let Some(key) = entry.get("PUBLIC_KEY").and_then(|k| k.as_str()) {
+                    add_peer(&mut weights, key, 1.0);
+                }
+            }
+        }
+        // [VALIDATORS] table keyed by instance name
+        Some(toml::Value::Table(table)) => {
+            for name in table.keys() {
+                add_peer(&mut weights, name, 1.0);
+            }
+        }
+        _ => {}
+    }
+
+    weights
+        .into_iter()
+        .map(|(id, weight)| QuorumPeer { id, weight })
+        .collect()
+}
+
+fn walk_quorum_set(set: &toml::value::Table, weight: f64, out: &mut BTreeMap<String, f64>) {
+    let validators: Vec<&str> = set
+        .get("VALIDATORS")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .collect();
+    let inner: Vec<&toml::value::Table> = set.values().filter_map(|v| v.as_table()).collect();
+
+    let members = validators.len() + inner.len();
+    if members == 0 {
+        return;
+    }
+    let percent = set
+        .get("THRESHOLD_PERCENT")
+        .and_then(|v| v.as_integer())
+        .map_or(DEFAULT_THRESHOLD_PERCENT, |p| p as f64);
+    let needed = (members as f64 * percent / 100.0)
+        .ceil()
+        .clamp(1.0, members as f64);
+    let member_weight = weight * needed / members as f64;
+
+    for entry in validators {
+        add_peer(out, entry, member_weight);
+    }
+    for inner_set in inner {
+        walk_quorum_set(inner_set, member_weight, out);
+    }
+}
+
+fn add_peer(out: &mut BTreeMap<String, f64>, entry: &str, weight: f64) {
+    // "GABC… alias" → key; "$alias" → alias
+    let Some(token) = entry.split_whitespace().next() else {
+        return;
+    };
+    let id = token.trim_start_matches(['$', '@']).to_string();
+    if id.is_empty() {
+        return;
+    }
+    let current = out.entry(id).or_insert(0.0);
+    *current = current.max(weight);
+}
+
+/// Public key → (namespace, name) of the StellarNode publishing it
+pub fn build_key_index(nodes: &[StellarNode]) -> HashMap<String, (String, String)> {
+    nodes
+        .iter()
+        .filter_map(|n| {
+            let key = n.status.as_ref()?.validator_public_key.clone()?;
+            Some((
+                key,
+                (
+                    n.metadata.namespace.clone().unwrap_or_default(),
+                    n.metadata.name.clone()?,
+                ),
+            ))
+        })
+        .collect()
+}
+
+/// Resolve peers to in-cluster StellarNodes as `(namespace, name, weight)`.
+///
+/// Keys with no StellarNode behind them (external validators) are dropped;
+/// plain names resolve in `default_namespace`.
+pub fn resolve_peers(
+    peers: &[QuorumPeer],
+    index: &HashMap<String, (String, String)>,
+    default_namespace: &str,
+) -> Vec<(String, String, f64)> {
+    peers
+        .iter()
+        .filter_map(|peer| {
+            if let Some((namespace, name)) = index.get(&peer.id) {
+                Some((namespace.clone(), name.clone(), peer.weight))
+            } else if strkey::is_public_key(&peer.id) {
+                None
+            } else {
+                Some((default_namespace.to_string(), peer.id.clone(), peer.weight))
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,138 @@
+//! Tests for quorum set peer parsing and key resolution.
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::strkey::encode_public_key;
+    use crate::crd::{StellarNode, StellarNodeStatus};
+    use crate::scheduler::quorum::*;
+
+    use std::collections::HashMap;
+
+    fn key(n: u8) -> String {
+        encode_public_key(&[n; 32])
+    }
+
+    fn weight_of(peers: &[QuorumPeer], id: &str) -> f64 {
+        peers.iter().find(|p| p.id == id).unwrap().weight
+    }
+
+    #[test]
+    fn test_nested_quorum_set_weights() {
+        let toml = format!(
+            r#"
+[QUORUM_SET]
+THRESHOLD_PERCENT = 100
+VALIDATORS = ["{} core-1", "$core-2"]
+
+[QUORUM_SET.partners]
+THRESHOLD_PERCENT = 50
+VALIDATORS = ["partner-a", "partner-b", "partner-c", "partner-d"]
+"#,
+            key(1)
+        );
+        let peers = parse_quorum_peers(&toml);
+        assert_eq!(peers.len(), 6);
+
+        // Top level needs all 3 members
+        assert_eq!(weight_of(&peers, &key(1)), 1.0);
+        assert_eq!(weight_of(&peers, "core-2"), 1.0);
+        // Inner set needs 2 of 4, so each partner weighs half of the inner set
+        assert_eq!(weight_of(&peers, "partner-a"), 0.5);
+    }
+
+    #[test]
+    fn test_default_threshold() {
+        let peers = parse_quorum_peers(
+            r#"
+[QUORUM_SET]
+VALIDATORS = ["a", "b", "c", "d"]
+"#,
+        );
+        // 67% of 4 rounds up to 3
+        assert_eq!(weight_of(&peers, "b"), 0.75);
+    }
+
+    #[test]
+    fn test_validators_array_and_table() {
+        let toml = format!(
+            r#"
+[[VALIDATORS]]
+NAME = "sdf_1"
+HOME_DOMAIN = "stellar.org"
+PUBLIC_KEY = "{}"
+"#,
+            key(2)
+        );
+        let peers = parse_quorum_peers(&toml);
+        assert_eq!(
+            peers,
+            vec![QuorumPeer {
+                id: key(2),
+                weight: 1.0
+            }]
+        );
+
+        let legacy = parse_quorum_peers(
+            r#"
+[VALIDATORS]
+validator-1 = "validator-1.stellar.svc:11625"
+"#,
+        );
+        assert_eq!(legacy[0].id, "validator-1");
+
+        assert!(parse_quorum_peers("not [valid toml").is_empty());
+    }
+
+    fn node(namespace: &str, name: &str, public_key: Option<String>) -> StellarNode {
+        let mut node: StellarNode = serde_json::from_value(serde_json::json!({
+            "apiVersion": "stellar.org/v1alpha1",
+            "kind": "StellarNode",
+            "metadata": { "name": name, "namespace": namespace },
+            "spec": { "nodeType": "Validator", "network": "Testnet", "version": "v21.0.0" }
+        }))
+        .unwrap();
+        node.status = Some(StellarNodeStatus {
+            validator_public_key: public_key,
+            ..Default::default()
+        });
+        node
+    }
+
+    #[test]
+    fn test_resolve_peers_across_namespaces() {
+        let nodes = vec![
+            node("stellar-a", "validator-1", Some(key(1))),
+            node("stellar-b", "validator-2", Some(key(2))),
+            node("stellar-a", "validator-3", None),
+        ];
+        let index = build_key_index(&nodes);
+        assert_eq!(index.len(), 2);
+
+        let peers = vec![
+            QuorumPeer {
+                id: key(2),
+                weight: 1.0,
+            },
+            // External validator with no StellarNode behind it
+            QuorumPeer {
+                id: key(9),
+                weight: 1.0,
+            },
+            QuorumPeer {
+                id: "validator-3".to_string(),
+                weight: 0.5,
+            },
+        ];
+        let mut resolved = resolve_peers(&peers, &index, "stellar-a");
+        resolved.sort_by(|a, b| a.1.cmp(&b.1));
+        assert_eq!(
+            resolved,
+            vec![
+                ("stellar-b".to_string(), "validator-2".to_string(), 1.0),
+                ("stellar-a".to_string(), "validator-3".to_string(), 0.5),
+            ]
+        );
+
+        assert!(resolve_peers(&peers[..1], &HashMap::new(), "stellar-a").is_empty());
+    }
+}
@@ -1,10 +1,12 @@
 use anyhow::Result;
-use k8s_openapi::api::core::v1::{Node, Pod};
+use k8s_openapi::api::core::v1::{ConfigMap, Node, Pod};
 use kube::{Client, ResourceExt};
 use std::collections::HashMap;
-use toml;
 use tracing;
 
+use super::quorum;
+use crate::carbon_aware::CarbonAwareScheduler;
+
 // Topology labels
 const LABEL_ZONE: &str = "topology.kubernetes.io/zone";
 const LABEL_REGION: &str = "topology.kubernetes.io/region";
@@ -13,6 +15,7 @@ pub async fn score_nodes<'a>(
     pod: &Pod,
     candidates: &[&'a Node],
     client: &Client,
+    carbon: Option<&CarbonAwareScheduler>,
 ) -> Result<Option<&'a Node>> {
     // 1. Check for Quorum Proximity scheduling (Stellar Validators)
     if is_validator_pod(pod) {
@@ -21,9 +24,11 @@ pub async fn score_nodes<'a>(
         }
     }
 
-    // 2. Check if carbon-aware scheduling should be used
-    if should_use_carbon_aware_scheduling(pod) {
-        return score_nodes_carbon_aware(pod, candidates, client).await;
+    // 2. Carbon-aware scoring for opted-in pods, while carbon data is fresh
+    if let Some(carbon) = carbon {
+        if carbon.should_schedule_carbon_aware(pod) && carbon.has_fresh_data().await {
+            return score_nodes_carbon_aware(pod, candidates, carbon).await;
+        }
     }
 
     // 3. Traditional topology-based scoring
@@ -41,7 +46,8 @@ fn is_validator_pod(pod: &Pod) -> bool {
 }
 
 /// Score nodes based on Stellar quorum set proximity and redundancy.
-/// Prioritizes nodes that provide the best latency/redundancy balance.
+/// Prioritizes nodes that provide the best latency/redundancy balance,
+/// weighting each peer by how much the quorum set depends on it.
 async fn score_nodes_quorum_proximity<'a>(
     pod: &Pod,
     candidates: &[&'a Node],
@@ -63,36 +69,52 @@ async fn score_nodes_quorum_proximity<'a>(
         Err(_) => return Ok(None),
     };
 
-    let quorum_set_toml = match node_cr
-        .spec
-        .validator_config
-        .as_ref()
-        .and_then(|c| c.quorum_set.as_ref())
-    {
+    let quorum_set_toml = match effective_quorum_config(&node_cr, client).await {
         Some(q) => q,
         None => return Ok(None),
     };
 
-    // Parse peer names/keys from quorumSet TOML
-    let peer_names = extract_peer_names_from_toml(quorum_set_toml);
-    if peer_names.is_empty() {
+    // Parse peer keys/names from the quorum set and map keys to StellarNodes
+    let peers = quorum::parse_quorum_peers(&quorum_set_toml);
+    if peers.is_empty() {
         return Ok(None);
     }
+    let all_stellar_nodes: kube::Api<crate::crd::StellarNode> = kube::Api::all(client.clone());
+    let key_index = match all_stellar_nodes
+        .list(&kube::api::ListParams::default())
+        .await
+    {
+        Ok(list) => quorum::build_key_index(&list.items),
+        Err(e) => {
+            tracing::warn!("Failed to list StellarNodes for quorum key index: {}", e);
+            Default::default()
+        }
+    };
+    let resolved = quorum::resolve_peers(&peers, &key_index, namespace);
+    tracing::debug!(
+        "Quorum set of {} references {} peers, {} running in-cluster",
+        instance_name,
+        peers.len(),
+        resolved.len()
+    );
 
     // Find where peers are currently running
     let mut peer_nodes = Vec::new();
-    let all_pods: kube::Api<Pod> = kube::Api::all(client.clone());
     let all_nodes: kube::Api<Node> = kube::Api::all(client.clone());
 
-    for peer_name in peer_names {
+    for (peer_namespace, peer_name, weight) in resolved {
+        if peer_namespace == namespace && &peer_name == instance_name {
+            continue;
+        }
         // Find pods for this peer instance
+        let peer_pods: kube::Api<Pod> = kube::Api::namespaced(client.clone(), &peer_namespace);
         let lp = kube::api::ListParams::default()
             .labels(&format!("app.kubernetes.io/instance={}", peer_name));
-        if let Ok(pods) = all_pods.list(&lp).await {
+        if let Ok(pods) = peer_pods.list(&lp).await {
             for p in pods {
                 if let Some(node_name) = p.spec.as_ref().and_then(|s| s.node_name.as_ref()) {
                     if let Ok(node) = all_nodes.get(node_name).await {
-                        peer_nodes.push(node);
+                        peer_nodes.push((node, weight));
                     }
                 }
             }
@@ -105,11 +127,11 @@ async fn score_nodes_quorum_proximity<'a>(
     }
 
     // Score candidates
-    let mut best_score = i64::MIN;
+    let mut best_score = f64::MIN;
     let mut best_node = None;
 
     for node in candidates {
-        let mut score: i64 = 0;
+        let mut score = 0.0;
         let node_name = node.name_any();
         let node_zone = node
             .metadata
@@ -122,7 +144,7 @@ async fn score_nodes_quorum_proximity<'a>(
             .as_ref()
             .and_then(|l| l.get(LABEL_REGION));
 
-        for peer_node in &peer_nodes {
+        for (peer_node, weight) in &peer_nodes {
             let peer_node_name = peer_node.name_any();
             let peer_zone = peer_node
                 .metadata
@@ -137,24 +159,24 @@ async fn score_nodes_quorum_proximity<'a>(
 
             // 1. Anti-affinity: Strongly discourage same node
             if node_name == peer_node_name {
-                score -= 1000;
+                score -= 1000.0 * weight;
             }
 
             // 2. Redundancy: Prefer different zones
             if let (Some(nz), Some(pz)) = (node_zone, peer_zone) {
                 if nz != pz {
-                    score += 100;
+                    score += 100.0 * weight;
                 } else {
-                    score -= 50; // Same zone penalty
+                    score -= 50.0 * weight; // Same zone penalty
                 }
             }
 
             // 3. Latency: Prefer same region (low latency)
             if let (Some(nr), Some(pr)) = (node_region, peer_region) {
                 if nr == pr {
-                    score += 50;
+                    score += 50.0 * weight;
                 } else {
-                    score -= 20; // Different region penalty
+                    score -= 20.0 * weight; // Different region penalty
                 }
             }
         }
@@ -166,7 +188,7 @@ async fn score_nodes_quorum_proximity<'a>(
     }
 
     tracing::info!(
-        "Quorum Proximity scoring for pod {}: selected node {} with score {}",
+        "Quorum Proximity scoring for pod {}: selected node {} with score {:.1}",
         pod.name_any(),
         best_node.map(|n| n.name_any()).unwrap_or_default(),
         best_score
@@ -175,105 +197,50 @@ async fn score_nodes_quorum_proximity<'a>(
     Ok(best_node)
 }
 
-/// Helper to extract peer instance names from Stellar Core quorum set TOML.
-/// Handles both [VALIDATORS] map and [QUORUM_SET] VSL formats.
-fn extract_peer_names_from_toml(toml_str: &str) -> Vec<String> {
-    let mut names = Vec::new();
-
-    // Try to parse as TOML Table
-    if let Ok(value) = toml_str.parse::<toml::Value>() {
-        // Case 1: [VALIDATORS] section
-        if let Some(validators) = value.get("VALIDATORS").and_then(|v| v.as_table()) {
-            for key in validators.keys() {
-                names.push(key.clone());
-            }
-        }
-
-        // Case 2: [QUORUM_SET] section (from VSL)
-        if let Some(qs) = value.get("QUORUM_SET").and_then(|v| v.as_table()) {
-            if let Some(validators) = qs.get("VALIDATORS").and_then(|v| v.as_array()) {
-                for v in validators {
-                    if let Some(s) = v.as_str() {
-                        // VSL uses public keys, but in K8S we might use names
-                        // For now, if it looks like a public key (starts with G),
-                        // we'd need a mapping. If it's a simple name, use it.
-                        if !s.starts_with('G') {
-                            names.push(s.to_string());
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    names
-}
-
-/// Check if pod should use carbon-aware scheduling
-fn should_use_carbon_aware_scheduling(pod: &Pod) -> bool {
-    // Check for carbon-aware scheduling annotation
-    if let Some(annotations) = &pod.metadata.annotations {
-        if let Some(value) = annotations.get("stellar.org/carbon-aware") {
-            return value == "true" || value == "enabled";
-        }
-    }
-
-    // Check for read pool pods (they are non-critical)
-    if let Some(labels) = &pod.metadata.labels {
-        if labels.get("stellar.org/role").map(|s| s.as_str()) == Some("read-replica") {
-            return true;
-        }
+/// The quorum configuration a validator actually runs with: the inline
+/// `quorumSet`, or the stellar-core.cfg rendered into its ConfigMap (which
+/// carries VSL-derived quorum sets).
+async fn effective_quorum_config(
+    node: &crate::crd::StellarNode,
+    client: &Client,
+) -> Option<String> {
+    let config = node.spec.validator_config.as_ref()?;
+    if let Some(q) = &config.quorum_set {
+        return Some(q.clone());
     }
-
-    false
+    config.vl_source.as_ref()?;
+
+    let namespace = node.namespace().unwrap_or_else(|| "default".to_string());
+    let config_maps: kube::Api<ConfigMap> = kube::Api::namespaced(client.clone(), &namespace);
+    config_maps
+        .get_opt(&format!("{}-config", node.name_any()))
+        .await
+        .ok()
+        .flatten()?
+        .data?
+        .remove("stellar-core.cfg")
 }
 
-/// Carbon-aware scoring using mock data (for now)
+/// Pick the candidate in the region with the lowest carbon intensity
 async fn score_nodes_carbon_aware<'a>(
-    _pod: &Pod,
+    pod: &Pod,
     candidates: &[&'a Node],
-    _client: &Client,
+    carbon: &CarbonAwareScheduler,
 ) -> Result<Option<&'a Node>> {
-    // Mock carbon intensity data by region
-    // In real implementation, this would come from the carbon intensity API
-    let mock_carbon_intensity = HashMap::from([
-        ("us-west-2", 150.0),      // Washington/Oregon - hydro heavy
-        ("us-east-1", 400.0),      // Virginia - mixed
-        ("eu-west-1", 300.0),      // Ireland - mixed
-        ("eu-central-1", 450.0),   // Frankfurt - coal heavy
-        ("ap-southeast-1", 600.0), // Singapore - gas heavy
-    ]);
-
-    let mut best_node = None;
-    let mut best_intensity = f64::MAX;
-
-    for node in candidates {
-        let region = extract_region_from_node(node);
-
-        if let Some(r) = region {
-            if let Some(&intensity) = mock_carbon_intensity.get(&r.as_str()) {
-                if intensity < best_intensity {
-                    best_intensity = intensity;
-                    best_node = Some(*node);
-                }
-            }
-        } else if best_node.is_none() {
-            // Fallback to first node if no region info
-            best_node = Some(*node);
-        }
-    }
+    let scored = carbon.score_nodes_carbon_aware(pod, candidates).await?;
+    let best = scored.first().map(|(score, node)| (*score, *node));
 
-    if let Some(node) = best_node {
+    if let Some((score, node)) = best {
         let region = extract_region_from_node(node).unwrap_or_else(|| "unknown".to_string());
         tracing::info!(
-            "Carbon-aware scheduling: selected node {} in region {} with intensity {} gCO2/kWh",
+            "Carbon-aware scheduling: selected node {} in region {} with carbon score {:.2}",
             node.name_any(),
             region,
-            best_intensity
+            score
         );
     }
 
-    Ok(best_node)
+    Ok(best.map(|(_, node)| node))
 }
 
 /// Traditional topology-based scoring
@@ -0,0 +1,139 @@
+//! StellarValidationPlugin Custom Resource
+//!
+//! Declares a Wasm plugin for the admission webhook. The module is read from
+//! a ConfigMap, a Secret or an OCI artifact, verified against the pinned
+//! SHA-256 (and an Ed25519 signature when signing keys are configured) and
+//! loaded by [`super::loader::PluginLoader`].
+
+use std::collections::BTreeMap;
+
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::types::{ConfigMapRef, Operation, PluginKind, PluginLimits, SecretRef};
+
+/// Spec of a declaratively loaded Wasm plugin
+#[derive(CustomResource, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[kube(
+    group = "stellar.org",
+    version = "v1alpha1",
+    kind = "StellarValidationPlugin",
+    status = "StellarValidationPluginStatus",
+    shortname = "svp",
+    printcolumn = r#"{"name":"Kind","type":"string","jsonPath":".spec.kind"}"#,
+    printcolumn = r#"{"name":"Version","type":"string","jsonPath":".spec.version"}"#,
+    printcolumn = r#"{"name":"Phase","type":"string","jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"Age","type":"date","jsonPath":".metadata.creationTimestamp"}"#
+)]
+#[serde(rename_all = "camelCase")]
+pub struct StellarValidationPluginSpec {
+    /// Semantic version of the plugin (e.g., "1.0.0")
+    pub version: String,
+
+    /// Human-readable description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Whether the plugin validates or mutates requests
+    #[serde(default)]
+    pub kind: PluginKind,
+
+    /// Order in which mutating plugins run (lower first, ties by name)
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Operations this plugin handles
+    #[serde(default = "default_operations")]
+    pub operations: Vec<Operation>,
+
+    /// Whether this plugin is enabled
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Fail-open behavior (allow if plugin fails)
+    #[serde(default)]
+    pub fail_open: bool,
+
+    /// Resource limits for plugin execution
+    #[serde(default)]
+    pub limits: PluginLimits,
+
+    /// Where to read the Wasm module from
+    pub source: PluginSource,
+
+    /// Hex-encoded SHA-256 of the Wasm module
+    pub sha256: String,
+
+    /// Base64-encoded Ed25519 signature over the Wasm module.
+    /// Required when the webhook is started with plugin signing keys.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+
+    /// Custom configuration passed to the plugin
+    #[serde(default)]
+    pub plugin_config: BTreeMap<String, serde_json::Value>,
+}
+
+fn default_operations() -> Vec<Operation> {
+    vec![Operation::Create, Operation::Update]
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Location of a plugin's Wasm module; exactly one field must be set
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginSource {
+    /// ConfigMap key holding the module (`binaryData`, or base64 in `data`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_map_ref: Option<ConfigMapRef>,
+
+    /// Secret key holding the module
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_ref: Option<SecretRef>,
+
+    /// OCI artifact holding the module as a single Wasm layer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oci: Option<OciPluginSource>,
+}
+
+/// Wasm module published as an OCI artifact
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OciPluginSource {
+    /// Artifact reference, e.g. `ghcr.io/org/plugin:1.0.0` or `...@sha256:<digest>`
+    pub reference: String,
+
+    /// `kubernetes.io/dockerconfigjson` Secret with registry credentials
+    /// (in the webhook namespace)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pull_secret: Option<String>,
+}
+
+/// Observed state of a StellarValidationPlugin
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StellarValidationPluginStatus {
+    /// Loaded, Failed or Disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<String>,
+
+    /// Details about the last load attempt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// SHA-256 of the module currently serving requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loaded_sha256: Option<String>,
+
+    /// When the serving module was loaded (RFC 3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_loaded_time: Option<String>,
+
+    /// Generation of the spec last acted on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub observed_generation: Option<i64>,
+}
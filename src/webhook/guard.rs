@@ -0,0 +1,382 @@
+//! Per-plugin concurrency limit and circuit breaker
+//!
+//! Every loaded plugin gets a semaphore bounding its concurrent executions
+//! and a circuit breaker counting consecutive limit violations (fuel,
+//! timeout or memory). Once `circuit_breaker_threshold` is reached the
+//! plugin is disabled for `circuit_breaker_cooldown_secs`; the first
+//! execution after that is a trial which closes the breaker if it stays
+//! within its limits and reopens it otherwise.
+//!
+//! Rejected executions are returned as plugin errors, so the plugin's
+//! `failOpen` setting decides whether the admission request is allowed.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{info, warn};
+
+use super::types::PluginLimits;
+use crate::error::{Error, Result};
+
+/// Resource limit a plugin execution ran into
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitKind {
+    Fuel,
+    Timeout,
+    Memory,
+}
+
+impl std::fmt::Display for LimitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fuel => write!(f, "fuel"),
+            Self::Timeout => write!(f, "timeout"),
+            Self::Memory => write!(f, "memory"),
+        }
+    }
+}
+
+/// State of a plugin's circuit breaker
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CircuitState {
+    /// Executing normally
+    Closed,
+    /// Disabled after repeated limit violations
+    Open,
+    /// Cooldown elapsed, the next execution is a trial
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// Value exported by the `stellar_webhook_plugin_circuit_state` gauge
+    pub fn as_gauge(self) -> i64 {
+        match self {
+            Self::Closed => 0,
+            Self::HalfOpen => 1,
+            Self::Open => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for CircuitState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Closed => write!(f, "closed"),
+            Self::Open => write!(f, "open"),
+            Self::HalfOpen => write!(f, "half-open"),
+        }
+    }
+}
+
+/// Circuit breaker over consecutive limit violations
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    consecutive_violations: u32,
+    opened_at: Option<Instant>,
+    trial_in_flight: bool,
+}
+
+impl CircuitBreaker {
+    /// A `threshold` of 0 never opens the breaker
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            consecutive_violations: 0,
+            opened_at: None,
+            trial_in_flight: false,
+        }
+    }
+
+    pub fn state(&self, now: Instant) -> CircuitState {
+        match self.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if now.duration_since(opened_at) < self.cooldown => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    pub fn consecutive_violations(&self) -> u32 {
+        self.consecutive_violations
+    }
+
+    /// Whether an execution may start. While half-open only one trial
+    /// execution runs at a time.
+    pub fn admit(&mut self, now: Instant) -> bool {
+        match self.state(now) {
+            CircuitState::Closed => true,
+            CircuitState::Open => false,
+            CircuitState::HalfOpen if self.trial_in_flight => false,
+            CircuitState::HalfOpen => {
+                self.trial_in_flight = true;
+                true
+            }
+        }
+    }
+
+    /// Time left until the breaker lets a trial execution through
+    pub fn retry_after(&self, now: Instant) -> Duration {
+        self.opened_at
+            .map(|opened_at| self.cooldown.saturating_sub(now.duration_since(opened_at)))
+            .unwrap_or_default()
+    }
+
+    /// Record an execution that stayed within its limits
+    pub fn record_success(&mut self) {
+        self.consecutive_violations = 0;
+        self.opened_at = None;
+        self.trial_in_flight = false;
+    }
+
+    /// Give up a half-open trial that ended without an outcome
+    pub fn release_trial(&mut self) {
+        self.trial_in_flight = false;
+    }
+
+    /// Record a limit violation; returns true if it opened the breaker
+    pub fn record_violation(&mut self, now: Instant) -> bool {
+        self.consecutive_violations = self.consecutive_violations.saturating_add(1);
+        let was_trial = std::mem::take(&mut self.trial_in_flight);
+        if self.threshold == 0 {
+            return false;
+        }
+        if was_trial || self.consecutive_violations >= self.threshold {
+            self.opened_at = Some(now);
+            return true;
+        }
+        false
+    }
+}
+
+/// Concurrency limit and circuit breaker of one loaded plugin
+pub struct PluginGuard {
+    name: String,
+    semaphore: Arc<Semaphore>,
+    breaker: Mutex<CircuitBreaker>,
+}
+
+impl PluginGuard {
+    pub fn new(name: &str, limits: &PluginLimits) -> Self {
+        let guard = Self {
+            name: name.to_string(),
+            semaphore: Arc::new(Semaphore::new(limits.max_concurrency.max(1) as usize)),
+            breaker: Mutex::new(CircuitBreaker::new(
+                limits.circuit_breaker_threshold,
+                Duration::from_secs(limits.circuit_breaker_cooldown_secs),
+            )),
+        };
+        guard.export_state(CircuitState::Closed);
+        guard
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.breaker().state(Instant::now())
+    }
+
+    /// Wait up to `wait` for a concurrency slot, then check the breaker.
+    /// The returned [`Admission`] must be held for the duration of the execution.
+    pub async fn enter(self: &Arc<Self>, wait: Duration) -> Result<Admission> {
+        let permit = tokio::time::timeout(wait, self.semaphore.clone().acquire_owned())
+            .await
+            .map_err(|_| {
+                self.export_rejected("concurrency");
+                Error::PluginError(format!(
+                    "Plugin {} concurrency limit reached, no slot within {}ms",
+                    self.name,
+                    wait.as_millis()
+                ))
+            })?
+            .map_err(|_| Error::PluginError(format!("Plugin {} is unloaded", self.name)))?;
+
+        let now = Instant::now();
+        let mut breaker = self.breaker();
+        if !breaker.admit(now) {
+            self.export_rejected("circuit_open");
+            return Err(Error::PluginError(format!(
+                "Plugin {} is disabled after {} consecutive limit violations, retrying in {}s",
+                self.name,
+                breaker.consecutive_violations(),
+                breaker.retry_after(now).as_secs()
+            )));
+        }
+        self.export_state(breaker.state(now));
+        Ok(Admission {
+            guard: Arc::clone(self),
+            _permit: permit,
+            recorded: false,
+        })
+    }
+
+    /// Record the outcome of an execution admitted by [`Self::enter`]
+    fn record(&self, violation: Option<LimitKind>) {
+        let now = Instant::now();
+        let mut breaker = self.breaker();
+        let before = breaker.state(now);
+        match violation {
+            None => {
+                breaker.record_success();
+                if before != CircuitState::Closed {
+                    info!("Plugin {} re-enabled after a successful trial", self.name);
+                }
+            }
+            Some(limit) => {
+                if breaker.record_violation(now) {
+                    warn!(
+                        "Plugin {} disabled for {}s after {} consecutive limit violations (last: {})",
+                        self.name,
+                        breaker.cooldown.as_secs(),
+                        breaker.consecutive_violations(),
+                        limit
+                    );
+                }
+            }
+        }
+        self.export_state(breaker.state(now));
+    }
+
+    fn release_trial(&self) {
+        let mut breaker = self.breaker();
+        breaker.release_trial();
+        self.export_state(breaker.state(Instant::now()));
+    }
+
+    fn breaker(&self) -> std::sync::MutexGuard<'_, CircuitBreaker> {
+        // The breaker holds no invariants a panicking holder could break
+        self.breaker.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[cfg(feature = "metrics")]
+    fn export_state(&self, state: CircuitState) {
+        crate::controller::metrics::set_wasm_plugin_circuit_state(&self.name, state.as_gauge());
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    fn export_state(&self, _state: CircuitState) {}
+
+    #[cfg(feature = "metrics")]
+    fn export_rejected(&self, reason: &str) {
+        crate::controller::metrics::inc_wasm_plugin_rejected(&self.name, reason);
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    fn export_rejected(&self, _reason: &str) {}
+}
+
+/// An execution admitted by [`PluginGuard::enter`].
+///
+/// Holds the concurrency slot until dropped. Dropping it without
+/// [`Self::record`], e.g. when the execution panicked, releases a half-open
+/// trial without counting it either way.
+pub struct Admission {
+    guard: Arc<PluginGuard>,
+    _permit: OwnedSemaphorePermit,
+    recorded: bool,
+}
+
+impl Admission {
+    /// Record the outcome of the execution
+    pub fn record(mut self, violation: Option<LimitKind>) {
+        self.guard.record(violation);
+        self.recorded = true;
+    }
+}
+
+impl Drop for Admission {
+    fn drop(&mut self) {
+        if !self.recorded {
+            self.guard.release_trial();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_after_consecutive_violations() {
+        let start = Instant::now();
+        let mut breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+
+        breaker.record_violation(start);
+        breaker.record_violation(start);
+        // A clean execution resets the count
+        breaker.record_success();
+        assert!(!breaker.record_violation(start));
+        assert!(!breaker.record_violation(start));
+        assert_eq!(breaker.state(start), CircuitState::Closed);
+
+        assert!(breaker.record_violation(start));
+        assert_eq!(breaker.state(start), CircuitState::Open);
+        assert!(!breaker.admit(start + Duration::from_secs(30)));
+        assert_eq!(
+            breaker.retry_after(start + Duration::from_secs(30)),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn test_breaker_half_open_trial() {
+        let start = Instant::now();
+        let mut breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+        breaker.record_violation(start);
+
+        let later = start + Duration::from_secs(61);
+        assert_eq!(breaker.state(later), CircuitState::HalfOpen);
+        assert!(breaker.admit(later));
+        // Only one trial at a time
+        assert!(!breaker.admit(later));
+
+        // A failed trial reopens for another cooldown
+        assert!(breaker.record_violation(later));
+        assert_eq!(breaker.state(later), CircuitState::Open);
+
+        let much_later = later + Duration::from_secs(61);
+        assert!(breaker.admit(much_later));
+        breaker.record_success();
+        assert_eq!(breaker.state(much_later), CircuitState::Closed);
+        assert_eq!(breaker.consecutive_violations(), 0);
+    }
+
+    #[test]
+    fn test_breaker_threshold_zero_never_opens() {
+        let start = Instant::now();
+        let mut breaker = CircuitBreaker::new(0, Duration::from_secs(60));
+        for _ in 0..100 {
+            assert!(!breaker.record_violation(start));
+        }
+        assert!(breaker.admit(start));
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_trial_is_released() {
+        let limits = PluginLimits {
+            max_concurrency: 4,
+            circuit_breaker_threshold: 1,
+            circuit_breaker_cooldown_secs: 0,
+            ..Default::default()
+        };
+        let guard = Arc::new(PluginGuard::new("policy", &limits));
+        let wait = Duration::from_millis(10);
+
+        guard
+            .enter(wait)
+            .await
+            .unwrap()
+            .record(Some(LimitKind::Timeout));
+        assert_eq!(guard.state(), CircuitState::HalfOpen);
+
+        let trial = guard.enter(wait).await.unwrap();
+        assert!(guard.enter(wait).await.is_err());
+
+        // The request was dropped before the trial reported back
+        drop(trial);
+        guard.enter(wait).await.unwrap().record(None);
+        assert_eq!(guard.state(), CircuitState::Closed);
+    }
+}
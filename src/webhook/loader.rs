@@ -0,0 +1,704 @@
+//! Declarative Plugin Loading
+//!
+//! Watches `StellarValidationPlugin` resources and the ConfigMaps/Secrets
+//! holding their modules, and keeps the webhook's plugin set in sync:
+//!
+//! - Modules are fetched from a ConfigMap, a Secret or an OCI registry.
+//! - Every module must match the plugin's pinned `sha256` and carry a valid
+//!   Ed25519 `signature` over the module bytes from one of the configured
+//!   signing keys. Unsigned modules are only loaded when the webhook runs with
+//!   `--allow-unsigned-plugins`.
+//! - Only verified modules reach [`WasmRuntime::load_plugin`]; if a new
+//!   version fails to fetch or verify, the previous one keeps serving.
+//! - Swapping a module only replaces the runtime's cached copy; admission
+//!   requests already executing keep the module they started with.
+//!
+//! ConfigMaps and Secrets are only watched when labelled
+//! `stellar.org/wasm-plugin`, so updating a module in place triggers a reload
+//! without the webhook having to watch every Secret in the cluster.
+//!
+//! [`WasmRuntime::load_plugin`]: super::runtime::WasmRuntime::load_plugin
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::{Signature, VerifyingKey};
+use futures::{stream, StreamExt};
+use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+use kube::{
+    api::{Api, Patch, PatchParams},
+    runtime::{watcher, WatchStreamExt},
+    Client, ResourceExt,
+};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, warn};
+
+use super::crd::StellarValidationPlugin;
+use super::oci::{registry_credentials, OciPuller, OciReference};
+use super::server::WebhookServer;
+use super::types::{PluginConfig, PluginMetadata};
+use crate::error::{Error, Result};
+
+/// Label marking ConfigMaps/Secrets that hold plugin modules
+pub const PLUGIN_SOURCE_LABEL: &str = "stellar.org/wasm-plugin";
+
+const FIELD_MANAGER: &str = "stellar-webhook";
+
+/// How often plugins whose last load failed are retried
+const RETRY_INTERVAL: Duration = Duration::from_secs(300);
+
+pub const PHASE_LOADED: &str = "Loaded";
+pub const PHASE_FAILED: &str = "Failed";
+pub const PHASE_DISABLED: &str = "Disabled";
+
+// ---------------------------------------------------------------------------
+// Verification
+// ---------------------------------------------------------------------------
+
+/// Verify a module against its pinned SHA-256 and its Ed25519 signature.
+/// Returns the module's hex SHA-256.
+///
+/// With `allow_unsigned`, a module is accepted on its SHA-256 alone when it
+/// carries no signature or no signing keys are configured.
+pub fn verify_module(
+    wasm: &[u8],
+    sha256: &str,
+    signature: Option<&str>,
+    signing_keys: &[[u8; 32]],
+    allow_unsigned: bool,
+) -> Result<String> {
+    let digest = format!("{:x}", Sha256::digest(wasm));
+    if !digest.eq_ignore_ascii_case(sha256.trim()) {
+        return Err(Error::PluginError(format!(
+            "Module SHA-256 mismatch: expected {}, got {digest}",
+            sha256.trim()
+        )));
+    }
+
+    let signature = match signature {
+        Some(signature) if !signing_keys.is_empty() => signature,
+        _ if allow_unsigned => return Ok(digest),
+        Some(_) => {
+            return Err(Error::PluginError(
+                "Module is signed but no plugin signing keys are configured".into(),
+            ))
+        }
+        None => {
+            return Err(Error::PluginError(
+                "Module is not signed and unsigned plugins are not allowed".into(),
+            ))
+        }
+    };
+    let signature: [u8; 64] = BASE64
+        .decode(signature.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| {
+            Error::PluginError(
+                "Signature must be a base64-encoded 64-byte Ed25519 signature".into(),
+            )
+        })?;
+    let signature = Signature::from_bytes(&signature);
+
+    let trusted = signing_keys.iter().any(|key| {
+        VerifyingKey::from_bytes(key)
+            .map(|key| key.verify_strict(wasm, &signature).is_ok())
+            .unwrap_or(false)
+    });
+    if !trusted {
+        return Err(Error::PluginError(
+            "Module signature does not match any trusted plugin signing key".into(),
+        ));
+    }
+    Ok(digest)
+}
+
+// ---------------------------------------------------------------------------
+// Resource helpers
+// ---------------------------------------------------------------------------
+
+/// The webhook plugin configuration for a `StellarValidationPlugin`
+pub fn plugin_config(plugin: &StellarValidationPlugin) -> PluginConfig {
+    let spec = &plugin.spec;
+    PluginConfig {
+        metadata: PluginMetadata {
+            name: plugin.name_any(),
+            version: spec.version.clone(),
+            description: spec.description.clone(),
+            author: None,
+            // Checked again by the runtime when compiling
+            sha256: Some(spec.sha256.trim().to_lowercase()),
+            limits: spec.limits.clone(),
+        },
+        wasm_binary: None,
+        config_map_ref: spec.source.config_map_ref.clone(),
+        secret_ref: spec.source.secret_ref.clone(),
+        url: spec.source.oci.as_ref().map(|o| o.reference.clone()),
+        kind: spec.kind,
+        priority: spec.priority,
+        operations: spec.operations.clone(),
+        enabled: spec.enabled,
+        fail_open: spec.fail_open,
+        plugin_config: spec.plugin_config.clone(),
+    }
+}
+
+/// Kind of object a module is stored in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SourceKind {
+    ConfigMap,
+    Secret,
+}
+
+/// Whether `plugin` reads its module from the given ConfigMap/Secret
+pub fn references(
+    plugin: &StellarValidationPlugin,
+    kind: SourceKind,
+    namespace: &str,
+    name: &str,
+    default_namespace: &str,
+) -> bool {
+    let source = &plugin.spec.source;
+    let target = match kind {
+        SourceKind::ConfigMap => source
+            .config_map_ref
+            .as_ref()
+            .map(|r| (r.name.as_str(), r.namespace.as_deref())),
+        SourceKind::Secret => source
+            .secret_ref
+            .as_ref()
+            .map(|r| (r.name.as_str(), r.namespace.as_deref())),
+    };
+    target.is_some_and(|(n, ns)| n == name && ns.unwrap_or(default_namespace) == namespace)
+}
+
+// ---------------------------------------------------------------------------
+// Loader
+// ---------------------------------------------------------------------------
+
+/// Outcome of the last sync of a plugin
+struct SyncState {
+    generation: Option<i64>,
+    /// SHA-256 of the module serving requests, if any
+    serving: Option<String>,
+    failed: bool,
+}
+
+enum Change {
+    Plugin(Box<watcher::Event<StellarValidationPlugin>>),
+    Source(SourceKind, String, String),
+    Retry,
+}
+
+/// Keeps the webhook's plugins in sync with `StellarValidationPlugin` resources
+pub struct PluginLoader {
+    client: Client,
+    server: Arc<WebhookServer>,
+    /// Namespace of the webhook; default for source refs and pull secrets
+    namespace: String,
+    signing_keys: Vec<[u8; 32]>,
+    /// Load modules without a verifiable signature
+    allow_unsigned: bool,
+    http: reqwest::Client,
+    plugins: BTreeMap<String, StellarValidationPlugin>,
+    state: HashMap<String, SyncState>,
+}
+
+impl PluginLoader {
+    pub fn new(
+        client: Client,
+        server: Arc<WebhookServer>,
+        namespace: String,
+        signing_keys: Vec<[u8; 32]>,
+    ) -> Self {
+        Self {
+            client,
+            server,
+            namespace,
+            signing_keys,
+            allow_unsigned: false,
+            http: reqwest::Client::new(),
+            plugins: BTreeMap::new(),
+            state: HashMap::new(),
+        }
+    }
+
+    /// Load modules that carry no signature, or when no signing keys are
+    /// configured, on their pinned SHA-256 alone
+    pub fn with_allow_unsigned(mut self, allow_unsigned: bool) -> Self {
+        self.allow_unsigned = allow_unsigned;
+        self
+    }
+
+    /// Watch plugins and their sources until the watch streams end
+    pub async fn run(mut self) -> Result<()> {
+        if self.allow_unsigned {
+            warn!("Unsigned plugins are allowed; modules are only checked against their sha256");
+        } else if self.signing_keys.is_empty() {
+            warn!("No plugin signing keys configured; every plugin will be rejected");
+        }
+
+        let sources = watcher::Config::default().labels(PLUGIN_SOURCE_LABEL);
+        let plugins = watcher(
+            Api::<StellarValidationPlugin>::all(self.client.clone()),
+            watcher::Config::default(),
+        )
+        .default_backoff()
+        .map(|event| event.map(|e| Change::Plugin(Box::new(e))));
+        let config_maps = watcher(Api::<ConfigMap>::all(self.client.clone()), sources.clone())
+            .default_backoff()
+            .touched_objects()
+            .map(|cm| {
+                cm.map(|cm| {
+                    Change::Source(
+                        SourceKind::ConfigMap,
+                        cm.namespace().unwrap_or_default(),
+                        cm.name_any(),
+                    )
+                })
+            });
+        let secrets = watcher(Api::<Secret>::all(self.client.clone()), sources)
+            .default_backoff()
+            .touched_objects()
+            .map(|s| {
+                s.map(|s| {
+                    Change::Source(
+                        SourceKind::Secret,
+                        s.namespace().unwrap_or_default(),
+                        s.name_any(),
+                    )
+                })
+            });
+        let retries = stream::unfold((), |_| async {
+            tokio::time::sleep(RETRY_INTERVAL).await;
+            Some((Ok(Change::Retry), ()))
+        });
+
+        let mut changes = stream::select_all([
+            plugins.boxed(),
+            config_maps.boxed(),
+            secrets.boxed(),
+            retries.boxed(),
+        ]);
+        let mut listed = BTreeSet::new();
+
+        while let Some(change) = changes.next().await {
+            let change = match change {
+                Ok(change) => change,
+                Err(e) => {
+                    warn!("Plugin watch error: {}", e);
+                    continue;
+                }
+            };
+            match change {
+                Change::Plugin(event) => match *event {
+                    watcher::Event::Init => listed.clear(),
+                    watcher::Event::InitApply(plugin) => {
+                        listed.insert(plugin.name_any());
+                        self.apply(plugin, false).await;
+                    }
+                    watcher::Event::InitDone => {
+                        // Drop plugins deleted while the watch was down
+                        let stale: Vec<String> = self
+                            .plugins
+                            .keys()
+                            .filter(|name| !listed.contains(*name))
+                            .cloned()
+                            .collect();
+                        for name in stale {
+                            self.remove(&name).await;
+                        }
+                    }
+                    watcher::Event::Apply(plugin) => self.apply(plugin, false).await,
+                    watcher::Event::Delete(plugin) => self.remove(&plugin.name_any()).await,
+                },
+                Change::Source(kind, namespace, name) => {
+                    let affected: Vec<StellarValidationPlugin> = self
+                        .plugins
+                        .values()
+                        .filter(|p| references(p, kind, &namespace, &name, &self.namespace))
+                        .cloned()
+                        .collect();
+                    for plugin in affected {
+                        debug!(
+                            "{:?} {}/{} changed, reloading plugin {}",
+                            kind,
+                            namespace,
+                            name,
+                            plugin.name_any()
+                        );
+                        self.apply(plugin, true).await;
+                    }
+                }
+                Change::Retry => {
+                    let failed: Vec<StellarValidationPlugin> = self
+                        .plugins
+                        .values()
+                        .filter(|p| self.state.get(&p.name_any()).is_some_and(|s| s.failed))
+                        .cloned()
+                        .collect();
+                    for plugin in failed {
+                        self.apply(plugin, true).await;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Bring one plugin in line with its resource. Without `refetch`, an
+    /// unchanged generation is skipped (e.g. our own status updates).
+    async fn apply(&mut self, plugin: StellarValidationPlugin, refetch: bool) {
+        let name = plugin.name_any();
+        let generation = plugin.metadata.generation;
+        self.plugins.insert(name.clone(), plugin.clone());
+
+        let previous = self.state.get(&name);
+        if !refetch && previous.is_some_and(|s| s.generation == generation) {
+            return;
+        }
+        let serving = previous.and_then(|s| s.serving.clone());
+
+        if !plugin.spec.enabled {
+            if serving.is_some() {
+                self.unload(&name).await;
+            }
+            self.state.insert(
+                name.clone(),
+                SyncState {
+                    generation,
+                    serving: None,
+                    failed: false,
+                },
+            );
+            self.patch_status(&plugin, PHASE_DISABLED, "Plugin is disabled", None)
+                .await;
+            return;
+        }
+
+        let result = match self.fetch_module(&plugin).await.and_then(|wasm| {
+            let digest = verify_module(
+                &wasm,
+                &plugin.spec.sha256,
+                plugin.spec.signature.as_deref(),
+                &self.signing_keys,
+                self.allow_unsigned,
+            )?;
+            Ok((wasm, digest))
+        }) {
+            // Same module and spec as the one serving: nothing to swap
+            Ok((_, digest))
+                if serving.as_deref() == Some(digest.as_str())
+                    && previous.is_some_and(|s| s.generation == generation && !s.failed) =>
+            {
+                return;
+            }
+            Ok((wasm, digest)) => self
+                .server
+                .install_plugin(plugin_config(&plugin), &wasm)
+                .await
+                .map(|_| digest),
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(digest) => {
+                info!(
+                    "Loaded plugin {} v{} (sha256 {})",
+                    name, plugin.spec.version, digest
+                );
+                self.state.insert(
+                    name.clone(),
+                    SyncState {
+                        generation,
+                        serving: Some(digest.clone()),
+                        failed: false,
+                    },
+                );
+                self.patch_status(
+                    &plugin,
+                    PHASE_LOADED,
+                    &format!("Serving version {}", plugin.spec.version),
+                    Some(&digest),
+                )
+                .await;
+            }
+            Err(e) => {
+                warn!("Failed to load plugin {}: {}", name, e);
+                let message = match &serving {
+                    Some(_) => format!("{e}; the previously loaded module is still serving"),
+                    None => e.to_string(),
+                };
+                self.state.insert(
+                    name.clone(),
+                    SyncState {
+                        generation,
+                        serving: serving.clone(),
+                        failed: true,
+                    },
+                );
+                self.patch_status(&plugin, PHASE_FAILED, &message, serving.as_deref())
+                    .await;
+            }
+        }
+    }
+
+    async fn remove(&mut self, name: &str) {
+        self.plugins.remove(name);
+        if self.state.remove(name).is_some_and(|s| s.serving.is_some()) {
+            self.unload(name).await;
+        }
+    }
+
+    async fn unload(&self, name: &str) {
+        match self.server.remove_plugin(name).await {
+            Ok(()) => info!("Unloaded plugin {}", name),
+            Err(e) => warn!("Failed to unload plugin {}: {}", name, e),
+        }
+    }
+
+    /// Read the module bytes from the plugin's source
+    async fn fetch_module(&self, plugin: &StellarValidationPlugin) -> Result<Vec<u8>> {
+        let source = &plugin.spec.source;
+        let configured = [
+            source.config_map_ref.is_some(),
+            source.secret_ref.is_some(),
+            source.oci.is_some(),
+        ];
+        if configured.iter().filter(|set| **set).count() != 1 {
+            return Err(Error::PluginError(
+                "Exactly one of source.configMapRef, source.secretRef or source.oci must be set"
+                    .to_string(),
+            ));
+        }
+
+        if let Some(r) = &source.config_map_ref {
+            let namespace = r.namespace.as_deref().unwrap_or(&self.namespace);
+            let cm = Api::<ConfigMap>::namespaced(self.client.clone(), namespace)
+                .get(&r.name)
+                .await?;
+            if let Some(bytes) = cm.binary_data.as_ref().and_then(|d| d.get(&r.key)) {
+                return Ok(bytes.0.clone());
+            }
+            let text = cm
+                .data
+                .as_ref()
+                .and_then(|d| d.get(&r.key))
+                .ok_or_else(|| {
+                    Error::PluginError(format!(
+                        "ConfigMap {namespace}/{} has no key {}",
+                        r.name, r.key
+                    ))
+                })?;
+            return BASE64.decode(text.trim()).map_err(|e| {
+                Error::PluginError(format!(
+                    "ConfigMap {namespace}/{} key {} is not base64: {e}",
+                    r.name, r.key
+                ))
+            });
+        }
+
+        if let Some(r) = &source.secret_ref {
+            let namespace = r.namespace.as_deref().unwrap_or(&self.namespace);
+            let secret = Api::<Secret>::namespaced(self.client.clone(), namespace)
+                .get(&r.name)
+                .await?;
+            return secret
+                .data
+                .as_ref()
+                .and_then(|d| d.get(&r.key))
+                .map(|b| b.0.clone())
+                .ok_or_else(|| {
+                    Error::PluginError(format!(
+                        "Secret {namespace}/{} has no key {}",
+                        r.name, r.key
+                    ))
+                });
+        }
+
+        let oci = source.oci.as_ref().ok_or_else(|| {
+            Error::PluginError(format!("Plugin {} has no module source", plugin.name_any()))
+        })?;
+        let reference: OciReference = oci.reference.parse()?;
+        let credentials = match &oci.pull_secret {
+            Some(name) => {
+                let secret = Api::<Secret>::namespaced(self.client.clone(), &self.namespace)
+                    .get(name)
+                    .await?;
+                match secret
+                    .data
+                    .as_ref()
+                    .and_then(|d| d.get(".dockerconfigjson"))
+                {
+                    Some(config) => registry_credentials(&config.0, &reference.registry)?,
+                    None => {
+                        return Err(Error::PluginError(format!(
+                            "Pull secret {name} has no .dockerconfigjson"
+                        )))
+                    }
+                }
+            }
+            None => None,
+        };
+        OciPuller::new(&self.http, credentials)
+            .pull_wasm(&reference)
+            .await
+    }
+
+    async fn patch_status(
+        &self,
+        plugin: &StellarValidationPlugin,
+        phase: &str,
+        message: &str,
+        serving: Option<&str>,
+    ) {
+        let mut status = serde_json::json!({
+            "phase": phase,
+            "message": message,
+            "loadedSha256": serving,
+            "observedGeneration": plugin.metadata.generation,
+        });
+        let previous = plugin.status.as_ref();
+        if serving.is_some() && previous.and_then(|s| s.loaded_sha256.as_deref()) != serving {
+            status["lastLoadedTime"] = serde_json::json!(chrono::Utc::now().to_rfc3339());
+        }
+
+        let api: Api<StellarValidationPlugin> = Api::all(self.client.clone());
+        if let Err(e) = api
+            .patch_status(
+                &plugin.name_any(),
+                &PatchParams::apply(FIELD_MANAGER),
+                &Patch::Merge(&serde_json::json!({ "status": status })),
+            )
+            .await
+        {
+            warn!(
+                "Failed to update status of plugin {}: {}",
+                plugin.name_any(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const WASM: &[u8] = b"\0asm\x01\0\0\0";
+
+    fn sha256(data: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(data))
+    }
+
+    fn plugin(spec: serde_json::Value) -> StellarValidationPlugin {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "stellar.org/v1alpha1",
+            "kind": "StellarValidationPlugin",
+            "metadata": { "name": "org-defaults", "generation": 2 },
+            "spec": spec,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_verify_module_digest() {
+        assert_eq!(
+            verify_module(WASM, &sha256(WASM), None, &[], true).unwrap(),
+            sha256(WASM)
+        );
+        // Case-insensitive
+        assert!(verify_module(WASM, &sha256(WASM).to_uppercase(), None, &[], true).is_ok());
+
+        let err = verify_module(b"tampered", &sha256(WASM), None, &[], true).unwrap_err();
+        assert!(err.to_string().contains("SHA-256 mismatch"));
+    }
+
+    #[test]
+    fn test_verify_module_signature() {
+        let signer = SigningKey::from_bytes(&[7u8; 32]);
+        let other = SigningKey::from_bytes(&[9u8; 32]);
+        let keys = [signer.verifying_key().to_bytes()];
+        let signature = BASE64.encode(signer.sign(WASM).to_bytes());
+
+        assert!(verify_module(WASM, &sha256(WASM), Some(&signature), &keys, false).is_ok());
+
+        let err = verify_module(WASM, &sha256(WASM), None, &keys, false).unwrap_err();
+        assert!(err.to_string().contains("not signed"));
+
+        let forged = BASE64.encode(other.sign(WASM).to_bytes());
+        let err = verify_module(WASM, &sha256(WASM), Some(&forged), &keys, false).unwrap_err();
+        assert!(err.to_string().contains("does not match any trusted"));
+        // Allowing unsigned modules does not accept a bad signature
+        assert!(verify_module(WASM, &sha256(WASM), Some(&forged), &keys, true).is_err());
+
+        let err =
+            verify_module(WASM, &sha256(WASM), Some("bm90IGEgc2ln"), &keys, false).unwrap_err();
+        assert!(err.to_string().contains("64-byte"));
+    }
+
+    #[test]
+    fn test_unsigned_modules_need_opt_in() {
+        let err = verify_module(WASM, &sha256(WASM), None, &[], false).unwrap_err();
+        assert!(err.to_string().contains("unsigned plugins are not allowed"));
+        let err = verify_module(WASM, &sha256(WASM), Some("c2ln"), &[], false).unwrap_err();
+        assert!(err.to_string().contains("no plugin signing keys"));
+
+        let keys = [SigningKey::from_bytes(&[7u8; 32])
+            .verifying_key()
+            .to_bytes()];
+        assert!(verify_module(WASM, &sha256(WASM), None, &keys, true).is_ok());
+    }
+
+    #[test]
+    fn test_plugin_config_from_resource() {
+        let p = plugin(serde_json::json!({
+            "version": "1.2.0",
+            "kind": "Mutating",
+            "priority": 5,
+            "source": { "oci": { "reference": "ghcr.io/org/defaults:1.2.0" } },
+            "sha256": "ABCDEF",
+        }));
+        let config = plugin_config(&p);
+        assert_eq!(config.metadata.name, "org-defaults");
+        assert_eq!(config.metadata.sha256.as_deref(), Some("abcdef"));
+        assert_eq!(config.kind, super::super::types::PluginKind::Mutating);
+        assert_eq!(config.priority, 5);
+        assert_eq!(config.url.as_deref(), Some("ghcr.io/org/defaults:1.2.0"));
+        assert!(config.enabled);
+        assert!(config.wasm_binary.is_none());
+    }
+
+    #[test]
+    fn test_references_source() {
+        let p = plugin(serde_json::json!({
+            "version": "1.0.0",
+            "source": { "configMapRef": { "name": "org-defaults", "key": "plugin.wasm" } },
+            "sha256": "abc",
+        }));
+        assert!(references(
+            &p,
+            SourceKind::ConfigMap,
+            "stellar-webhook",
+            "org-defaults",
+            "stellar-webhook"
+        ));
+        assert!(!references(
+            &p,
+            SourceKind::ConfigMap,
+            "other",
+            "org-defaults",
+            "stellar-webhook"
+        ));
+        assert!(!references(
+            &p,
+            SourceKind::Secret,
+            "stellar-webhook",
+            "org-defaults",
+            "stellar-webhook"
+        ));
+    }
+}
@@ -1,14 +1,19 @@
 //! Webhook Module
 //!
 //! This module provides a Wasm-based admission webhook for custom
-//! StellarNode validation logic.
+//! StellarNode validation and mutation logic.
 //!
 //! # Features
 //!
 //! - **Wasm Plugin Runtime**: Execute custom validation logic in a sandboxed environment
-//! - **Admission Webhook**: Kubernetes ValidatingAdmissionWebhook integration
+//! - **Admission Webhook**: Kubernetes validating and mutating webhook integration
+//! - **Mutating Plugins**: Chain plugin-provided JSON patches after the built-in defaults
 //! - **Plugin Management**: Load, unload, and manage validation plugins
+//! - **Declarative Plugins**: `StellarValidationPlugin` resources backed by
+//!   ConfigMaps, Secrets or OCI artifacts, verified and hot-reloaded
 //! - **Security**: Resource limits, fuel metering, and integrity verification
+//! - **Isolation**: Per-plugin concurrency limits and a circuit breaker that
+//!   disables plugins repeatedly exceeding their limits
 //!
 //! # Example
 //!
@@ -39,16 +44,28 @@
 //! server.start("0.0.0.0:8443".parse()?).await?;
 //! ```
 
+pub mod crd;
+pub mod guard;
+pub mod loader;
 pub mod mutation;
+pub mod oci;
 pub mod runtime;
 pub mod server;
 pub mod types;
 
+pub use crd::{
+    StellarValidationPlugin, StellarValidationPluginSpec, StellarValidationPluginStatus,
+};
+pub use guard::{CircuitState, LimitKind};
+pub use loader::PluginLoader;
 pub use mutation::apply_mutations;
 pub use runtime::{WasmRuntime, WasmRuntimeBuilder};
-pub use server::{LoadPluginRequest, PluginInfo, PluginListResponse, TlsConfig, WebhookServer};
+pub use server::{
+    LoadPluginRequest, PluginInfo, PluginListResponse, ServerMutationResult, TlsConfig,
+    WebhookServer,
+};
 pub use types::{
-    AggregatedValidationResult, ConfigMapRef, DbTriggerInput, DbTriggerOutput, Operation,
-    PluginConfig, PluginExecutionResult, PluginLimits, PluginMetadata, SecretRef, UserInfo,
-    ValidationError, ValidationErrorType, ValidationInput, ValidationOutput,
+    AggregatedValidationResult, ConfigMapRef, DbTriggerInput, DbTriggerOutput, MutationOutput,
+    Operation, PluginConfig, PluginExecutionResult, PluginKind, PluginLimits, PluginMetadata,
+    SecretRef, UserInfo, ValidationError, ValidationErrorType, ValidationInput, ValidationOutput,
 };
@@ -0,0 +1,370 @@
+//! Pulling Wasm plugins from OCI registries
+//!
+//! A minimal OCI distribution client: it resolves the manifest, picks the
+//! Wasm layer and downloads it, handling anonymous and basic-auth bearer
+//! token challenges. Only what plugin loading needs is implemented.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use reqwest::header::{ACCEPT, WWW_AUTHENTICATE};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tracing::debug;
+
+use crate::error::{Error, Result};
+
+/// Media types Wasm modules are published under (wasm-to-oci, ORAS)
+const WASM_LAYER_MEDIA_TYPES: [&str; 2] = [
+    "application/vnd.wasm.content.layer.v1+wasm",
+    "application/vnd.module.wasm.content.layer.v1+wasm",
+];
+
+const MANIFEST_MEDIA_TYPES: &str =
+    "application/vnd.oci.image.manifest.v1+json, application/vnd.docker.distribution.manifest.v2+json";
+
+const DOCKER_HUB_REGISTRY: &str = "registry-1.docker.io";
+
+/// Largest module accepted from a registry
+const MAX_MODULE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// A parsed `registry/repository[:tag|@digest]` reference
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OciReference {
+    pub registry: String,
+    pub repository: String,
+    /// Tag or `sha256:` digest
+    pub reference: String,
+}
+
+impl std::str::FromStr for OciReference {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::PluginError(format!("Invalid OCI reference '{s}'"));
+
+        let (name, reference) = match s.split_once('@') {
+            Some((name, digest)) => (name, digest.to_string()),
+            None => match s.rsplit_once(':') {
+                Some((name, tag)) if !tag.contains('/') => (name, tag.to_string()),
+                _ => (s, "latest".to_string()),
+            },
+        };
+
+        let (registry, repository) = match name.split_once('/') {
+            Some((first, rest))
+                if first.contains('.') || first.contains(':') || first == "localhost" =>
+            {
+                (first.to_string(), rest.to_string())
+            }
+            Some(_) => (DOCKER_HUB_REGISTRY.to_string(), name.to_string()),
+            None => (DOCKER_HUB_REGISTRY.to_string(), format!("library/{name}")),
+        };
+        if repository.is_empty() || reference.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            registry,
+            repository,
+            reference,
+        })
+    }
+}
+
+/// Registry credentials
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// Find the credentials for `registry` in a `.dockerconfigjson` document
+pub fn registry_credentials(docker_config: &[u8], registry: &str) -> Result<Option<Credentials>> {
+    #[derive(Deserialize)]
+    struct DockerConfig {
+        #[serde(default)]
+        auths: std::collections::BTreeMap<String, AuthEntry>,
+    }
+    #[derive(Deserialize)]
+    struct AuthEntry {
+        auth: Option<String>,
+        username: Option<String>,
+        password: Option<String>,
+    }
+
+    let config: DockerConfig = serde_json::from_slice(docker_config)
+        .map_err(|e| Error::PluginError(format!("Invalid .dockerconfigjson: {e}")))?;
+    let host = |key: &str| {
+        let key = key
+            .trim_start_matches("https://")
+            .trim_start_matches("http://");
+        let host = key.split('/').next().unwrap_or(key).to_string();
+        // Docker Hub credentials are stored under its legacy index URL
+        if host == "index.docker.io" || host == "docker.io" {
+            DOCKER_HUB_REGISTRY.to_string()
+        } else {
+            host
+        }
+    };
+
+    let Some(entry) = config
+        .auths
+        .into_iter()
+        .find(|(key, _)| host(key) == registry)
+        .map(|(_, entry)| entry)
+    else {
+        return Ok(None);
+    };
+    if let (Some(username), Some(password)) = (entry.username, entry.password) {
+        return Ok(Some(Credentials { username, password }));
+    }
+    let Some(auth) = entry.auth else {
+        return Ok(None);
+    };
+    let decoded = BASE64
+        .decode(auth)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(|| Error::PluginError(format!("Invalid auth for registry {registry}")))?;
+    let (username, password) = decoded
+        .split_once(':')
+        .ok_or_else(|| Error::PluginError(format!("Invalid auth for registry {registry}")))?;
+    Ok(Some(Credentials {
+        username: username.to_string(),
+        password: password.to_string(),
+    }))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    #[serde(default)]
+    layers: Vec<Descriptor>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Descriptor {
+    media_type: String,
+    digest: String,
+    size: u64,
+}
+
+/// Pick the layer holding the Wasm module
+fn wasm_layer(manifest: &Manifest) -> Result<Descriptor> {
+    let mut wasm = manifest
+        .layers
+        .iter()
+        .filter(|l| WASM_LAYER_MEDIA_TYPES.contains(&l.media_type.as_str()));
+    match (wasm.next(), wasm.next(), manifest.layers.as_slice()) {
+        (Some(layer), None, _) => Ok(layer.clone()),
+        (None, _, [only]) => Ok(only.clone()),
+        _ => Err(Error::PluginError(
+            "OCI artifact must contain exactly one Wasm layer".to_string(),
+        )),
+    }
+}
+
+/// Read `key="value"` parameters of a `WWW-Authenticate: Bearer` challenge
+fn challenge_params(challenge: &str) -> std::collections::BTreeMap<String, String> {
+    challenge
+        .trim_start_matches("Bearer")
+        .split(',')
+        .filter_map(|part| {
+            let (key, value) = part.trim().split_once('=')?;
+            Some((key.to_string(), value.trim_matches('"').to_string()))
+        })
+        .collect()
+}
+
+/// OCI registry client for a single pull
+pub struct OciPuller<'a> {
+    http: &'a reqwest::Client,
+    credentials: Option<Credentials>,
+    token: Option<String>,
+}
+
+impl<'a> OciPuller<'a> {
+    pub fn new(http: &'a reqwest::Client, credentials: Option<Credentials>) -> Self {
+        Self {
+            http,
+            credentials,
+            token: None,
+        }
+    }
+
+    /// Download the Wasm module referenced by `reference`
+    pub async fn pull_wasm(&mut self, reference: &OciReference) -> Result<Vec<u8>> {
+        let base = format!("https://{}/v2/{}", reference.registry, reference.repository);
+
+        let manifest: Manifest = self
+            .get(
+                &format!("{base}/manifests/{}", reference.reference),
+                Some(MANIFEST_MEDIA_TYPES),
+            )
+            .await?
+            .json()
+            .await
+            .map_err(|e| Error::PluginError(format!("Invalid OCI manifest: {e}")))?;
+        let layer = wasm_layer(&manifest)?;
+        if layer.size > MAX_MODULE_BYTES {
+            return Err(Error::PluginError(format!(
+                "Wasm layer is {} bytes, larger than the {MAX_MODULE_BYTES} byte limit",
+                layer.size
+            )));
+        }
+
+        let blob = self
+            .get(&format!("{base}/blobs/{}", layer.digest), None)
+            .await?
+            .bytes()
+            .await
+            .map_err(|e| Error::PluginError(format!("Failed to download Wasm layer: {e}")))?;
+        let digest = format!("sha256:{:x}", Sha256::digest(&blob));
+        if digest != layer.digest {
+            return Err(Error::PluginError(format!(
+                "Wasm layer digest mismatch: manifest says {}, got {digest}",
+                layer.digest
+            )));
+        }
+        debug!("Pulled {} bytes from {}", blob.len(), base);
+        Ok(blob.to_vec())
+    }
+
+    async fn get(&mut self, url: &str, accept: Option<&str>) -> Result<reqwest::Response> {
+        let http = self.http;
+        let request = |token: Option<&str>, credentials: Option<&Credentials>| {
+            let mut request: RequestBuilder = http.get(url);
+            if let Some(accept) = accept {
+                request = request.header(ACCEPT, accept);
+            }
+            match (token, credentials) {
+                (Some(token), _) => request.bearer_auth(token),
+                (None, Some(c)) => request.basic_auth(&c.username, Some(&c.password)),
+                (None, None) => request,
+            }
+        };
+
+        let send = |request: RequestBuilder| async move {
+            request
+                .send()
+                .await
+                .map_err(|e| Error::PluginError(format!("OCI request to {url} failed: {e}")))
+        };
+
+        let mut response = send(request(self.token.as_deref(), None)).await?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            let challenge = response
+                .headers()
+                .get(WWW_AUTHENTICATE)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+            response = if challenge.starts_with("Bearer") {
+                self.token = Some(self.fetch_token(&challenge).await?);
+                send(request(self.token.as_deref(), None)).await?
+            } else {
+                send(request(None, self.credentials.as_ref())).await?
+            };
+        }
+
+        if !response.status().is_success() {
+            return Err(Error::PluginError(format!(
+                "OCI request to {url} returned {}",
+                response.status()
+            )));
+        }
+        Ok(response)
+    }
+
+    async fn fetch_token(&self, challenge: &str) -> Result<String> {
+        #[derive(Deserialize)]
+        struct TokenResponse {
+            token: Option<String>,
+            access_token: Option<String>,
+        }
+
+        let params = challenge_params(challenge);
+        let realm = params.get("realm").ok_or_else(|| {
+            Error::PluginError(format!("OCI auth challenge without realm: {challenge}"))
+        })?;
+        let query: Vec<(&str, &str)> = ["service", "scope"]
+            .iter()
+            .filter_map(|k| params.get(*k).map(|v| (*k, v.as_str())))
+            .collect();
+
+        let mut request = self.http.get(realm).query(&query);
+        if let Some(c) = &self.credentials {
+            request = request.basic_auth(&c.username, Some(&c.password));
+        }
+        let response = request
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| Error::PluginError(format!("OCI token request failed: {e}")))?;
+        let token: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| Error::PluginError(format!("Invalid OCI token response: {e}")))?;
+        token
+            .token
+            .or(token.access_token)
+            .ok_or_else(|| Error::PluginError("OCI token response has no token".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reference() {
+        let r: OciReference = "ghcr.io/org/plugins/quota:1.2.0".parse().unwrap();
+        assert_eq!(r.registry, "ghcr.io");
+        assert_eq!(r.repository, "org/plugins/quota");
+        assert_eq!(r.reference, "1.2.0");
+
+        let r: OciReference = "localhost:5000/quota@sha256:abcd".parse().unwrap();
+        assert_eq!(r.registry, "localhost:5000");
+        assert_eq!(r.repository, "quota");
+        assert_eq!(r.reference, "sha256:abcd");
+
+        let r: OciReference = "quota".parse().unwrap();
+        assert_eq!(r.registry, DOCKER_HUB_REGISTRY);
+        assert_eq!(r.repository, "library/quota");
+        assert_eq!(r.reference, "latest");
+    }
+
+    #[test]
+    fn test_registry_credentials() {
+        let config = serde_json::json!({
+            "auths": {
+                "ghcr.io": { "auth": BASE64.encode("bot:s3cret") },
+                "https://index.docker.io/v1/": { "username": "hub", "password": "pw" }
+            }
+        })
+        .to_string();
+
+        let ghcr = registry_credentials(config.as_bytes(), "ghcr.io")
+            .unwrap()
+            .unwrap();
+        assert_eq!(ghcr.username, "bot");
+        assert_eq!(ghcr.password, "s3cret");
+        let hub = registry_credentials(config.as_bytes(), DOCKER_HUB_REGISTRY)
+            .unwrap()
+            .unwrap();
+        assert_eq!(hub.username, "hub");
+        assert!(registry_credentials(config.as_bytes(), "quay.io")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_bearer_challenge() {
+        let params = challenge_params(
+            r#"Bearer realm="https://ghcr.io/token",service="ghcr.io",scope="repository:org/quota:pull""#,
+        );
+        assert_eq!(params["realm"], "https://ghcr.io/token");
+        assert_eq!(params["service"], "ghcr.io");
+        assert_eq!(params["scope"], "repository:org/quota:pull");
+    }
+}
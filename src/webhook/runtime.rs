@@ -1,11 +1,28 @@
 //! Wasmtime Runtime with Sandboxed Execution
 //!
 //! This module provides a secure, sandboxed environment for executing
-//! Wasm validation plugins using Wasmtime.
+//! Wasm validation and mutation plugins using Wasmtime.
+//!
+//! # Plugin ABI
+//!
+//! Plugins export `memory` and a `() -> i32` entry point: `validate` for
+//! validating plugins, `mutate` for mutating ones, and optionally
+//! `process_trigger` for database triggers. Input JSON is read with the
+//! `env.get_input_len`/`env.read_input` imports and output JSON is written
+//! with `env.write_output`. `mutate` receives a [`ValidationInput`] and
+//! returns a [`MutationOutput`]; a non-zero return code is a plugin error.
+//!
+//! # Limits
+//!
+//! Each execution gets its own store with the plugin's fuel budget, an
+//! epoch deadline derived from `timeout_ms` and a memory limiter that also
+//! records the linear-memory high-water mark reported as `memory_used`.
+//! Executions go through the plugin's [`PluginGuard`], which bounds
+//! concurrency and disables plugins that keep exceeding their limits.
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use tokio::sync::RwLock;
 use tracing::{debug, info, instrument, warn};
@@ -13,9 +30,10 @@ use wasmtime::*;
 use wasmtime_wasi::preview1::{self, WasiP1Ctx};
 use wasmtime_wasi::WasiCtxBuilder;
 
+use super::guard::{CircuitState, LimitKind, PluginGuard};
 use super::types::{
-    DbTriggerInput, DbTriggerOutput, PluginConfig, PluginExecutionResult, PluginLimits,
-    PluginMetadata, ValidationInput, ValidationOutput,
+    DbTriggerInput, DbTriggerOutput, MutationOutput, PluginConfig, PluginExecutionResult,
+    PluginKind, PluginLimits, PluginMetadata, ValidationInput, ValidationOutput,
 };
 use crate::error::{Error, Result};
 
@@ -31,10 +49,14 @@ pub struct WasmRuntime {
     default_limits: PluginLimits,
 }
 
+/// Interval at which the engine epoch advances; timeouts are rounded up to it
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
 /// Cached compiled Wasm module
 struct CachedModule {
     module: Module,
     metadata: PluginMetadata,
+    guard: Arc<PluginGuard>,
     #[allow(dead_code)]
     compiled_at: Instant,
 }
@@ -44,6 +66,88 @@ struct PluginState {
     wasi: WasiP1Ctx,
     input_buffer: Vec<u8>,
     output_buffer: Vec<u8>,
+    memory: MemoryLimiter,
+}
+
+/// Enforces the memory limit and records the linear-memory high-water mark
+struct MemoryLimiter {
+    limits: StoreLimits,
+    peak_bytes: usize,
+    exceeded: bool,
+}
+
+impl ResourceLimiter for MemoryLimiter {
+    fn memory_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> wasmtime::Result<bool> {
+        let allowed = self.limits.memory_growing(current, desired, maximum)?;
+        if allowed {
+            self.peak_bytes = self.peak_bytes.max(desired);
+        } else {
+            self.exceeded = true;
+        }
+        Ok(allowed)
+    }
+
+    fn table_growing(
+        &mut self,
+        current: u32,
+        desired: u32,
+        maximum: Option<u32>,
+    ) -> wasmtime::Result<bool> {
+        self.limits.table_growing(current, desired, maximum)
+    }
+}
+
+/// Outcome of a single execution inside the sandbox
+struct SyncExecution {
+    /// Return code and output buffer of the entry point
+    result: Result<(i32, Vec<u8>)>,
+    /// Limit that stopped the execution, if any
+    violation: Option<LimitKind>,
+    fuel_consumed: u64,
+    memory_used: u64,
+}
+
+/// Completed execution of a plugin entry point
+struct RawExecution {
+    result_code: i32,
+    output: Vec<u8>,
+    fuel_consumed: u64,
+    memory_used: u64,
+    execution_time_ms: u64,
+}
+
+/// Failure while instantiating or calling an entry point
+enum EntryPointError {
+    /// Wasm trap, e.g. fuel exhaustion or an epoch interrupt
+    Trap(Trap, Error),
+    Other(Error),
+}
+
+impl EntryPointError {
+    fn from_wasmtime(context: &str, e: wasmtime::Error) -> Self {
+        let error = Error::PluginError(format!("{context}: {e}"));
+        match e.downcast_ref::<Trap>() {
+            Some(trap) => Self::Trap(*trap, error),
+            None => Self::Other(error),
+        }
+    }
+
+    fn into_error(self) -> Error {
+        match self {
+            Self::Trap(_, e) | Self::Other(e) => e,
+        }
+    }
+}
+
+impl From<Error> for EntryPointError {
+    fn from(e: Error) -> Self {
+        Self::Other(e)
+    }
 }
 
 impl WasmRuntime {
@@ -77,6 +181,7 @@ impl WasmRuntime {
 
         let engine = Engine::new(&config)
             .map_err(|e| Error::PluginError(format!("Engine creation failed: {e}")))?;
+        Self::spawn_epoch_ticker(&engine)?;
 
         Ok(Self {
             engine,
@@ -85,9 +190,37 @@ impl WasmRuntime {
         })
     }
 
-    /// Load a plugin from binary data
-    #[instrument(skip(self, wasm_bytes), fields(plugin_name = %metadata.name))]
+    /// Advance the engine epoch every [`EPOCH_TICK`] so epoch deadlines
+    /// fire. The thread exits once the engine is dropped.
+    fn spawn_epoch_ticker(engine: &Engine) -> Result<()> {
+        let engine = engine.weak();
+        std::thread::Builder::new()
+            .name("wasm-epoch-ticker".to_string())
+            .spawn(move || {
+                while let Some(engine) = engine.upgrade() {
+                    engine.increment_epoch();
+                    drop(engine);
+                    std::thread::sleep(EPOCH_TICK);
+                }
+            })
+            .map_err(|e| Error::PluginError(format!("Failed to start epoch ticker: {e}")))?;
+        Ok(())
+    }
+
+    /// Load a validating plugin from binary data
     pub async fn load_plugin(&self, wasm_bytes: &[u8], metadata: PluginMetadata) -> Result<()> {
+        self.load_plugin_with_kind(wasm_bytes, metadata, PluginKind::Validating)
+            .await
+    }
+
+    /// Load a plugin from binary data, checking it exports the entry point for `kind`
+    #[instrument(skip(self, wasm_bytes), fields(plugin_name = %metadata.name))]
+    pub async fn load_plugin_with_kind(
+        &self,
+        wasm_bytes: &[u8],
+        metadata: PluginMetadata,
+        kind: PluginKind,
+    ) -> Result<()> {
         // Verify integrity if SHA256 is provided
         if let Some(expected_hash) = &metadata.sha256 {
             let actual_hash = Self::compute_sha256(wasm_bytes);
@@ -106,11 +239,13 @@ impl WasmRuntime {
         })?;
 
         // Validate the module exports the required function
-        Self::validate_module_exports(&module, &metadata.name)?;
+        Self::validate_module_exports(&module, &metadata.name, kind)?;
 
-        // Cache the compiled module
+        // Cache the compiled module. A reloaded plugin starts with a
+        // closed circuit breaker.
         let cached = CachedModule {
             module,
+            guard: Arc::new(PluginGuard::new(&metadata.name, &metadata.limits)),
             metadata: metadata.clone(),
             compiled_at: Instant::now(),
         };
@@ -139,6 +274,12 @@ impl WasmRuntime {
         cache.values().map(|c| c.metadata.clone()).collect()
     }
 
+    /// Circuit breaker state of a loaded plugin
+    pub async fn circuit_state(&self, name: &str) -> Option<CircuitState> {
+        let cache = self.module_cache.read().await;
+        cache.get(name).map(|c| c.guard.state())
+    }
+
     /// Execute a plugin with the given input
     #[instrument(skip(self, input), fields(plugin_name = %plugin_name))]
     pub async fn execute(
@@ -147,57 +288,37 @@ impl WasmRuntime {
         input: &ValidationInput,
         limits: Option<PluginLimits>,
     ) -> Result<PluginExecutionResult> {
-        let start_time = Instant::now();
-
-        // Get the cached module
-        let cache = self.module_cache.read().await;
-        let cached = cache
-            .get(plugin_name)
-            .ok_or_else(|| Error::PluginError(format!("Plugin {plugin_name} not loaded")))?;
-
-        let module = cached.module.clone();
-        let metadata = cached.metadata.clone();
-        drop(cache);
-
-        // Use provided limits or defaults
-        let limits = limits.unwrap_or_else(|| metadata.limits.clone());
-
-        // Serialize input
         let input_json = serde_json::to_vec(input)
             .map_err(|e| Error::PluginError(format!("Failed to serialize input: {e}")))?;
+        let execution = self
+            .run_entry_point(plugin_name, input_json, limits, "validate")
+            .await?;
 
-        // Execute in a blocking task to not block the async runtime
-        let engine = self.engine.clone();
-        let (result_code, output_buffer, fuel_consumed) = tokio::task::spawn_blocking(move || {
-            Self::execute_sync(&engine, &module, input_json, &limits, "validate")
-        })
-        .await
-        .map_err(|e| Error::PluginError(format!("Plugin execution task failed: {e}")))??;
-
-        let execution_time = start_time.elapsed();
-
-        let output: ValidationOutput = if output_buffer.is_empty() {
+        let output: ValidationOutput = if execution.output.is_empty() {
             // Default to denied if no output
-            if result_code == 0 {
+            if execution.result_code == 0 {
                 ValidationOutput::allowed()
             } else {
-                ValidationOutput::denied(format!("Plugin returned error code: {result_code}"))
+                ValidationOutput::denied(format!(
+                    "Plugin returned error code: {}",
+                    execution.result_code
+                ))
             }
         } else {
-            serde_json::from_slice(&output_buffer)
+            serde_json::from_slice(&execution.output)
                 .map_err(|e| Error::PluginError(format!("Failed to parse plugin output: {e}")))?
         };
 
         Ok(PluginExecutionResult {
             plugin_name: plugin_name.to_string(),
             output,
-            execution_time_ms: execution_time.as_millis() as u64,
-            memory_used_bytes: 0, // TODO
-            fuel_consumed,
+            execution_time_ms: execution.execution_time_ms,
+            memory_used_bytes: execution.memory_used,
+            fuel_consumed: execution.fuel_consumed,
         })
     }
 
-    /// Execute multiple plugins in parallel
+    /// Execute validating plugins in parallel
     pub async fn execute_all(
         &self,
         plugins: &[PluginConfig],
@@ -211,7 +332,9 @@ impl WasmRuntime {
             }
 
             // Check if plugin handles this operation
-            if !plugin.operations.contains(&input.operation) {
+            if plugin.kind != PluginKind::Validating
+                || !plugin.operations.contains(&input.operation)
+            {
                 continue;
             }
 
@@ -275,6 +398,44 @@ impl WasmRuntime {
         results
     }
 
+    /// Execute a mutating plugin, returning the patch it produced
+    #[instrument(skip(self, input), fields(plugin_name = %plugin_name))]
+    pub async fn execute_mutation(
+        &self,
+        plugin_name: &str,
+        input: &ValidationInput,
+        limits: Option<PluginLimits>,
+    ) -> Result<ExecutionResult<MutationOutput>> {
+        let input_json = serde_json::to_vec(input)
+            .map_err(|e| Error::PluginError(format!("Failed to serialize input: {e}")))?;
+        let execution = self
+            .run_entry_point(plugin_name, input_json, limits, "mutate")
+            .await?;
+
+        if execution.result_code != 0 {
+            return Err(Error::PluginError(format!(
+                "Mutating plugin {plugin_name} returned error code {}",
+                execution.result_code
+            )));
+        }
+
+        // No output means no changes
+        let output: MutationOutput = if execution.output.is_empty() {
+            MutationOutput::default()
+        } else {
+            serde_json::from_slice(&execution.output).map_err(|e| {
+                Error::PluginError(format!("Failed to parse mutating plugin output: {e}"))
+            })?
+        };
+
+        Ok(ExecutionResult {
+            output,
+            memory_used: execution.memory_used,
+            fuel_consumed: execution.fuel_consumed,
+            execution_time_ms: execution.execution_time_ms,
+        })
+    }
+
     /// Execute a db trigger plugin
     #[instrument(skip(self, input), fields(plugin_name = %plugin_name))]
     pub async fn execute_db_trigger(
@@ -283,8 +444,39 @@ impl WasmRuntime {
         input: &DbTriggerInput,
         limits: Option<PluginLimits>,
     ) -> Result<ExecutionResult<DbTriggerOutput>> {
-        let start_time = Instant::now();
+        let input_json = serde_json::to_vec(input)
+            .map_err(|e| Error::PluginError(format!("Failed to serialize input: {e}")))?;
+        let execution = self
+            .run_entry_point(plugin_name, input_json, limits, "process_trigger")
+            .await?;
 
+        if execution.result_code != 0 {
+            return Err(Error::PluginError(format!(
+                "DB Trigger Plugin {} returned error code {}",
+                plugin_name, execution.result_code
+            )));
+        }
+
+        let output: DbTriggerOutput = serde_json::from_slice(&execution.output).map_err(|e| {
+            Error::PluginError(format!("Failed to parse DB trigger plugin output: {e}"))
+        })?;
+
+        Ok(ExecutionResult {
+            output,
+            memory_used: execution.memory_used,
+            fuel_consumed: execution.fuel_consumed,
+            execution_time_ms: execution.execution_time_ms,
+        })
+    }
+
+    /// Run a plugin entry point through its guard, recording metrics
+    async fn run_entry_point(
+        &self,
+        plugin_name: &str,
+        input_json: Vec<u8>,
+        limits: Option<PluginLimits>,
+        func_name: &'static str,
+    ) -> Result<RawExecution> {
         // Get the cached module
         let cache = self.module_cache.read().await;
         let cached = cache
@@ -292,41 +484,53 @@ impl WasmRuntime {
             .ok_or_else(|| Error::PluginError(format!("Plugin {plugin_name} not loaded")))?;
 
         let module = cached.module.clone();
-        let metadata = cached.metadata.clone();
-        drop(cache);
-
+        let guard = cached.guard.clone();
         // Use provided limits or defaults
-        let limits = limits.unwrap_or_else(|| metadata.limits.clone());
+        let limits = limits.unwrap_or_else(|| cached.metadata.limits.clone());
+        drop(cache);
 
-        // Serialize input
-        let input_json = serde_json::to_vec(input)
-            .map_err(|e| Error::PluginError(format!("Failed to serialize input: {e}")))?;
+        let admission = guard
+            .enter(Duration::from_millis(limits.timeout_ms))
+            .await?;
 
-        // Execute in a blocking task to not block the async runtime
+        // Execute in a blocking task to not block the async runtime. The
+        // outcome is recorded there, so it is not lost when the request is
+        // dropped mid-execution.
+        let start_time = Instant::now();
         let engine = self.engine.clone();
-        let (result_code, output_buffer, fuel_consumed) = tokio::task::spawn_blocking(move || {
-            Self::execute_sync(&engine, &module, input_json, &limits, "process_trigger")
+        let execution = tokio::task::spawn_blocking(move || {
+            let execution = Self::execute_sync(&engine, &module, input_json, &limits, func_name);
+            admission.record(execution.violation);
+            execution
         })
         .await
-        .map_err(|e| Error::PluginError(format!("Plugin execution task failed: {e}")))??;
+        .map_err(|e| Error::PluginError(format!("Plugin execution task failed: {e}")))?;
+        let execution_time = start_time.elapsed();
 
-        if result_code != 0 {
-            return Err(Error::PluginError(format!(
-                "DB Trigger Plugin {} returned error code {}",
-                plugin_name, result_code
-            )));
+        #[cfg(feature = "metrics")]
+        {
+            use crate::controller::metrics;
+            metrics::observe_wasm_plugin_execution(
+                plugin_name,
+                execution_time.as_secs_f64(),
+                execution.fuel_consumed,
+                execution.memory_used,
+            );
+            if let Some(limit) = execution.violation {
+                metrics::inc_wasm_plugin_limit_exceeded(plugin_name, &limit.to_string());
+            }
         }
+        debug!(
+            "Plugin {} {} used {} fuel and {} bytes of memory in {:?}",
+            plugin_name, func_name, execution.fuel_consumed, execution.memory_used, execution_time
+        );
 
-        let output: DbTriggerOutput = serde_json::from_slice(&output_buffer).map_err(|e| {
-            Error::PluginError(format!("Failed to parse DB trigger plugin output: {e}"))
-        })?;
-
-        let execution_time = start_time.elapsed();
-
-        Ok(ExecutionResult {
+        let (result_code, output) = execution.result?;
+        Ok(RawExecution {
+            result_code,
             output,
-            memory_used: 0,
-            fuel_consumed,
+            fuel_consumed: execution.fuel_consumed,
+            memory_used: execution.memory_used,
             execution_time_ms: execution_time.as_millis() as u64,
         })
     }
@@ -338,12 +542,7 @@ impl WasmRuntime {
         input_json: Vec<u8>,
         limits: &PluginLimits,
         func_name: &str,
-    ) -> Result<(i32, Vec<u8>, u64)> {
-        // Create store with limits
-        let _store_limits = StoreLimitsBuilder::new()
-            .memory_size(limits.max_memory_bytes as usize)
-            .build();
-
+    ) -> SyncExecution {
         // Create WASI context (sandboxed, no filesystem or network access)
         let wasi = WasiCtxBuilder::new().build_p1();
 
@@ -351,10 +550,60 @@ impl WasmRuntime {
             wasi,
             input_buffer: input_json,
             output_buffer: Vec::with_capacity(4096),
+            memory: MemoryLimiter {
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(limits.max_memory_bytes as usize)
+                    .build(),
+                peak_bytes: 0,
+                exceeded: false,
+            },
         };
 
         let mut store = Store::new(engine, state);
+        store.limiter(|state| &mut state.memory);
+
+        let result = Self::call_entry_point(engine, module, &mut store, limits, func_name);
 
+        let fuel_remaining = store.get_fuel().unwrap_or(0);
+        let state = store.data();
+        let violation = match &result {
+            Err(EntryPointError::Trap(Trap::OutOfFuel, _)) => Some(LimitKind::Fuel),
+            Err(EntryPointError::Trap(Trap::Interrupt, _)) => Some(LimitKind::Timeout),
+            Err(_) if state.memory.exceeded => Some(LimitKind::Memory),
+            _ => None,
+        };
+
+        let result = result.map_err(|e| match violation {
+            Some(LimitKind::Fuel) => {
+                Error::PluginError("Plugin exceeded instruction limit".to_string())
+            }
+            Some(LimitKind::Timeout) => Error::PluginError(format!(
+                "Plugin execution timeout after {}ms",
+                limits.timeout_ms
+            )),
+            Some(LimitKind::Memory) => Error::PluginError(format!(
+                "Plugin exceeded memory limit of {} bytes",
+                limits.max_memory_bytes
+            )),
+            None => e.into_error(),
+        });
+
+        SyncExecution {
+            result: result.map(|code| (code, state.output_buffer.clone())),
+            violation,
+            fuel_consumed: limits.max_fuel.saturating_sub(fuel_remaining),
+            memory_used: state.memory.peak_bytes as u64,
+        }
+    }
+
+    /// Instantiate the module in `store` and call `func_name`
+    fn call_entry_point(
+        engine: &Engine,
+        module: &Module,
+        store: &mut Store<PluginState>,
+        limits: &PluginLimits,
+        func_name: &str,
+    ) -> std::result::Result<i32, EntryPointError> {
         // Set fuel limit
         store
             .set_fuel(limits.max_fuel)
@@ -362,7 +611,7 @@ impl WasmRuntime {
 
         // Set epoch deadline for timeout
         store.epoch_deadline_trap();
-        store.set_epoch_deadline(1);
+        store.set_epoch_deadline(limits.timeout_ms.div_ceil(EPOCH_TICK.as_millis() as u64) + 1);
 
         // Create linker with WASI
         let mut linker = Linker::new(engine);
@@ -374,34 +623,18 @@ impl WasmRuntime {
 
         // Instantiate the module
         let instance = linker
-            .instantiate(&mut store, module)
-            .map_err(|e| Error::PluginError(format!("Failed to instantiate module: {e}")))?;
+            .instantiate(&mut *store, module)
+            .map_err(|e| EntryPointError::from_wasmtime("Failed to instantiate module", e))?;
 
         // Get the target function
-        let validate_fn = instance
-            .get_typed_func::<(), i32>(&mut store, func_name)
+        let entry_point = instance
+            .get_typed_func::<(), i32>(&mut *store, func_name)
             .map_err(|e| Error::PluginError(format!("Failed to get {func_name} function: {e}")))?;
 
         // Call the target function
-        let result_code = validate_fn.call(&mut store, ()).map_err(|e| {
-            if e.to_string().contains("fuel") {
-                Error::PluginError("Plugin exceeded instruction limit".to_string())
-            } else if e.to_string().contains("epoch") {
-                Error::PluginError("Plugin execution timeout".to_string())
-            } else {
-                Error::PluginError(format!("Plugin execution failed: {e}"))
-            }
-        })?;
-
-        // Get fuel consumed
-        let fuel_remaining = store.get_fuel().unwrap_or(0);
-        let fuel_consumed = limits.max_fuel.saturating_sub(fuel_remaining);
-
-        // Get output from state
-        let state = store.data();
-        let output_buffer = state.output_buffer.clone();
-
-        Ok((result_code, output_buffer, fuel_consumed))
+        entry_point
+            .call(&mut *store, ())
+            .map_err(|e| EntryPointError::from_wasmtime("Plugin execution failed", e))
     }
 
     /// Add host functions for plugin I/O
@@ -491,13 +724,14 @@ impl WasmRuntime {
     }
 
     /// Validate that the module exports required functions
-    fn validate_module_exports(module: &Module, name: &str) -> Result<()> {
+    fn validate_module_exports(module: &Module, name: &str, kind: PluginKind) -> Result<()> {
         let exports: Vec<_> = module.exports().collect();
 
-        let has_validate = exports.iter().any(|e| e.name() == "validate");
-        if !has_validate {
+        let entry_point = kind.entry_point();
+        let has_entry_point = exports.iter().any(|e| e.name() == entry_point);
+        if !has_entry_point {
             return Err(Error::PluginError(format!(
-                "Plugin {name} must export a 'validate' function"
+                "{kind} plugin {name} must export a '{entry_point}' function"
             )));
         }
 
@@ -611,6 +845,7 @@ impl WasmRuntimeBuilder {
 
         let engine = Engine::new(&config)
             .map_err(|e| Error::PluginError(format!("Engine creation failed: {e}")))?;
+        WasmRuntime::spawn_epoch_ticker(&engine)?;
 
         Ok(WasmRuntime {
             engine,
@@ -654,6 +889,148 @@ mod tests {
         assert!(runtime.is_ok());
     }
 
+    fn input() -> ValidationInput {
+        ValidationInput {
+            operation: super::super::types::Operation::Create,
+            object: None,
+            old_object: None,
+            namespace: "default".to_string(),
+            name: "test-node".to_string(),
+            user_info: super::super::types::UserInfo {
+                username: "test-user".to_string(),
+                uid: None,
+                groups: vec![],
+                extra: Default::default(),
+            },
+            context: Default::default(),
+        }
+    }
+
+    async fn load(runtime: &WasmRuntime, name: &str, body: &str, limits: PluginLimits) {
+        let wasm = wat::parse_str(format!(
+            r#"(module (memory (export "memory") 1) (func (export "validate") (result i32) {body}))"#
+        ))
+        .unwrap();
+        let metadata = PluginMetadata {
+            name: name.to_string(),
+            version: "0.0.1".to_string(),
+            description: None,
+            author: None,
+            sha256: None,
+            limits,
+        };
+        runtime.load_plugin(&wasm, metadata).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_memory_high_water_mark() {
+        let runtime = WasmRuntime::new().unwrap();
+        load(
+            &runtime,
+            "grower",
+            "(drop (memory.grow (i32.const 2))) (i32.const 0)",
+            PluginLimits::default(),
+        )
+        .await;
+        let result = runtime.execute("grower", &input(), None).await.unwrap();
+        assert!(result.output.allowed);
+        assert_eq!(result.memory_used_bytes, 3 * 65536);
+        assert!(result.fuel_consumed > 0);
+
+        // Growth past the limit fails the plugin
+        let limits = PluginLimits {
+            max_memory_bytes: 2 * 65536,
+            ..Default::default()
+        };
+        load(
+            &runtime,
+            "hog",
+            "(if (i32.lt_s (memory.grow (i32.const 2)) (i32.const 0)) (then unreachable)) (i32.const 0)",
+            limits,
+        )
+        .await;
+        let err = runtime.execute("hog", &input(), None).await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("exceeded memory limit of 131072 bytes"));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_is_enforced() {
+        let runtime = WasmRuntime::new().unwrap();
+        let limits = PluginLimits {
+            timeout_ms: 50,
+            max_fuel: u64::MAX / 2,
+            ..Default::default()
+        };
+        load(&runtime, "spinner", "(loop (br 0)) (i32.const 0)", limits).await;
+        let err = runtime
+            .execute("spinner", &input(), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timeout after 50ms"), "got: {err}");
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_disables_plugin() {
+        let runtime = WasmRuntime::new().unwrap();
+        let limits = PluginLimits {
+            max_fuel: 10_000,
+            circuit_breaker_threshold: 2,
+            ..Default::default()
+        };
+        load(&runtime, "looper", "(loop (br 0)) (i32.const 0)", limits).await;
+
+        for _ in 0..2 {
+            let err = runtime.execute("looper", &input(), None).await.unwrap_err();
+            assert!(err.to_string().contains("exceeded instruction limit"));
+        }
+        assert_eq!(
+            runtime.circuit_state("looper").await,
+            Some(CircuitState::Open)
+        );
+        let err = runtime.execute("looper", &input(), None).await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("disabled after 2 consecutive limit violations"));
+
+        // Fail-open plugins are skipped with a warning while disabled
+        let config = |fail_open| PluginConfig {
+            metadata: PluginMetadata {
+                name: "looper".to_string(),
+                version: "0.0.1".to_string(),
+                description: None,
+                author: None,
+                sha256: None,
+                limits: PluginLimits::default(),
+            },
+            wasm_binary: None,
+            config_map_ref: None,
+            secret_ref: None,
+            url: None,
+            kind: PluginKind::Validating,
+            priority: 0,
+            operations: vec![super::super::types::Operation::Create],
+            enabled: true,
+            fail_open,
+            plugin_config: Default::default(),
+        };
+        let results = runtime.execute_all(&[config(true)], &input()).await;
+        let result = results[0].as_ref().unwrap();
+        assert!(result.output.allowed);
+        assert!(result.output.warnings[0].contains("disabled after 2"));
+        let results = runtime.execute_all(&[config(false)], &input()).await;
+        assert!(results[0].is_err());
+
+        // Reloading the plugin closes the breaker
+        load(&runtime, "looper", "(i32.const 0)", PluginLimits::default()).await;
+        assert_eq!(
+            runtime.circuit_state("looper").await,
+            Some(CircuitState::Closed)
+        );
+        assert!(runtime.execute("looper", &input(), None).await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_runtime_builder() {
         let runtime = WasmRuntimeBuilder::new()
@@ -1,7 +1,13 @@
 //! Admission Webhook Server
 //!
-//! This module implements a Kubernetes ValidatingAdmissionWebhook server
-//! that executes Wasm plugins for custom StellarNode validation.
+//! This module implements a Kubernetes admission webhook server that
+//! executes Wasm plugins for custom StellarNode validation and mutation.
+//!
+//! `/mutate` applies the built-in defaults first, then every enabled
+//! mutating plugin in priority order. Each plugin sees the object as left by
+//! the previous ones, and its patch is only kept if it touches `/spec`,
+//! `/metadata/labels` or `/metadata/annotations` and the result still passes
+//! StellarNode schema validation.
 
 use std::collections::BTreeMap;
 use std::net::SocketAddr;
@@ -19,11 +25,13 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::{error, info, instrument, warn};
 
+use super::guard::CircuitState;
 use super::runtime::WasmRuntime;
 use super::types::{
-    Operation, PluginConfig, PluginExecutionResult, PluginMetadata, UserInfo, ValidationInput,
-    ValidationOutput,
+    Operation, PluginConfig, PluginExecutionResult, PluginKind, PluginMetadata, UserInfo,
+    ValidationInput, ValidationOutput,
 };
+use crate::controller::quorum_analysis;
 use crate::crd::StellarNode;
 use crate::error::{Error, Result};
 
@@ -57,6 +65,10 @@ pub struct LoadPluginRequest {
     pub enabled: bool,
     #[serde(default)]
     pub fail_open: bool,
+    #[serde(default)]
+    pub kind: PluginKind,
+    #[serde(default)]
+    pub priority: i32,
 }
 
 fn default_true() -> bool {
@@ -77,6 +89,11 @@ pub struct PluginInfo {
     pub description: Option<String>,
     pub operations: Vec<Operation>,
     pub enabled: bool,
+    pub kind: PluginKind,
+    pub priority: i32,
+    /// Circuit breaker state; `open` means the plugin is temporarily disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub circuit: Option<CircuitState>,
 }
 
 /// Health check response
@@ -96,6 +113,20 @@ pub struct ServerValidationResult {
     pub total_execution_time_ms: u64,
 }
 
+/// Result of running the built-in and plugin mutations
+#[derive(Debug)]
+pub struct ServerMutationResult {
+    pub allowed: bool,
+    pub message: Option<String>,
+    /// Combined patch, built-in operations first
+    pub patch: json_patch::Patch,
+    pub warnings: Vec<String>,
+    pub total_execution_time_ms: u64,
+}
+
+/// Paths mutating plugins may modify
+const MUTABLE_PATHS: [&str; 3] = ["/spec", "/metadata/labels", "/metadata/annotations"];
+
 /// Validation result response
 #[derive(Debug, Serialize)]
 pub struct ValidationResultResponse {
@@ -143,9 +174,17 @@ impl WebhookServer {
             base64::Engine::decode(&base64::engine::general_purpose::STANDARD, wasm_binary_str)
                 .map_err(|e| Error::PluginError(format!("Invalid base64 wasm_binary: {e}")))?;
 
+        self.install_plugin(config, &wasm_bytes).await
+    }
+
+    /// Add or replace a plugin from its module bytes.
+    ///
+    /// Requests already executing keep the module they started with; a
+    /// module that fails to compile leaves the previous version in place.
+    pub async fn install_plugin(&self, config: PluginConfig, wasm_bytes: &[u8]) -> Result<()> {
         // Load into runtime
         self.runtime
-            .load_plugin(&wasm_bytes, config.metadata.clone())
+            .load_plugin_with_kind(wasm_bytes, config.metadata.clone(), config.kind)
             .await?;
 
         // Add to plugins list
@@ -178,11 +217,20 @@ impl WebhookServer {
     #[instrument(skip(self, input))]
     pub async fn validate(&self, input: ValidationInput) -> ServerValidationResult {
         // Built-in validation: reject invalid nodeType or missing required fields before plugins
+        let mut quorum_warnings = Vec::new();
         if let Some(ref object) = input.object {
             if matches!(input.operation, Operation::Create | Operation::Update) {
                 if let Some(builtin) = validate_spec_builtin(object) {
                     return builtin;
                 }
+                let old_object = match input.operation {
+                    Operation::Update => input.old_object.as_ref(),
+                    _ => None,
+                };
+                match validate_quorum_builtin(object, old_object) {
+                    Ok(warnings) => quorum_warnings = warnings,
+                    Err(denied) => return denied,
+                }
             }
         }
 
@@ -192,7 +240,7 @@ impl WebhookServer {
             return ServerValidationResult {
                 allowed: true,
                 message: Some("No validation plugins configured".to_string()),
-                warnings: vec![],
+                warnings: quorum_warnings,
                 plugin_results: vec![],
                 total_execution_time_ms: 0,
             };
@@ -203,7 +251,7 @@ impl WebhookServer {
 
         let mut allowed = true;
         let mut messages = Vec::new();
-        let mut warnings = Vec::new();
+        let mut warnings = quorum_warnings;
         let mut plugin_results = Vec::new();
 
         for result in results {
@@ -245,12 +293,104 @@ impl WebhookServer {
         }
     }
 
+    /// Mutate a StellarNode: apply the built-in patch, then chain the
+    /// mutating plugins in priority order
+    #[instrument(skip(self, input, builtin))]
+    pub async fn mutate(
+        &self,
+        input: ValidationInput,
+        builtin: json_patch::Patch,
+    ) -> ServerMutationResult {
+        let start = std::time::Instant::now();
+        let mut result = ServerMutationResult {
+            allowed: true,
+            message: None,
+            patch: json_patch::Patch::default(),
+            warnings: vec![],
+            total_execution_time_ms: 0,
+        };
+
+        let Some(mut object) = input.object.clone() else {
+            result.patch = builtin;
+            return result;
+        };
+        if let Err(e) = json_patch::patch(&mut object, &builtin.0) {
+            result.allowed = false;
+            result.message = Some(format!("Built-in mutation failed: {e}"));
+            return result;
+        }
+        result.patch = builtin;
+
+        let mut plugins: Vec<PluginConfig> = self
+            .plugins
+            .read()
+            .await
+            .iter()
+            .filter(|p| {
+                p.enabled
+                    && p.kind == PluginKind::Mutating
+                    && p.operations.contains(&input.operation)
+            })
+            .cloned()
+            .collect();
+        plugins.sort_by(|a, b| {
+            a.priority
+                .cmp(&b.priority)
+                .then_with(|| a.metadata.name.cmp(&b.metadata.name))
+        });
+
+        for plugin in plugins {
+            let name = &plugin.metadata.name;
+            let plugin_input = ValidationInput {
+                object: Some(object.clone()),
+                ..input.clone()
+            };
+            let outcome = match self
+                .runtime
+                .execute_mutation(name, &plugin_input, Some(plugin.metadata.limits.clone()))
+                .await
+            {
+                Ok(execution) => apply_plugin_patch(&object, &execution.output.patch)
+                    .map(|mutated| (mutated, execution.output)),
+                Err(e) => Err(e.to_string()),
+            };
+
+            match outcome {
+                Ok((mutated, output)) => {
+                    object = mutated;
+                    result.patch.0.extend(output.patch.0);
+                    result
+                        .warnings
+                        .extend(output.warnings.iter().map(|w| format!("[{name}] {w}")));
+                }
+                Err(e) if plugin.fail_open => {
+                    warn!("Mutating plugin {} failed (fail-open): {}", name, e);
+                    result.warnings.push(format!(
+                        "[{name}] Plugin failed but fail-open is enabled, its patch was skipped: {e}"
+                    ));
+                }
+                Err(e) => {
+                    result.allowed = false;
+                    result.message = Some(format!("Mutating plugin {name}: {e}"));
+                    break;
+                }
+            }
+        }
+
+        result.total_execution_time_ms = start.elapsed().as_millis() as u64;
+        result
+    }
+
     /// Start the webhook server
     pub async fn start(self, addr: SocketAddr) -> Result<()> {
-        // Check TLS config before moving self into Arc
-        let has_tls = self.tls_config.is_some();
+        Arc::new(self).serve(addr).await
+    }
 
-        let state = Arc::new(self);
+    /// Start the webhook server on a shared instance, e.g. one also updated
+    /// by [`super::loader::PluginLoader`]
+    pub async fn serve(self: Arc<Self>, addr: SocketAddr) -> Result<()> {
+        let has_tls = self.tls_config.is_some();
+        let state = self;
 
         let app = Router::new()
             .route("/health", get(health_handler))
@@ -262,10 +402,12 @@ impl WebhookServer {
             .route("/plugins", get(list_plugins_handler))
             .route("/plugins", post(add_plugin_handler))
             .route(
-                "/plugins/:name",
+                "/plugins/{name}",
                 axum::routing::delete(remove_plugin_handler),
             )
             .with_state(state);
+        #[cfg(feature = "metrics")]
+        let app = app.route("/metrics", get(metrics_handler));
 
         info!("Starting webhook server on {}", addr);
 
@@ -290,6 +432,14 @@ impl WebhookServer {
 
 // HTTP Handlers
 
+#[cfg(feature = "metrics")]
+async fn metrics_handler() -> String {
+    use prometheus_client::encoding::text::encode;
+    let mut buffer = String::new();
+    encode(&mut buffer, &crate::controller::metrics::REGISTRY).unwrap();
+    buffer
+}
+
 async fn health_handler(State(state): State<Arc<WebhookServer>>) -> impl IntoResponse {
     let plugins = state.runtime.list_plugins().await;
     Json(HealthResponse {
@@ -370,54 +520,67 @@ async fn validate_handler(
     (StatusCode::OK, Json(response.into_review()))
 }
 
-#[instrument(skip(_state, review))]
+#[instrument(skip(state, review))]
 async fn mutate_handler(
-    State(_state): State<Arc<WebhookServer>>,
+    State(state): State<Arc<WebhookServer>>,
     Json(review): Json<AdmissionReview<StellarNode>>,
 ) -> impl IntoResponse {
     use super::mutation::apply_mutations;
 
-    let request: Result<AdmissionRequest<StellarNode>, _> = review.try_into();
-
-    match request {
-        Ok(req) => {
-            // Apply mutations to the StellarNode
-            match apply_mutations(&req) {
-                Ok(Some(patch)) => {
-                    let mut response = AdmissionResponse::from(&req);
-                    // Convert JSON patch to bytes
-                    let patch_bytes = serde_json::to_vec(&patch)
-                        .map_err(|e| format!("Failed to serialize patch: {}", e))
-                        .unwrap_or_default();
-                    response.patch = Some(patch_bytes);
-
-                    info!("Applied mutations to StellarNode {}", req.name);
-                    (StatusCode::OK, Json(response.into_review()))
-                }
-                Ok(None) => {
-                    // No mutations needed
-                    let response = AdmissionResponse::from(&req);
-                    (StatusCode::OK, Json(response.into_review()))
-                }
-                Err(e) => {
-                    error!("Failed to apply mutations: {}", e);
-                    let response =
-                        AdmissionResponse::from(&req).deny(format!("Mutation failed: {e}"));
-                    (StatusCode::OK, Json(response.into_review()))
-                }
-            }
-        }
+    let req: AdmissionRequest<StellarNode> = match review.try_into() {
+        Ok(req) => req,
         Err(e) => {
             error!("Failed to parse admission request: {}", e);
-            (
+            return (
                 StatusCode::BAD_REQUEST,
                 Json(
                     AdmissionResponse::invalid(format!("Invalid admission request: {e}"))
                         .into_review(),
                 ),
-            )
+            );
         }
+    };
+
+    // Built-in defaults
+    let builtin = match apply_mutations(&req).and_then(|patch| {
+        patch
+            .map(serde_json::from_value::<json_patch::Patch>)
+            .transpose()
+            .map_err(Error::SerializationError)
+    }) {
+        Ok(patch) => patch.unwrap_or_default(),
+        Err(e) => {
+            error!("Failed to apply mutations: {}", e);
+            let response = AdmissionResponse::from(&req).deny(format!("Mutation failed: {e}"));
+            return (StatusCode::OK, Json(response.into_review()));
+        }
+    };
+
+    let result = state.mutate(build_validation_input(&req), builtin).await;
+
+    let mut response = if !result.allowed {
+        AdmissionResponse::from(&req).deny(
+            result
+                .message
+                .unwrap_or_else(|| "Mutation failed".to_string()),
+        )
+    } else if result.patch.0.is_empty() {
+        AdmissionResponse::from(&req)
+    } else {
+        match AdmissionResponse::from(&req).with_patch(result.patch) {
+            Ok(response) => {
+                info!("Applied mutations to StellarNode {}", req.name);
+                response
+            }
+            Err(e) => AdmissionResponse::from(&req).deny(format!("Mutation failed: {e}")),
+        }
+    };
+
+    if !result.warnings.is_empty() {
+        response.warnings = Some(result.warnings);
     }
+
+    (StatusCode::OK, Json(response.into_review()))
 }
 
 #[instrument(skip(state, payload))]
@@ -526,17 +689,20 @@ async fn db_trigger_handler(
 }
 
 async fn list_plugins_handler(State(state): State<Arc<WebhookServer>>) -> impl IntoResponse {
-    let plugins = state.plugins.read().await;
-    let infos: Vec<PluginInfo> = plugins
-        .iter()
-        .map(|p| PluginInfo {
-            name: p.metadata.name.clone(),
-            version: p.metadata.version.clone(),
-            description: p.metadata.description.clone(),
-            operations: p.operations.clone(),
+    let plugins = state.plugins.read().await.clone();
+    let mut infos = Vec::with_capacity(plugins.len());
+    for p in plugins {
+        infos.push(PluginInfo {
+            circuit: state.runtime.circuit_state(&p.metadata.name).await,
+            name: p.metadata.name,
+            version: p.metadata.version,
+            description: p.metadata.description,
+            operations: p.operations,
             enabled: p.enabled,
-        })
-        .collect();
+            kind: p.kind,
+            priority: p.priority,
+        });
+    }
 
     Json(PluginListResponse { plugins: infos })
 }
@@ -557,6 +723,8 @@ async fn add_plugin_handler(
         config_map_ref: None,
         secret_ref: None,
         url: None,
+        kind: request.kind,
+        priority: request.priority,
         operations: request.operations,
         enabled: request.enabled,
         fail_open: request.fail_open,
@@ -626,6 +794,127 @@ fn validate_spec_builtin(object: &serde_json::Value) -> Option<ServerValidationR
     })
 }
 
+/// Apply a mutating plugin's patch to `object`, returning the result.
+///
+/// The patch may only touch [`MUTABLE_PATHS`] and the mutated object must
+/// still be a valid StellarNode.
+fn apply_plugin_patch(
+    object: &serde_json::Value,
+    patch: &json_patch::Patch,
+) -> std::result::Result<serde_json::Value, String> {
+    let is_mutable = |path: &str| {
+        MUTABLE_PATHS
+            .iter()
+            .any(|p| path == *p || path.starts_with(&format!("{p}/")))
+    };
+    for op in &patch.0 {
+        // `test` only reads its path and `copy` only reads its source
+        let written = match op {
+            json_patch::PatchOperation::Test(_) => None,
+            _ => Some(op.path().as_str()),
+        };
+        let moved_from = match op {
+            json_patch::PatchOperation::Move(m) => Some(m.from.as_str()),
+            _ => None,
+        };
+        if let Some(path) = written
+            .into_iter()
+            .chain(moved_from)
+            .find(|p| !is_mutable(p))
+        {
+            return Err(format!("patch may not modify {path}"));
+        }
+    }
+
+    let mut mutated = object.clone();
+    json_patch::patch(&mut mutated, &patch.0).map_err(|e| format!("invalid patch: {e}"))?;
+    if let Some(invalid) = validate_spec_builtin(&mutated) {
+        return Err(format!(
+            "patched object fails validation: {}",
+            invalid.message.unwrap_or_default()
+        ));
+    }
+    Ok(mutated)
+}
+
+/// Run the quorum safety analysis on a validator's `quorumSet`.
+///
+/// Returns the warnings to attach to the admission response, or a denial when
+/// the quorum set is unparseable or unsafe. On updates (`old_object` set), a
+/// quorum set is only denied for problems the previous one did not have, so
+/// a validator that is already unsafe can still be edited; the remaining
+/// problems are returned as warnings.
+fn validate_quorum_builtin(
+    object: &serde_json::Value,
+    old_object: Option<&serde_json::Value>,
+) -> std::result::Result<Vec<String>, ServerValidationResult> {
+    let Some(new) = quorum_problems(object) else {
+        return Ok(vec![]);
+    };
+    let previous = old_object.and_then(quorum_problems);
+
+    let introduced: Vec<&str> = new
+        .errors
+        .iter()
+        .map(String::as_str)
+        .filter(|e| {
+            previous
+                .as_ref()
+                .is_none_or(|p| !p.errors.iter().any(|o| o == e))
+        })
+        .collect();
+    if !introduced.is_empty() {
+        return Err(ServerValidationResult {
+            allowed: false,
+            message: Some(format!(
+                "spec.validatorConfig.quorumSet: {}",
+                introduced.join("; ")
+            )),
+            warnings: vec![],
+            plugin_results: vec![],
+            total_execution_time_ms: 0,
+        });
+    }
+    Ok(new
+        .errors
+        .iter()
+        .map(|e| format!("spec.validatorConfig.quorumSet is still unsafe: {e}"))
+        .chain(
+            new.warnings
+                .iter()
+                .map(|w| format!("spec.validatorConfig.quorumSet: {w}")),
+        )
+        .collect())
+}
+
+/// Errors and warnings of a StellarNode's `quorumSet`, or `None` when it has none
+struct QuorumProblems {
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+fn quorum_problems(object: &serde_json::Value) -> Option<QuorumProblems> {
+    let quorum_set = serde_json::from_value::<StellarNode>(object.clone())
+        .ok()?
+        .spec
+        .validator_config?
+        .quorum_set?;
+    match quorum_analysis::parse_quorum_config(&quorum_set) {
+        Ok(Some(config)) => {
+            let report = quorum_analysis::analyze(&config);
+            Some(QuorumProblems {
+                errors: report.errors().map(String::from).collect(),
+                warnings: report.warnings().map(String::from).collect(),
+            })
+        }
+        Ok(None) => None,
+        Err(e) => Some(QuorumProblems {
+            errors: vec![e.to_string()],
+            warnings: vec![],
+        }),
+    }
+}
+
 /// Build ValidationInput from AdmissionRequest
 fn build_validation_input(req: &AdmissionRequest<StellarNode>) -> ValidationInput {
     let operation = match req.operation {
@@ -864,6 +1153,8 @@ mod tests {
             config_map_ref: None,
             secret_ref: None,
             url: None,
+            kind: PluginKind::Validating,
+            priority: 0,
             operations: vec![Operation::Create],
             enabled: true,
             fail_open: false,
@@ -940,6 +1231,8 @@ mod tests {
             config_map_ref: None,
             secret_ref: None,
             url: None,
+            kind: PluginKind::Validating,
+            priority: 0,
             operations: vec![Operation::Create],
             enabled: true,
             fail_open: true,
@@ -972,4 +1265,299 @@ mod tests {
             "expected warning about plugin failure"
         );
     }
+
+    fn validator_with_quorum(quorum_set: &str) -> serde_json::Value {
+        serde_json::json!({
+            "metadata": { "name": "my-validator", "namespace": "default" },
+            "spec": {
+                "nodeType": "Validator",
+                "network": "Testnet",
+                "version": "v21.0.0",
+                "replicas": 1,
+                "validatorConfig": {
+                    "seedSecretRef": "validator-seed",
+                    "quorumSet": quorum_set
+                }
+            }
+        })
+    }
+
+    /// A quorum set that allows disjoint quorums is denied
+    #[tokio::test]
+    async fn unsafe_quorum_set_rejected() {
+        let server = WebhookServer::new(WasmRuntime::new().unwrap());
+        let object = validator_with_quorum(
+            "[QUORUM_SET]\nTHRESHOLD_PERCENT = 50\nVALIDATORS = [\"$a\", \"$b\", \"$c\", \"$d\"]\n",
+        );
+
+        let result = server
+            .validate(validation_input(Operation::Create, Some(object)))
+            .await;
+        assert!(!result.allowed);
+        let message = result.message.unwrap_or_default();
+        assert!(message.contains("THRESHOLD_PERCENT"), "got: {message}");
+    }
+
+    /// Liveness risks are admitted with warnings
+    #[tokio::test]
+    async fn quorum_single_point_of_failure_warns() {
+        let server = WebhookServer::new(WasmRuntime::new().unwrap());
+        let object = validator_with_quorum(
+            "[QUORUM_SET]\nTHRESHOLD_PERCENT = 100\nVALIDATORS = [\"$a\", \"$b\"]\n",
+        );
+
+        let result = server
+            .validate(validation_input(Operation::Update, Some(object)))
+            .await;
+        assert!(result.allowed, "got: {:?}", result.message);
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.contains("single point of failure")));
+    }
+
+    /// Updates are only denied when they make the quorum set less safe
+    #[tokio::test]
+    async fn quorum_update_denied_only_when_worse() {
+        let server = WebhookServer::new(WasmRuntime::new().unwrap());
+        let unsafe_set = validator_with_quorum(
+            "[QUORUM_SET]\nTHRESHOLD_PERCENT = 50\nVALIDATORS = [\"$a\", \"$b\", \"$c\", \"$d\"]\n",
+        );
+        let update = |object: serde_json::Value, old: serde_json::Value| ValidationInput {
+            old_object: Some(old),
+            ..validation_input(Operation::Update, Some(object))
+        };
+
+        // Editing an already unsafe validator is allowed, with a warning
+        let mut edited = unsafe_set.clone();
+        edited["spec"]["version"] = serde_json::json!("v21.1.0");
+        let result = server.validate(update(edited, unsafe_set.clone())).await;
+        assert!(result.allowed, "got: {:?}", result.message);
+        assert!(result.warnings.iter().any(|w| w.contains("still unsafe")));
+
+        // Breaking a safe quorum set is denied
+        let safe = validator_with_quorum("[QUORUM_SET]\nVALIDATORS = [\"$a\", \"$b\", \"$c\"]\n");
+        let result = server.validate(update(unsafe_set.clone(), safe)).await;
+        assert!(!result.allowed);
+
+        // So is an update adding a new problem to an unsafe one
+        let duplicated = validator_with_quorum(
+            "[QUORUM_SET]\nTHRESHOLD_PERCENT = 50\nVALIDATORS = [\"$a\", \"$a\", \"$c\", \"$d\"]\n",
+        );
+        let result = server.validate(update(duplicated, unsafe_set)).await;
+        assert!(!result.allowed);
+        let message = result.message.unwrap_or_default();
+        assert!(message.contains("more than once"), "got: {message}");
+    }
+
+    /// Mutating plugin whose `mutate` export always returns `output`
+    fn mutating_plugin(
+        name: &str,
+        priority: i32,
+        fail_open: bool,
+        output: serde_json::Value,
+    ) -> PluginConfig {
+        let json = output.to_string();
+        let wasm = wat::parse_str(format!(
+            r#"
+            (module
+                (import "env" "write_output" (func $write_output (param i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 0) "{}")
+                (func (export "mutate") (result i32)
+                    (drop (call $write_output (i32.const 0) (i32.const {})))
+                    (i32.const 0))
+            )
+            "#,
+            json.replace('\\', "\\\\").replace('"', "\\\""),
+            json.len()
+        ))
+        .unwrap();
+
+        PluginConfig {
+            metadata: PluginMetadata {
+                name: name.to_string(),
+                version: "0.0.1".to_string(),
+                description: None,
+                author: None,
+                sha256: None,
+                limits: PluginLimits::default(),
+            },
+            wasm_binary: Some(base64::Engine::encode(
+                &base64::engine::general_purpose::STANDARD,
+                &wasm,
+            )),
+            config_map_ref: None,
+            secret_ref: None,
+            url: None,
+            kind: PluginKind::Mutating,
+            priority,
+            operations: vec![Operation::Create],
+            enabled: true,
+            fail_open,
+            plugin_config: BTreeMap::new(),
+        }
+    }
+
+    fn validator_object() -> serde_json::Value {
+        serde_json::json!({
+            "apiVersion": "stellar.org/v1alpha1",
+            "kind": "StellarNode",
+            "metadata": { "name": "my-validator", "namespace": "default" },
+            "spec": {
+                "nodeType": "Validator",
+                "network": "Testnet",
+                "version": "v21.0.0",
+                "replicas": 1,
+                "validatorConfig": { "seedSecretRef": "validator-seed" }
+            }
+        })
+    }
+
+    /// Mutating plugins run in priority order, each seeing the previous patches
+    #[tokio::test]
+    async fn mutating_plugins_chain_in_priority_order() {
+        let server = WebhookServer::new(WasmRuntime::new().unwrap());
+        // Only succeeds once the label exists
+        server
+            .add_plugin(mutating_plugin(
+                "team-override",
+                20,
+                false,
+                serde_json::json!({
+                    "patch": [{ "op": "replace", "path": "/metadata/labels/team", "value": "payments" }],
+                    "warnings": ["team label overridden"]
+                }),
+            ))
+            .await
+            .unwrap();
+        server
+            .add_plugin(mutating_plugin(
+                "org-defaults",
+                10,
+                false,
+                serde_json::json!({
+                    "patch": [{ "op": "add", "path": "/metadata/labels", "value": { "team": "core" } }]
+                }),
+            ))
+            .await
+            .unwrap();
+
+        let builtin: json_patch::Patch = serde_json::from_value(serde_json::json!([
+            { "op": "replace", "path": "/spec/version", "value": "v21.3.0" }
+        ]))
+        .unwrap();
+        let result = server
+            .mutate(
+                validation_input(Operation::Create, Some(validator_object())),
+                builtin,
+            )
+            .await;
+        assert!(result.allowed, "got: {:?}", result.message);
+        assert_eq!(result.patch.0.len(), 3);
+        assert_eq!(
+            result.warnings,
+            vec!["[team-override] team label overridden"]
+        );
+
+        let mut object = validator_object();
+        json_patch::patch(&mut object, &result.patch.0).unwrap();
+        assert_eq!(object["metadata"]["labels"]["team"], "payments");
+        assert_eq!(object["spec"]["version"], "v21.3.0");
+
+        // Mutating plugins are not run as validators
+        let validated = server
+            .validate(validation_input(
+                Operation::Create,
+                Some(validator_object()),
+            ))
+            .await;
+        assert!(validated.allowed);
+        assert!(validated.plugin_results.is_empty());
+    }
+
+    /// Patches outside the mutable paths or breaking the schema are rejected
+    #[tokio::test]
+    async fn invalid_plugin_patches_rejected() {
+        let server = WebhookServer::new(WasmRuntime::new().unwrap());
+        server
+            .add_plugin(mutating_plugin(
+                "rename",
+                0,
+                false,
+                serde_json::json!({
+                    "patch": [{ "op": "replace", "path": "/metadata/name", "value": "other" }]
+                }),
+            ))
+            .await
+            .unwrap();
+        let input = validation_input(Operation::Create, Some(validator_object()));
+        let result = server
+            .mutate(input.clone(), json_patch::Patch::default())
+            .await;
+        assert!(!result.allowed);
+        let message = result.message.unwrap_or_default();
+        assert!(
+            message.contains("may not modify /metadata/name"),
+            "got: {message}"
+        );
+
+        server
+            .add_plugin(mutating_plugin(
+                "rename",
+                0,
+                false,
+                serde_json::json!({
+                    "patch": [{ "op": "remove", "path": "/spec/validatorConfig" }]
+                }),
+            ))
+            .await
+            .unwrap();
+        let result = server
+            .mutate(input.clone(), json_patch::Patch::default())
+            .await;
+        assert!(!result.allowed);
+        let message = result.message.unwrap_or_default();
+        assert!(message.contains("fails validation"), "got: {message}");
+
+        // With fail-open the patch is dropped instead
+        server
+            .add_plugin(mutating_plugin(
+                "rename",
+                0,
+                true,
+                serde_json::json!({
+                    "patch": [{ "op": "remove", "path": "/spec/validatorConfig" }]
+                }),
+            ))
+            .await
+            .unwrap();
+        let result = server.mutate(input, json_patch::Patch::default()).await;
+        assert!(result.allowed);
+        assert!(result.patch.0.is_empty());
+        assert!(result.warnings[0].contains("patch was skipped"));
+    }
+
+    /// A mutating plugin must export `mutate`
+    #[tokio::test]
+    async fn mutating_plugin_requires_mutate_export() {
+        let server = WebhookServer::new(WasmRuntime::new().unwrap());
+        let wasm = wat::parse_str(
+            r#"
+            (module
+                (func (export "validate") (result i32) (i32.const 0))
+                (memory (export "memory") 1)
+            )
+            "#,
+        )
+        .unwrap();
+        let mut config = mutating_plugin("no-mutate", 0, false, serde_json::json!({}));
+        config.wasm_binary = Some(base64::Engine::encode(
+            &base64::engine::general_purpose::STANDARD,
+            &wasm,
+        ));
+
+        let err = server.add_plugin(config).await.unwrap_err();
+        assert!(err.to_string().contains("must export a 'mutate' function"));
+    }
 }
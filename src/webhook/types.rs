@@ -49,6 +49,20 @@ pub struct PluginLimits {
     /// Maximum fuel (Wasmtime instruction count limit, default: 1_000_000)
     #[serde(default = "default_max_fuel")]
     pub max_fuel: u64,
+
+    /// Maximum concurrent executions of the plugin (default: 8)
+    #[serde(default = "default_max_concurrency")]
+    pub max_concurrency: u32,
+
+    /// Consecutive fuel, timeout or memory limit violations after which the
+    /// plugin is disabled (default: 5, 0 never disables)
+    #[serde(default = "default_circuit_breaker_threshold")]
+    pub circuit_breaker_threshold: u32,
+
+    /// How long a disabled plugin stays disabled before a trial execution
+    /// (default: 60)
+    #[serde(default = "default_circuit_breaker_cooldown_secs")]
+    pub circuit_breaker_cooldown_secs: u64,
 }
 
 fn default_timeout_ms() -> u64 {
@@ -63,12 +77,27 @@ fn default_max_fuel() -> u64 {
     1_000_000
 }
 
+fn default_max_concurrency() -> u32 {
+    8
+}
+
+fn default_circuit_breaker_threshold() -> u32 {
+    5
+}
+
+fn default_circuit_breaker_cooldown_secs() -> u64 {
+    60
+}
+
 impl Default for PluginLimits {
     fn default() -> Self {
         Self {
             timeout_ms: default_timeout_ms(),
             max_memory_bytes: default_max_memory(),
             max_fuel: default_max_fuel(),
+            max_concurrency: default_max_concurrency(),
+            circuit_breaker_threshold: default_circuit_breaker_threshold(),
+            circuit_breaker_cooldown_secs: default_circuit_breaker_cooldown_secs(),
         }
     }
 }
@@ -120,6 +149,48 @@ pub struct DbTriggerOutput {
     pub ledger_sequence: u64,
 }
 
+/// Output returned by Wasm mutating plugins
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MutationOutput {
+    /// RFC 6902 operations to apply to the object the plugin received
+    #[serde(default)]
+    pub patch: json_patch::Patch,
+
+    /// Warnings returned to the client
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Admission phase a plugin takes part in
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+pub enum PluginKind {
+    /// Exports `validate` and allows or denies requests
+    #[default]
+    Validating,
+    /// Exports `mutate` and returns JSON patches
+    Mutating,
+}
+
+impl PluginKind {
+    /// Name of the function the plugin must export
+    pub fn entry_point(&self) -> &'static str {
+        match self {
+            PluginKind::Validating => "validate",
+            PluginKind::Mutating => "mutate",
+        }
+    }
+}
+
+impl std::fmt::Display for PluginKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginKind::Validating => write!(f, "Validating"),
+            PluginKind::Mutating => write!(f, "Mutating"),
+        }
+    }
+}
+
 /// Kubernetes operation type
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "UPPERCASE")]
@@ -352,7 +423,15 @@ pub struct PluginConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
 
-    /// Operations this plugin validates
+    /// Whether the plugin validates or mutates requests
+    #[serde(default)]
+    pub kind: PluginKind,
+
+    /// Order in which mutating plugins run (lower first, ties by name)
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Operations this plugin handles
     #[serde(default = "default_operations")]
     pub operations: Vec<Operation>,
 
@@ -415,7 +494,7 @@ pub struct PluginExecutionResult {
     /// Execution time in milliseconds
     pub execution_time_ms: u64,
 
-    /// Memory used in bytes
+    /// Linear memory high-water mark in bytes
     pub memory_used_bytes: u64,
 
     /// Fuel consumed
@@ -70,6 +70,7 @@ mod tests {
                 read_replica_config: None,
                 db_maintenance_config: None,
                 oci_snapshot: None,
+                decentralized_backup: None,
                 service_mesh: Some(ServiceMeshConfig {
                     sidecar_injection: true,
                     istio: Some(IstioMeshConfig {